@@ -0,0 +1,313 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use ink_lang as ink;
+
+#[ink::contract]
+mod access_control {
+    #[cfg(not(feature = "ink-as-dependency"))]
+    use ink_storage::collections::HashMap as StorageHashMap;
+
+    /// A role, which is the Blake2x256 hash of its name by convention.
+    pub type RoleId = Hash;
+
+    /// A registry of roles which other contracts query for their permissions,
+    /// so several contracts can share one set of roles instead of each keeping
+    /// an owner of its own.
+    ///
+    /// Every role is administered by another role whose members grant and
+    /// revoke it. The zero hash is the admin role, which administers every
+    /// role until `set_role_admin` hands a role to another one, including
+    /// itself. The creator of the registry starts out with the admin role.
+    ///
+    /// A contract checks a permission by calling `has_role(role, account)`,
+    /// whose selector is `0x8D194A68`, on the registry. The staking example
+    /// does so to find out who may change its reward rate.
+    #[ink(storage)]
+    pub struct AccessControl {
+        /// The accounts holding each role.
+        members: StorageHashMap<(RoleId, AccountId), ()>,
+        /// The number of accounts holding each role.
+        member_counts: StorageHashMap<RoleId, u32>,
+        /// The role administering each role, unless it is the admin role.
+        admin_roles: StorageHashMap<RoleId, RoleId>,
+    }
+
+    /// Event emitted when `account` is granted `role`.
+    #[ink(event)]
+    pub struct RoleGranted {
+        #[ink(topic)]
+        role: RoleId,
+        #[ink(topic)]
+        account: AccountId,
+        #[ink(topic)]
+        sender: AccountId,
+    }
+
+    /// Event emitted when `account` loses `role`.
+    #[ink(event)]
+    pub struct RoleRevoked {
+        #[ink(topic)]
+        role: RoleId,
+        #[ink(topic)]
+        account: AccountId,
+        #[ink(topic)]
+        sender: AccountId,
+    }
+
+    /// Event emitted when the role administering `role` changes.
+    #[ink(event)]
+    pub struct RoleAdminChanged {
+        #[ink(topic)]
+        role: RoleId,
+        previous: RoleId,
+        admin: RoleId,
+    }
+
+    /// The access control error types.
+    #[derive(Debug, PartialEq, Eq, scale::Encode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum Error {
+        /// Returned if the caller does not hold the role administering the
+        /// role.
+        MissingRole,
+        /// Returned if the number of accounts holding a role overflows.
+        Overflow,
+    }
+
+    /// The access control result type.
+    pub type Result<T> = core::result::Result<T, Error>;
+
+    impl AccessControl {
+        /// Creates a registry where the caller holds the admin role.
+        #[ink(constructor)]
+        pub fn new() -> Self {
+            let caller = Self::env().caller();
+            let mut instance = Self {
+                members: StorageHashMap::new(),
+                member_counts: StorageHashMap::new(),
+                admin_roles: StorageHashMap::new(),
+            };
+            instance.members.insert((RoleId::default(), caller), ());
+            instance.member_counts.insert(RoleId::default(), 1);
+            Self::env().emit_event(RoleGranted {
+                role: RoleId::default(),
+                account: caller,
+                sender: caller,
+            });
+            instance
+        }
+
+        /// Returns `true` if `account` holds `role`.
+        #[ink(message)]
+        pub fn has_role(&self, role: RoleId, account: AccountId) -> bool {
+            self.members.contains_key(&(role, account))
+        }
+
+        /// Returns the number of accounts holding `role`.
+        #[ink(message)]
+        pub fn member_count(&self, role: RoleId) -> u32 {
+            self.member_counts.get(&role).copied().unwrap_or(0)
+        }
+
+        /// Returns the role administering `role`.
+        #[ink(message)]
+        pub fn role_admin(&self, role: RoleId) -> RoleId {
+            self.admin_roles.get(&role).copied().unwrap_or_default()
+        }
+
+        /// Grants `role` to `account`.
+        ///
+        /// On success a `RoleGranted` event is emitted, unless `account` held
+        /// `role` already.
+        ///
+        /// # Errors
+        ///
+        /// Returns `MissingRole` error if the caller does not hold the role
+        /// administering `role`.
+        ///
+        /// Returns `Overflow` error if the number of accounts holding `role`
+        /// overflows.
+        #[ink(message)]
+        pub fn grant_role(&mut self, role: RoleId, account: AccountId) -> Result<()> {
+            let sender = self.ensure_admin_of(role)?;
+            if self.has_role(role, account) {
+                return Ok(())
+            }
+            let count = self
+                .member_count(role)
+                .checked_add(1)
+                .ok_or(Error::Overflow)?;
+            self.members.insert((role, account), ());
+            self.member_counts.insert(role, count);
+            self.env().emit_event(RoleGranted {
+                role,
+                account,
+                sender,
+            });
+            Ok(())
+        }
+
+        /// Revokes `role` from `account`.
+        ///
+        /// On success a `RoleRevoked` event is emitted, unless `account` did
+        /// not hold `role`.
+        ///
+        /// # Errors
+        ///
+        /// Returns `MissingRole` error if the caller does not hold the role
+        /// administering `role`.
+        #[ink(message)]
+        pub fn revoke_role(&mut self, role: RoleId, account: AccountId) -> Result<()> {
+            let sender = self.ensure_admin_of(role)?;
+            self.remove_member(role, account, sender);
+            Ok(())
+        }
+
+        /// Gives up `role` held by the caller.
+        ///
+        /// A `RoleRevoked` event is emitted, unless the caller did not hold
+        /// `role`.
+        #[ink(message)]
+        pub fn renounce_role(&mut self, role: RoleId) {
+            let caller = self.env().caller();
+            self.remove_member(role, caller, caller);
+        }
+
+        /// Hands the administration of `role` to `admin`.
+        ///
+        /// On success a `RoleAdminChanged` event is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `MissingRole` error if the caller does not hold the role
+        /// administering `role` so far.
+        #[ink(message)]
+        pub fn set_role_admin(&mut self, role: RoleId, admin: RoleId) -> Result<()> {
+            self.ensure_admin_of(role)?;
+            let previous = self.role_admin(role);
+            self.admin_roles.insert(role, admin);
+            self.env().emit_event(RoleAdminChanged {
+                role,
+                previous,
+                admin,
+            });
+            Ok(())
+        }
+
+        /// Returns the caller if it holds the role administering `role`.
+        fn ensure_admin_of(&self, role: RoleId) -> Result<AccountId> {
+            let caller = self.env().caller();
+            if !self.has_role(self.role_admin(role), caller) {
+                return Err(Error::MissingRole)
+            }
+            Ok(caller)
+        }
+
+        /// Removes `account` from the holders of `role` on behalf of `sender`.
+        fn remove_member(&mut self, role: RoleId, account: AccountId, sender: AccountId) {
+            if self.members.take(&(role, account)).is_none() {
+                return
+            }
+            let count = self.member_count(role).saturating_sub(1);
+            self.member_counts.insert(role, count);
+            self.env().emit_event(RoleRevoked {
+                role,
+                account,
+                sender,
+            });
+        }
+    }
+
+    /// Unit tests.
+    #[cfg(test)]
+    mod tests {
+        /// Imports all the definitions from the outer scope so we can use them here.
+        use super::*;
+
+        use ink_lang as ink;
+        use test_utils::set_caller;
+
+        fn minter() -> RoleId {
+            RoleId::from([0x01; 32])
+        }
+
+        #[ink::test]
+        fn new_works() {
+            let registry = AccessControl::new();
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            assert!(registry.has_role(RoleId::default(), accounts.alice));
+            assert!(!registry.has_role(RoleId::default(), accounts.bob));
+            assert_eq!(registry.member_count(RoleId::default()), 1);
+            assert_eq!(registry.role_admin(minter()), RoleId::default());
+            assert_eq!(ink_env::test::recorded_events().count(), 1);
+        }
+
+        #[ink::test]
+        fn admins_grant_and_revoke_roles() {
+            let mut registry = AccessControl::new();
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            assert_eq!(registry.grant_role(minter(), accounts.bob), Ok(()));
+            assert_eq!(registry.grant_role(minter(), accounts.bob), Ok(()));
+            assert!(registry.has_role(minter(), accounts.bob));
+            assert_eq!(registry.member_count(minter()), 1);
+
+            assert_eq!(registry.revoke_role(minter(), accounts.bob), Ok(()));
+            assert!(!registry.has_role(minter(), accounts.bob));
+            assert_eq!(registry.member_count(minter()), 0);
+            assert_eq!(ink_env::test::recorded_events().count(), 3);
+        }
+
+        #[ink::test]
+        fn only_admins_grant_roles() {
+            let mut registry = AccessControl::new();
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            registry.grant_role(minter(), accounts.bob).unwrap();
+            set_caller!(accounts.bob);
+            assert_eq!(
+                registry.grant_role(minter(), accounts.charlie),
+                Err(Error::MissingRole)
+            );
+            assert_eq!(
+                registry.revoke_role(RoleId::default(), accounts.alice),
+                Err(Error::MissingRole)
+            );
+            assert_eq!(
+                registry.set_role_admin(minter(), minter()),
+                Err(Error::MissingRole)
+            );
+
+            registry.renounce_role(minter());
+            assert!(!registry.has_role(minter(), accounts.bob));
+        }
+
+        #[ink::test]
+        fn role_admins_can_be_handed_over() {
+            let mut registry = AccessControl::new();
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let operator = RoleId::from([0x02; 32]);
+
+            registry.grant_role(operator, accounts.bob).unwrap();
+            assert_eq!(registry.set_role_admin(minter(), operator), Ok(()));
+            assert_eq!(registry.role_admin(minter()), operator);
+            assert_eq!(
+                registry.grant_role(minter(), accounts.charlie),
+                Err(Error::MissingRole)
+            );
+
+            set_caller!(accounts.bob);
+            assert_eq!(registry.grant_role(minter(), accounts.charlie), Ok(()));
+            assert!(registry.has_role(minter(), accounts.charlie));
+        }
+    }
+}
\ No newline at end of file
@@ -0,0 +1,102 @@
+import BN from 'bn.js';
+import { expect } from 'chai';
+import { patract, network } from 'redspot';
+import { blake2AsHex } from '@polkadot/util-crypto';
+
+const { getContractFactory, getRandomSigner } = patract;
+
+const { api, getSigners } = network;
+
+const ADMIN_ROLE = `0x${'00'.repeat(32)}`;
+const MINTER_ROLE = blake2AsHex('minter');
+const OPERATOR_ROLE = blake2AsHex('operator');
+
+describe('ACCESS CONTROL', () => {
+  after(() => {
+    return api.disconnect();
+  });
+
+  async function setup() {
+    const one = new BN(10).pow(new BN(api.registry.chainDecimals[0]));
+    const signers = await getSigners();
+    const Alice = signers[0];
+    const sender = await getRandomSigner(Alice, one.muln(10000));
+    const member = await getRandomSigner(Alice, one.muln(10000));
+    const contractFactory = await getContractFactory('access_control', sender);
+    const contract = await contractFactory.deploy('new');
+
+    return { sender, member, contract, Alice, one };
+  }
+
+  it('Gives the creator the admin role', async () => {
+    const { contract, sender, member } = await setup();
+
+    expect(
+      (await contract.query.hasRole(ADMIN_ROLE, sender.address)).output
+    ).to.equal(true);
+    expect(
+      (await contract.query.hasRole(ADMIN_ROLE, member.address)).output
+    ).to.equal(false);
+    expect((await contract.query.memberCount(ADMIN_ROLE)).output).to.equal(1);
+  });
+
+  it('Admins grant and revoke roles', async () => {
+    const { contract, sender, member } = await setup();
+
+    await expect(contract.tx.grantRole(MINTER_ROLE, member.address))
+      .to.emit(contract, 'RoleGranted')
+      .withArgs(MINTER_ROLE, member.address, sender.address);
+    expect(
+      (await contract.query.hasRole(MINTER_ROLE, member.address)).output
+    ).to.equal(true);
+
+    await expect(contract.tx.revokeRole(MINTER_ROLE, member.address))
+      .to.emit(contract, 'RoleRevoked')
+      .withArgs(MINTER_ROLE, member.address, sender.address);
+    expect(
+      (await contract.query.hasRole(MINTER_ROLE, member.address)).output
+    ).to.equal(false);
+  });
+
+  it('Only admins grant roles', async () => {
+    const { contract, member } = await setup();
+
+    await expect(
+      contract.tx.grantRole(MINTER_ROLE, member.address, {
+        signer: member
+      })
+    ).to.not.emit(contract, 'RoleGranted');
+  });
+
+  it('Hands the administration of a role over', async () => {
+    const { contract, member, Alice } = await setup();
+
+    await contract.tx.grantRole(OPERATOR_ROLE, member.address);
+    await expect(contract.tx.setRoleAdmin(MINTER_ROLE, OPERATOR_ROLE))
+      .to.emit(contract, 'RoleAdminChanged')
+      .withArgs(MINTER_ROLE, ADMIN_ROLE, OPERATOR_ROLE);
+
+    await expect(
+      contract.tx.grantRole(MINTER_ROLE, Alice.address)
+    ).to.not.emit(contract, 'RoleGranted');
+    await expect(
+      contract.tx.grantRole(MINTER_ROLE, Alice.address, {
+        signer: member
+      })
+    ).to.emit(contract, 'RoleGranted');
+  });
+
+  it('Members renounce their roles', async () => {
+    const { contract, member } = await setup();
+
+    await contract.tx.grantRole(MINTER_ROLE, member.address);
+    await expect(
+      contract.tx.renounceRole(MINTER_ROLE, {
+        signer: member
+      })
+    )
+      .to.emit(contract, 'RoleRevoked')
+      .withArgs(MINTER_ROLE, member.address, member.address);
+    expect((await contract.query.memberCount(MINTER_ROLE)).output).to.equal(0);
+  });
+});
@@ -0,0 +1,1130 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use ink_lang as ink;
+
+#[ink::contract]
+mod amm_pair {
+    use erc20::{
+        BALANCE_OF_SELECTOR,
+        TRANSFER_FROM_SELECTOR,
+        TRANSFER_SELECTOR,
+    };
+    use ink_env::{
+        call::{
+            build_call,
+            utils::ReturnType,
+            ExecutionInput,
+            Selector,
+        },
+        DefaultEnvironment,
+        ReturnFlags,
+    };
+    use ink_storage::traits::{
+        PackedLayout,
+        SpreadLayout,
+    };
+    #[cfg(not(feature = "ink-as-dependency"))]
+    use ink_storage::{
+        collections::HashMap as StorageHashMap,
+        lazy::Lazy,
+    };
+
+    /// Selector of the oracle example's `price(token, max_age) -> Result<Balance>`.
+    const PRICE_SELECTOR: [u8; 4] = [0xD4, 0xBD, 0x7B, 0xC1];
+
+    /// The liquidity locked forever by the first provider, so the total supply
+    /// can never drop back to zero and make the price of a share arbitrary.
+    pub const MINIMUM_LIQUIDITY: Balance = 1000;
+
+    /// The fee taken from every swap, in parts of `FEE_DENOMINATOR`.
+    pub const FEE: Balance = 3;
+
+    /// The denominator of `FEE`.
+    pub const FEE_DENOMINATOR: Balance = 1000;
+
+    /// Limits how far swaps may move the price of a pair from an oracle's.
+    #[derive(
+        Debug,
+        Copy,
+        Clone,
+        PartialEq,
+        Eq,
+        scale::Encode,
+        scale::Decode,
+        SpreadLayout,
+        PackedLayout,
+    )]
+    #[cfg_attr(
+        feature = "std",
+        derive(::scale_info::TypeInfo, ::ink_storage::traits::StorageLayout)
+    )]
+    pub struct PriceGuard {
+        /// The contract prices are read from.
+        pub oracle: AccountId,
+        /// The percentage the price of the pair may differ from the oracle's.
+        pub max_deviation: u8,
+        /// The oldest price, in milliseconds, the pair accepts.
+        pub max_price_age: Timestamp,
+    }
+
+    /// A constant product market maker between two erc20 tokens.
+    ///
+    /// Liquidity providers deposit both tokens at the current ratio of the
+    /// reserves and get liquidity tokens for their share of the pair, which are
+    /// themselves erc20 tokens. Traders swap one token for the other at a price
+    /// keeping the product of the reserves constant, plus a fee which stays in
+    /// the pair and so goes to the providers.
+    ///
+    /// The pair pulls the tokens it receives with `transfer_from`, so callers
+    /// approve it first. Tokens transferred to the pair directly can be added to
+    /// the reserves with `sync` or taken by anyone with `skim`.
+    ///
+    /// A pair created with `with_price_guard` rejects swaps which would leave its
+    /// price further than `max_deviation` percent from the prices of an oracle,
+    /// such as the oracle example, so a single trade can not push it far from
+    /// the market price.
+    #[ink(storage)]
+    pub struct AmmPair {
+        /// The first token of the pair.
+        token_0: AccountId,
+        /// The second token of the pair.
+        token_1: AccountId,
+        /// The amount of the first token the pair accounts for.
+        reserve_0: Lazy<Balance>,
+        /// The amount of the second token the pair accounts for.
+        reserve_1: Lazy<Balance>,
+        /// Total supply of liquidity tokens.
+        total_supply: Lazy<Balance>,
+        /// Mapping from owner to number of owned liquidity tokens.
+        balances: StorageHashMap<AccountId, Balance>,
+        /// Mapping of the liquidity token amount which an account is allowed to
+        /// withdraw from another account.
+        allowances: StorageHashMap<(AccountId, AccountId), Balance>,
+        /// The limit swaps have to keep the price of the pair in, if any.
+        price_guard: Option<PriceGuard>,
+    }
+
+    /// Event emitted when a liquidity token transfer occurs.
+    #[ink(event)]
+    pub struct Transfer {
+        #[ink(topic)]
+        from: Option<AccountId>,
+        #[ink(topic)]
+        to: Option<AccountId>,
+        #[ink(topic)]
+        value: Balance,
+    }
+
+    /// Event emitted when an approval occurs that `spender` is allowed to withdraw
+    /// up to the amount of `value` liquidity tokens from `owner`.
+    #[ink(event)]
+    pub struct Approval {
+        #[ink(topic)]
+        owner: AccountId,
+        #[ink(topic)]
+        spender: AccountId,
+        #[ink(topic)]
+        value: Balance,
+    }
+
+    /// Event emitted when liquidity is added to the pair.
+    #[ink(event)]
+    pub struct Mint {
+        #[ink(topic)]
+        sender: AccountId,
+        amount_0: Balance,
+        amount_1: Balance,
+    }
+
+    /// Event emitted when liquidity is removed from the pair.
+    #[ink(event)]
+    pub struct Burn {
+        #[ink(topic)]
+        sender: AccountId,
+        amount_0: Balance,
+        amount_1: Balance,
+    }
+
+    /// Event emitted when one token of the pair is swapped for the other.
+    #[ink(event)]
+    pub struct Swap {
+        #[ink(topic)]
+        sender: AccountId,
+        #[ink(topic)]
+        token_in: AccountId,
+        amount_in: Balance,
+        amount_out: Balance,
+    }
+
+    /// Event emitted whenever the reserves change.
+    #[ink(event)]
+    pub struct Sync {
+        reserve_0: Balance,
+        reserve_1: Balance,
+    }
+
+    /// The pair error types.
+    #[derive(Debug, PartialEq, Eq, scale::Encode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum Error {
+        /// Returned if not enough balance to fulfill a request is available.
+        InsufficientBalance,
+        /// Returned if not enough allowance to fulfill a request is available.
+        InsufficientAllowance,
+        /// Returned if a token is not one of the pair.
+        InvalidToken,
+        /// Returned if an amount given or resulting from a call is zero.
+        InsufficientAmount,
+        /// Returned if the pair has no liquidity to swap against.
+        InsufficientLiquidity,
+        /// Returned if a call would give the caller less than its minimum.
+        SlippageExceeded,
+        /// Returned if a swap would lower the product of the reserves.
+        InvariantViolated,
+        /// Returned if an amount does not fit into a `Balance`.
+        Overflow,
+        /// Returned if the token balance of the pair could not be read.
+        TokenCallFailed,
+        /// Returned if tokens could not be transferred.
+        TransferFailed,
+        /// Returned if the oracle of the price guard has no recent price.
+        PriceUnavailable,
+        /// Returned if a swap would move the price of the pair too far from the
+        /// oracle's.
+        PriceDeviation,
+    }
+
+    /// The pair result type.
+    pub type Result<T> = core::result::Result<T, Error>;
+
+    impl AmmPair {
+        /// Creates a pair between `token_0` and `token_1` without any liquidity.
+        ///
+        /// # Panics
+        ///
+        /// If `token_0` and `token_1` are the same.
+        #[ink(constructor)]
+        pub fn new(token_0: AccountId, token_1: AccountId) -> Self {
+            assert!(token_0 != token_1, "identical tokens");
+            Self {
+                token_0,
+                token_1,
+                reserve_0: Lazy::new(0),
+                reserve_1: Lazy::new(0),
+                total_supply: Lazy::new(0),
+                balances: StorageHashMap::new(),
+                allowances: StorageHashMap::new(),
+                price_guard: None,
+            }
+        }
+
+        /// Creates a pair between `token_0` and `token_1` without any liquidity
+        /// whose price swaps keep within `max_deviation` percent of the prices
+        /// `oracle` reported in the last `max_price_age` milliseconds.
+        ///
+        /// # Panics
+        ///
+        /// If `token_0` and `token_1` are the same.
+        #[ink(constructor)]
+        pub fn with_price_guard(
+            token_0: AccountId,
+            token_1: AccountId,
+            oracle: AccountId,
+            max_deviation: u8,
+            max_price_age: Timestamp,
+        ) -> Self {
+            let mut pair = Self::new(token_0, token_1);
+            pair.price_guard = Some(PriceGuard {
+                oracle,
+                max_deviation,
+                max_price_age,
+            });
+            pair
+        }
+
+        /// Returns the two tokens of the pair.
+        #[ink(message)]
+        pub fn tokens(&self) -> (AccountId, AccountId) {
+            (self.token_0, self.token_1)
+        }
+
+        /// Returns the limit swaps have to keep the price of the pair in, if any.
+        #[ink(message)]
+        pub fn price_guard(&self) -> Option<PriceGuard> {
+            self.price_guard
+        }
+
+        /// Returns the amounts of the two tokens the pair accounts for.
+        #[ink(message)]
+        pub fn reserves(&self) -> (Balance, Balance) {
+            (*self.reserve_0, *self.reserve_1)
+        }
+
+        /// Returns the amount of the other token a swap of `amount_in` of
+        /// `token_in` would give at the current reserves.
+        ///
+        /// # Errors
+        ///
+        /// Returns `InvalidToken` error if `token_in` is not one of the pair.
+        ///
+        /// Returns `InsufficientAmount` error if `amount_in` is zero or too small
+        /// to give anything.
+        ///
+        /// Returns `InsufficientLiquidity` error if the pair has no liquidity.
+        ///
+        /// Returns `Overflow` error if `amount_in` is too large.
+        #[ink(message)]
+        pub fn amount_out(
+            &self,
+            token_in: AccountId,
+            amount_in: Balance,
+        ) -> Result<Balance> {
+            let (reserve_in, reserve_out) = self.directed_reserves(token_in)?;
+            if amount_in == 0 {
+                return Err(Error::InsufficientAmount)
+            }
+            if reserve_in == 0 || reserve_out == 0 {
+                return Err(Error::InsufficientLiquidity)
+            }
+            let amount_in_with_fee = amount_in
+                .checked_mul(FEE_DENOMINATOR - FEE)
+                .ok_or(Error::Overflow)?;
+            let denominator = reserve_in
+                .checked_mul(FEE_DENOMINATOR)
+                .and_then(|reserve_in| reserve_in.checked_add(amount_in_with_fee))
+                .ok_or(Error::Overflow)?;
+            let amount_out = mul_div(amount_in_with_fee, reserve_out, denominator)
+                .ok_or(Error::Overflow)?;
+            if amount_out == 0 {
+                return Err(Error::InsufficientAmount)
+            }
+            Ok(amount_out)
+        }
+
+        /// Deposits both tokens of the caller at the current ratio of the
+        /// reserves and returns the amounts deposited and the liquidity tokens
+        /// minted for them.
+        ///
+        /// At most `amount_0_desired` and `amount_1_desired` are deposited, the
+        /// first deposit sets the ratio. On success a `Mint` and a `Sync` event
+        /// are emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `InsufficientAmount` error if no liquidity tokens would be
+        /// minted.
+        ///
+        /// Returns `SlippageExceeded` error if less than `min_liquidity` liquidity
+        /// tokens would be minted.
+        ///
+        /// Returns `Overflow` error if the reserves would not fit into a
+        /// `Balance`.
+        ///
+        /// Returns `TransferFailed` error, reverting all changes, if the tokens
+        /// could not be pulled from the caller.
+        #[ink(message)]
+        pub fn add_liquidity(
+            &mut self,
+            amount_0_desired: Balance,
+            amount_1_desired: Balance,
+            min_liquidity: Balance,
+        ) -> Result<(Balance, Balance, Balance)> {
+            let provider = self.env().caller();
+            let (amount_0, amount_1, liquidity) = self.mint_liquidity(
+                provider,
+                amount_0_desired,
+                amount_1_desired,
+                min_liquidity,
+            )?;
+            self.flush();
+            if !self.token_transfer_from(self.token_0, provider, amount_0)
+                || !self.token_transfer_from(self.token_1, provider, amount_1)
+            {
+                Self::revert(Error::TransferFailed)
+            }
+            Ok((amount_0, amount_1, liquidity))
+        }
+
+        /// Burns `liquidity` liquidity tokens of the caller and sends it its
+        /// share of both reserves, returning the two amounts.
+        ///
+        /// On success a `Burn` and a `Sync` event are emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `InsufficientBalance` error if the caller owns less than
+        /// `liquidity` liquidity tokens.
+        ///
+        /// Returns `InsufficientAmount` error if the caller would get nothing of
+        /// one of the tokens.
+        ///
+        /// Returns `SlippageExceeded` error if the caller would get less than
+        /// `min_amount_0` or `min_amount_1`.
+        ///
+        /// Returns `TransferFailed` error, reverting all changes, if the tokens
+        /// could not be sent.
+        #[ink(message)]
+        pub fn remove_liquidity(
+            &mut self,
+            liquidity: Balance,
+            min_amount_0: Balance,
+            min_amount_1: Balance,
+        ) -> Result<(Balance, Balance)> {
+            let provider = self.env().caller();
+            let (amount_0, amount_1) =
+                self.burn_liquidity(provider, liquidity, min_amount_0, min_amount_1)?;
+            self.flush();
+            if !self.token_transfer(self.token_0, provider, amount_0)
+                || !self.token_transfer(self.token_1, provider, amount_1)
+            {
+                Self::revert(Error::TransferFailed)
+            }
+            Ok((amount_0, amount_1))
+        }
+
+        /// Swaps `amount_in` of `token_in` of the caller for the other token and
+        /// returns the amount of it sent to the caller.
+        ///
+        /// On success a `Swap` and a `Sync` event are emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns the errors of `amount_out`.
+        ///
+        /// Returns `SlippageExceeded` error if the caller would get less than
+        /// `min_amount_out`.
+        ///
+        /// Returns `InvariantViolated` error if the swap would lower the product
+        /// of the reserves after taking the fee.
+        ///
+        /// Returns `PriceUnavailable` error if the pair has a price guard whose
+        /// oracle has no recent price for one of the tokens.
+        ///
+        /// Returns `PriceDeviation` error if the pair has a price guard and the
+        /// swap would move its price too far from the oracle's.
+        ///
+        /// Returns `TransferFailed` error, reverting all changes, if the tokens
+        /// could not be transferred.
+        #[ink(message)]
+        pub fn swap(
+            &mut self,
+            token_in: AccountId,
+            amount_in: Balance,
+            min_amount_out: Balance,
+        ) -> Result<Balance> {
+            let trader = self.env().caller();
+            let amount_out =
+                self.apply_swap(trader, token_in, amount_in, min_amount_out)?;
+            let token_out = if token_in == self.token_0 {
+                self.token_1
+            } else {
+                self.token_0
+            };
+            self.flush();
+            if !self.token_transfer_from(token_in, trader, amount_in)
+                || !self.token_transfer(token_out, trader, amount_out)
+            {
+                Self::revert(Error::TransferFailed)
+            }
+            Ok(amount_out)
+        }
+
+        /// Sets the reserves to the balances of the pair, adding tokens which
+        /// were transferred to it directly.
+        ///
+        /// On success a `Sync` event is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `TokenCallFailed` error if a balance of the pair could not be
+        /// read.
+        #[ink(message)]
+        pub fn sync(&mut self) -> Result<()> {
+            let balance_0 = self.token_balance(self.token_0)?;
+            let balance_1 = self.token_balance(self.token_1)?;
+            self.set_reserves(balance_0, balance_1);
+            Ok(())
+        }
+
+        /// Sends the tokens the pair holds above its reserves to `to` and returns
+        /// their amounts.
+        ///
+        /// # Errors
+        ///
+        /// Returns `TokenCallFailed` error if a balance of the pair could not be
+        /// read.
+        ///
+        /// Returns `TransferFailed` error, reverting all changes, if the tokens
+        /// could not be sent.
+        #[ink(message)]
+        pub fn skim(&mut self, to: AccountId) -> Result<(Balance, Balance)> {
+            let (reserve_0, reserve_1) = self.reserves();
+            let amount_0 = self.token_balance(self.token_0)?.saturating_sub(reserve_0);
+            let amount_1 = self.token_balance(self.token_1)?.saturating_sub(reserve_1);
+            if (amount_0 > 0 && !self.token_transfer(self.token_0, to, amount_0))
+                || (amount_1 > 0 && !self.token_transfer(self.token_1, to, amount_1))
+            {
+                Self::revert(Error::TransferFailed)
+            }
+            Ok((amount_0, amount_1))
+        }
+
+        /// Returns the total liquidity token supply.
+        #[ink(message)]
+        pub fn total_supply(&self) -> Balance {
+            *self.total_supply
+        }
+
+        /// Returns the liquidity token balance for the specified `owner`.
+        ///
+        /// Returns `0` if the account is non-existent.
+        #[ink(message)]
+        pub fn balance_of(&self, owner: AccountId) -> Balance {
+            self.balances.get(&owner).copied().unwrap_or(0)
+        }
+
+        /// Returns the amount which `spender` is still allowed to withdraw from `owner`.
+        ///
+        /// Returns `0` if no allowance has been set.
+        #[ink(message)]
+        pub fn allowance(&self, owner: AccountId, spender: AccountId) -> Balance {
+            self.allowances.get(&(owner, spender)).copied().unwrap_or(0)
+        }
+
+        /// Transfers `value` amount of liquidity tokens from the caller's account to
+        /// account `to`.
+        ///
+        /// On success a `Transfer` event is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `InsufficientBalance` error if there are not enough tokens on
+        /// the caller's account balance.
+        #[ink(message)]
+        pub fn transfer(&mut self, to: AccountId, value: Balance) -> Result<()> {
+            let from = self.env().caller();
+            self.transfer_from_to(from, to, value)
+        }
+
+        /// Allows `spender` to withdraw from the caller's account multiple times, up to
+        /// the `value` amount.
+        ///
+        /// If this function is called again it overwrites the current allowance with `value`.
+        ///
+        /// An `Approval` event is emitted.
+        #[ink(message)]
+        pub fn approve(&mut self, spender: AccountId, value: Balance) -> Result<()> {
+            let owner = self.env().caller();
+            self.allowances.insert((owner, spender), value);
+            self.env().emit_event(Approval {
+                owner,
+                spender,
+                value,
+            });
+            Ok(())
+        }
+
+        /// Transfers `value` liquidity tokens on the behalf of `from` to the account
+        /// `to`.
+        ///
+        /// On success a `Transfer` event is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `InsufficientAllowance` error if there are not enough tokens allowed
+        /// for the caller to withdraw from `from`.
+        ///
+        /// Returns `InsufficientBalance` error if there are not enough tokens on
+        /// the account balance of `from`.
+        #[ink(message)]
+        pub fn transfer_from(
+            &mut self,
+            from: AccountId,
+            to: AccountId,
+            value: Balance,
+        ) -> Result<()> {
+            let caller = self.env().caller();
+            let allowance = self.allowance(from, caller);
+            if allowance < value {
+                return Err(Error::InsufficientAllowance)
+            }
+            self.transfer_from_to(from, to, value)?;
+            self.allowances.insert((from, caller), allowance - value);
+            Ok(())
+        }
+
+        /// Records a deposit of `provider` and mints its liquidity tokens,
+        /// returning the amounts to pull and the liquidity tokens minted.
+        fn mint_liquidity(
+            &mut self,
+            provider: AccountId,
+            amount_0_desired: Balance,
+            amount_1_desired: Balance,
+            min_liquidity: Balance,
+        ) -> Result<(Balance, Balance, Balance)> {
+            let (reserve_0, reserve_1) = self.reserves();
+            let total_supply = *self.total_supply;
+            let (amount_0, amount_1, liquidity) = if total_supply == 0 {
+                let liquidity = sqrt_product(amount_0_desired, amount_1_desired)
+                    .saturating_sub(MINIMUM_LIQUIDITY);
+                (amount_0_desired, amount_1_desired, liquidity)
+            } else {
+                // Deposit as much as possible at the ratio of the reserves.
+                let (amount_0, amount_1) =
+                    match mul_div(amount_0_desired, reserve_1, reserve_0) {
+                        Some(amount_1) if amount_1 <= amount_1_desired => {
+                            (amount_0_desired, amount_1)
+                        }
+                        _ => {
+                            let amount_0 =
+                                mul_div(amount_1_desired, reserve_0, reserve_1)
+                                    .ok_or(Error::Overflow)?;
+                            (amount_0, amount_1_desired)
+                        }
+                    };
+                let liquidity_0 =
+                    mul_div(amount_0, total_supply, reserve_0).ok_or(Error::Overflow)?;
+                let liquidity_1 =
+                    mul_div(amount_1, total_supply, reserve_1).ok_or(Error::Overflow)?;
+                (amount_0, amount_1, liquidity_0.min(liquidity_1))
+            };
+            if liquidity == 0 {
+                return Err(Error::InsufficientAmount)
+            }
+            if liquidity < min_liquidity {
+                return Err(Error::SlippageExceeded)
+            }
+            let reserve_0 = reserve_0.checked_add(amount_0).ok_or(Error::Overflow)?;
+            let reserve_1 = reserve_1.checked_add(amount_1).ok_or(Error::Overflow)?;
+            if total_supply == 0 {
+                self.mint(AccountId::from([0x00; 32]), MINIMUM_LIQUIDITY);
+            }
+            self.mint(provider, liquidity);
+            self.env().emit_event(Mint {
+                sender: provider,
+                amount_0,
+                amount_1,
+            });
+            self.set_reserves(reserve_0, reserve_1);
+            Ok((amount_0, amount_1, liquidity))
+        }
+
+        /// Burns `liquidity` liquidity tokens of `provider`, returning the amounts
+        /// of both tokens to send to it.
+        fn burn_liquidity(
+            &mut self,
+            provider: AccountId,
+            liquidity: Balance,
+            min_amount_0: Balance,
+            min_amount_1: Balance,
+        ) -> Result<(Balance, Balance)> {
+            let balance = self.balance_of(provider);
+            if balance < liquidity {
+                return Err(Error::InsufficientBalance)
+            }
+            let (reserve_0, reserve_1) = self.reserves();
+            let total_supply = *self.total_supply;
+            // `liquidity` is at most the total supply, so the shares of the reserves
+            // fit into a `Balance`.
+            let amount_0 =
+                mul_div(liquidity, reserve_0, total_supply).ok_or(Error::Overflow)?;
+            let amount_1 =
+                mul_div(liquidity, reserve_1, total_supply).ok_or(Error::Overflow)?;
+            if amount_0 == 0 || amount_1 == 0 {
+                return Err(Error::InsufficientAmount)
+            }
+            if amount_0 < min_amount_0 || amount_1 < min_amount_1 {
+                return Err(Error::SlippageExceeded)
+            }
+            self.balances.insert(provider, balance - liquidity);
+            *self.total_supply -= liquidity;
+            self.env().emit_event(Transfer {
+                from: Some(provider),
+                to: None,
+                value: liquidity,
+            });
+            self.env().emit_event(Burn {
+                sender: provider,
+                amount_0,
+                amount_1,
+            });
+            self.set_reserves(reserve_0 - amount_0, reserve_1 - amount_1);
+            Ok((amount_0, amount_1))
+        }
+
+        /// Records a swap of `amount_in` of `token_in` by `trader`, returning the
+        /// amount of the other token to send to it.
+        fn apply_swap(
+            &mut self,
+            trader: AccountId,
+            token_in: AccountId,
+            amount_in: Balance,
+            min_amount_out: Balance,
+        ) -> Result<Balance> {
+            let amount_out = self.amount_out(token_in, amount_in)?;
+            if amount_out < min_amount_out {
+                return Err(Error::SlippageExceeded)
+            }
+            let (reserve_in, reserve_out) = self.directed_reserves(token_in)?;
+            let new_reserve_in =
+                reserve_in.checked_add(amount_in).ok_or(Error::Overflow)?;
+            let new_reserve_out = reserve_out - amount_out;
+            // The price already keeps the product of the reserves, check it anyway
+            // so a rounding mistake can never drain the pair.
+            let adjusted_in = new_reserve_in
+                .checked_mul(FEE_DENOMINATOR)
+                .ok_or(Error::Overflow)?
+                - amount_in * FEE;
+            let adjusted_reserve_in = reserve_in
+                .checked_mul(FEE_DENOMINATOR)
+                .ok_or(Error::Overflow)?;
+            if mul_wide(adjusted_in, new_reserve_out)
+                < mul_wide(adjusted_reserve_in, reserve_out)
+            {
+                return Err(Error::InvariantViolated)
+            }
+            let (reserve_0, reserve_1) = if token_in == self.token_0 {
+                (new_reserve_in, new_reserve_out)
+            } else {
+                (new_reserve_out, new_reserve_in)
+            };
+            self.ensure_oracle_price(reserve_0, reserve_1)?;
+            self.set_reserves(reserve_0, reserve_1);
+            self.env().emit_event(Swap {
+                sender: trader,
+                token_in,
+                amount_in,
+                amount_out,
+            });
+            Ok(amount_out)
+        }
+
+        /// Returns `PriceDeviation` error if the pair has a price guard and
+        /// `reserve_1` is worth more than `max_deviation` percent more or less
+        /// than `reserve_0` at the oracle's prices.
+        fn ensure_oracle_price(
+            &self,
+            reserve_0: Balance,
+            reserve_1: Balance,
+        ) -> Result<()> {
+            let guard = match self.price_guard {
+                Some(guard) => guard,
+                None => return Ok(()),
+            };
+            let price_0 = self.price(guard, self.token_0)?;
+            let price_1 = self.price(guard, self.token_1)?;
+            if price_0 == 0 {
+                return Err(Error::PriceUnavailable)
+            }
+            // `value_1` differs from `reserve_0` as much as the price of the pair
+            // from the oracle's.
+            let value_1 = mul_div(reserve_1, price_1, price_0).ok_or(Error::Overflow)?;
+            let (low, high) = if value_1 < reserve_0 {
+                (value_1, reserve_0)
+            } else {
+                (reserve_0, value_1)
+            };
+            let limit = mul_div(low, 100 + Balance::from(guard.max_deviation), 100)
+                .ok_or(Error::Overflow)?;
+            if high > limit {
+                return Err(Error::PriceDeviation)
+            }
+            Ok(())
+        }
+
+        /// Returns the reserves of `token_in` and of the other token.
+        fn directed_reserves(&self, token_in: AccountId) -> Result<(Balance, Balance)> {
+            let (reserve_0, reserve_1) = self.reserves();
+            if token_in == self.token_0 {
+                Ok((reserve_0, reserve_1))
+            } else if token_in == self.token_1 {
+                Ok((reserve_1, reserve_0))
+            } else {
+                Err(Error::InvalidToken)
+            }
+        }
+
+        /// Sets the reserves and emits a `Sync` event.
+        fn set_reserves(&mut self, reserve_0: Balance, reserve_1: Balance) {
+            *self.reserve_0 = reserve_0;
+            *self.reserve_1 = reserve_1;
+            self.env().emit_event(Sync {
+                reserve_0,
+                reserve_1,
+            });
+        }
+
+        /// Mints `value` liquidity tokens to `to`.
+        fn mint(&mut self, to: AccountId, value: Balance) {
+            let to_balance = self.balance_of(to);
+            self.balances.insert(to, to_balance + value);
+            *self.total_supply += value;
+            self.env().emit_event(Transfer {
+                from: None,
+                to: Some(to),
+                value,
+            });
+        }
+
+        /// Transfers `value` amount of liquidity tokens from the account `from` to
+        /// account `to`.
+        ///
+        /// On success a `Transfer` event is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `InsufficientBalance` error if there are not enough tokens on
+        /// the account balance of `from`.
+        fn transfer_from_to(
+            &mut self,
+            from: AccountId,
+            to: AccountId,
+            value: Balance,
+        ) -> Result<()> {
+            let from_balance = self.balance_of(from);
+            if from_balance < value {
+                return Err(Error::InsufficientBalance)
+            }
+            self.balances.insert(from, from_balance - value);
+            let to_balance = self.balance_of(to);
+            self.balances.insert(to, to_balance + value);
+            self.env().emit_event(Transfer {
+                from: Some(from),
+                to: Some(to),
+                value,
+            });
+            Ok(())
+        }
+
+        /// Writes the contract storage.
+        fn flush(&self) {
+            // ink! writes the contract storage back only when a message returns.
+            // Write it now so the token contracts, which the deployer picked, already
+            // see the new reserves if they call back into the pair.
+            ink_storage::traits::push_spread_root::<Self>(
+                self,
+                &ink_primitives::Key::from([0x00; 32]),
+            );
+        }
+
+        /// Returns the balance of the pair in `token`.
+        fn token_balance(&self, token: AccountId) -> Result<Balance> {
+            build_call::<DefaultEnvironment>()
+                .callee(token)
+                .gas_limit(0)
+                .transferred_value(0)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(BALANCE_OF_SELECTOR))
+                        .push_arg(self.env().account_id()),
+                )
+                .returns::<ReturnType<Balance>>()
+                .fire()
+                .map_err(|_| Error::TokenCallFailed)
+        }
+
+        /// Returns the price of `token` from the oracle of `guard`.
+        fn price(&self, guard: PriceGuard, token: AccountId) -> Result<Balance> {
+            let result = build_call::<DefaultEnvironment>()
+                .callee(guard.oracle)
+                .gas_limit(0)
+                .transferred_value(0)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(PRICE_SELECTOR))
+                        .push_arg(token)
+                        .push_arg(guard.max_price_age),
+                )
+                .returns::<ReturnType<core::result::Result<Balance, u8>>>()
+                .fire();
+            match result {
+                Ok(Ok(price)) => Ok(price),
+                _ => Err(Error::PriceUnavailable),
+            }
+        }
+
+        /// Transfers `value` of `token` from the pair to `to` and returns whether
+        /// that succeeded.
+        fn token_transfer(
+            &self,
+            token: AccountId,
+            to: AccountId,
+            value: Balance,
+        ) -> bool {
+            let result = build_call::<DefaultEnvironment>()
+                .callee(token)
+                .gas_limit(0)
+                .transferred_value(0)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(TRANSFER_SELECTOR))
+                        .push_arg(to)
+                        .push_arg(value),
+                )
+                .returns::<ReturnType<core::result::Result<(), u8>>>()
+                .fire();
+            matches!(result, Ok(Ok(())))
+        }
+
+        /// Transfers `value` of `token` from `from` to the pair and returns whether
+        /// that succeeded.
+        fn token_transfer_from(
+            &self,
+            token: AccountId,
+            from: AccountId,
+            value: Balance,
+        ) -> bool {
+            let result = build_call::<DefaultEnvironment>()
+                .callee(token)
+                .gas_limit(0)
+                .transferred_value(0)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(TRANSFER_FROM_SELECTOR))
+                        .push_arg(from)
+                        .push_arg(self.env().account_id())
+                        .push_arg(value),
+                )
+                .returns::<ReturnType<core::result::Result<(), u8>>>()
+                .fire();
+            matches!(result, Ok(Ok(())))
+        }
+
+        /// Ends the current call with `error`, so the reserves and liquidity shares
+        /// stay as they were when moving the tokens fails.
+        fn revert(error: Error) -> ! {
+            ink_env::return_value::<Result<()>>(
+                ReturnFlags::default().set_reverted(true),
+                &Err(error),
+            )
+        }
+    }
+
+    /// Returns the full product of `a` and `b` as its high and low half.
+    fn mul_wide(a: u128, b: u128) -> (u128, u128) {
+        const MASK: u128 = u64::MAX as u128;
+        let (a_high, a_low) = (a >> 64, a & MASK);
+        let (b_high, b_low) = (b >> 64, b & MASK);
+        let (middle, middle_carry) = (a_high * b_low).overflowing_add(a_low * b_high);
+        let (low, low_carry) = (a_low * b_low).overflowing_add(middle << 64);
+        let high = a_high * b_high
+            + (middle >> 64)
+            + ((middle_carry as u128) << 64)
+            + low_carry as u128;
+        (high, low)
+    }
+
+    /// Returns `(high << 128 | low) / divisor` rounded down, or `None` if
+    /// `divisor` is zero or the quotient does not fit into a `u128`.
+    fn div_wide((mut high, mut low): (u128, u128), divisor: u128) -> Option<u128> {
+        if high >= divisor {
+            return None
+        }
+        // Long division bit by bit, `high` holds the remainder.
+        let mut quotient = 0;
+        for _ in 0..128 {
+            let carry = high >> 127;
+            high = (high << 1) | (low >> 127);
+            low <<= 1;
+            quotient <<= 1;
+            if carry == 1 || high >= divisor {
+                high = high.wrapping_sub(divisor);
+                quotient |= 1;
+            }
+        }
+        Some(quotient)
+    }
+
+    /// Returns `a * b / divisor` rounded down without overflowing in between, or
+    /// `None` if `divisor` is zero or the result does not fit into a `u128`.
+    fn mul_div(a: u128, b: u128, divisor: u128) -> Option<u128> {
+        div_wide(mul_wide(a, b), divisor)
+    }
+
+    /// Returns the square root of `a * b` rounded down.
+    fn sqrt_product(a: u128, b: u128) -> u128 {
+        if a == 0 || b == 0 {
+            return 0
+        }
+        // Newton's method, starting above the root so every step goes down.
+        let product = mul_wide(a, b);
+        let mut root = a.max(b);
+        loop {
+            // `root` is at least the square root, so the quotient fits.
+            let quotient = div_wide(product, root).expect("root above square root");
+            let next = root / 2 + quotient / 2 + (root & quotient & 1);
+            if next >= root {
+                return root
+            }
+            root = next;
+        }
+    }
+
+    /// Unit tests.
+    ///
+    /// The token transfers of adding and removing liquidity and of swapping are
+    /// left to the redspot tests. The bookkeeping before them is checked here.
+    #[cfg(test)]
+    mod tests {
+        /// Imports all the definitions from the outer scope so we can use them here.
+        use super::*;
+
+        use ink_lang as ink;
+
+        /// Returns an empty pair between two made up tokens.
+        fn pair() -> AmmPair {
+            AmmPair::new(AccountId::from([0x07; 32]), AccountId::from([0x08; 32]))
+        }
+
+        #[test]
+        fn mul_div_handles_wide_products() {
+            assert_eq!(mul_wide(u128::MAX, u128::MAX), (u128::MAX - 1, 1));
+            assert_eq!(mul_div(u128::MAX, u128::MAX, u128::MAX), Some(u128::MAX));
+            assert_eq!(mul_div(u128::MAX, 3, 4), Some(u128::MAX / 4 * 3 + 2));
+            assert_eq!(mul_div(7, 5, 3), Some(11));
+            assert_eq!(mul_div(u128::MAX, 2, 1), None);
+            assert_eq!(mul_div(1, 1, 0), None);
+        }
+
+        #[test]
+        fn sqrt_product_rounds_down() {
+            assert_eq!(sqrt_product(0, 5), 0);
+            assert_eq!(sqrt_product(4, 9), 6);
+            assert_eq!(sqrt_product(2, 4), 2);
+            assert_eq!(sqrt_product(u128::MAX, u128::MAX), u128::MAX);
+            assert_eq!(sqrt_product(u128::MAX, 1), u64::MAX as u128);
+        }
+
+        #[ink::test]
+        fn with_price_guard_works() {
+            let guarded = AmmPair::with_price_guard(
+                AccountId::from([0x07; 32]),
+                AccountId::from([0x08; 32]),
+                AccountId::from([0x09; 32]),
+                10,
+                60_000,
+            );
+
+            assert_eq!(
+                guarded.price_guard(),
+                Some(PriceGuard {
+                    oracle: AccountId::from([0x09; 32]),
+                    max_deviation: 10,
+                    max_price_age: 60_000,
+                })
+            );
+            assert_eq!(guarded.reserves(), (0, 0));
+            assert_eq!(pair().price_guard(), None);
+        }
+
+        #[ink::test]
+        #[should_panic(expected = "identical tokens")]
+        fn new_rejects_identical_tokens() {
+            AmmPair::new(AccountId::from([0x07; 32]), AccountId::from([0x07; 32]));
+        }
+
+        #[ink::test]
+        fn first_deposit_locks_minimum_liquidity() {
+            let mut pair = pair();
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            assert_eq!(
+                pair.mint_liquidity(accounts.bob, 1000, 1000, 0),
+                Err(Error::InsufficientAmount)
+            );
+            assert_eq!(
+                pair.mint_liquidity(accounts.bob, 4000, 9000, 0),
+                Ok((4000, 9000, 5000))
+            );
+            assert_eq!(pair.reserves(), (4000, 9000));
+            assert_eq!(pair.total_supply(), 6000);
+            assert_eq!(pair.balance_of(accounts.bob), 5000);
+            assert_eq!(pair.balance_of(AccountId::from([0x00; 32])), 1000);
+        }
+
+        #[ink::test]
+        fn deposits_follow_reserve_ratio() {
+            let mut pair = pair();
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            pair.mint_liquidity(accounts.bob, 4000, 9000, 0)
+                .expect("Cannot add liquidity");
+            assert_eq!(
+                pair.mint_liquidity(accounts.charlie, 400, 2000, 0),
+                Ok((400, 900, 600))
+            );
+            assert_eq!(
+                pair.mint_liquidity(accounts.charlie, 4000, 900, 0),
+                Ok((400, 900, 600))
+            );
+            assert_eq!(
+                pair.mint_liquidity(accounts.charlie, 400, 900, 601),
+                Err(Error::SlippageExceeded)
+            );
+            assert_eq!(pair.reserves(), (4800, 10800));
+            assert_eq!(pair.balance_of(accounts.charlie), 1200);
+        }
+
+        #[ink::test]
+        fn burn_returns_share_of_reserves() {
+            let mut pair = pair();
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            pair.mint_liquidity(accounts.bob, 4000, 9000, 0)
+                .expect("Cannot add liquidity");
+            assert_eq!(
+                pair.burn_liquidity(accounts.bob, 5001, 0, 0),
+                Err(Error::InsufficientBalance)
+            );
+            assert_eq!(
+                pair.burn_liquidity(accounts.bob, 3000, 2001, 0),
+                Err(Error::SlippageExceeded)
+            );
+            assert_eq!(
+                pair.burn_liquidity(accounts.bob, 3000, 0, 0),
+                Ok((2000, 4500))
+            );
+            assert_eq!(pair.reserves(), (2000, 4500));
+            assert_eq!(pair.total_supply(), 3000);
+            assert_eq!(pair.balance_of(accounts.bob), 2000);
+        }
+
+        #[ink::test]
+        fn swaps_keep_the_product_of_reserves() {
+            let mut pair = pair();
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let (token_0, token_1) = pair.tokens();
+
+            assert_eq!(
+                pair.amount_out(token_0, 100),
+                Err(Error::InsufficientLiquidity)
+            );
+            pair.mint_liquidity(accounts.bob, 10000, 10000, 0)
+                .expect("Cannot add liquidity");
+            assert_eq!(
+                pair.amount_out(AccountId::from([0x09; 32]), 100),
+                Err(Error::InvalidToken)
+            );
+            assert_eq!(pair.amount_out(token_0, 0), Err(Error::InsufficientAmount));
+            // 997000 * 10000 / (10000 * 1000 + 997000) rounded down.
+            assert_eq!(pair.amount_out(token_0, 1000), Ok(906));
+            assert_eq!(
+                pair.apply_swap(accounts.charlie, token_0, 1000, 907),
+                Err(Error::SlippageExceeded)
+            );
+            assert_eq!(
+                pair.apply_swap(accounts.charlie, token_0, 1000, 906),
+                Ok(906)
+            );
+            assert_eq!(pair.reserves(), (11000, 9094));
+
+            assert_eq!(pair.amount_out(token_1, 906), Ok(993));
+            assert_eq!(pair.apply_swap(accounts.charlie, token_1, 906, 0), Ok(993));
+            assert_eq!(pair.reserves(), (10007, 10000));
+        }
+    }
+}
\ No newline at end of file
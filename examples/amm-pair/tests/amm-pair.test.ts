@@ -0,0 +1,149 @@
+import BN from 'bn.js';
+import { expect } from 'chai';
+import { patract, network } from 'redspot';
+
+const { getContractFactory, getRandomSigner } = patract;
+
+const { api, getSigners } = network;
+
+describe('AMM PAIR', () => {
+  after(() => {
+    return api.disconnect();
+  });
+
+  async function setup() {
+    const one = new BN(10).pow(new BN(api.registry.chainDecimals[0]));
+    const signers = await getSigners();
+    const Alice = signers[0];
+    const sender = await getRandomSigner(Alice, one.muln(10000));
+    const trader = await getRandomSigner(Alice, one.muln(10000));
+    const tokenFactory = await getContractFactory('erc20', sender);
+    const token0 = await tokenFactory.deploy(
+      'new',
+      '1000000',
+      'Redspot',
+      'RDS',
+      10
+    );
+    const token1 = await tokenFactory.deploy(
+      'new',
+      '1000000',
+      'Patract',
+      'PAT',
+      10
+    );
+    const contractFactory = await getContractFactory('amm_pair', sender);
+    const contract = await contractFactory.deploy(
+      'new',
+      token0.address,
+      token1.address
+    );
+    await token0.tx.approve(contract.address, 100000);
+    await token1.tx.approve(contract.address, 100000);
+    await token0.tx.transfer(trader.address, 10000);
+    await token0.tx.approve(contract.address, 10000, { signer: trader });
+
+    return {
+      sender,
+      trader,
+      token0,
+      token1,
+      contractFactory,
+      contract,
+      Alice,
+      one
+    };
+  }
+
+  it('Adds liquidity', async () => {
+    const { contract, sender } = await setup();
+
+    await expect(contract.tx.addLiquidity(10000, 10000, 0))
+      .to.emit(contract, 'Mint')
+      .withArgs(sender.address, 10000, 10000);
+    expect((await contract.query.balanceOf(sender.address)).output).to.equal(
+      9000
+    );
+    expect((await contract.query.totalSupply()).output).to.equal(10000);
+  });
+
+  it('Swaps at the constant product price', async () => {
+    const { contract, token0, token1, trader } = await setup();
+
+    await contract.tx.addLiquidity(10000, 10000, 0);
+
+    await expect(
+      contract.tx.swap(token0.address, 1000, 907, { signer: trader })
+    ).to.not.emit(contract, 'Swap');
+    await expect(() =>
+      contract.tx.swap(token0.address, 1000, 906, { signer: trader })
+    ).to.changeTokenBalance(token1, trader, 906);
+    await expect(
+      contract.tx.swap(token0.address, 1000, 0, { signer: trader })
+    )
+      .to.emit(contract, 'Swap')
+      .withArgs(trader.address, token0.address, 1000, 755);
+  });
+
+  it('Keeps the price near the oracle price', async () => {
+    const { contractFactory, token0, token1, sender, trader } = await setup();
+
+    const oracleFactory = await getContractFactory('oracle', sender);
+    const oracle = await oracleFactory.deploy('new', [sender.address]);
+    await oracle.tx.setPrices([
+      [token0.address, 1],
+      [token1.address, 1]
+    ]);
+    const guarded = await contractFactory.deploy(
+      'withPriceGuard',
+      token0.address,
+      token1.address,
+      oracle.address,
+      10,
+      600000
+    );
+    await token0.tx.approve(guarded.address, 100000);
+    await token1.tx.approve(guarded.address, 100000);
+    await token0.tx.approve(guarded.address, 10000, { signer: trader });
+    await guarded.tx.addLiquidity(10000, 10000, 0);
+
+    // Leaves the price of the pair 4% off the oracle's.
+    await expect(
+      guarded.tx.swap(token0.address, 200, 0, { signer: trader })
+    ).to.emit(guarded, 'Swap');
+    // Would leave it 25% off.
+    await expect(
+      guarded.tx.swap(token0.address, 1000, 0, { signer: trader })
+    ).to.not.emit(guarded, 'Swap');
+  });
+
+  it('Syncs the reserves to the token balances', async () => {
+    const { contract, token0, token1 } = await setup();
+
+    await contract.tx.addLiquidity(10000, 10000, 0);
+    await token0.tx.transfer(contract.address, 500);
+    await token1.tx.transfer(contract.address, 300);
+
+    await expect(contract.tx.sync())
+      .to.emit(contract, 'Sync')
+      .withArgs(10500, 10300);
+    expect((await contract.query.reserves()).output?.toJSON()).to.deep.equal([
+      10500,
+      10300
+    ]);
+  });
+
+  it('Removes liquidity', async () => {
+    const { contract, token0, sender } = await setup();
+
+    await contract.tx.addLiquidity(10000, 10000, 0);
+
+    await expect(() =>
+      contract.tx.removeLiquidity(4500, 0, 0)
+    ).to.changeTokenBalance(token0, sender, 4500);
+    await expect(contract.tx.removeLiquidity(4501, 0, 0)).to.not.emit(
+      contract,
+      'Burn'
+    );
+  });
+});
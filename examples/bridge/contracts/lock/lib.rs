@@ -0,0 +1,215 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use ink_lang as ink;
+
+#[ink::contract]
+mod bridge_lock {
+    use erc20::TRANSFER_FROM_SELECTOR;
+    use ink_env::{
+        call::{
+            build_call,
+            utils::ReturnType,
+            ExecutionInput,
+            Selector,
+        },
+        DefaultEnvironment,
+        ReturnFlags,
+    };
+    #[cfg(not(feature = "ink-as-dependency"))]
+    use ink_storage::lazy::Lazy;
+
+    /// The source side of a token bridge, locking an erc20 token to be minted
+    /// on the destination side.
+    ///
+    /// Every lock is numbered with a nonce and announced with a `Locked`
+    /// event. Relayers watch these events and sign a mint request for each
+    /// of them, which the bridge mint contract on the destination side
+    /// accepts once enough relayers signed it. The nonce tells locks of the
+    /// same amount to the same recipient apart, so each is minted only once.
+    ///
+    /// The locked tokens stay in the contract, backing the tokens minted on
+    /// the destination side.
+    #[ink(storage)]
+    pub struct BridgeLock {
+        /// The erc20 token locked.
+        token: AccountId,
+        /// The nonce of the next lock.
+        next_nonce: Lazy<u64>,
+        /// The tokens locked so far.
+        total_locked: Lazy<Balance>,
+    }
+
+    /// Event emitted when tokens are locked to be minted for `recipient` on
+    /// the destination side.
+    #[ink(event)]
+    pub struct Locked {
+        nonce: u64,
+        #[ink(topic)]
+        sender: AccountId,
+        #[ink(topic)]
+        recipient: AccountId,
+        amount: Balance,
+    }
+
+    /// The bridge lock error types.
+    #[derive(Debug, PartialEq, Eq, scale::Encode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum Error {
+        /// Returned if zero tokens are locked.
+        ZeroAmount,
+        /// Returned if the nonce or the locked tokens overflow.
+        Overflow,
+        /// Returned if the tokens could not be transferred.
+        TransferFailed,
+    }
+
+    /// The bridge lock result type.
+    pub type Result<T> = core::result::Result<T, Error>;
+
+    impl BridgeLock {
+        /// Creates a bridge locking `token`.
+        #[ink(constructor)]
+        pub fn new(token: AccountId) -> Self {
+            Self {
+                token,
+                next_nonce: Lazy::new(0),
+                total_locked: Lazy::new(0),
+            }
+        }
+
+        /// Returns the erc20 token locked.
+        #[ink(message)]
+        pub fn token(&self) -> AccountId {
+            self.token
+        }
+
+        /// Returns the nonce of the next lock, which is the number of locks so
+        /// far.
+        #[ink(message)]
+        pub fn next_nonce(&self) -> u64 {
+            *self.next_nonce
+        }
+
+        /// Returns the tokens locked so far.
+        #[ink(message)]
+        pub fn total_locked(&self) -> Balance {
+            *self.total_locked
+        }
+
+        /// Locks `amount` tokens of the caller to be minted for `recipient` on
+        /// the destination side and returns the nonce of the lock.
+        ///
+        /// The caller has to approve the bridge to spend the tokens first. On
+        /// success a `Locked` event is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `ZeroAmount` error if `amount` is zero.
+        ///
+        /// Returns `TransferFailed` error, reverting all changes, if the tokens
+        /// could not be transferred.
+        #[ink(message)]
+        pub fn lock(&mut self, recipient: AccountId, amount: Balance) -> Result<u64> {
+            let sender = self.env().caller();
+            let nonce = self.record_lock(sender, recipient, amount)?;
+            let this = self.env().account_id();
+            if !self.transfer_from(sender, this, amount) {
+                Self::revert(Error::TransferFailed)
+            }
+            Ok(nonce)
+        }
+
+        /// Records the lock of `amount` tokens of `sender` for `recipient` and
+        /// returns its nonce.
+        fn record_lock(
+            &mut self,
+            sender: AccountId,
+            recipient: AccountId,
+            amount: Balance,
+        ) -> Result<u64> {
+            if amount == 0 {
+                return Err(Error::ZeroAmount)
+            }
+            let nonce = *self.next_nonce;
+            let next_nonce = nonce.checked_add(1).ok_or(Error::Overflow)?;
+            let total_locked = self
+                .total_locked
+                .checked_add(amount)
+                .ok_or(Error::Overflow)?;
+            *self.next_nonce = next_nonce;
+            *self.total_locked = total_locked;
+            self.env().emit_event(Locked {
+                nonce,
+                sender,
+                recipient,
+                amount,
+            });
+            Ok(nonce)
+        }
+
+        /// Transfers `value` tokens from `from` to `to` on behalf of the bridge
+        /// and returns whether that succeeded.
+        fn transfer_from(&self, from: AccountId, to: AccountId, value: Balance) -> bool {
+            let result = build_call::<DefaultEnvironment>()
+                .callee(self.token)
+                .gas_limit(0)
+                .transferred_value(0)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(TRANSFER_FROM_SELECTOR))
+                        .push_arg(from)
+                        .push_arg(to)
+                        .push_arg(value),
+                )
+                .returns::<ReturnType<core::result::Result<(), u8>>>()
+                .fire();
+            matches!(result, Ok(Ok(())))
+        }
+
+        /// Ends the current call with `error`, so no lock is recorded for the
+        /// relayers when pulling in the tokens fails.
+        fn revert(error: Error) -> ! {
+            ink_env::return_value::<Result<()>>(
+                ReturnFlags::default().set_reverted(true),
+                &Err(error),
+            )
+        }
+    }
+
+    /// Unit tests.
+    ///
+    /// Pulling in the locked tokens is left to the redspot tests.
+    #[cfg(test)]
+    mod tests {
+        /// Imports all the definitions from the outer scope so we can use them here.
+        use super::*;
+
+        use ink_lang as ink;
+
+        #[ink::test]
+        fn new_works() {
+            let bridge = BridgeLock::new(AccountId::from([0x07; 32]));
+
+            assert_eq!(bridge.token(), AccountId::from([0x07; 32]));
+            assert_eq!(bridge.next_nonce(), 0);
+            assert_eq!(bridge.total_locked(), 0);
+        }
+
+        #[ink::test]
+        fn locks_are_numbered() {
+            let mut bridge = BridgeLock::new(AccountId::from([0x07; 32]));
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            assert_eq!(
+                bridge.record_lock(accounts.alice, accounts.bob, 0),
+                Err(Error::ZeroAmount)
+            );
+            assert_eq!(bridge.record_lock(accounts.alice, accounts.bob, 10), Ok(0));
+            assert_eq!(bridge.record_lock(accounts.alice, accounts.bob, 10), Ok(1));
+            assert_eq!(bridge.next_nonce(), 2);
+            assert_eq!(bridge.total_locked(), 20);
+            assert_eq!(ink_env::test::recorded_events().count(), 2);
+        }
+    }
+}
\ No newline at end of file
@@ -0,0 +1,413 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use ink_lang as ink;
+
+#[ink::contract]
+mod bridge_mint {
+    use ink_env::hash::Blake2x256;
+    use ink_prelude::vec::Vec;
+    #[cfg(not(feature = "ink-as-dependency"))]
+    use ink_storage::{
+        collections::HashMap as StorageHashMap,
+        lazy::Lazy,
+    };
+
+    /// The destination side of a token bridge, minting a wrapped token for
+    /// the tokens locked by the bridge lock contract on the source side.
+    ///
+    /// A mint request for a lock is an ECDSA signature of a relayer over the
+    /// Blake2x256 hash of the SCALE encoded
+    /// `(source, contract, nonce, recipient, amount)` tuple, where `source` is
+    /// the bridge lock contract and `contract` this one. A relayer is the
+    /// account derived from its signing key, as Substrate does for ECDSA
+    /// signers. Every lock is minted once `threshold` distinct relayers signed
+    /// it, and only once.
+    ///
+    /// The wrapped token can be transferred like the erc20 example.
+    #[ink(storage)]
+    pub struct BridgeMint {
+        /// The bridge lock contract on the source side.
+        source: AccountId,
+        /// The accounts allowed to sign mint requests.
+        relayers: StorageHashMap<AccountId, ()>,
+        /// The number of relayers which have to sign a mint request.
+        threshold: u8,
+        /// Mapping from the nonce of a lock to whether it was minted.
+        minted: StorageHashMap<u64, ()>,
+        /// Total supply of the wrapped token.
+        total_supply: Lazy<Balance>,
+        /// Mapping from owner to the number of wrapped tokens owned.
+        balances: StorageHashMap<AccountId, Balance>,
+    }
+
+    /// Event emitted when tokens are minted for a lock on the source side.
+    #[ink(event)]
+    pub struct Minted {
+        nonce: u64,
+        #[ink(topic)]
+        recipient: AccountId,
+        amount: Balance,
+    }
+
+    /// Event emitted when a token transfer occurs.
+    #[ink(event)]
+    pub struct Transfer {
+        #[ink(topic)]
+        from: Option<AccountId>,
+        #[ink(topic)]
+        to: Option<AccountId>,
+        value: Balance,
+    }
+
+    /// The bridge mint error types.
+    #[derive(Debug, PartialEq, Eq, scale::Encode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum Error {
+        /// Returned if the lock was minted already.
+        AlreadyMinted,
+        /// Returned if a signature can not be recovered.
+        InvalidSignature,
+        /// Returned if a signature was not produced by a relayer.
+        UnknownRelayer,
+        /// Returned if a relayer signed the mint request more than once.
+        DuplicateSignature,
+        /// Returned if fewer relayers than the threshold signed the request.
+        NotEnoughSignatures,
+        /// Returned if the total supply overflows.
+        Overflow,
+        /// Returned if not enough balance to fulfill a request is available.
+        InsufficientBalance,
+    }
+
+    /// The bridge mint result type.
+    pub type Result<T> = core::result::Result<T, Error>;
+
+    impl BridgeMint {
+        /// Creates the destination side of the bridge with the lock contract
+        /// `source`, minting requests signed by `threshold` of `relayers`.
+        ///
+        /// # Panics
+        ///
+        /// If `threshold` is zero or more than the number of distinct
+        /// `relayers`.
+        #[ink(constructor)]
+        pub fn new(source: AccountId, relayers: Vec<AccountId>, threshold: u8) -> Self {
+            let relayers: StorageHashMap<_, _> =
+                relayers.into_iter().map(|relayer| (relayer, ())).collect();
+            assert!(
+                threshold > 0 && u32::from(threshold) <= relayers.len(),
+                "invalid threshold"
+            );
+            Self {
+                source,
+                relayers,
+                threshold,
+                minted: StorageHashMap::new(),
+                total_supply: Lazy::new(0),
+                balances: StorageHashMap::new(),
+            }
+        }
+
+        /// Returns the bridge lock contract on the source side.
+        #[ink(message)]
+        pub fn source(&self) -> AccountId {
+            self.source
+        }
+
+        /// Returns `true` if `account` may sign mint requests.
+        #[ink(message)]
+        pub fn is_relayer(&self, account: AccountId) -> bool {
+            self.relayers.contains_key(&account)
+        }
+
+        /// Returns the number of relayers which have to sign a mint request.
+        #[ink(message)]
+        pub fn threshold(&self) -> u8 {
+            self.threshold
+        }
+
+        /// Returns `true` if the lock with `nonce` was minted.
+        #[ink(message)]
+        pub fn is_minted(&self, nonce: u64) -> bool {
+            self.minted.contains_key(&nonce)
+        }
+
+        /// Returns the total supply of the wrapped token.
+        #[ink(message)]
+        pub fn total_supply(&self) -> Balance {
+            *self.total_supply
+        }
+
+        /// Returns the wrapped token balance of `owner`.
+        #[ink(message)]
+        pub fn balance_of(&self, owner: AccountId) -> Balance {
+            self.balances.get(&owner).copied().unwrap_or(0)
+        }
+
+        /// Transfers `value` wrapped tokens from the caller to `to`.
+        ///
+        /// On success a `Transfer` event is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `InsufficientBalance` error if there are not enough tokens on
+        /// the caller's account balance.
+        #[ink(message)]
+        pub fn transfer(&mut self, to: AccountId, value: Balance) -> Result<()> {
+            let from = self.env().caller();
+            let from_balance = self.balance_of(from);
+            if from_balance < value {
+                return Err(Error::InsufficientBalance)
+            }
+            self.balances.insert(from, from_balance - value);
+            let to_balance = self.balance_of(to);
+            self.balances.insert(to, to_balance + value);
+            self.env().emit_event(Transfer {
+                from: Some(from),
+                to: Some(to),
+                value,
+            });
+            Ok(())
+        }
+
+        /// Mints `amount` wrapped tokens for `recipient` for the lock with
+        /// `nonce` on the source side, with the `signatures` of relayers over
+        /// the mint request.
+        ///
+        /// Anyone may submit a signed request. On success a `Minted` and a
+        /// `Transfer` event are emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `AlreadyMinted` error if the lock was minted.
+        ///
+        /// Returns `InvalidSignature` error if a signature can not be recovered.
+        ///
+        /// Returns `UnknownRelayer` error if a signature was not produced by a
+        /// relayer for this request.
+        ///
+        /// Returns `DuplicateSignature` error if a relayer signed twice.
+        ///
+        /// Returns `NotEnoughSignatures` error if fewer relayers than the
+        /// threshold signed the request.
+        #[ink(message)]
+        pub fn mint(
+            &mut self,
+            nonce: u64,
+            recipient: AccountId,
+            amount: Balance,
+            signatures: Vec<[u8; 65]>,
+        ) -> Result<()> {
+            if self.is_minted(nonce) {
+                return Err(Error::AlreadyMinted)
+            }
+            let message = self.mint_request_hash(nonce, recipient, amount);
+            let signers = signatures
+                .iter()
+                .map(|signature| self.recover_signer(signature, &message))
+                .collect::<Result<Vec<_>>>()?;
+            self.ensure_approved(&signers)?;
+            self.record_mint(nonce, recipient, amount)
+        }
+
+        /// Returns the hash relayers sign to request minting `amount` tokens
+        /// for `recipient` for the lock with `nonce`.
+        fn mint_request_hash(
+            &self,
+            nonce: u64,
+            recipient: AccountId,
+            amount: Balance,
+        ) -> [u8; 32] {
+            self.env().hash_encoded::<Blake2x256, _>(&(
+                self.source,
+                self.env().account_id(),
+                nonce,
+                recipient,
+                amount,
+            ))
+        }
+
+        /// Returns the account which produced `signature` over `message`.
+        ///
+        /// There is no ECDSA recovery in ink! 3.0.0-rc2, so the key is recovered
+        /// with libsecp256k1 as Substrate does, taking the last byte as the
+        /// recovery id with or without the Ethereum offset of 27.
+        fn recover_signer(
+            &self,
+            signature: &[u8; 65],
+            message: &[u8; 32],
+        ) -> Result<AccountId> {
+            let recovery_id = match signature[64] {
+                id if id > 26 => id - 27,
+                id => id,
+            };
+            let recovery_id = secp256k1::RecoveryId::parse(recovery_id)
+                .map_err(|_| Error::InvalidSignature)?;
+            let signature = secp256k1::Signature::parse_slice(&signature[..64])
+                .map_err(|_| Error::InvalidSignature)?;
+            let message = secp256k1::Message::parse(message);
+            let public_key = secp256k1::recover(&message, &signature, &recovery_id)
+                .map_err(|_| Error::InvalidSignature)?
+                .serialize_compressed();
+            Ok(AccountId::from(
+                self.env().hash_bytes::<Blake2x256>(&public_key),
+            ))
+        }
+
+        /// Returns `Ok` if `signers` are at least `threshold` distinct relayers.
+        fn ensure_approved(&self, signers: &[AccountId]) -> Result<()> {
+            for (index, signer) in signers.iter().enumerate() {
+                if !self.is_relayer(*signer) {
+                    return Err(Error::UnknownRelayer)
+                }
+                if signers[..index].contains(signer) {
+                    return Err(Error::DuplicateSignature)
+                }
+            }
+            if signers.len() < usize::from(self.threshold) {
+                return Err(Error::NotEnoughSignatures)
+            }
+            Ok(())
+        }
+
+        /// Marks the lock with `nonce` as minted and mints `amount` tokens for
+        /// `recipient`.
+        fn record_mint(
+            &mut self,
+            nonce: u64,
+            recipient: AccountId,
+            amount: Balance,
+        ) -> Result<()> {
+            let total_supply = self
+                .total_supply
+                .checked_add(amount)
+                .ok_or(Error::Overflow)?;
+            *self.total_supply = total_supply;
+            self.minted.insert(nonce, ());
+            let balance = self.balance_of(recipient);
+            self.balances.insert(recipient, balance + amount);
+            self.env().emit_event(Minted {
+                nonce,
+                recipient,
+                amount,
+            });
+            self.env().emit_event(Transfer {
+                from: None,
+                to: Some(recipient),
+                value: amount,
+            });
+            Ok(())
+        }
+    }
+
+    /// Unit tests.
+    ///
+    /// The off-chain environment can not produce ECDSA signatures, so the
+    /// signed requests are covered by the redspot tests and the relayers are
+    /// checked with their accounts here.
+    #[cfg(test)]
+    mod tests {
+        /// Imports all the definitions from the outer scope so we can use them here.
+        use super::*;
+
+        use ink_lang as ink;
+
+        /// Returns a bridge minting requests signed by two of Alice, Bob and
+        /// Charlie.
+        fn bridge() -> BridgeMint {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            BridgeMint::new(
+                AccountId::from([0x07; 32]),
+                vec![accounts.alice, accounts.bob, accounts.charlie],
+                2,
+            )
+        }
+
+        #[ink::test]
+        fn new_works() {
+            let bridge = bridge();
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            assert_eq!(bridge.source(), AccountId::from([0x07; 32]));
+            assert!(bridge.is_relayer(accounts.bob));
+            assert!(!bridge.is_relayer(accounts.django));
+            assert_eq!(bridge.threshold(), 2);
+            assert!(!bridge.is_minted(0));
+            assert_eq!(bridge.total_supply(), 0);
+        }
+
+        #[ink::test]
+        #[should_panic(expected = "invalid threshold")]
+        fn new_rejects_threshold_above_relayers() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            BridgeMint::new(
+                AccountId::from([0x07; 32]),
+                vec![accounts.alice, accounts.alice],
+                2,
+            );
+        }
+
+        #[ink::test]
+        fn relayers_are_verified() {
+            let bridge = bridge();
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            assert_eq!(
+                bridge.ensure_approved(&[accounts.alice, accounts.bob]),
+                Ok(())
+            );
+            assert_eq!(
+                bridge.ensure_approved(&[accounts.alice]),
+                Err(Error::NotEnoughSignatures)
+            );
+            assert_eq!(
+                bridge.ensure_approved(&[accounts.alice, accounts.alice]),
+                Err(Error::DuplicateSignature)
+            );
+            assert_eq!(
+                bridge.ensure_approved(&[accounts.alice, accounts.django]),
+                Err(Error::UnknownRelayer)
+            );
+        }
+
+        #[ink::test]
+        fn mint_rejects_invalid_signatures() {
+            let mut bridge = bridge();
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            assert_eq!(
+                bridge.mint(0, accounts.bob, 10, vec![[0; 65], [0; 65]]),
+                Err(Error::InvalidSignature)
+            );
+            assert!(!bridge.is_minted(0));
+        }
+
+        #[ink::test]
+        fn mints_are_recorded_once() {
+            let mut bridge = bridge();
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            assert_eq!(bridge.record_mint(0, accounts.bob, 10), Ok(()));
+            assert!(bridge.is_minted(0));
+            assert_eq!(bridge.balance_of(accounts.bob), 10);
+            assert_eq!(bridge.total_supply(), 10);
+            assert_eq!(
+                bridge.mint(0, accounts.bob, 10, Vec::new()),
+                Err(Error::AlreadyMinted)
+            );
+            assert_eq!(ink_env::test::recorded_events().count(), 2);
+        }
+    }
+}
\ No newline at end of file
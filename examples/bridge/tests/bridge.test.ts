@@ -0,0 +1,125 @@
+import BN from 'bn.js';
+import { expect } from 'chai';
+import { patract, network } from 'redspot';
+import { Keyring } from '@polkadot/keyring';
+import type { KeyringPair } from '@polkadot/keyring/types';
+import { u8aToHex } from '@polkadot/util';
+
+const { getContractFactory, getRandomSigner } = patract;
+
+const { api, getSigners } = network;
+
+describe('BRIDGE', () => {
+  after(() => {
+    return api.disconnect();
+  });
+
+  async function setup() {
+    const one = new BN(10).pow(new BN(api.registry.chainDecimals[0]));
+    const signers = await getSigners();
+    const Alice = signers[0];
+    const sender = await getRandomSigner(Alice, one.muln(10000));
+    const recipient = await getRandomSigner(Alice, one.muln(10000));
+    const tokenFactory = await getContractFactory('erc20', sender);
+    const token = await tokenFactory.deploy(
+      'new',
+      '1000000',
+      'Redspot',
+      'RDS',
+      10
+    );
+    const lockFactory = await getContractFactory('bridge_lock', sender);
+    const lock = await lockFactory.deploy('new', token.address);
+    // Relayers sign with ECDSA keys, which the contract recovers.
+    const keyring = new Keyring({ type: 'ecdsa' });
+    const relayers = ['//Relayer1', '//Relayer2', '//Relayer3'].map((uri) =>
+      keyring.addFromUri(uri)
+    );
+    const mintFactory = await getContractFactory('bridge_mint', sender);
+    const mint = await mintFactory.deploy(
+      'new',
+      lock.address,
+      relayers.map((relayer) => relayer.address),
+      2
+    );
+    await token.tx.approve(lock.address, 1000);
+
+    // Signs the mint request of a lock. The ECDSA pair hashes the encoded
+    // request with Blake2x256 before signing, as the contract expects.
+    function sign(
+      relayer: KeyringPair,
+      nonce: number,
+      to: string,
+      amount: number
+    ) {
+      const request = api.createType(
+        '(AccountId, AccountId, u64, AccountId, Balance)',
+        [lock.address, mint.address, nonce, to, amount]
+      );
+
+      return u8aToHex(relayer.sign(request.toU8a()));
+    }
+
+    return {
+      sender,
+      recipient,
+      token,
+      lock,
+      mint,
+      relayers,
+      sign,
+      Alice,
+      one
+    };
+  }
+
+  it('Locks tokens with increasing nonces', async () => {
+    const { lock, token, sender, recipient } = await setup();
+
+    await expect(() =>
+      lock.tx.lock(recipient.address, 300)
+    ).to.changeTokenBalance(token, sender, -300);
+    await expect(lock.tx.lock(recipient.address, 200))
+      .to.emit(lock, 'Locked')
+      .withArgs(1, sender.address, recipient.address, 200);
+    expect((await lock.query.totalLocked()).output).to.equal(500);
+  });
+
+  it('Mints requests signed by enough relayers once', async () => {
+    const { lock, mint, recipient, relayers, sign } = await setup();
+
+    await lock.tx.lock(recipient.address, 300);
+    const [first, second] = relayers.map((relayer) =>
+      sign(relayer, 0, recipient.address, 300)
+    );
+
+    await expect(
+      mint.tx.mint(0, recipient.address, 300, [first])
+    ).to.not.emit(mint, 'Minted');
+    await expect(
+      mint.tx.mint(0, recipient.address, 300, [first, first])
+    ).to.not.emit(mint, 'Minted');
+    await expect(mint.tx.mint(0, recipient.address, 300, [first, second]))
+      .to.emit(mint, 'Minted')
+      .withArgs(0, recipient.address, 300);
+    expect((await mint.query.balanceOf(recipient.address)).output).to.equal(
+      300
+    );
+
+    await expect(
+      mint.tx.mint(0, recipient.address, 300, [first, second])
+    ).to.not.emit(mint, 'Minted');
+  });
+
+  it('Rejects signatures over another request', async () => {
+    const { mint, recipient, relayers, sign } = await setup();
+
+    const signatures = relayers.map((relayer) =>
+      sign(relayer, 0, recipient.address, 300)
+    );
+
+    await expect(
+      mint.tx.mint(0, recipient.address, 301, signatures)
+    ).to.not.emit(mint, 'Minted');
+  });
+});
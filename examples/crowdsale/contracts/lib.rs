@@ -0,0 +1,593 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use ink_lang as ink;
+
+#[ink::contract]
+mod crowdsale {
+    use erc20::{
+        BALANCE_OF_SELECTOR,
+        TRANSFER_SELECTOR,
+    };
+    use ink_env::{
+        call::{
+            build_call,
+            utils::ReturnType,
+            ExecutionInput,
+            Selector,
+        },
+        DefaultEnvironment,
+        ReturnFlags,
+    };
+    use ink_storage::traits::{
+        PackedLayout,
+        SpreadLayout,
+    };
+    #[cfg(not(feature = "ink-as-dependency"))]
+    use ink_storage::{
+        collections::HashMap as StorageHashMap,
+        lazy::Lazy,
+    };
+
+    /// The stage a sale is in.
+    #[derive(
+        Debug,
+        Copy,
+        Clone,
+        PartialEq,
+        Eq,
+        scale::Encode,
+        scale::Decode,
+        SpreadLayout,
+        PackedLayout,
+    )]
+    #[cfg_attr(
+        feature = "std",
+        derive(::scale_info::TypeInfo, ::ink_storage::traits::StorageLayout)
+    )]
+    pub enum SaleState {
+        /// Tokens can be bought, or the sale ended but was not finalized yet.
+        Open,
+        /// The soft cap was reached, buyers can claim their tokens.
+        Succeeded,
+        /// The soft cap was missed, buyers can get their funds back.
+        Failed,
+    }
+
+    /// Sells an erc20 token for native funds at a fixed rate.
+    ///
+    /// The contract has to hold the tokens for sale, so the owner transfers
+    /// them to it after creating the sale. Purchases are limited per account and
+    /// in total by the hard cap. Once the sale ended or the hard cap was reached
+    /// anyone can finalize it: if the soft cap was reached buyers claim their
+    /// tokens and the owner withdraws the funds, otherwise buyers get their
+    /// funds back and the owner its tokens.
+    #[ink(storage)]
+    pub struct Crowdsale {
+        /// The account receiving the funds raised.
+        owner: AccountId,
+        /// The erc20 token for sale.
+        token: AccountId,
+        /// The tokens a buyer gets per unit of native funds.
+        rate: Balance,
+        /// The native funds which have to be raised for the sale to succeed.
+        soft_cap: Balance,
+        /// The most native funds the sale raises.
+        hard_cap: Balance,
+        /// The most native funds a single account may spend.
+        max_per_account: Balance,
+        /// The last block in which tokens can be bought.
+        end: BlockNumber,
+        /// The stage the sale is in.
+        state: Lazy<SaleState>,
+        /// The native funds raised so far.
+        raised: Lazy<Balance>,
+        /// Mapping from buyer to the native funds it spent and did not claim
+        /// tokens or a refund for yet.
+        contributions: StorageHashMap<AccountId, Balance>,
+        /// The native funds buyers claimed tokens for.
+        claimed: Lazy<Balance>,
+        /// Whether the owner withdrew the funds raised.
+        withdrawn: Lazy<bool>,
+    }
+
+    /// Event emitted when tokens are bought.
+    #[ink(event)]
+    pub struct TokensPurchased {
+        #[ink(topic)]
+        buyer: AccountId,
+        value: Balance,
+        tokens: Balance,
+    }
+
+    /// Event emitted when the sale is finalized.
+    #[ink(event)]
+    pub struct Finalized {
+        state: SaleState,
+        raised: Balance,
+    }
+
+    /// Event emitted when a buyer claims its tokens.
+    #[ink(event)]
+    pub struct TokensClaimed {
+        #[ink(topic)]
+        buyer: AccountId,
+        tokens: Balance,
+    }
+
+    /// Event emitted when a buyer gets its funds back.
+    #[ink(event)]
+    pub struct Refunded {
+        #[ink(topic)]
+        buyer: AccountId,
+        value: Balance,
+    }
+
+    /// Event emitted when the owner withdraws funds and unsold tokens.
+    #[ink(event)]
+    pub struct Withdrawn {
+        value: Balance,
+        tokens: Balance,
+    }
+
+    /// The crowdsale error types.
+    #[derive(Debug, PartialEq, Eq, scale::Encode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum Error {
+        /// Returned if the sale ended or was finalized.
+        SaleNotOpen,
+        /// Returned if the sale did not end yet.
+        SaleNotEnded,
+        /// Returned if no native funds were sent along.
+        ZeroPurchase,
+        /// Returned if a purchase would raise more than the hard cap.
+        HardCapExceeded,
+        /// Returned if a purchase would take the buyer above its limit.
+        LimitExceeded,
+        /// Returned if the sale is not in the stage the call needs.
+        InvalidState,
+        /// Returned if the caller is not the owner.
+        NotOwner,
+        /// Returned if the token balance of the sale could not be read.
+        TokenCallFailed,
+        /// Returned if funds or tokens could not be transferred.
+        TransferFailed,
+    }
+
+    /// The crowdsale result type.
+    pub type Result<T> = core::result::Result<T, Error>;
+
+    impl Crowdsale {
+        /// Creates a sale of `token` at `rate` tokens per unit of native funds
+        /// open for `duration` blocks.
+        ///
+        /// The sale succeeds if it raises at least `soft_cap` and raises at most
+        /// `hard_cap`, and a single account may spend at most `max_per_account`.
+        ///
+        /// # Panics
+        ///
+        /// If `soft_cap` is greater than `hard_cap`.
+        #[ink(constructor)]
+        pub fn new(
+            token: AccountId,
+            rate: Balance,
+            soft_cap: Balance,
+            hard_cap: Balance,
+            max_per_account: Balance,
+            duration: BlockNumber,
+        ) -> Self {
+            assert!(soft_cap <= hard_cap, "invalid caps");
+            Self {
+                owner: Self::env().caller(),
+                token,
+                rate,
+                soft_cap,
+                hard_cap,
+                max_per_account,
+                end: Self::env().block_number().saturating_add(duration),
+                state: Lazy::new(SaleState::Open),
+                raised: Lazy::new(0),
+                contributions: StorageHashMap::new(),
+                claimed: Lazy::new(0),
+                withdrawn: Lazy::new(false),
+            }
+        }
+
+        /// Returns the erc20 token for sale.
+        #[ink(message)]
+        pub fn token(&self) -> AccountId {
+            self.token
+        }
+
+        /// Returns the tokens a buyer gets per unit of native funds.
+        #[ink(message)]
+        pub fn rate(&self) -> Balance {
+            self.rate
+        }
+
+        /// Returns the soft and the hard cap.
+        #[ink(message)]
+        pub fn caps(&self) -> (Balance, Balance) {
+            (self.soft_cap, self.hard_cap)
+        }
+
+        /// Returns the most native funds a single account may spend.
+        #[ink(message)]
+        pub fn max_per_account(&self) -> Balance {
+            self.max_per_account
+        }
+
+        /// Returns the last block in which tokens can be bought.
+        #[ink(message)]
+        pub fn end(&self) -> BlockNumber {
+            self.end
+        }
+
+        /// Returns the stage the sale is in.
+        #[ink(message)]
+        pub fn state(&self) -> SaleState {
+            *self.state
+        }
+
+        /// Returns the native funds raised so far.
+        #[ink(message)]
+        pub fn raised(&self) -> Balance {
+            *self.raised
+        }
+
+        /// Returns the native funds `buyer` spent and did not claim tokens or a
+        /// refund for yet.
+        #[ink(message)]
+        pub fn contribution_of(&self, buyer: AccountId) -> Balance {
+            self.contributions.get(&buyer).copied().unwrap_or(0)
+        }
+
+        /// Buys tokens with the native funds sent along and returns the amount of
+        /// tokens bought.
+        ///
+        /// The tokens can be claimed once the sale succeeded. On success a
+        /// `TokensPurchased` event is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `SaleNotOpen` error if the sale ended or was finalized.
+        ///
+        /// Returns `ZeroPurchase` error if no native funds were sent along.
+        ///
+        /// Returns `HardCapExceeded` error if the sale would raise more than the
+        /// hard cap.
+        ///
+        /// Returns `LimitExceeded` error if the caller would spend more than its
+        /// limit.
+        ///
+        /// All errors revert the call, so the funds sent along are kept by the
+        /// caller.
+        #[ink(message, payable)]
+        pub fn buy(&mut self) -> Result<Balance> {
+            let buyer = self.env().caller();
+            let value = self.env().transferred_balance();
+            match self.purchase(buyer, value) {
+                Ok(tokens) => Ok(tokens),
+                Err(error) => Self::revert(error),
+            }
+        }
+
+        /// Finalizes the sale once it ended or reached the hard cap and returns
+        /// the stage it ends up in.
+        ///
+        /// Anyone may finalize the sale. On success a `Finalized` event is
+        /// emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `SaleNotOpen` error if the sale was finalized.
+        ///
+        /// Returns `SaleNotEnded` error if the sale did not end and did not reach
+        /// the hard cap.
+        #[ink(message)]
+        pub fn finalize(&mut self) -> Result<SaleState> {
+            if *self.state != SaleState::Open {
+                return Err(Error::SaleNotOpen)
+            }
+            let raised = *self.raised;
+            if self.env().block_number() <= self.end && raised < self.hard_cap {
+                return Err(Error::SaleNotEnded)
+            }
+            let state = if raised >= self.soft_cap {
+                SaleState::Succeeded
+            } else {
+                SaleState::Failed
+            };
+            *self.state = state;
+            self.env().emit_event(Finalized { state, raised });
+            Ok(state)
+        }
+
+        /// Sends the tokens the caller bought to it and returns their amount.
+        ///
+        /// On success a `TokensClaimed` event is emitted if the caller bought
+        /// tokens.
+        ///
+        /// # Errors
+        ///
+        /// Returns `InvalidState` error if the sale did not succeed.
+        ///
+        /// Returns `TransferFailed` error, reverting all changes, if the tokens
+        /// could not be transferred.
+        #[ink(message)]
+        pub fn claim(&mut self) -> Result<Balance> {
+            if *self.state != SaleState::Succeeded {
+                return Err(Error::InvalidState)
+            }
+            let buyer = self.env().caller();
+            let value = self.contributions.take(&buyer).unwrap_or(0);
+            if value == 0 {
+                return Ok(0)
+            }
+            *self.claimed += value;
+            let tokens = value * self.rate;
+            if !self.transfer_tokens(buyer, tokens) {
+                Self::revert(Error::TransferFailed)
+            }
+            self.env().emit_event(TokensClaimed { buyer, tokens });
+            Ok(tokens)
+        }
+
+        /// Sends the native funds the caller spent back to it and returns their
+        /// amount.
+        ///
+        /// On success a `Refunded` event is emitted if the caller spent funds.
+        ///
+        /// # Errors
+        ///
+        /// Returns `InvalidState` error if the sale did not fail.
+        ///
+        /// Returns `TransferFailed` error if the funds could not be sent.
+        #[ink(message)]
+        pub fn refund(&mut self) -> Result<Balance> {
+            if *self.state != SaleState::Failed {
+                return Err(Error::InvalidState)
+            }
+            let buyer = self.env().caller();
+            let value = self.contribution_of(buyer);
+            if value == 0 {
+                return Ok(0)
+            }
+            // The refund runs no code of `buyer`, so the contribution is only
+            // cleared once it went out.
+            self.env()
+                .transfer(buyer, value)
+                .map_err(|_| Error::TransferFailed)?;
+            self.contributions.take(&buyer);
+            self.env().emit_event(Refunded { buyer, value });
+            Ok(value)
+        }
+
+        /// Sends the funds raised, if the sale succeeded, and the tokens not owed
+        /// to buyers to the owner.
+        ///
+        /// On success a `Withdrawn` event is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `NotOwner` error if the caller is not the owner.
+        ///
+        /// Returns `InvalidState` error if the sale was not finalized.
+        ///
+        /// Returns `TokenCallFailed` error if the token balance of the sale could
+        /// not be read.
+        ///
+        /// Returns `TransferFailed` error, reverting all changes, if the funds or
+        /// the tokens could not be transferred.
+        #[ink(message)]
+        pub fn withdraw(&mut self) -> Result<()> {
+            if self.env().caller() != self.owner {
+                return Err(Error::NotOwner)
+            }
+            let state = *self.state;
+            if state == SaleState::Open {
+                return Err(Error::InvalidState)
+            }
+            let value = if state == SaleState::Succeeded && !*self.withdrawn {
+                *self.raised
+            } else {
+                0
+            };
+            // Tokens bought in a failed sale are not owed to anyone.
+            let owed = if state == SaleState::Succeeded {
+                (*self.raised - *self.claimed) * self.rate
+            } else {
+                0
+            };
+            let tokens = self.token_balance()?.saturating_sub(owed);
+            *self.withdrawn = true;
+            if value > 0 && self.env().transfer(self.owner, value).is_err() {
+                Self::revert(Error::TransferFailed)
+            }
+            if tokens > 0 && !self.transfer_tokens(self.owner, tokens) {
+                Self::revert(Error::TransferFailed)
+            }
+            self.env().emit_event(Withdrawn { value, tokens });
+            Ok(())
+        }
+
+        /// Records a purchase of `buyer` for `value` native funds and returns the
+        /// amount of tokens bought.
+        fn purchase(&mut self, buyer: AccountId, value: Balance) -> Result<Balance> {
+            if *self.state != SaleState::Open || self.env().block_number() > self.end {
+                return Err(Error::SaleNotOpen)
+            }
+            if value == 0 {
+                return Err(Error::ZeroPurchase)
+            }
+            let raised = self
+                .raised
+                .checked_add(value)
+                .filter(|raised| *raised <= self.hard_cap)
+                .ok_or(Error::HardCapExceeded)?;
+            let contribution = self
+                .contribution_of(buyer)
+                .checked_add(value)
+                .filter(|contribution| *contribution <= self.max_per_account)
+                .ok_or(Error::LimitExceeded)?;
+            let tokens = value.checked_mul(self.rate).ok_or(Error::HardCapExceeded)?;
+            *self.raised = raised;
+            self.contributions.insert(buyer, contribution);
+            self.env().emit_event(TokensPurchased {
+                buyer,
+                value,
+                tokens,
+            });
+            Ok(tokens)
+        }
+
+        /// Returns the balance of the sale in the token.
+        fn token_balance(&self) -> Result<Balance> {
+            build_call::<DefaultEnvironment>()
+                .callee(self.token)
+                .gas_limit(0)
+                .transferred_value(0)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(BALANCE_OF_SELECTOR))
+                        .push_arg(self.env().account_id()),
+                )
+                .returns::<ReturnType<Balance>>()
+                .fire()
+                .map_err(|_| Error::TokenCallFailed)
+        }
+
+        /// Transfers `value` tokens from the sale to `to` and returns whether that
+        /// succeeded.
+        fn transfer_tokens(&self, to: AccountId, value: Balance) -> bool {
+            let result = build_call::<DefaultEnvironment>()
+                .callee(self.token)
+                .gas_limit(0)
+                .transferred_value(0)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(TRANSFER_SELECTOR))
+                        .push_arg(to)
+                        .push_arg(value),
+                )
+                .returns::<ReturnType<core::result::Result<(), u8>>>()
+                .fire();
+            matches!(result, Ok(Ok(())))
+        }
+
+        /// Ends the current call with `error`, so a failed purchase hands the funds
+        /// sent along back and a failed payout is not counted as paid.
+        fn revert(error: Error) -> ! {
+            ink_env::return_value::<Result<()>>(
+                ReturnFlags::default().set_reverted(true),
+                &Err(error),
+            )
+        }
+    }
+
+    /// Unit tests.
+    ///
+    /// Delivering tokens is left to the redspot tests. Purchases are checked
+    /// through `purchase`, since `buy` reverts on errors.
+    #[cfg(test)]
+    mod tests {
+        /// Imports all the definitions from the outer scope so we can use them here.
+        use super::*;
+
+        use ink_lang as ink;
+        use test_utils::{
+            advance_blocks,
+            set_caller,
+        };
+
+        fn native_balance_of(account: AccountId) -> Balance {
+            ink_env::test::get_account_balance::<ink_env::DefaultEnvironment>(account)
+                .expect("Cannot get account balance")
+        }
+
+        /// Returns a sale at two tokens per unit with caps of `50` and `100`,
+        /// a limit of `60` per account and open for two blocks.
+        fn sale() -> Crowdsale {
+            Crowdsale::new(AccountId::from([0x07; 32]), 2, 50, 100, 60, 2)
+        }
+
+        #[ink::test]
+        fn new_works() {
+            let sale = sale();
+
+            assert_eq!(sale.token(), AccountId::from([0x07; 32]));
+            assert_eq!(sale.rate(), 2);
+            assert_eq!(sale.caps(), (50, 100));
+            assert_eq!(sale.max_per_account(), 60);
+            assert_eq!(sale.state(), SaleState::Open);
+            assert_eq!(sale.raised(), 0);
+        }
+
+        #[ink::test]
+        #[should_panic(expected = "invalid caps")]
+        fn new_rejects_soft_cap_above_hard_cap() {
+            Crowdsale::new(AccountId::from([0x07; 32]), 2, 101, 100, 60, 2);
+        }
+
+        #[ink::test]
+        fn purchases_respect_limits() {
+            let mut sale = sale();
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            assert_eq!(sale.purchase(accounts.bob, 0), Err(Error::ZeroPurchase));
+            assert_eq!(sale.purchase(accounts.bob, 40), Ok(80));
+            assert_eq!(sale.purchase(accounts.bob, 21), Err(Error::LimitExceeded));
+            assert_eq!(sale.purchase(accounts.bob, 20), Ok(40));
+            assert_eq!(
+                sale.purchase(accounts.charlie, 41),
+                Err(Error::HardCapExceeded)
+            );
+            assert_eq!(sale.contribution_of(accounts.bob), 60);
+            assert_eq!(sale.raised(), 60);
+
+            advance_blocks!(3);
+            assert_eq!(sale.purchase(accounts.charlie, 1), Err(Error::SaleNotOpen));
+        }
+
+        #[ink::test]
+        fn finalize_waits_for_end_or_hard_cap() {
+            let mut sale = sale();
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            assert_eq!(sale.purchase(accounts.bob, 60), Ok(120));
+            assert_eq!(sale.finalize(), Err(Error::SaleNotEnded));
+            assert_eq!(sale.purchase(accounts.charlie, 40), Ok(80));
+            assert_eq!(sale.finalize(), Ok(SaleState::Succeeded));
+            assert_eq!(sale.finalize(), Err(Error::SaleNotOpen));
+            assert_eq!(sale.purchase(accounts.charlie, 1), Err(Error::SaleNotOpen));
+            assert_eq!(sale.refund(), Err(Error::InvalidState));
+        }
+
+        #[ink::test]
+        fn failed_sale_refunds() {
+            let mut sale = sale();
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let contract = ink_env::account_id::<ink_env::DefaultEnvironment>()
+                .expect("Cannot get contract id");
+
+            assert_eq!(sale.purchase(accounts.bob, 30), Ok(60));
+            ink_env::test::set_account_balance::<ink_env::DefaultEnvironment>(
+                contract, 30,
+            )
+            .expect("Cannot set contract balance");
+            advance_blocks!(3);
+            assert_eq!(sale.finalize(), Ok(SaleState::Failed));
+            assert_eq!(sale.claim(), Err(Error::InvalidState));
+
+            let bob_native = native_balance_of(accounts.bob);
+            set_caller!(accounts.bob);
+            assert_eq!(sale.refund(), Ok(30));
+            assert_eq!(sale.refund(), Ok(0));
+            assert_eq!(native_balance_of(accounts.bob), bob_native + 30);
+            assert_eq!(sale.withdraw(), Err(Error::NotOwner));
+        }
+    }
+}
\ No newline at end of file
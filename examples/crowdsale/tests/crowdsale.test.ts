@@ -0,0 +1,122 @@
+import BN from 'bn.js';
+import { expect } from 'chai';
+import { patract, network } from 'redspot';
+
+const { getContractFactory, getRandomSigner } = patract;
+
+const { api, getSigners } = network;
+
+describe('CROWDSALE', () => {
+  after(() => {
+    return api.disconnect();
+  });
+
+  async function setup() {
+    const one = new BN(10).pow(new BN(api.registry.chainDecimals[0]));
+    const signers = await getSigners();
+    const Alice = signers[0];
+    const sender = await getRandomSigner(Alice, one.muln(10000));
+    const buyer = await getRandomSigner(Alice, one.muln(10000));
+    const tokenFactory = await getContractFactory('erc20', sender);
+    const token = await tokenFactory.deploy(
+      'new',
+      one.muln(1000),
+      'Redspot',
+      'RDS',
+      10
+    );
+    const contractFactory = await getContractFactory('crowdsale', sender);
+    const contract = await contractFactory.deploy(
+      'new',
+      token.address,
+      2,
+      one.muln(50),
+      one.muln(100),
+      one.muln(60),
+      3
+    );
+    await token.tx.transfer(contract.address, one.muln(200));
+
+    // The development node seals a block for every transaction, so blocks pass
+    // by sending transactions which change nothing.
+    async function advanceBlocks(blocks: number) {
+      for (let i = 0; i < blocks; i++) {
+        await token.tx.approve(contract.address, 0);
+      }
+    }
+
+    return {
+      sender,
+      buyer,
+      token,
+      contractFactory,
+      contract,
+      advanceBlocks,
+      Alice,
+      one
+    };
+  }
+
+  it('Sells tokens up to the limit per account', async () => {
+    const { contract, buyer, one } = await setup();
+
+    await expect(contract.tx.buy({ signer: buyer, value: one.muln(40) }))
+      .to.emit(contract, 'TokensPurchased')
+      .withArgs(buyer.address, one.muln(40), one.muln(80));
+    await expect(
+      contract.tx.buy({ signer: buyer, value: one.muln(21) })
+    ).to.not.emit(contract, 'TokensPurchased');
+
+    const contribution = await contract.query.contributionOf(buyer.address);
+    expect(new BN(contribution.output?.toString()).eq(one.muln(40))).to.equal(
+      true
+    );
+  });
+
+  it('Delivers the tokens after reaching the hard cap', async () => {
+    const { contract, token, sender, buyer, one } = await setup();
+
+    await contract.tx.buy({ signer: buyer, value: one.muln(60) });
+    await contract.tx.buy({ value: one.muln(40) });
+    await expect(contract.tx.finalize()).to.emit(contract, 'Finalized');
+
+    await expect(() =>
+      contract.tx.claim({ signer: buyer })
+    ).to.changeTokenBalance(token, buyer, one.muln(120));
+    await expect(contract.tx.withdraw()).to.emit(contract, 'Withdrawn');
+    await expect(() => contract.tx.claim()).to.changeTokenBalance(
+      token,
+      sender,
+      one.muln(80)
+    );
+  });
+
+  it('Refunds the buyers if the soft cap is missed', async () => {
+    const {
+      contract,
+      token,
+      sender,
+      buyer,
+      one,
+      advanceBlocks
+    } = await setup();
+
+    await contract.tx.buy({ signer: buyer, value: one.muln(30) });
+    await expect(contract.tx.finalize()).to.not.emit(contract, 'Finalized');
+    await advanceBlocks(3);
+    await expect(contract.tx.finalize()).to.emit(contract, 'Finalized');
+
+    await expect(contract.tx.claim({ signer: buyer })).to.not.emit(
+      contract,
+      'TokensClaimed'
+    );
+    await expect(contract.tx.refund({ signer: buyer }))
+      .to.emit(contract, 'Refunded')
+      .withArgs(buyer.address, one.muln(30));
+    await expect(() => contract.tx.withdraw()).to.changeTokenBalance(
+      token,
+      sender,
+      one.muln(200)
+    );
+  });
+});
@@ -0,0 +1,344 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use ink_lang as ink;
+
+#[ink::contract]
+mod dutch_auction {
+    use ink_env::{
+        call::{
+            build_call,
+            utils::ReturnType,
+            ExecutionInput,
+            Selector,
+        },
+        DefaultEnvironment,
+        ReturnFlags,
+    };
+
+    /// The ID type of the erc721 example's tokens.
+    pub type TokenId = u32;
+
+    /// Selector of the erc721 example's `transfer_from(from, to, id) -> Result<()>`.
+    ///
+    /// Spelled out here since the auction pays in native funds and so does not
+    /// depend on the erc20 crate, which exports the same selector.
+    const TRANSFER_FROM_SELECTOR: [u8; 4] = [0x0B, 0x39, 0x6F, 0x18];
+
+    /// Sells an erc721 token for native funds at a price falling with every
+    /// block until someone buys it.
+    ///
+    /// The price starts at `start_price` in the block the sale is created and
+    /// falls linearly to `end_price` over `duration` blocks, where it stays. The
+    /// seller keeps the token until it is bought, but has to approve this
+    /// contract for it.
+    #[ink(storage)]
+    pub struct DutchAuction {
+        /// The account selling the token.
+        seller: AccountId,
+        /// The erc721 contract of the token.
+        nft: AccountId,
+        /// The ID of the token.
+        token_id: TokenId,
+        /// The price in the block the sale was created.
+        start_price: Balance,
+        /// The lowest price, reached after `duration` blocks.
+        end_price: Balance,
+        /// The block the sale was created in.
+        start_block: BlockNumber,
+        /// The number of blocks the price falls for.
+        duration: BlockNumber,
+        /// The account which bought the token, if any.
+        buyer: Option<AccountId>,
+        /// Whether the seller cancelled the sale.
+        cancelled: bool,
+    }
+
+    /// Event emitted when the token is bought.
+    #[ink(event)]
+    pub struct Purchased {
+        #[ink(topic)]
+        buyer: AccountId,
+        price: Balance,
+    }
+
+    /// Event emitted when the seller cancels the sale.
+    #[ink(event)]
+    pub struct Cancelled {
+        at: BlockNumber,
+    }
+
+    /// The auction error types.
+    #[derive(Debug, PartialEq, Eq, scale::Encode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum Error {
+        /// Returned if the token was bought or the sale cancelled.
+        SaleClosed,
+        /// Returned if less than the current price was sent along.
+        InsufficientPayment,
+        /// Returned if the caller is not the seller.
+        NotSeller,
+        /// Returned if the token or the funds could not be transferred.
+        TransferFailed,
+    }
+
+    /// The auction result type.
+    pub type Result<T> = core::result::Result<T, Error>;
+
+    impl DutchAuction {
+        /// Offers the token `token_id` of the erc721 contract `nft` of the caller
+        /// at a price falling from `start_price` to `end_price` over `duration`
+        /// blocks.
+        ///
+        /// # Panics
+        ///
+        /// If `end_price` is greater than `start_price`.
+        #[ink(constructor)]
+        pub fn new(
+            nft: AccountId,
+            token_id: TokenId,
+            start_price: Balance,
+            end_price: Balance,
+            duration: BlockNumber,
+        ) -> Self {
+            assert!(end_price <= start_price, "invalid prices");
+            Self {
+                seller: Self::env().caller(),
+                nft,
+                token_id,
+                start_price,
+                end_price,
+                start_block: Self::env().block_number(),
+                duration,
+                buyer: None,
+                cancelled: false,
+            }
+        }
+
+        /// Returns the account selling the token.
+        #[ink(message)]
+        pub fn seller(&self) -> AccountId {
+            self.seller
+        }
+
+        /// Returns the erc721 contract and the ID of the token for sale.
+        #[ink(message)]
+        pub fn token(&self) -> (AccountId, TokenId) {
+            (self.nft, self.token_id)
+        }
+
+        /// Returns the account which bought the token, if any.
+        #[ink(message)]
+        pub fn buyer(&self) -> Option<AccountId> {
+            self.buyer
+        }
+
+        /// Returns `true` if the token can still be bought.
+        #[ink(message)]
+        pub fn is_open(&self) -> bool {
+            self.buyer.is_none() && !self.cancelled
+        }
+
+        /// Returns the price of the token in the current block.
+        #[ink(message)]
+        pub fn price(&self) -> Balance {
+            let elapsed = self.env().block_number() - self.start_block;
+            if elapsed >= self.duration {
+                return self.end_price
+            }
+            // Split `decay * elapsed / duration` so the multiplication can not
+            // overflow.
+            let decay = self.start_price - self.end_price;
+            let (elapsed, duration) = (elapsed as Balance, self.duration as Balance);
+            self.start_price
+                - (decay / duration * elapsed + decay % duration * elapsed / duration)
+        }
+
+        /// Buys the token at the current price.
+        ///
+        /// At least the current price has to be sent along, anything above it is
+        /// sent back. On success a `Purchased` event is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `SaleClosed` error if the token was bought or the sale
+        /// cancelled.
+        ///
+        /// Returns `InsufficientPayment` error if less than the current price
+        /// was sent along.
+        ///
+        /// Returns `TransferFailed` error, reverting all changes, if the token or
+        /// the funds could not be transferred, for example because the seller
+        /// does not own the token anymore.
+        #[ink(message, payable)]
+        pub fn buy(&mut self) -> Result<()> {
+            if !self.is_open() {
+                return Err(Error::SaleClosed)
+            }
+            let buyer = self.env().caller();
+            let paid = self.env().transferred_balance();
+            let price = self.price();
+            if paid < price {
+                return Err(Error::InsufficientPayment)
+            }
+            self.buyer = Some(buyer);
+            // ink! writes the contract storage back only when a message returns.
+            // Write it now so the erc721 contract, which the seller picked, already
+            // sees the token as bought and can not buy it a second time.
+            ink_storage::traits::push_spread_root::<Self>(
+                self,
+                &ink_primitives::Key::from([0x00; 32]),
+            );
+            if !self.transfer_token(buyer) {
+                Self::revert(Error::TransferFailed)
+            }
+            // Neither the seller nor the buyer runs code on receiving funds.
+            if self.env().transfer(self.seller, price).is_err() {
+                Self::revert(Error::TransferFailed)
+            }
+            if paid > price && self.env().transfer(buyer, paid - price).is_err() {
+                Self::revert(Error::TransferFailed)
+            }
+            self.env().emit_event(Purchased { buyer, price });
+            Ok(())
+        }
+
+        /// Cancels the sale.
+        ///
+        /// On success a `Cancelled` event is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `NotSeller` error if the caller is not the seller.
+        ///
+        /// Returns `SaleClosed` error if the token was bought or the sale
+        /// cancelled.
+        #[ink(message)]
+        pub fn cancel(&mut self) -> Result<()> {
+            if self.env().caller() != self.seller {
+                return Err(Error::NotSeller)
+            }
+            if !self.is_open() {
+                return Err(Error::SaleClosed)
+            }
+            self.cancelled = true;
+            self.env().emit_event(Cancelled {
+                at: self.env().block_number(),
+            });
+            Ok(())
+        }
+
+        /// Transfers the token from the seller to `to` and returns whether that
+        /// succeeded.
+        fn transfer_token(&self, to: AccountId) -> bool {
+            let result = build_call::<DefaultEnvironment>()
+                .callee(self.nft)
+                .gas_limit(0)
+                .transferred_value(0)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(TRANSFER_FROM_SELECTOR))
+                        .push_arg(self.seller)
+                        .push_arg(to)
+                        .push_arg(self.token_id),
+                )
+                .returns::<ReturnType<core::result::Result<(), u8>>>()
+                .fire();
+            matches!(result, Ok(Ok(())))
+        }
+
+        /// Ends the current call with `error`, so the auction stays open and the
+        /// funds sent along go back to the buyer when moving the token or the funds
+        /// fails.
+        fn revert(error: Error) -> ! {
+            ink_env::return_value::<Result<()>>(
+                ReturnFlags::default().set_reverted(true),
+                &Err(error),
+            )
+        }
+    }
+
+    /// Unit tests.
+    ///
+    /// Handing over the token is left to the redspot tests.
+    #[cfg(test)]
+    mod tests {
+        /// Imports all the definitions from the outer scope so we can use them here.
+        use super::*;
+
+        use ink_lang as ink;
+        use test_utils::{
+            advance_blocks,
+            set_caller,
+        };
+
+        /// Returns a sale of token `1` with a price falling from `100` to `40`
+        /// over four blocks.
+        fn sale() -> DutchAuction {
+            DutchAuction::new(AccountId::from([0x07; 32]), 1, 100, 40, 4)
+        }
+
+        #[ink::test]
+        fn new_works() {
+            let sale = sale();
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            assert_eq!(sale.seller(), accounts.alice);
+            assert_eq!(sale.token(), (AccountId::from([0x07; 32]), 1));
+            assert_eq!(sale.buyer(), None);
+            assert!(sale.is_open());
+        }
+
+        #[ink::test]
+        #[should_panic(expected = "invalid prices")]
+        fn new_rejects_rising_price() {
+            DutchAuction::new(AccountId::from([0x07; 32]), 1, 40, 100, 4);
+        }
+
+        #[ink::test]
+        fn price_falls_linearly() {
+            let sale = sale();
+
+            assert_eq!(sale.price(), 100);
+            advance_blocks!(1);
+            assert_eq!(sale.price(), 85);
+            advance_blocks!(2);
+            assert_eq!(sale.price(), 55);
+            advance_blocks!(1);
+            assert_eq!(sale.price(), 40);
+            advance_blocks!(1);
+            assert_eq!(sale.price(), 40);
+        }
+
+        #[ink::test]
+        fn buy_needs_current_price() {
+            let mut sale = sale();
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            advance_blocks!(2);
+            set_caller!(accounts.bob, 69);
+            assert_eq!(sale.buy(), Err(Error::InsufficientPayment));
+            assert!(sale.is_open());
+        }
+
+        #[ink::test]
+        fn cancel_closes_sale() {
+            let mut sale = sale();
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            set_caller!(accounts.bob, 0);
+            assert_eq!(sale.cancel(), Err(Error::NotSeller));
+            ink_env::test::pop_execution_context();
+
+            assert_eq!(sale.cancel(), Ok(()));
+            assert!(!sale.is_open());
+            assert_eq!(sale.cancel(), Err(Error::SaleClosed));
+            set_caller!(accounts.bob, 100);
+            assert_eq!(sale.buy(), Err(Error::SaleClosed));
+        }
+    }
+}
\ No newline at end of file
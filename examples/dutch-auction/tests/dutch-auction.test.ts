@@ -0,0 +1,82 @@
+import BN from 'bn.js';
+import { expect } from 'chai';
+import { patract, network } from 'redspot';
+
+const { getContractFactory, getRandomSigner } = patract;
+
+const { api, getSigners } = network;
+
+describe('DUTCH AUCTION', () => {
+  after(() => {
+    return api.disconnect();
+  });
+
+  async function setup() {
+    const one = new BN(10).pow(new BN(api.registry.chainDecimals[0]));
+    const signers = await getSigners();
+    const Alice = signers[0];
+    const sender = await getRandomSigner(Alice, one.muln(10000));
+    const buyer = await getRandomSigner(Alice, one.muln(10000));
+    const nftFactory = await getContractFactory('erc721', sender);
+    const nft = await nftFactory.deploy('new');
+    await nft.tx.mint(1);
+    const contractFactory = await getContractFactory('dutch_auction', sender);
+    const contract = await contractFactory.deploy(
+      'new',
+      nft.address,
+      1,
+      one.muln(100),
+      one.muln(40),
+      10
+    );
+    await nft.tx.approve(contract.address, 1);
+
+    return { sender, buyer, nft, contractFactory, contract, Alice, one };
+  }
+
+  it('Lowers the price over blocks', async () => {
+    const { contract, nft, one } = await setup();
+
+    const price = async () =>
+      new BN((await contract.query.price()).output?.toString());
+
+    const first = await price();
+    // Sealing a block without changing anything.
+    await nft.tx.approve(contract.address, 1);
+    const second = await price();
+
+    expect(first.lte(one.muln(100))).to.equal(true);
+    expect(second.lt(first)).to.equal(true);
+  });
+
+  it('Sells the token to the first buyer', async () => {
+    const { contract, nft, buyer, one } = await setup();
+
+    await expect(
+      contract.tx.buy({ signer: buyer, value: one.muln(100) })
+    ).to.emit(contract, 'Purchased');
+    expect((await nft.query.ownerOf(1)).output).to.equal(buyer.address);
+    expect((await contract.query.isOpen()).output).to.equal(false);
+
+    await expect(
+      contract.tx.buy({ value: one.muln(100) })
+    ).to.not.emit(contract, 'Purchased');
+  });
+
+  it('Can not buy below the current price', async () => {
+    const { contract, buyer, one } = await setup();
+
+    await expect(
+      contract.tx.buy({ signer: buyer, value: one.muln(39) })
+    ).to.not.emit(contract, 'Purchased');
+  });
+
+  it('Can not buy after the seller cancelled', async () => {
+    const { contract, buyer, one } = await setup();
+
+    await expect(contract.tx.cancel()).to.emit(contract, 'Cancelled');
+    await expect(
+      contract.tx.buy({ signer: buyer, value: one.muln(100) })
+    ).to.not.emit(contract, 'Purchased');
+  });
+});
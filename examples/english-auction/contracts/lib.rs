@@ -0,0 +1,539 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use ink_lang as ink;
+
+#[ink::contract]
+mod english_auction {
+    use erc20::TRANSFER_SELECTOR;
+    use ink_env::{
+        call::{
+            build_call,
+            utils::ReturnType,
+            ExecutionInput,
+            Selector,
+        },
+        DefaultEnvironment,
+        ReturnFlags,
+    };
+    use ink_storage::traits::{
+        PackedLayout,
+        SpreadLayout,
+    };
+    #[cfg(not(feature = "ink-as-dependency"))]
+    use ink_storage::{
+        collections::HashMap as StorageHashMap,
+        lazy::Lazy,
+    };
+
+    /// An auction ID.
+    pub type AuctionId = u32;
+
+    /// The ID type of the erc721 example's tokens.
+    pub type TokenId = u32;
+
+    /// Selector of the `transfer_from(from, to, value) -> Result<()>` message
+    /// shared by the erc20 and erc721 examples.
+    ///
+    /// Not imported from the erc20 crate since it is called on the erc721
+    /// contract too.
+    const TRANSFER_FROM_SELECTOR: [u8; 4] = [0x0B, 0x39, 0x6F, 0x18];
+
+    /// An auction of an escrowed erc721 token.
+    #[derive(
+        Debug,
+        Clone,
+        PartialEq,
+        Eq,
+        scale::Encode,
+        scale::Decode,
+        SpreadLayout,
+        PackedLayout,
+    )]
+    #[cfg_attr(
+        feature = "std",
+        derive(::scale_info::TypeInfo, ::ink_storage::traits::StorageLayout)
+    )]
+    pub struct Auction {
+        /// The account selling the token.
+        pub seller: AccountId,
+        /// The erc721 contract of the token.
+        pub nft: AccountId,
+        /// The ID of the token.
+        pub token_id: TokenId,
+        /// The lowest bid accepted.
+        pub reserve_price: Balance,
+        /// The last block in which bids are accepted.
+        pub end: BlockNumber,
+        /// The highest bid and its bidder, if any.
+        pub highest_bid: Option<(AccountId, Balance)>,
+        /// Whether the auction was settled.
+        pub settled: bool,
+    }
+
+    /// Sells erc721 tokens to the highest bidder in an erc20 token.
+    ///
+    /// Sellers escrow their token in the contract when creating an auction.
+    /// Bids are escrowed as well and a bidder who is outbid withdraws its bid
+    /// with `withdraw`. Once an auction ended anyone can settle it, sending the
+    /// token to the highest bidder and the bid to the seller, or the token back
+    /// to the seller if nobody bid.
+    ///
+    /// Outbid bids are not sent back from within `bid`: a refund that fails,
+    /// for example because the payment token froze the previous bidder, would
+    /// make every higher bid fail too.
+    #[ink(storage)]
+    pub struct EnglishAuction {
+        /// The erc20 token bids are made in.
+        payment_token: AccountId,
+        /// Mapping from ID to auction.
+        auctions: StorageHashMap<AuctionId, Auction>,
+        /// The ID the next auction gets.
+        next_auction_id: Lazy<AuctionId>,
+        /// Mapping from bidder to the outbid bids it can withdraw.
+        pending_returns: StorageHashMap<AccountId, Balance>,
+    }
+
+    /// Event emitted when an auction is created.
+    #[ink(event)]
+    pub struct AuctionCreated {
+        #[ink(topic)]
+        id: AuctionId,
+        #[ink(topic)]
+        seller: AccountId,
+        nft: AccountId,
+        token_id: TokenId,
+        end: BlockNumber,
+    }
+
+    /// Event emitted when a bid becomes the highest bid of an auction.
+    #[ink(event)]
+    pub struct BidPlaced {
+        #[ink(topic)]
+        id: AuctionId,
+        #[ink(topic)]
+        bidder: AccountId,
+        amount: Balance,
+    }
+
+    /// Event emitted when a bidder withdraws its outbid bids.
+    #[ink(event)]
+    pub struct Withdrawn {
+        #[ink(topic)]
+        bidder: AccountId,
+        amount: Balance,
+    }
+
+    /// Event emitted when an auction is settled.
+    ///
+    /// `winner` is `None` if nobody bid.
+    #[ink(event)]
+    pub struct AuctionSettled {
+        #[ink(topic)]
+        id: AuctionId,
+        winner: Option<AccountId>,
+        amount: Balance,
+    }
+
+    /// The auction error types.
+    #[derive(Debug, PartialEq, Eq, scale::Encode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum Error {
+        /// Returned if no auction has the given ID.
+        AuctionNotFound,
+        /// Returned if the auction ended.
+        AuctionEnded,
+        /// Returned if the auction did not end yet.
+        AuctionNotEnded,
+        /// Returned if the auction was settled.
+        AlreadySettled,
+        /// Returned if a bid is below the reserve price or not above the
+        /// highest bid.
+        BidTooLow,
+        /// Returned if a token could not be transferred.
+        TransferFailed,
+    }
+
+    /// The auction result type.
+    pub type Result<T> = core::result::Result<T, Error>;
+
+    impl EnglishAuction {
+        /// Creates an auction house taking bids in `payment_token`.
+        #[ink(constructor)]
+        pub fn new(payment_token: AccountId) -> Self {
+            Self {
+                payment_token,
+                auctions: StorageHashMap::new(),
+                next_auction_id: Lazy::new(0),
+                pending_returns: StorageHashMap::new(),
+            }
+        }
+
+        /// Returns the erc20 token bids are made in.
+        #[ink(message)]
+        pub fn payment_token(&self) -> AccountId {
+            self.payment_token
+        }
+
+        /// Returns the auction with ID `id`, if any.
+        #[ink(message)]
+        pub fn auction(&self, id: AuctionId) -> Option<Auction> {
+            self.auctions.get(&id).cloned()
+        }
+
+        /// Returns the outbid bids `bidder` can withdraw.
+        #[ink(message)]
+        pub fn pending_return(&self, bidder: AccountId) -> Balance {
+            self.pending_returns.get(&bidder).copied().unwrap_or(0)
+        }
+
+        /// Auctions the token `token_id` of the erc721 contract `nft` for at
+        /// least `reserve_price`, taking bids for `duration` blocks, and returns
+        /// the auction ID.
+        ///
+        /// The caller has to approve this contract for the token first, which
+        /// is escrowed until the auction is settled. On success an
+        /// `AuctionCreated` event is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `TransferFailed` error if the token could not be transferred
+        /// from the caller.
+        #[ink(message)]
+        pub fn create(
+            &mut self,
+            nft: AccountId,
+            token_id: TokenId,
+            reserve_price: Balance,
+            duration: BlockNumber,
+        ) -> Result<AuctionId> {
+            let seller = self.env().caller();
+            let this = self.env().account_id();
+            // Nothing was written yet, so a failed transfer leaves no trace.
+            if !Self::transfer_from(nft, seller, this, token_id) {
+                return Err(Error::TransferFailed)
+            }
+            let end = self.env().block_number().saturating_add(duration);
+            let id = *self.next_auction_id;
+            *self.next_auction_id += 1;
+            self.auctions.insert(
+                id,
+                Auction {
+                    seller,
+                    nft,
+                    token_id,
+                    reserve_price,
+                    end,
+                    highest_bid: None,
+                    settled: false,
+                },
+            );
+            self.env().emit_event(AuctionCreated {
+                id,
+                seller,
+                nft,
+                token_id,
+                end,
+            });
+            Ok(id)
+        }
+
+        /// Bids `amount` payment tokens on the auction with ID `id`.
+        ///
+        /// The caller has to approve this contract to spend `amount` tokens
+        /// first. The previous highest bid is added to the pending returns of its
+        /// bidder. On success a `BidPlaced` event is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `AuctionNotFound` error if there is no such auction.
+        ///
+        /// Returns `AuctionEnded` error if the auction ended.
+        ///
+        /// Returns `BidTooLow` error if `amount` is below the reserve price or
+        /// not above the highest bid.
+        ///
+        /// Returns `TransferFailed` error, reverting all changes, if the bid can
+        /// not be escrowed.
+        #[ink(message)]
+        pub fn bid(&mut self, id: AuctionId, amount: Balance) -> Result<()> {
+            let bidder = self.env().caller();
+            self.place_bid(id, bidder, amount)?;
+            let (token, this) = (self.payment_token, self.env().account_id());
+            if !Self::transfer_from(token, bidder, this, amount) {
+                Self::revert(Error::TransferFailed)
+            }
+            self.env().emit_event(BidPlaced { id, bidder, amount });
+            Ok(())
+        }
+
+        /// Sends the caller its outbid bids and returns their amount.
+        ///
+        /// On success a `Withdrawn` event is emitted if there was anything to
+        /// withdraw.
+        ///
+        /// # Errors
+        ///
+        /// Returns `TransferFailed` error, reverting all changes, if the bids
+        /// can not be sent to the caller. They stay pending then.
+        #[ink(message)]
+        pub fn withdraw(&mut self) -> Result<Balance> {
+            let bidder = self.env().caller();
+            let amount = self.pending_returns.take(&bidder).unwrap_or(0);
+            if amount == 0 {
+                return Ok(0)
+            }
+            if !Self::transfer(self.payment_token, bidder, amount) {
+                Self::revert(Error::TransferFailed)
+            }
+            self.env().emit_event(Withdrawn { bidder, amount });
+            Ok(amount)
+        }
+
+        /// Settles the ended auction with ID `id`.
+        ///
+        /// Sends the token to the highest bidder and the highest bid to the
+        /// seller, or the token back to the seller if nobody bid. Anyone may
+        /// settle an auction. On success an `AuctionSettled` event is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `AuctionNotFound` error if there is no such auction.
+        ///
+        /// Returns `AuctionNotEnded` error if the auction did not end yet.
+        ///
+        /// Returns `AlreadySettled` error if the auction was settled.
+        ///
+        /// Returns `TransferFailed` error, reverting all changes, if a token can
+        /// not be transferred.
+        #[ink(message)]
+        pub fn settle(&mut self, id: AuctionId) -> Result<()> {
+            let now = self.env().block_number();
+            let auction = self.auctions.get_mut(&id).ok_or(Error::AuctionNotFound)?;
+            if now <= auction.end {
+                return Err(Error::AuctionNotEnded)
+            }
+            if auction.settled {
+                return Err(Error::AlreadySettled)
+            }
+            auction.settled = true;
+            let auction = auction.clone();
+            // ink! writes the contract storage back only when a message returns.
+            // Write it now so the erc721 contract, which the seller picked, already
+            // sees the auction as settled and can not settle it a second time.
+            ink_storage::traits::push_spread_root::<Self>(
+                self,
+                &ink_primitives::Key::from([0x00; 32]),
+            );
+            let (winner, amount) = match auction.highest_bid {
+                Some((bidder, amount)) => (Some(bidder), amount),
+                None => (None, 0),
+            };
+            let this = self.env().account_id();
+            let receiver = winner.unwrap_or(auction.seller);
+            if !Self::transfer_from(auction.nft, this, receiver, auction.token_id) {
+                Self::revert(Error::TransferFailed)
+            }
+            if amount > 0 && !Self::transfer(self.payment_token, auction.seller, amount) {
+                Self::revert(Error::TransferFailed)
+            }
+            self.env().emit_event(AuctionSettled { id, winner, amount });
+            Ok(())
+        }
+
+        /// Makes `amount` the highest bid of `bidder` on the auction with ID `id`
+        /// and adds the previous highest bid to the pending returns of its bidder.
+        ///
+        /// # Errors
+        ///
+        /// Returns `AuctionNotFound` error if there is no such auction.
+        ///
+        /// Returns `AuctionEnded` error if the auction ended.
+        ///
+        /// Returns `BidTooLow` error if `amount` is below the reserve price or
+        /// not above the highest bid.
+        fn place_bid(
+            &mut self,
+            id: AuctionId,
+            bidder: AccountId,
+            amount: Balance,
+        ) -> Result<()> {
+            let now = self.env().block_number();
+            let auction = self.auctions.get_mut(&id).ok_or(Error::AuctionNotFound)?;
+            if now > auction.end {
+                return Err(Error::AuctionEnded)
+            }
+            if amount < auction.reserve_price
+                || matches!(auction.highest_bid, Some((_, highest)) if amount <= highest)
+            {
+                return Err(Error::BidTooLow)
+            }
+            let previous = auction.highest_bid.replace((bidder, amount));
+            if let Some((previous_bidder, previous_amount)) = previous {
+                let pending = self.pending_return(previous_bidder);
+                self.pending_returns
+                    .insert(previous_bidder, pending + previous_amount);
+            }
+            Ok(())
+        }
+
+        /// Transfers `value` erc20 tokens of `token` from this contract to `to`
+        /// and returns whether that succeeded.
+        fn transfer(token: AccountId, to: AccountId, value: Balance) -> bool {
+            let result = build_call::<DefaultEnvironment>()
+                .callee(token)
+                .gas_limit(0)
+                .transferred_value(0)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(TRANSFER_SELECTOR))
+                        .push_arg(to)
+                        .push_arg(value),
+                )
+                .returns::<ReturnType<core::result::Result<(), u8>>>()
+                .fire();
+            matches!(result, Ok(Ok(())))
+        }
+
+        /// Calls `transfer_from(from, to, value)` of the erc20 or erc721 contract
+        /// `token` and returns whether that succeeded.
+        ///
+        /// `value` is the amount of erc20 tokens or the ID of the erc721 token.
+        fn transfer_from<V>(
+            token: AccountId,
+            from: AccountId,
+            to: AccountId,
+            value: V,
+        ) -> bool
+        where
+            V: scale::Encode,
+        {
+            let result = build_call::<DefaultEnvironment>()
+                .callee(token)
+                .gas_limit(0)
+                .transferred_value(0)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(TRANSFER_FROM_SELECTOR))
+                        .push_arg(from)
+                        .push_arg(to)
+                        .push_arg(value),
+                )
+                .returns::<ReturnType<core::result::Result<(), u8>>>()
+                .fire();
+            matches!(result, Ok(Ok(())))
+        }
+
+        /// Ends the current call with `error`, so the auction is left as it was
+        /// when escrowing the bid or moving the token fails, and withdrawn bids
+        /// stay pending when sending them fails.
+        fn revert(error: Error) -> ! {
+            ink_env::return_value::<Result<()>>(
+                ReturnFlags::default().set_reverted(true),
+                &Err(error),
+            )
+        }
+    }
+
+    /// Unit tests.
+    ///
+    /// Moving tokens is left to the redspot tests. The conditions are checked on an
+    /// auction inserted directly, and bids are placed through `place_bid`, which
+    /// `bid` uses.
+    #[cfg(test)]
+    mod tests {
+        /// Imports all the definitions from the outer scope so we can use them here.
+        use super::*;
+
+        use ink_lang as ink;
+        use test_utils::advance_blocks;
+
+        /// Returns an auction house with an auction of Bob's token `1` ending in
+        /// two blocks, with a reserve price of `10` and a highest bid of `20`.
+        fn auction_house() -> EnglishAuction {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let now = ink_env::block_number::<ink_env::DefaultEnvironment>()
+                .expect("Cannot get block number");
+            let mut auction_house = EnglishAuction::new(AccountId::from([0x07; 32]));
+            auction_house.auctions.insert(
+                0,
+                Auction {
+                    seller: accounts.bob,
+                    nft: AccountId::from([0x08; 32]),
+                    token_id: 1,
+                    reserve_price: 10,
+                    end: now + 2,
+                    highest_bid: Some((accounts.charlie, 20)),
+                    settled: false,
+                },
+            );
+            auction_house
+        }
+
+        #[ink::test]
+        fn new_works() {
+            let auction_house = EnglishAuction::new(AccountId::from([0x07; 32]));
+
+            assert_eq!(auction_house.payment_token(), AccountId::from([0x07; 32]));
+            assert_eq!(auction_house.auction(0), None);
+        }
+
+        #[ink::test]
+        fn bids_have_to_raise() {
+            let mut auction_house = auction_house();
+
+            assert_eq!(auction_house.bid(1, 30), Err(Error::AuctionNotFound));
+            assert_eq!(auction_house.bid(0, 20), Err(Error::BidTooLow));
+
+            let auction = auction_house.auctions.get_mut(&0).expect("auction exists");
+            auction.highest_bid = None;
+            assert_eq!(auction_house.bid(0, 9), Err(Error::BidTooLow));
+        }
+
+        #[ink::test]
+        fn outbid_bids_can_be_withdrawn() {
+            let mut auction_house = auction_house();
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            assert_eq!(auction_house.pending_return(accounts.charlie), 0);
+            assert_eq!(auction_house.place_bid(0, accounts.django, 30), Ok(()));
+            assert_eq!(auction_house.pending_return(accounts.charlie), 20);
+            assert_eq!(auction_house.place_bid(0, accounts.charlie, 40), Ok(()));
+            assert_eq!(auction_house.place_bid(0, accounts.django, 50), Ok(()));
+            assert_eq!(auction_house.pending_return(accounts.charlie), 60);
+            assert_eq!(auction_house.pending_return(accounts.django), 0);
+            assert_eq!(
+                auction_house
+                    .auction(0)
+                    .and_then(|auction| auction.highest_bid),
+                Some((accounts.django, 50))
+            );
+
+            // Alice was never outbid, so there is nothing to send.
+            assert_eq!(auction_house.withdraw(), Ok(0));
+            assert_eq!(ink_env::test::recorded_events().count(), 0);
+        }
+
+        #[ink::test]
+        fn bids_end_with_auction() {
+            let mut auction_house = auction_house();
+
+            advance_blocks!(2);
+            assert_eq!(auction_house.settle(0), Err(Error::AuctionNotEnded));
+            advance_blocks!(1);
+            assert_eq!(auction_house.bid(0, 30), Err(Error::AuctionEnded));
+        }
+
+        #[ink::test]
+        fn auctions_are_settled_once() {
+            let mut auction_house = auction_house();
+
+            assert_eq!(auction_house.settle(1), Err(Error::AuctionNotFound));
+            advance_blocks!(3);
+            let auction = auction_house.auctions.get_mut(&0).expect("auction exists");
+            auction.settled = true;
+            assert_eq!(auction_house.settle(0), Err(Error::AlreadySettled));
+        }
+    }
+}
\ No newline at end of file
@@ -0,0 +1,155 @@
+import BN from 'bn.js';
+import { expect } from 'chai';
+import { patract, network } from 'redspot';
+
+const { getContractFactory, getRandomSigner } = patract;
+
+const { api, getSigners } = network;
+
+const DURATION = 5;
+
+describe('ENGLISH AUCTION', () => {
+  after(() => {
+    return api.disconnect();
+  });
+
+  async function setup() {
+    const one = new BN(10).pow(new BN(api.registry.chainDecimals[0]));
+    const signers = await getSigners();
+    const Alice = signers[0];
+    const sender = await getRandomSigner(Alice, one.muln(10000));
+    const bidder = await getRandomSigner(Alice, one.muln(10000));
+    const otherBidder = await getRandomSigner(Alice, one.muln(10000));
+    const nftFactory = await getContractFactory('erc721', sender);
+    const nft = await nftFactory.deploy('new');
+    const tokenFactory = await getContractFactory('erc20', sender);
+    const token = await tokenFactory.deploy(
+      'new',
+      '1000',
+      'Redspot',
+      'RDS',
+      10
+    );
+    const contractFactory = await getContractFactory(
+      'english_auction',
+      sender
+    );
+    const contract = await contractFactory.deploy('new', token.address);
+
+    for (const account of [bidder, otherBidder]) {
+      await token.tx.transfer(account.address, 100);
+      await token.tx.approve(contract.address, 100, { signer: account });
+    }
+    await nft.tx.mint(1);
+    await nft.tx.approve(contract.address, 1);
+    await contract.tx.create(nft.address, 1, 10, DURATION);
+
+    // The development node seals a block for every transaction, so blocks
+    // pass by sending transactions which change nothing.
+    async function advanceBlocks(blocks: number) {
+      for (let i = 0; i < blocks; i++) {
+        await token.tx.approve(contract.address, 0);
+      }
+    }
+
+    return {
+      sender,
+      bidder,
+      otherBidder,
+      nft,
+      token,
+      contractFactory,
+      contract,
+      advanceBlocks,
+      Alice,
+      one
+    };
+  }
+
+  it('Escrows the auctioned token', async () => {
+    const { contract, nft } = await setup();
+
+    expect((await nft.query.ownerOf(1)).output).to.equal(contract.address);
+  });
+
+  it('Outbid bidders withdraw their bids', async () => {
+    const { contract, token, bidder, otherBidder } = await setup();
+
+    await expect(() =>
+      contract.tx.bid(0, 20, { signer: bidder })
+    ).to.changeTokenBalance(token, bidder, -20);
+    await expect(() =>
+      contract.tx.bid(0, 30, { signer: otherBidder })
+    ).to.changeTokenBalance(token, bidder, 0);
+    await expect(contract.tx.bid(0, 30, { signer: bidder })).to.not.emit(
+      contract,
+      'BidPlaced'
+    );
+
+    expect(
+      (await contract.query.pendingReturn(bidder.address)).output
+    ).to.equal(20);
+    await expect(() =>
+      contract.tx.withdraw({ signer: bidder })
+    ).to.changeTokenBalance(token, bidder, 20);
+    expect(
+      (await contract.query.pendingReturn(bidder.address)).output
+    ).to.equal(0);
+  });
+
+  it('A frozen bidder does not block higher bids', async () => {
+    const { contract, token, bidder, otherBidder } = await setup();
+
+    await contract.tx.bid(0, 20, { signer: bidder });
+    await token.tx.freeze(bidder.address);
+
+    await expect(contract.tx.bid(0, 30, { signer: otherBidder }))
+      .to.emit(contract, 'BidPlaced')
+      .withArgs(0, otherBidder.address, 30);
+    await expect(contract.tx.withdraw({ signer: bidder })).to.not.emit(
+      contract,
+      'Withdrawn'
+    );
+    expect(
+      (await contract.query.pendingReturn(bidder.address)).output
+    ).to.equal(20);
+
+    await token.tx.unfreeze(bidder.address);
+    await expect(contract.tx.withdraw({ signer: bidder }))
+      .to.emit(contract, 'Withdrawn')
+      .withArgs(bidder.address, 20);
+  });
+
+  it('Settles to the highest bidder', async () => {
+    const {
+      contract,
+      nft,
+      token,
+      sender,
+      bidder,
+      advanceBlocks
+    } = await setup();
+
+    await contract.tx.bid(0, 20, { signer: bidder });
+    await expect(contract.tx.settle(0)).to.not.emit(
+      contract,
+      'AuctionSettled'
+    );
+
+    await advanceBlocks(DURATION);
+    await expect(() => contract.tx.settle(0)).to.changeTokenBalance(
+      token,
+      sender,
+      20
+    );
+    expect((await nft.query.ownerOf(1)).output).to.equal(bidder.address);
+  });
+
+  it('Returns the token if nobody bid', async () => {
+    const { contract, nft, sender, advanceBlocks } = await setup();
+
+    await advanceBlocks(DURATION);
+    await expect(contract.tx.settle(0)).to.emit(contract, 'AuctionSettled');
+    expect((await nft.query.ownerOf(1)).output).to.equal(sender.address);
+  });
+});
@@ -0,0 +1,591 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use ink_lang as ink;
+
+#[ink::contract]
+mod erc1155 {
+    use ink_env::{
+        call::{
+            build_call,
+            utils::ReturnType,
+            ExecutionInput,
+            Selector,
+        },
+        DefaultEnvironment,
+        ReturnFlags,
+    };
+    use ink_prelude::vec::Vec;
+    #[cfg(not(feature = "ink-as-dependency"))]
+    use ink_storage::collections::HashMap as StorageHashMap;
+
+    /// A token ID.
+    pub type TokenId = u128;
+
+    /// Selector of the `on_erc1155_received(operator, from, id, value, data) -> bool`
+    /// message that `safe_transfer_from` invokes on receiving contracts.
+    ///
+    /// This is the selector ink! derives for a message named `on_erc1155_received`.
+    const ON_ERC1155_RECEIVED_SELECTOR: [u8; 4] = [0xC2, 0xEE, 0xD9, 0x98];
+
+    /// Selector of the
+    /// `on_erc1155_batch_received(operator, from, ids, values, data) -> bool`
+    /// message that `safe_batch_transfer_from` invokes on receiving contracts.
+    ///
+    /// This is the selector ink! derives for a message named
+    /// `on_erc1155_batch_received`.
+    const ON_ERC1155_BATCH_RECEIVED_SELECTOR: [u8; 4] = [0x16, 0x20, 0x49, 0x85];
+
+    /// An ERC-1155 multi-token contract.
+    ///
+    /// Every token ID is created with a fixed supply by `create`. An ID created
+    /// with a supply of `1` is a non-fungible token.
+    #[ink(storage)]
+    pub struct Erc1155 {
+        /// Mapping from `(owner, id)` to number of owned tokens.
+        balances: StorageHashMap<(AccountId, TokenId), Balance>,
+        /// Mapping from `(owner, operator)` for operators allowed to transfer
+        /// all tokens of the owner.
+        operator_approvals: StorageHashMap<(AccountId, AccountId), ()>,
+        /// The ID the next call to `create` assigns.
+        next_id: TokenId,
+    }
+
+    /// Event emitted when `value` tokens of `id` are moved by `operator`.
+    ///
+    /// `from` is `None` for created tokens.
+    #[ink(event)]
+    pub struct TransferSingle {
+        #[ink(topic)]
+        operator: AccountId,
+        #[ink(topic)]
+        from: Option<AccountId>,
+        #[ink(topic)]
+        to: Option<AccountId>,
+        id: TokenId,
+        value: Balance,
+    }
+
+    /// Event emitted when `values[i]` tokens of `ids[i]` are moved by `operator`
+    /// for every index `i`.
+    #[ink(event)]
+    pub struct TransferBatch {
+        #[ink(topic)]
+        operator: AccountId,
+        #[ink(topic)]
+        from: Option<AccountId>,
+        #[ink(topic)]
+        to: Option<AccountId>,
+        ids: Vec<TokenId>,
+        values: Vec<Balance>,
+    }
+
+    /// Event emitted when an operator is enabled or disabled for an owner.
+    /// The operator can manage all tokens of the owner.
+    #[ink(event)]
+    pub struct ApprovalForAll {
+        #[ink(topic)]
+        owner: AccountId,
+        #[ink(topic)]
+        operator: AccountId,
+        approved: bool,
+    }
+
+    /// The multi-token error types.
+    #[derive(Debug, PartialEq, Eq, scale::Encode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum Error {
+        /// Returned if not enough balance to fulfill a request is available.
+        InsufficientBalance,
+        /// Returned if the caller is neither the owner nor an operator of the owner.
+        NotApproved,
+        /// Returned if the recipient is the zero account.
+        ZeroRecipient,
+        /// Returned if an owner tries to make itself its operator.
+        SelfApproval,
+        /// Returned if the lists of a batch request differ in length.
+        LengthMismatch,
+        /// Returned if the receiving contract did not accept the tokens.
+        TransferRejected,
+    }
+
+    /// The multi-token result type.
+    pub type Result<T> = core::result::Result<T, Error>;
+
+    impl Erc1155 {
+        /// Creates a new multi-token contract without any tokens.
+        #[ink(constructor)]
+        pub fn new() -> Self {
+            Self {
+                balances: StorageHashMap::new(),
+                operator_approvals: StorageHashMap::new(),
+                next_id: 1,
+            }
+        }
+
+        /// Returns the number of tokens of `id` owned by `owner`.
+        ///
+        /// Returns `0` if the account is non-existent.
+        #[ink(message)]
+        pub fn balance_of(&self, owner: AccountId, id: TokenId) -> Balance {
+            self.balances.get(&(owner, id)).copied().unwrap_or(0)
+        }
+
+        /// Returns the number of tokens of `ids[i]` owned by `owners[i]` for every
+        /// index `i`.
+        ///
+        /// # Errors
+        ///
+        /// Returns `LengthMismatch` error if `owners` and `ids` differ in length.
+        #[ink(message)]
+        pub fn balance_of_batch(
+            &self,
+            owners: Vec<AccountId>,
+            ids: Vec<TokenId>,
+        ) -> Result<Vec<Balance>> {
+            if owners.len() != ids.len() {
+                return Err(Error::LengthMismatch)
+            }
+            Ok(owners
+                .into_iter()
+                .zip(ids)
+                .map(|(owner, id)| self.balance_of(owner, id))
+                .collect())
+        }
+
+        /// Returns `true` if `operator` may transfer all tokens of `owner`.
+        #[ink(message)]
+        pub fn is_approved_for_all(&self, owner: AccountId, operator: AccountId) -> bool {
+            self.operator_approvals.contains_key(&(owner, operator))
+        }
+
+        /// Enables or disables `operator` to transfer all tokens of the caller.
+        ///
+        /// An `ApprovalForAll` event is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `SelfApproval` error if `operator` is the caller.
+        #[ink(message)]
+        pub fn set_approval_for_all(
+            &mut self,
+            operator: AccountId,
+            approved: bool,
+        ) -> Result<()> {
+            let owner = self.env().caller();
+            if operator == owner {
+                return Err(Error::SelfApproval)
+            }
+            if approved {
+                self.operator_approvals.insert((owner, operator), ());
+            } else {
+                self.operator_approvals.take(&(owner, operator));
+            }
+            self.env().emit_event(ApprovalForAll {
+                owner,
+                operator,
+                approved,
+            });
+            Ok(())
+        }
+
+        /// Creates a new token ID with a supply of `value` owned by the caller and
+        /// returns it.
+        ///
+        /// A `TransferSingle` event from `None` is emitted.
+        #[ink(message)]
+        pub fn create(&mut self, value: Balance) -> TokenId {
+            let caller = self.env().caller();
+            let id = self.next_id;
+            self.next_id += 1;
+            self.balances.insert((caller, id), value);
+            self.env().emit_event(TransferSingle {
+                operator: caller,
+                from: None,
+                to: Some(caller),
+                id,
+                value,
+            });
+            id
+        }
+
+        /// Transfers `value` tokens of `id` from `from` to `to` and notifies `to` if
+        /// it is a contract.
+        ///
+        /// A receiving contract must implement the `on_erc1155_received` message
+        /// (see `ON_ERC1155_RECEIVED_SELECTOR`) and return `true` to accept the
+        /// tokens. If it returns `false` or traps the whole call is reverted.
+        ///
+        /// On success a `TransferSingle` event is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `NotApproved` error if the caller is neither `from` nor an
+        /// operator of `from`.
+        ///
+        /// Returns `ZeroRecipient` error if `to` is the zero account.
+        ///
+        /// Returns `InsufficientBalance` error if `from` owns less than `value`
+        /// tokens of `id`.
+        ///
+        /// Returns `TransferRejected` error, reverting all changes, if the receiving
+        /// contract does not accept the tokens.
+        #[ink(message)]
+        pub fn safe_transfer_from(
+            &mut self,
+            from: AccountId,
+            to: AccountId,
+            id: TokenId,
+            value: Balance,
+            data: Vec<u8>,
+        ) -> Result<()> {
+            let operator = self.env().caller();
+            self.ensure_transfer_allowed(operator, from, to)?;
+            if self.balance_of(from, id) < value {
+                return Err(Error::InsufficientBalance)
+            }
+            self.move_tokens(from, to, id, value);
+            self.env().emit_event(TransferSingle {
+                operator,
+                from: Some(from),
+                to: Some(to),
+                id,
+                value,
+            });
+            let accepted = build_call::<DefaultEnvironment>()
+                .callee(to)
+                .gas_limit(0)
+                .transferred_value(0)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(ON_ERC1155_RECEIVED_SELECTOR))
+                        .push_arg(operator)
+                        .push_arg(from)
+                        .push_arg(id)
+                        .push_arg(value)
+                        .push_arg(data),
+                )
+                .returns::<ReturnType<bool>>()
+                .fire();
+            Self::check_acceptance(accepted)
+        }
+
+        /// Transfers `values[i]` tokens of `ids[i]` from `from` to `to` for every
+        /// index `i` and notifies `to` once if it is a contract.
+        ///
+        /// A receiving contract must implement the `on_erc1155_batch_received`
+        /// message (see `ON_ERC1155_BATCH_RECEIVED_SELECTOR`) and return `true` to
+        /// accept the tokens. If it returns `false` or traps the whole call is
+        /// reverted.
+        ///
+        /// Either all transfers succeed or none of them do. On success a single
+        /// `TransferBatch` event is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `NotApproved` error if the caller is neither `from` nor an
+        /// operator of `from`.
+        ///
+        /// Returns `ZeroRecipient` error if `to` is the zero account.
+        ///
+        /// Returns `LengthMismatch` error if `ids` and `values` differ in length.
+        ///
+        /// Returns `InsufficientBalance` error if `from` owns less tokens of an ID
+        /// than the batch moves in total.
+        ///
+        /// Returns `TransferRejected` error, reverting all changes, if the receiving
+        /// contract does not accept the tokens.
+        #[ink(message)]
+        pub fn safe_batch_transfer_from(
+            &mut self,
+            from: AccountId,
+            to: AccountId,
+            ids: Vec<TokenId>,
+            values: Vec<Balance>,
+            data: Vec<u8>,
+        ) -> Result<()> {
+            let operator = self.env().caller();
+            self.ensure_transfer_allowed(operator, from, to)?;
+            self.ensure_batch_covered(from, &ids, &values)?;
+            for (&id, &value) in ids.iter().zip(&values) {
+                self.move_tokens(from, to, id, value);
+            }
+            self.env().emit_event(TransferBatch {
+                operator,
+                from: Some(from),
+                to: Some(to),
+                ids: ids.clone(),
+                values: values.clone(),
+            });
+            let accepted = build_call::<DefaultEnvironment>()
+                .callee(to)
+                .gas_limit(0)
+                .transferred_value(0)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(
+                        ON_ERC1155_BATCH_RECEIVED_SELECTOR,
+                    ))
+                    .push_arg(operator)
+                    .push_arg(from)
+                    .push_arg(ids)
+                    .push_arg(values)
+                    .push_arg(data),
+                )
+                .returns::<ReturnType<bool>>()
+                .fire();
+            Self::check_acceptance(accepted)
+        }
+
+        /// Returns `NotApproved` error if `operator` may not move the tokens of
+        /// `from` and `ZeroRecipient` error if `to` is the zero account.
+        fn ensure_transfer_allowed(
+            &self,
+            operator: AccountId,
+            from: AccountId,
+            to: AccountId,
+        ) -> Result<()> {
+            if operator != from && !self.is_approved_for_all(from, operator) {
+                return Err(Error::NotApproved)
+            }
+            if to == AccountId::from([0x0; 32]) {
+                return Err(Error::ZeroRecipient)
+            }
+            Ok(())
+        }
+
+        /// Returns `InsufficientBalance` error if `from` owns less tokens of an ID
+        /// than moving `values[i]` tokens of `ids[i]` for every index `i` takes.
+        ///
+        /// An ID may appear several times in `ids`, so the values are summed up
+        /// per ID before comparing them with the balances.
+        fn ensure_batch_covered(
+            &self,
+            from: AccountId,
+            ids: &[TokenId],
+            values: &[Balance],
+        ) -> Result<()> {
+            if ids.len() != values.len() {
+                return Err(Error::LengthMismatch)
+            }
+            let mut totals: Vec<(TokenId, Balance)> = Vec::new();
+            for (&id, &value) in ids.iter().zip(values) {
+                match totals.iter_mut().find(|(total_id, _)| *total_id == id) {
+                    Some((_, total)) => {
+                        *total =
+                            total.checked_add(value).ok_or(Error::InsufficientBalance)?
+                    }
+                    None => totals.push((id, value)),
+                }
+            }
+            if totals
+                .into_iter()
+                .any(|(id, total)| self.balance_of(from, id) < total)
+            {
+                return Err(Error::InsufficientBalance)
+            }
+            Ok(())
+        }
+
+        /// Moves `value` tokens of `id` from `from`, which must own them, to `to`.
+        fn move_tokens(
+            &mut self,
+            from: AccountId,
+            to: AccountId,
+            id: TokenId,
+            value: Balance,
+        ) {
+            let from_balance = self.balance_of(from, id);
+            self.balances.insert((from, id), from_balance - value);
+            let to_balance = self.balance_of(to, id);
+            self.balances.insert((to, id), to_balance + value);
+        }
+
+        /// Maps the answer of a receiving contract to the result of a safe
+        /// transfer, reverting all changes if the tokens were not accepted.
+        ///
+        /// Accounts without code accept all tokens.
+        fn check_acceptance(
+            accepted: core::result::Result<bool, ink_env::Error>,
+        ) -> Result<()> {
+            match accepted {
+                Ok(true) | Err(ink_env::Error::NotCallable) => Ok(()),
+                _ => Self::revert(Error::TransferRejected),
+            }
+        }
+
+        /// Ends the current call with `error`, undoing the transfer a receiving
+        /// contract rejected.
+        fn revert(error: Error) -> ! {
+            ink_env::return_value::<Result<()>>(
+                ReturnFlags::default().set_reverted(true),
+                &Err(error),
+            )
+        }
+    }
+
+    /// Unit tests.
+    ///
+    /// Safe transfers a recipient accepts are left to the redspot tests.
+    #[cfg(test)]
+    mod tests {
+        /// Imports all the definitions from the outer scope so we can use them here.
+        use super::*;
+
+        use ink_lang as ink;
+        use test_utils::set_caller;
+
+        #[ink::test]
+        fn create_works() {
+            let mut erc1155 = Erc1155::new();
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            assert_eq!(erc1155.create(100), 1);
+            assert_eq!(erc1155.create(1), 2);
+            assert_eq!(erc1155.balance_of(accounts.alice, 1), 100);
+            assert_eq!(erc1155.balance_of(accounts.alice, 2), 1);
+            assert_eq!(erc1155.balance_of(accounts.bob, 1), 0);
+            assert_eq!(ink_env::test::recorded_events().count(), 2);
+        }
+
+        #[ink::test]
+        fn balance_of_batch_works() {
+            let mut erc1155 = Erc1155::new();
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            erc1155.create(100);
+            erc1155.create(1);
+            assert_eq!(
+                erc1155.balance_of_batch(
+                    vec![accounts.alice, accounts.alice, accounts.bob],
+                    vec![1, 2, 1]
+                ),
+                Ok(vec![100, 1, 0])
+            );
+            assert_eq!(
+                erc1155.balance_of_batch(vec![accounts.alice], vec![1, 2]),
+                Err(Error::LengthMismatch)
+            );
+        }
+
+        #[ink::test]
+        fn set_approval_for_all_works() {
+            let mut erc1155 = Erc1155::new();
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            assert_eq!(
+                erc1155.set_approval_for_all(accounts.alice, true),
+                Err(Error::SelfApproval)
+            );
+            assert_eq!(erc1155.set_approval_for_all(accounts.bob, true), Ok(()));
+            assert!(erc1155.is_approved_for_all(accounts.alice, accounts.bob));
+            assert!(!erc1155.is_approved_for_all(accounts.bob, accounts.alice));
+            assert_eq!(erc1155.set_approval_for_all(accounts.bob, false), Ok(()));
+            assert!(!erc1155.is_approved_for_all(accounts.alice, accounts.bob));
+        }
+
+        #[ink::test]
+        fn transfers_fail_before_notifying() {
+            let mut erc1155 = Erc1155::new();
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            erc1155.create(100);
+            assert_eq!(
+                erc1155.safe_transfer_from(
+                    accounts.alice,
+                    accounts.bob,
+                    1,
+                    101,
+                    Vec::new()
+                ),
+                Err(Error::InsufficientBalance)
+            );
+            assert_eq!(
+                erc1155.safe_transfer_from(
+                    accounts.alice,
+                    AccountId::from([0x0; 32]),
+                    1,
+                    1,
+                    Vec::new()
+                ),
+                Err(Error::ZeroRecipient)
+            );
+            assert_eq!(
+                erc1155.safe_batch_transfer_from(
+                    accounts.alice,
+                    accounts.bob,
+                    vec![1],
+                    vec![1, 2],
+                    Vec::new()
+                ),
+                Err(Error::LengthMismatch)
+            );
+
+            set_caller!(accounts.bob);
+            assert_eq!(
+                erc1155.safe_transfer_from(
+                    accounts.alice,
+                    accounts.bob,
+                    1,
+                    1,
+                    Vec::new()
+                ),
+                Err(Error::NotApproved)
+            );
+            assert_eq!(erc1155.balance_of(accounts.alice, 1), 100);
+        }
+
+        #[ink::test]
+        fn batch_totals_are_checked_per_id() {
+            let mut erc1155 = Erc1155::new();
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            erc1155.create(100);
+            erc1155.create(1);
+            // Each value alone is covered, together they are not.
+            assert_eq!(
+                erc1155.safe_batch_transfer_from(
+                    accounts.alice,
+                    accounts.bob,
+                    vec![1, 2, 1],
+                    vec![60, 1, 60],
+                    Vec::new()
+                ),
+                Err(Error::InsufficientBalance)
+            );
+            assert_eq!(
+                erc1155.ensure_batch_covered(accounts.alice, &[1, 2, 1], &[60, 1, 40]),
+                Ok(())
+            );
+            assert_eq!(
+                erc1155.ensure_batch_covered(accounts.alice, &[1, 1], &[Balance::MAX, 1]),
+                Err(Error::InsufficientBalance)
+            );
+            assert_eq!(erc1155.balance_of(accounts.alice, 1), 100);
+            assert_eq!(erc1155.balance_of(accounts.bob, 1), 0);
+        }
+
+        #[ink::test]
+        fn move_tokens_works() {
+            let mut erc1155 = Erc1155::new();
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            erc1155.create(100);
+            erc1155.move_tokens(accounts.alice, accounts.bob, 1, 30);
+            assert_eq!(erc1155.balance_of(accounts.alice, 1), 70);
+            assert_eq!(erc1155.balance_of(accounts.bob, 1), 30);
+            // Moving tokens to their owner keeps the balance.
+            erc1155.move_tokens(accounts.bob, accounts.bob, 1, 30);
+            assert_eq!(erc1155.balance_of(accounts.bob, 1), 30);
+        }
+    }
+}
\ No newline at end of file
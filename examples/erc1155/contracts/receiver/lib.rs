@@ -0,0 +1,111 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use ink_lang as ink;
+
+#[ink::contract]
+mod erc1155_receiver {
+    use ink_prelude::vec::Vec;
+
+    /// A contract accepting or rejecting tokens sent by `safe_transfer_from` and
+    /// `safe_batch_transfer_from` of the `erc1155` contract.
+    #[ink(storage)]
+    pub struct Erc1155Receiver {
+        /// Whether incoming tokens are accepted.
+        accepting: bool,
+        /// The number of accepted transfers.
+        received: u32,
+    }
+
+    impl Erc1155Receiver {
+        /// Creates a receiver which accepts tokens if `accepting` is `true`.
+        #[ink(constructor)]
+        pub fn new(accepting: bool) -> Self {
+            Self {
+                accepting,
+                received: 0,
+            }
+        }
+
+        /// Sets whether incoming tokens are accepted.
+        #[ink(message)]
+        pub fn set_accepting(&mut self, accepting: bool) {
+            self.accepting = accepting;
+        }
+
+        /// Returns the number of accepted transfers.
+        #[ink(message)]
+        pub fn received(&self) -> u32 {
+            self.received
+        }
+
+        /// Called by the token contract after `value` tokens of `id` were moved
+        /// from `from` to this contract by `operator`.
+        #[ink(message)]
+        pub fn on_erc1155_received(
+            &mut self,
+            _operator: AccountId,
+            _from: AccountId,
+            _id: u128,
+            _value: Balance,
+            _data: Vec<u8>,
+        ) -> bool {
+            self.accept()
+        }
+
+        /// Called by the token contract after `values[i]` tokens of `ids[i]` were
+        /// moved from `from` to this contract by `operator` for every index `i`.
+        #[ink(message)]
+        pub fn on_erc1155_batch_received(
+            &mut self,
+            _operator: AccountId,
+            _from: AccountId,
+            _ids: Vec<u128>,
+            _values: Vec<Balance>,
+            _data: Vec<u8>,
+        ) -> bool {
+            self.accept()
+        }
+
+        /// Counts an incoming transfer if tokens are accepted.
+        fn accept(&mut self) -> bool {
+            if self.accepting {
+                self.received += 1;
+            }
+            self.accepting
+        }
+    }
+
+    /// Unit tests.
+    #[cfg(test)]
+    mod tests {
+        /// Imports all the definitions from the outer scope so we can use them here.
+        use super::*;
+
+        use ink_lang as ink;
+
+        #[ink::test]
+        fn accepts_while_accepting() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut receiver = Erc1155Receiver::new(true);
+
+            assert!(receiver.on_erc1155_received(
+                accounts.alice,
+                accounts.alice,
+                1,
+                10,
+                Vec::new()
+            ));
+            receiver.set_accepting(false);
+            assert!(!receiver.on_erc1155_batch_received(
+                accounts.alice,
+                accounts.alice,
+                vec![1],
+                vec![10],
+                Vec::new()
+            ));
+            assert_eq!(receiver.received(), 1);
+        }
+    }
+}
\ No newline at end of file
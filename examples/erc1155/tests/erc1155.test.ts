@@ -0,0 +1,145 @@
+import BN from 'bn.js';
+import { expect } from 'chai';
+import { patract, network } from 'redspot';
+
+const { getContractFactory, getRandomSigner } = patract;
+
+const { api, getSigners } = network;
+
+describe('ERC1155', () => {
+  after(() => {
+    return api.disconnect();
+  });
+
+  async function setup() {
+    const one = new BN(10).pow(new BN(api.registry.chainDecimals[0]));
+    const signers = await getSigners();
+    const Alice = signers[0];
+    const sender = await getRandomSigner(Alice, one.muln(10000));
+    const contractFactory = await getContractFactory('erc1155', sender);
+    const receiverFactory = await getContractFactory(
+      'erc1155_receiver',
+      sender
+    );
+    const contract = await contractFactory.deploy('new');
+    const receiver = await getRandomSigner();
+
+    // Token 1 is fungible, token 2 is non-fungible.
+    await contract.tx.create(100);
+    await contract.tx.create(1);
+
+    return {
+      sender,
+      contractFactory,
+      receiverFactory,
+      contract,
+      receiver,
+      Alice,
+      one
+    };
+  }
+
+  it('Assigns created tokens to the creator', async () => {
+    const { contract, sender } = await setup();
+
+    expect((await contract.query.balanceOf(sender.address, 1)).output).to.equal(
+      100
+    );
+    expect((await contract.query.balanceOf(sender.address, 2)).output).to.equal(
+      1
+    );
+  });
+
+  it('Transfers tokens to an account', async () => {
+    const { contract, sender, receiver } = await setup();
+
+    await expect(
+      contract.tx.safeTransferFrom(sender.address, receiver.address, 1, 7, [])
+    ).to.emit(contract, 'TransferSingle');
+
+    expect(
+      (await contract.query.balanceOf(receiver.address, 1)).output
+    ).to.equal(7);
+  });
+
+  it('Transfers a batch of fungible and non-fungible tokens', async () => {
+    const { contract, sender, receiver } = await setup();
+
+    await expect(
+      contract.tx.safeBatchTransferFrom(
+        sender.address,
+        receiver.address,
+        [1, 2],
+        [7, 1],
+        []
+      )
+    ).to.emit(contract, 'TransferBatch');
+
+    expect(
+      (await contract.query.balanceOf(receiver.address, 1)).output
+    ).to.equal(7);
+    expect(
+      (await contract.query.balanceOf(receiver.address, 2)).output
+    ).to.equal(1);
+    expect((await contract.query.balanceOf(sender.address, 2)).output).to.equal(
+      0
+    );
+  });
+
+  it('Operator can transfer tokens of the owner', async () => {
+    const { contract, sender, receiver, Alice, one } = await setup();
+
+    const operator = await getRandomSigner(Alice, one.muln(10000));
+
+    await expect(contract.tx.setApprovalForAll(operator.address, true))
+      .to.emit(contract, 'ApprovalForAll')
+      .withArgs(sender.address, operator.address, true);
+
+    await expect(
+      contract.tx.safeTransferFrom(sender.address, receiver.address, 1, 7, [], {
+        signer: operator
+      })
+    ).to.emit(contract, 'TransferSingle');
+  });
+
+  it('Notifies a receiving contract', async () => {
+    const { contract, receiverFactory, sender } = await setup();
+
+    const receiverContract = await receiverFactory.deploy('new', true);
+
+    await expect(
+      contract.tx.safeBatchTransferFrom(
+        sender.address,
+        receiverContract.address,
+        [1, 2],
+        [7, 1],
+        []
+      )
+    ).to.emit(contract, 'TransferBatch');
+
+    expect((await receiverContract.query.received()).output).to.equal(1);
+    expect(
+      (await contract.query.balanceOf(receiverContract.address, 2)).output
+    ).to.equal(1);
+  });
+
+  it('Can not transfer to a rejecting contract', async () => {
+    const { contract, receiverFactory, sender } = await setup();
+
+    const receiverContract = await receiverFactory.deploy('new', false);
+
+    await expect(
+      contract.tx.safeTransferFrom(
+        sender.address,
+        receiverContract.address,
+        1,
+        7,
+        []
+      )
+    ).to.not.emit(contract, 'TransferSingle');
+
+    expect((await contract.query.balanceOf(sender.address, 1)).output).to.equal(
+      100
+    );
+  });
+});
@@ -0,0 +1,99 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use ink_lang as ink;
+
+#[ink::contract]
+mod flash_borrower {
+    use ink_env::{
+        call::{
+            build_call,
+            utils::ReturnType,
+            ExecutionInput,
+            Selector,
+        },
+        DefaultEnvironment,
+    };
+    use ink_prelude::vec::Vec;
+
+    // The borrower lives in the workspace of the erc20 crate, where depending on
+    // it with `ink-as-dependency` would turn that feature on for the token contract
+    // too. So the selectors it exports are spelled out here.
+
+    /// Selector of the erc20 example's `transfer(to, value) -> Result<()>`.
+    const TRANSFER_SELECTOR: [u8; 4] = [0x84, 0xA1, 0x5D, 0xA1];
+
+    /// Selector of the erc20 example's `transfer_from(from, to, value) -> Result<()>`.
+    const TRANSFER_FROM_SELECTOR: [u8; 4] = [0x0B, 0x39, 0x6F, 0x18];
+
+    /// Selector of the erc20 example's `approve(spender, value) -> Result<()>`.
+    const APPROVE_SELECTOR: [u8; 4] = [0x68, 0x12, 0x66, 0xA0];
+
+    /// Borrows flash minted tokens of the erc20 example and spends them while
+    /// the flash mint lasts.
+    ///
+    /// The redspot tests use it to check that the minted tokens can be moved
+    /// during `on_flash_mint`: it sends them to `sink` and takes them back with
+    /// `transfer_from`, which `sink` has to approve first, before approving the
+    /// repayment.
+    #[ink(storage)]
+    pub struct FlashBorrower {
+        /// The erc20 token borrowed.
+        token: AccountId,
+        /// The account the borrowed tokens are moved to and back from.
+        sink: AccountId,
+    }
+
+    impl FlashBorrower {
+        /// Creates a borrower of `token` moving the borrowed tokens through
+        /// `sink`.
+        #[ink(constructor)]
+        pub fn new(token: AccountId, sink: AccountId) -> Self {
+            Self { token, sink }
+        }
+
+        /// Called by the token for a flash mint of `amount` tokens, which have
+        /// to be repaid with `fee`.
+        ///
+        /// Returns whether the borrowed tokens could be moved and the repayment
+        /// approved. Calls of anyone but the token are rejected.
+        #[ink(message)]
+        pub fn on_flash_mint(
+            &mut self,
+            _initiator: AccountId,
+            amount: Balance,
+            fee: Balance,
+            _data: Vec<u8>,
+        ) -> bool {
+            if self.env().caller() != self.token {
+                return false
+            }
+            let this = self.env().account_id();
+            self.call_token(
+                ExecutionInput::new(Selector::new(TRANSFER_SELECTOR))
+                    .push_arg(self.sink)
+                    .push_arg(amount),
+            ) && self.call_token(
+                ExecutionInput::new(Selector::new(TRANSFER_FROM_SELECTOR))
+                    .push_arg(self.sink)
+                    .push_arg(this)
+                    .push_arg(amount),
+            ) && self.call_token(
+                ExecutionInput::new(Selector::new(APPROVE_SELECTOR))
+                    .push_arg(self.token)
+                    .push_arg(amount + fee),
+            )
+        }
+
+        /// Sends `input` to the token and returns whether it returned `Ok`.
+        fn call_token<Args: scale::Encode>(&self, input: ExecutionInput<Args>) -> bool {
+            let result = build_call::<DefaultEnvironment>()
+                .callee(self.token)
+                .gas_limit(0)
+                .transferred_value(0)
+                .exec_input(input)
+                .returns::<ReturnType<core::result::Result<(), u8>>>()
+                .fire();
+            matches!(result, Ok(Ok(())))
+        }
+    }
+}
\ No newline at end of file
@@ -16,14 +16,151 @@
 
 use ink_lang as ink;
 
+/// Selector of `psp22_balance_of(owner) -> Balance`, which other contracts
+/// call to read the balance of an account with `build_call`.
+///
+/// Contracts depending on this crate with the `ink-as-dependency` feature use
+/// it instead of spelling the selector out, so they follow when it changes.
+/// It has to match the selector of `psp22_balance_of`.
+pub const BALANCE_OF_SELECTOR: [u8; 4] = [0x65, 0x68, 0x38, 0x2F];
+
+/// Selector of `transfer(to, value) -> Result<()>`, which other contracts call
+/// to pay out tokens they hold. It has to match the selector of `transfer`.
+pub const TRANSFER_SELECTOR: [u8; 4] = [0x84, 0xA1, 0x5D, 0xA1];
+
+/// Selector of `transfer_from(from, to, value) -> Result<()>`, which other
+/// contracts call to pull in tokens they were approved for. It has to match the
+/// selector of `transfer_from`.
+pub const TRANSFER_FROM_SELECTOR: [u8; 4] = [0x0B, 0x39, 0x6F, 0x18];
+
 #[ink::contract]
 mod erc20 {
+    use ink_env::{
+        call::{
+            build_call,
+            utils::ReturnType,
+            ExecutionInput,
+            Selector,
+        },
+        hash::Blake2x256,
+        DefaultEnvironment,
+        ReturnFlags,
+    };
+    use ink_prelude::{
+        string::String,
+        vec::Vec,
+    };
+    use ink_storage::traits::{
+        PackedLayout,
+        SpreadLayout,
+    };
     #[cfg(not(feature = "ink-as-dependency"))]
     use ink_storage::{
         collections::HashMap as StorageHashMap,
         lazy::Lazy,
     };
 
+    /// Selector of the `on_token_received(operator, from, value, data) -> bool`
+    /// message that `transfer_and_call` invokes on receiving contracts.
+    ///
+    /// This is the selector ink! derives for a message named `on_token_received`.
+    const ON_TOKEN_RECEIVED_SELECTOR: [u8; 4] = [0x41, 0xEB, 0x77, 0x88];
+
+    /// Tokens minted to an account that unlock linearly over time.
+    #[derive(
+        Debug,
+        Clone,
+        PartialEq,
+        Eq,
+        scale::Encode,
+        scale::Decode,
+        SpreadLayout,
+        PackedLayout,
+    )]
+    #[cfg_attr(
+        feature = "std",
+        derive(::scale_info::TypeInfo, ::ink_storage::traits::StorageLayout)
+    )]
+    pub struct VestingSchedule {
+        /// The total amount of tokens under this schedule.
+        amount: Balance,
+        /// The amount of tokens already released to the beneficiary.
+        released: Balance,
+        /// The timestamp from which the tokens start to vest.
+        start: Timestamp,
+        /// The time after `start` before which nothing can be released.
+        cliff: Timestamp,
+        /// The time after `start` at which all tokens are vested.
+        duration: Timestamp,
+    }
+
+    impl VestingSchedule {
+        /// Returns the amount of tokens vested at `now`, including released ones.
+        fn vested_amount(&self, now: Timestamp) -> Balance {
+            let elapsed = now.saturating_sub(self.start);
+            if elapsed < self.cliff {
+                return 0
+            }
+            if elapsed >= self.duration {
+                return self.amount
+            }
+            // Split `amount * elapsed / duration` so the multiplication can not
+            // overflow.
+            let (elapsed, duration) = (elapsed as Balance, self.duration as Balance);
+            self.amount / duration * elapsed + self.amount % duration * elapsed / duration
+        }
+    }
+
+    /// Descriptive token metadata for explorers and wallets.
+    ///
+    /// New fields may be appended in later versions, so consumers should not
+    /// rely on the encoded size.
+    #[derive(
+        Debug,
+        Default,
+        Clone,
+        PartialEq,
+        Eq,
+        scale::Encode,
+        scale::Decode,
+        SpreadLayout,
+        PackedLayout,
+    )]
+    #[cfg_attr(
+        feature = "std",
+        derive(::scale_info::TypeInfo, ::ink_storage::traits::StorageLayout)
+    )]
+    pub struct TokenInfo {
+        /// URI of the token logo.
+        pub logo_uri: Option<String>,
+        /// URL of the project behind the token.
+        pub project_url: Option<String>,
+        /// A short description of the token.
+        pub description: Option<String>,
+    }
+
+    /// Selector of the `on_flash_mint(initiator, amount, fee, data) -> bool`
+    /// message that `flash_mint` invokes on the receiving contract.
+    ///
+    /// This is the selector ink! derives for a message named `on_flash_mint`.
+    const ON_FLASH_MINT_SELECTOR: [u8; 4] = [0x03, 0x36, 0xE5, 0xE5];
+
+    /// The denominator of `transfer_fee_bps`, i.e. a fee of 100%.
+    const MAX_FEE_BPS: u16 = 10_000;
+
+    /// Selector of the PSP22 `transfer(to, value, data) -> PSP22Result<()>`
+    /// message that `rescue_tokens` invokes on other token contracts.
+    ///
+    /// This is the selector ink! derives for `PSP22::transfer`.
+    const PSP22_TRANSFER_SELECTOR: [u8; 4] = [0xDB, 0x20, 0xF9, 0xF5];
+
+    /// Selectors of the remaining state changing PSP22 messages, dispatched by
+    /// `multicall`.
+    const PSP22_TRANSFER_FROM_SELECTOR: [u8; 4] = [0x54, 0xB3, 0xC7, 0x6E];
+    const PSP22_APPROVE_SELECTOR: [u8; 4] = [0xB2, 0x0F, 0x1B, 0xBD];
+    const PSP22_INCREASE_ALLOWANCE_SELECTOR: [u8; 4] = [0x96, 0xD6, 0xB5, 0x7A];
+    const PSP22_DECREASE_ALLOWANCE_SELECTOR: [u8; 4] = [0xFE, 0xCB, 0x57, 0xD5];
+
     /// A simple ERC-20 contract.
     #[ink(storage)]
     pub struct Erc20 {
@@ -34,6 +171,52 @@ mod erc20 {
         /// Mapping of the token amount which an account is allowed to withdraw
         /// from another account.
         allowances: StorageHashMap<(AccountId, AccountId), Balance>,
+        /// Mapping from `(owner, spender)` to the last timestamp at which the
+        /// allowance can be used, for allowances given with an expiry.
+        allowance_expiries: StorageHashMap<(AccountId, AccountId), Timestamp>,
+        /// The account allowed to manage roles, pausing, freezing and snapshots.
+        owner: AccountId,
+        /// The account proposed as the next owner, if any.
+        pending_owner: Option<AccountId>,
+        /// Accounts holding the minter role.
+        minters: StorageHashMap<AccountId, ()>,
+        /// The token name.
+        name: Lazy<Option<String>>,
+        /// The token symbol.
+        symbol: Lazy<Option<String>>,
+        /// The number of decimals used by the token.
+        decimals: Lazy<u8>,
+        /// Descriptive metadata set by the owner.
+        token_info: Lazy<TokenInfo>,
+        /// The maximum total supply, if the token is capped.
+        cap: Lazy<Option<Balance>>,
+        /// Whether transfers and approvals are currently halted.
+        paused: bool,
+        /// Mapping from owner to the number of permits it has signed.
+        nonces: StorageHashMap<AccountId, u64>,
+        /// The id of the most recent snapshot, `0` if none was taken yet.
+        current_snapshot_id: u32,
+        /// Mapping from beneficiary to its vesting schedules.
+        vesting_schedules: StorageHashMap<AccountId, Vec<VestingSchedule>>,
+        /// The fee charged on transfers in basis points of the transferred value.
+        transfer_fee_bps: u16,
+        /// The account receiving transfer and flash mint fees.
+        fee_collector: AccountId,
+        /// The fee charged on flash mints in basis points of the minted amount.
+        flash_fee_bps: u16,
+        /// Mapping from owner to the account its voting power is delegated to.
+        delegates: StorageHashMap<AccountId, AccountId>,
+        /// Mapping from delegate to its `(block number, votes)` checkpoints in
+        /// ascending block order.
+        vote_checkpoints: StorageHashMap<AccountId, Vec<(BlockNumber, Balance)>>,
+        /// Accounts that can neither send nor receive transfers.
+        frozen: StorageHashMap<AccountId, ()>,
+        /// Mapping from owner to its `(snapshot id, balance)` checkpoints in
+        /// ascending snapshot id order.
+        account_snapshots: StorageHashMap<AccountId, Vec<(u32, Balance)>>,
+        /// The `(snapshot id, total supply)` checkpoints in ascending snapshot id
+        /// order.
+        total_supply_snapshots: Lazy<Vec<(u32, Balance)>>,
     }
 
     /// Event emitted when a token transfer occurs.
@@ -47,8 +230,21 @@ mod erc20 {
         value: Balance,
     }
 
+    /// Event emitted once for a whole `transfer_batch` call in place of one
+    /// `Transfer` event per recipient.
+    #[ink(event)]
+    pub struct BatchTransfer {
+        #[ink(topic)]
+        from: AccountId,
+        transfers: Vec<(AccountId, Balance)>,
+        total: Balance,
+    }
+
     /// Event emitted when an approval occurs that `spender` is allowed to withdraw
     /// up to the amount of `value` tokens from `owner`.
+    ///
+    /// It is also emitted when `spender` spends allowance, `value` is always the
+    /// allowance remaining after the operation.
     #[ink(event)]
     pub struct Approval {
         #[ink(topic)]
@@ -59,7 +255,122 @@ mod erc20 {
         value: Balance,
     }
 
+    /// Event emitted when `value` new tokens are minted to `to`.
+    #[ink(event)]
+    pub struct Mint {
+        #[ink(topic)]
+        to: AccountId,
+        #[ink(topic)]
+        value: Balance,
+    }
+
+    /// Event emitted when `amount` tokens are minted to `to` under a vesting schedule.
+    #[ink(event)]
+    pub struct VestingScheduleCreated {
+        #[ink(topic)]
+        to: AccountId,
+        amount: Balance,
+        start: Timestamp,
+        cliff: Timestamp,
+        duration: Timestamp,
+    }
+
+    /// Event emitted when `value` vested tokens are released to `account`.
+    #[ink(event)]
+    pub struct Released {
+        #[ink(topic)]
+        account: AccountId,
+        value: Balance,
+    }
+
+    /// Event emitted when `value` tokens owned by `from` are burned.
+    #[ink(event)]
+    pub struct Burn {
+        #[ink(topic)]
+        from: AccountId,
+        #[ink(topic)]
+        value: Balance,
+    }
+
+    /// Event emitted when the owner proposes `proposed` as the next owner.
+    #[ink(event)]
+    pub struct OwnershipProposed {
+        #[ink(topic)]
+        owner: AccountId,
+        #[ink(topic)]
+        proposed: AccountId,
+    }
+
+    /// Event emitted when ownership moves from `previous` to `owner`.
+    #[ink(event)]
+    pub struct OwnershipTransferred {
+        #[ink(topic)]
+        previous: AccountId,
+        #[ink(topic)]
+        owner: AccountId,
+    }
+
+    /// Event emitted when the owner pauses the contract.
+    #[ink(event)]
+    pub struct Paused {
+        #[ink(topic)]
+        account: AccountId,
+    }
+
+    /// Event emitted when the owner unpauses the contract.
+    #[ink(event)]
+    pub struct Unpaused {
+        #[ink(topic)]
+        account: AccountId,
+    }
+
+    /// Event emitted when the owner freezes `account`.
+    #[ink(event)]
+    pub struct Frozen {
+        #[ink(topic)]
+        account: AccountId,
+    }
+
+    /// Event emitted when the owner unfreezes `account`.
+    #[ink(event)]
+    pub struct Unfrozen {
+        #[ink(topic)]
+        account: AccountId,
+    }
+
+    /// Event emitted when `delegator` moves its voting power from `from_delegate`
+    /// to `to_delegate`.
+    #[ink(event)]
+    pub struct DelegateChanged {
+        #[ink(topic)]
+        delegator: AccountId,
+        #[ink(topic)]
+        from_delegate: Option<AccountId>,
+        #[ink(topic)]
+        to_delegate: AccountId,
+    }
+
+    /// Event emitted when the voting power of `delegate` changes.
+    #[ink(event)]
+    pub struct DelegateVotesChanged {
+        #[ink(topic)]
+        delegate: AccountId,
+        previous: Balance,
+        new: Balance,
+    }
+
+    /// Event emitted when the owner takes a new snapshot.
+    #[ink(event)]
+    pub struct Snapshot {
+        #[ink(topic)]
+        id: u32,
+    }
+
     /// The ERC-20 error types.
+    ///
+    /// `InsufficientBalance`, `InsufficientAllowance`, `ZeroRecipient`,
+    /// `ZeroSender` and `Custom` correspond to the `PSP22Error` variants of the
+    /// same meaning.
     #[derive(Debug, PartialEq, Eq, scale::Encode)]
     #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
     pub enum Error {
@@ -67,22 +378,183 @@ mod erc20 {
         InsufficientBalance,
         /// Returned if not enough allowance to fulfill a request is available.
         InsufficientAllowance,
+        /// Returned if tokens would be sent or minted to the zero address.
+        ZeroRecipient,
+        /// Returned if tokens would be sent from the zero address.
+        ZeroSender,
+        /// Returned if the caller is not the contract owner.
+        NotOwner,
+        /// Returned if the caller is not the proposed next owner.
+        NotPendingOwner,
+        /// Returned if the caller does not hold the minter role.
+        NotMinter,
+        /// Returned if minting would push the total supply above the cap.
+        CapExceeded,
+        /// Returned if a vesting schedule has a cliff longer than its duration.
+        InvalidSchedule,
+        /// Returned if the contract is paused.
+        Paused,
+        /// Returned if a permit is submitted after its deadline.
+        PermitExpired,
+        /// Returned if a permit signature was not produced by the owner.
+        InvalidSignature,
+        /// Returned if a transfer fee above 100% is configured.
+        InvalidFee,
+        /// Returned if past votes are requested for a block that is not mined yet.
+        BlockNotMined,
+        /// Returned if the receiver of a flash mint rejected it or did not
+        /// approve the repayment.
+        FlashMintFailed,
+        /// Returned if the sender or recipient of a transfer is frozen.
+        AccountFrozen,
+        /// Returned if the receiving contract rejected or failed to handle a
+        /// `transfer_and_call`.
+        TransferRejected,
+        /// Returned if the tokens to rescue are this contract's own tokens or the
+        /// other token contract failed to transfer them.
+        RescueFailed,
+        /// Returned if a `multicall` entry has an unknown selector or can not be
+        /// decoded.
+        InvalidCall,
+        /// Returned if an addition would exceed `Balance::MAX`.
+        Overflow,
+        /// Returned if a subtraction would go below `0`.
+        Underflow,
+        /// Returned if an allowance is used after its expiry.
+        AllowanceExpired,
+        /// Custom error type for cases not covered by the other variants.
+        Custom(String),
     }
 
     /// The ERC-20 result type.
     pub type Result<T> = core::result::Result<T, Error>;
 
+    /// The error type of the PSP22 token standard.
+    ///
+    /// Returned by the `psp22_*` messages so that callers written against PSP22
+    /// can decode it. Errors without a PSP22 counterpart become `Custom`.
+    #[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum PSP22Error {
+        /// Custom error type for cases not covered by the standard.
+        Custom(String),
+        /// Returned if not enough balance to fulfill a request is available.
+        InsufficientBalance,
+        /// Returned if not enough allowance to fulfill a request is available.
+        InsufficientAllowance,
+        /// Returned if the recipient's address is zero.
+        ZeroRecipientAddress,
+        /// Returned if the sender's address is zero.
+        ZeroSenderAddress,
+        /// Returned if a receiving contract rejected the transfer.
+        SafeTransferCheckFailed(String),
+    }
+
+    /// The PSP22 result type.
+    pub type PSP22Result<T> = core::result::Result<T, PSP22Error>;
+
+    impl From<Error> for PSP22Error {
+        fn from(error: Error) -> Self {
+            let custom = match error {
+                Error::InsufficientBalance => return PSP22Error::InsufficientBalance,
+                Error::InsufficientAllowance => return PSP22Error::InsufficientAllowance,
+                Error::ZeroRecipient => return PSP22Error::ZeroRecipientAddress,
+                Error::ZeroSender => return PSP22Error::ZeroSenderAddress,
+                Error::Custom(message) => return PSP22Error::Custom(message),
+                Error::TransferRejected => {
+                    return PSP22Error::SafeTransferCheckFailed(String::from(
+                        "TransferRejected",
+                    ))
+                }
+                Error::NotOwner => "NotOwner",
+                Error::NotPendingOwner => "NotPendingOwner",
+                Error::NotMinter => "NotMinter",
+                Error::CapExceeded => "CapExceeded",
+                Error::InvalidSchedule => "InvalidSchedule",
+                Error::Paused => "Paused",
+                Error::PermitExpired => "PermitExpired",
+                Error::InvalidSignature => "InvalidSignature",
+                Error::InvalidFee => "InvalidFee",
+                Error::AccountFrozen => "AccountFrozen",
+                Error::FlashMintFailed => "FlashMintFailed",
+                Error::BlockNotMined => "BlockNotMined",
+                Error::RescueFailed => "RescueFailed",
+                Error::InvalidCall => "InvalidCall",
+                Error::Overflow => "Overflow",
+                Error::Underflow => "Underflow",
+                Error::AllowanceExpired => "AllowanceExpired",
+            };
+            PSP22Error::Custom(String::from(custom))
+        }
+    }
+
     impl Erc20 {
-        /// Creates a new ERC-20 contract with the specified initial supply.
+        /// Creates a new ERC-20 contract with the specified initial supply and token
+        /// metadata.
+        #[ink(constructor)]
+        pub fn new(
+            initial_supply: Balance,
+            name: Option<String>,
+            symbol: Option<String>,
+            decimals: u8,
+        ) -> Self {
+            Self::new_init(initial_supply, None, name, symbol, decimals)
+        }
+
+        /// Creates a new ERC-20 contract whose total supply can never exceed `cap`.
+        ///
+        /// # Panics
+        ///
+        /// If `initial_supply` is greater than `cap`.
         #[ink(constructor)]
-        pub fn new(initial_supply: Balance) -> Self {
+        pub fn new_capped(
+            initial_supply: Balance,
+            cap: Balance,
+            name: Option<String>,
+            symbol: Option<String>,
+            decimals: u8,
+        ) -> Self {
+            assert!(initial_supply <= cap, "initial supply exceeds the cap");
+            Self::new_init(initial_supply, Some(cap), name, symbol, decimals)
+        }
+
+        fn new_init(
+            initial_supply: Balance,
+            cap: Option<Balance>,
+            name: Option<String>,
+            symbol: Option<String>,
+            decimals: u8,
+        ) -> Self {
             let caller = Self::env().caller();
             let mut balances = StorageHashMap::new();
             balances.insert(caller, initial_supply);
+            let mut minters = StorageHashMap::new();
+            minters.insert(caller, ());
             let instance = Self {
                 total_supply: Lazy::new(initial_supply),
                 balances,
                 allowances: StorageHashMap::new(),
+                allowance_expiries: StorageHashMap::new(),
+                owner: caller,
+                pending_owner: None,
+                minters,
+                name: Lazy::new(name),
+                symbol: Lazy::new(symbol),
+                decimals: Lazy::new(decimals),
+                token_info: Lazy::new(TokenInfo::default()),
+                cap: Lazy::new(cap),
+                paused: false,
+                nonces: StorageHashMap::new(),
+                vesting_schedules: StorageHashMap::new(),
+                transfer_fee_bps: 0,
+                fee_collector: caller,
+                flash_fee_bps: 0,
+                delegates: StorageHashMap::new(),
+                vote_checkpoints: StorageHashMap::new(),
+                frozen: StorageHashMap::new(),
+                current_snapshot_id: 0,
+                account_snapshots: StorageHashMap::new(),
+                total_supply_snapshots: Lazy::new(Vec::new()),
             };
             Self::env().emit_event(Transfer {
                 from: None,
@@ -92,26 +564,127 @@ mod erc20 {
             instance
         }
 
+        /// `PSP22::total_supply`: returns the total token supply.
+        #[ink(message, selector = "0x162DF8C2")]
+        pub fn psp22_total_supply(&self) -> Balance {
+            *self.total_supply
+        }
+
         /// Returns the total token supply.
+        ///
+        /// Same as `psp22_total_supply`, kept under its own selector for existing
+        /// callers.
         #[ink(message)]
         pub fn total_supply(&self) -> Balance {
-            *self.total_supply
+            self.psp22_total_supply()
         }
 
-        /// Returns the account balance for the specified `owner`.
+        /// Returns the maximum total supply, or `None` if the token is uncapped.
+        #[ink(message)]
+        pub fn cap(&self) -> Option<Balance> {
+            *self.cap
+        }
+
+        /// `PSP22Metadata::token_name`: returns the token name.
+        #[ink(message, selector = "0x3D261BD4")]
+        pub fn psp22_token_name(&self) -> Option<String> {
+            (*self.name).clone()
+        }
+
+        /// Returns the token name.
+        ///
+        /// Same as `psp22_token_name`, kept under its own selector for existing
+        /// callers.
+        #[ink(message)]
+        pub fn token_name(&self) -> Option<String> {
+            self.psp22_token_name()
+        }
+
+        /// `PSP22Metadata::token_symbol`: returns the token symbol.
+        #[ink(message, selector = "0x34205BE5")]
+        pub fn psp22_token_symbol(&self) -> Option<String> {
+            (*self.symbol).clone()
+        }
+
+        /// Returns the token symbol.
+        ///
+        /// Same as `psp22_token_symbol`, kept under its own selector for existing
+        /// callers.
+        #[ink(message)]
+        pub fn token_symbol(&self) -> Option<String> {
+            self.psp22_token_symbol()
+        }
+
+        /// `PSP22Metadata::token_decimals`: returns the number of decimals used by
+        /// the token.
+        #[ink(message, selector = "0x7271B782")]
+        pub fn psp22_token_decimals(&self) -> u8 {
+            *self.decimals
+        }
+
+        /// Returns the number of decimals used by the token.
+        ///
+        /// Same as `psp22_token_decimals`, kept under its own selector for existing
+        /// callers.
+        #[ink(message)]
+        pub fn token_decimals(&self) -> u8 {
+            self.psp22_token_decimals()
+        }
+
+        /// Returns the descriptive token metadata.
+        #[ink(message)]
+        pub fn token_info(&self) -> TokenInfo {
+            (*self.token_info).clone()
+        }
+
+        /// Replaces the descriptive token metadata with `info`.
+        ///
+        /// # Errors
+        ///
+        /// Returns `NotOwner` error if the caller is not the contract owner.
+        #[ink(message)]
+        pub fn set_token_info(&mut self, info: TokenInfo) -> Result<()> {
+            self.ensure_owner()?;
+            *self.token_info = info;
+            Ok(())
+        }
+
+        /// `PSP22::balance_of`: returns the account balance for the specified
+        /// `owner`.
         ///
         /// Returns `0` if the account is non-existent.
+        ///
+        /// Keep `BALANCE_OF_SELECTOR` in sync with the selector.
+        #[ink(message, selector = "0x6568382F")]
+        pub fn psp22_balance_of(&self, owner: AccountId) -> Balance {
+            self.balances.get(&owner).copied().unwrap_or(0)
+        }
+
+        /// Returns the account balance for the specified `owner`.
+        ///
+        /// Same as `psp22_balance_of`, kept under its own selector for existing
+        /// callers.
         #[ink(message)]
         pub fn balance_of(&self, owner: AccountId) -> Balance {
-            self.balances.get(&owner).copied().unwrap_or(0)
+            self.psp22_balance_of(owner)
+        }
+
+        /// `PSP22::allowance`: returns the amount which `spender` is still
+        /// allowed to withdraw from `owner`.
+        ///
+        /// Returns `0` if no allowance has been set `0` or the allowance expired.
+        #[ink(message, selector = "0x4D47D921")]
+        pub fn psp22_allowance(&self, owner: AccountId, spender: AccountId) -> Balance {
+            self.spendable_allowance(owner, spender).unwrap_or(0)
         }
 
         /// Returns the amount which `spender` is still allowed to withdraw from `owner`.
         ///
-        /// Returns `0` if no allowance has been set `0`.
+        /// Same as `psp22_allowance`, kept under its own selector for existing
+        /// callers.
         #[ink(message)]
         pub fn allowance(&self, owner: AccountId, spender: AccountId) -> Balance {
-            self.allowances.get(&(owner, spender)).copied().unwrap_or(0)
+            self.psp22_allowance(owner, spender)
         }
 
         /// Transfers `value` amount of tokens from the caller's account to account `to`.
@@ -120,415 +693,3359 @@ mod erc20 {
         ///
         /// # Errors
         ///
+        /// Returns `Paused` error if the contract is paused.
+        ///
+        /// Returns `AccountFrozen` error if the sender or a recipient is frozen.
+        ///
+        /// Returns `ZeroRecipient` error if a recipient is the zero address.
+        ///
         /// Returns `InsufficientBalance` error if there are not enough tokens on
         /// the caller's account balance.
-        #[ink(message)]
+        ///
+        /// Keep `TRANSFER_SELECTOR` in sync with the selector.
+        #[ink(message, selector = "0x84A15DA1")]
         pub fn transfer(&mut self, to: AccountId, value: Balance) -> Result<()> {
+            self.ensure_not_paused()?;
             let from = self.env().caller();
             self.transfer_from_to(from, to, value)
         }
 
-        /// Allows `spender` to withdraw from the caller's account multiple times, up to
-        /// the `value` amount.
-        ///
-        /// If this function is called again it overwrites the current allowance with `value`.
-        ///
-        /// An `Approval` event is emitted.
-        #[ink(message)]
-        pub fn approve(&mut self, spender: AccountId, value: Balance) -> Result<()> {
-            let owner = self.env().caller();
-            self.allowances.insert((owner, spender), value);
-            self.env().emit_event(Approval {
-                owner,
-                spender,
-                value,
-            });
-            Ok(())
-        }
-
-        /// Transfers `value` tokens on the behalf of `from` to the account `to`.
+        /// Transfers `value` amount of tokens from the caller's account to account `to`
+        /// and notifies `to` if it is a contract.
         ///
-        /// This can be used to allow a contract to transfer tokens on ones behalf and/or
-        /// to charge fees in sub-currencies, for example.
+        /// A receiving contract must implement the `on_token_received` message (see
+        /// `ON_TOKEN_RECEIVED_SELECTOR`) and return `true` to accept the tokens. If it
+        /// returns `false` or traps the whole call is reverted. The receiver already
+        /// holds the tokens while `on_token_received` runs, so it can spend them
+        /// right away. Transfers to accounts without code behave like `transfer`.
         ///
         /// On success a `Transfer` event is emitted.
         ///
         /// # Errors
         ///
-        /// Returns `InsufficientAllowance` error if there are not enough tokens allowed
-        /// for the caller to withdraw from `from`.
+        /// Returns `Paused` error if the contract is paused.
+        ///
+        /// Returns `AccountFrozen` error if the sender or a recipient is frozen.
+        ///
+        /// Returns `ZeroRecipient` error if a recipient is the zero address.
         ///
         /// Returns `InsufficientBalance` error if there are not enough tokens on
-        /// the the account balance of `from`.
+        /// the caller's account balance.
+        ///
+        /// Returns `TransferRejected` error, reverting all changes, if the receiving
+        /// contract does not accept the tokens.
         #[ink(message)]
-        pub fn transfer_from(
+        pub fn transfer_and_call(
             &mut self,
-            from: AccountId,
             to: AccountId,
             value: Balance,
+            data: Vec<u8>,
         ) -> Result<()> {
-            let caller = self.env().caller();
-            let allowance = self.allowance(from, caller);
-            if allowance < value {
-                return Err(Error::InsufficientAllowance)
+            self.transfer(to, value)?;
+            self.flush();
+            let from = self.env().caller();
+            let accepted = build_call::<DefaultEnvironment>()
+                .callee(to)
+                .gas_limit(0)
+                .transferred_value(0)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(ON_TOKEN_RECEIVED_SELECTOR))
+                        .push_arg(from)
+                        .push_arg(from)
+                        .push_arg(value)
+                        .push_arg(data),
+                )
+                .returns::<ReturnType<bool>>()
+                .fire();
+            match accepted {
+                Ok(true) | Err(ink_env::Error::NotCallable) => {
+                    self.reload();
+                    Ok(())
+                }
+                _ => Self::revert(Error::TransferRejected),
             }
-            self.transfer_from_to(from, to, value)?;
-            self.allowances.insert((from, caller), allowance - value);
-            Ok(())
         }
 
-        /// Transfers `value` amount of tokens from the caller's account to account `to`.
+        /// Transfers tokens from the caller's account to every `(to, value)` pair in
+        /// `transfers`.
         ///
-        /// On success a `Transfer` event is emitted.
+        /// Either all transfers succeed or none of them do. On success a single
+        /// `BatchTransfer` event is emitted.
         ///
         /// # Errors
         ///
-        /// Returns `InsufficientBalance` error if there are not enough tokens on
-        /// the caller's account balance.
-        fn transfer_from_to(
+        /// Returns `Paused` error if the contract is paused.
+        ///
+        /// Returns `AccountFrozen` error if the sender or a recipient is frozen.
+        ///
+        /// Returns `ZeroRecipient` error if a recipient is the zero address.
+        ///
+        /// Returns `Overflow` error if the sum of all values exceeds `Balance::MAX`.
+        ///
+        /// Returns `InsufficientBalance` error if the caller's account balance does
+        /// not cover the sum of all values.
+        #[ink(message)]
+        pub fn transfer_batch(
             &mut self,
-            from: AccountId,
-            to: AccountId,
-            value: Balance,
+            transfers: Vec<(AccountId, Balance)>,
         ) -> Result<()> {
+            self.ensure_not_paused()?;
+            let from = self.env().caller();
+            let total = transfers
+                .iter()
+                .try_fold(0 as Balance, |total, (_, value)| total.checked_add(*value))
+                .ok_or(Error::Overflow)?;
+            self.ensure_not_frozen(from)?;
+            for (to, _) in transfers.iter() {
+                if Self::is_zero(to) {
+                    return Err(Error::ZeroRecipient)
+                }
+                self.ensure_not_frozen(*to)?;
+            }
             let from_balance = self.balance_of(from);
-            if from_balance < value {
+            if from_balance < total {
                 return Err(Error::InsufficientBalance)
             }
-            self.balances.insert(from, from_balance - value);
-            let to_balance = self.balance_of(to);
-            self.balances.insert(to, to_balance + value);
-            self.env().emit_event(Transfer {
-                from: Some(from),
-                to: Some(to),
-                value,
+            self.set_balance(from, from_balance - total)?;
+            let mut total_fee: Balance = 0;
+            for (to, value) in transfers.iter() {
+                // A fee never exceeds its value, so `total_fee` stays below `total`.
+                let fee = self.transfer_fee_of(*value);
+                total_fee += fee;
+                let to_balance = self
+                    .balance_of(*to)
+                    .checked_add(value - fee)
+                    .ok_or(Error::Overflow)?;
+                self.set_balance(*to, to_balance)?;
+            }
+            self.collect_fee(from, total_fee)?;
+            self.env().emit_event(BatchTransfer {
+                from,
+                transfers,
+                total,
             });
             Ok(())
         }
-    }
-
-    /// Unit tests.
-    #[cfg(test)]
-    mod tests {
-        /// Imports all the definitions from the outer scope so we can use them here.
-        use super::*;
-        use ink_env::{
-            hash::{
-                Blake2x256,
-                CryptoHash,
-                HashOutput,
-            },
-            Clear,
-        };
-
-        type Event = <Erc20 as ::ink_lang::BaseEvent>::Type;
 
-        use ink_lang as ink;
+        /// Allows `spender` to withdraw from the caller's account multiple times, up to
+        /// the `value` amount.
+        ///
+        /// If this function is called again it overwrites the current allowance with `value`.
+        /// The allowance does not expire, even if it had an expiry before.
+        ///
+        /// An `Approval` event is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `Paused` error if the contract is paused.
+        #[ink(message)]
+        pub fn approve(&mut self, spender: AccountId, value: Balance) -> Result<()> {
+            self.ensure_not_paused()?;
+            let owner = self.env().caller();
+            self.allowance_expiries.take(&(owner, spender));
+            self.set_allowance(owner, spender, value);
+            Ok(())
+        }
 
-        fn assert_transfer_event(
-            event: &ink_env::test::EmittedEvent,
-            expected_from: Option<AccountId>,
-            expected_to: Option<AccountId>,
-            expected_value: Balance,
-        ) {
-            let decoded_event = <Event as scale::Decode>::decode(&mut &event.data[..])
-                .expect("encountered invalid contract event data buffer");
-            if let Event::Transfer(Transfer { from, to, value }) = decoded_event {
-                assert_eq!(from, expected_from, "encountered invalid Transfer.from");
-                assert_eq!(to, expected_to, "encountered invalid Transfer.to");
-                assert_eq!(value, expected_value, "encountered invalid Trasfer.value");
-            } else {
-                panic!("encountered unexpected event kind: expected a Transfer event")
+        /// Like `approve`, but the allowance can only be used up to and including
+        /// the block timestamp `expiry`.
+        ///
+        /// `increase_allowance` and `decrease_allowance` keep the expiry and fail once
+        /// it passed, `approve` and `approve_with_expiry` replace it. Expired
+        /// allowances can be removed with `purge_expired_allowances`.
+        ///
+        /// An `Approval` event is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `Paused` error if the contract is paused.
+        #[ink(message)]
+        pub fn approve_with_expiry(
+            &mut self,
+            spender: AccountId,
+            value: Balance,
+            expiry: Timestamp,
+        ) -> Result<()> {
+            self.ensure_not_paused()?;
+            let owner = self.env().caller();
+            self.allowance_expiries.insert((owner, spender), expiry);
+            self.set_allowance(owner, spender, value);
+            Ok(())
+        }
+
+        /// Returns the last block timestamp at which the allowance of `spender`
+        /// over the tokens of `owner` can be used, if it has an expiry.
+        #[ink(message)]
+        pub fn allowance_expiry(
+            &self,
+            owner: AccountId,
+            spender: AccountId,
+        ) -> Option<Timestamp> {
+            self.allowance_expiries.get(&(owner, spender)).copied()
+        }
+
+        /// Removes all expired allowances and returns how many were removed.
+        ///
+        /// Anyone can call this to free the storage they occupy. An `Approval` event
+        /// with a value of `0` is emitted for every removed allowance.
+        ///
+        /// This walks over every allowance with an expiry, so its cost grows with
+        /// their number.
+        #[ink(message)]
+        pub fn purge_expired_allowances(&mut self) -> u32 {
+            let now = self.env().block_timestamp();
+            let expired = self
+                .allowance_expiries
+                .iter()
+                .filter(|(_, expiry)| **expiry < now)
+                .map(|(key, _)| *key)
+                .collect::<Vec<_>>();
+            for (owner, spender) in expired.iter().copied() {
+                self.allowance_expiries.take(&(owner, spender));
+                self.allowances.take(&(owner, spender));
+                self.env().emit_event(Approval {
+                    owner,
+                    spender,
+                    value: 0,
+                });
             }
-            fn encoded_into_hash<T>(entity: &T) -> Hash
-            where
-                T: scale::Encode,
-            {
-                let mut result = Hash::clear();
-                let len_result = result.as_ref().len();
-                let encoded = entity.encode();
-                let len_encoded = encoded.len();
-                if len_encoded <= len_result {
-                    result.as_mut()[..len_encoded].copy_from_slice(&encoded);
-                    return result
-                }
-                let mut hash_output =
-                    <<Blake2x256 as HashOutput>::Type as Default>::default();
-                <Blake2x256 as CryptoHash>::hash(&encoded, &mut hash_output);
-                let copy_len = core::cmp::min(hash_output.len(), len_result);
-                result.as_mut()[0..copy_len].copy_from_slice(&hash_output[0..copy_len]);
-                result
+            expired.len() as u32
+        }
+
+        /// Increases the allowance of `spender` over the caller's tokens by `delta_value`.
+        ///
+        /// An `Approval` event with the resulting allowance is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `Paused` error if the contract is paused.
+        ///
+        /// Returns `AllowanceExpired` error if the allowance has expired.
+        #[ink(message)]
+        pub fn increase_allowance(
+            &mut self,
+            spender: AccountId,
+            delta_value: Balance,
+        ) -> Result<()> {
+            self.ensure_not_paused()?;
+            let owner = self.env().caller();
+            let value = self
+                .spendable_allowance(owner, spender)?
+                .saturating_add(delta_value);
+            self.set_allowance(owner, spender, value);
+            Ok(())
+        }
+
+        /// Decreases the allowance of `spender` over the caller's tokens by `delta_value`.
+        ///
+        /// The allowance saturates at `0` if `delta_value` exceeds it.
+        ///
+        /// An `Approval` event with the resulting allowance is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `Paused` error if the contract is paused.
+        ///
+        /// Returns `AllowanceExpired` error if the allowance has expired.
+        #[ink(message)]
+        pub fn decrease_allowance(
+            &mut self,
+            spender: AccountId,
+            delta_value: Balance,
+        ) -> Result<()> {
+            self.ensure_not_paused()?;
+            let owner = self.env().caller();
+            let value = self
+                .spendable_allowance(owner, spender)?
+                .saturating_sub(delta_value);
+            self.set_allowance(owner, spender, value);
+            Ok(())
+        }
+
+        /// Transfers `value` tokens on the behalf of `from` to the account `to`.
+        ///
+        /// This can be used to allow a contract to transfer tokens on ones behalf and/or
+        /// to charge fees in sub-currencies, for example.
+        ///
+        /// On success a `Transfer` event and an `Approval` event with the remaining
+        /// allowance are emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `Paused` error if the contract is paused.
+        ///
+        /// Returns `AccountFrozen` error if the sender or a recipient is frozen.
+        ///
+        /// Returns `ZeroRecipient` error if `to` is the zero address.
+        ///
+        /// Returns `ZeroSender` error if `from` is the zero address.
+        ///
+        /// Returns `AllowanceExpired` error if the caller's allowance has expired.
+        ///
+        /// Returns `InsufficientAllowance` error if there are not enough tokens allowed
+        /// for the caller to withdraw from `from`.
+        ///
+        /// Returns `InsufficientBalance` error if there are not enough tokens on
+        /// the the account balance of `from`.
+        ///
+        /// Keep `TRANSFER_FROM_SELECTOR` in sync with the selector.
+        #[ink(message, selector = "0x0B396F18")]
+        pub fn transfer_from(
+            &mut self,
+            from: AccountId,
+            to: AccountId,
+            value: Balance,
+        ) -> Result<()> {
+            self.ensure_not_paused()?;
+            let caller = self.env().caller();
+            let allowance = self.spendable_allowance(from, caller)?;
+            if allowance < value {
+                return Err(Error::InsufficientAllowance)
             }
-            let expected_topics = vec![
-                encoded_into_hash(&PrefixedValue {
-                    value: b"Erc20::Transfer",
-                    prefix: b"",
-                }),
-                encoded_into_hash(&PrefixedValue {
-                    prefix: b"Erc20::Transfer::from",
-                    value: &expected_from,
-                }),
-                encoded_into_hash(&PrefixedValue {
-                    prefix: b"Erc20::Transfer::to",
-                    value: &expected_to,
-                }),
-                encoded_into_hash(&PrefixedValue {
-                    prefix: b"Erc20::Transfer::value",
-                    value: &expected_value,
-                }),
-            ];
-            for (n, (actual_topic, expected_topic)) in
-                event.topics.iter().zip(expected_topics).enumerate()
-            {
-                let topic = actual_topic
-                    .decode::<Hash>()
-                    .expect("encountered invalid topic encoding");
-                assert_eq!(topic, expected_topic, "encountered invalid topic at {}", n);
+            self.transfer_from_to(from, to, value)?;
+            self.set_allowance(from, caller, allowance - value);
+            Ok(())
+        }
+
+        /// `PSP22::transfer`: transfers `value` tokens from the caller to `to`.
+        ///
+        /// `data` is not interpreted, use `transfer_and_call` to notify receiving
+        /// contracts. Otherwise behaves like `transfer`.
+        #[ink(message, selector = "0xDB20F9F5")]
+        pub fn psp22_transfer(
+            &mut self,
+            to: AccountId,
+            value: Balance,
+            data: Vec<u8>,
+        ) -> PSP22Result<()> {
+            let _ = data;
+            self.transfer(to, value).map_err(PSP22Error::from)
+        }
+
+        /// `PSP22::transfer_from`: transfers `value` tokens on the behalf of `from`
+        /// to `to`.
+        ///
+        /// `data` is not interpreted. Otherwise behaves like `transfer_from`.
+        #[ink(message, selector = "0x54B3C76E")]
+        pub fn psp22_transfer_from(
+            &mut self,
+            from: AccountId,
+            to: AccountId,
+            value: Balance,
+            data: Vec<u8>,
+        ) -> PSP22Result<()> {
+            let _ = data;
+            self.transfer_from(from, to, value)
+                .map_err(PSP22Error::from)
+        }
+
+        /// `PSP22::approve`: behaves like `approve`.
+        #[ink(message, selector = "0xB20F1BBD")]
+        pub fn psp22_approve(
+            &mut self,
+            spender: AccountId,
+            value: Balance,
+        ) -> PSP22Result<()> {
+            self.approve(spender, value).map_err(PSP22Error::from)
+        }
+
+        /// `PSP22::increase_allowance`: behaves like `increase_allowance`.
+        #[ink(message, selector = "0x96D6B57A")]
+        pub fn psp22_increase_allowance(
+            &mut self,
+            spender: AccountId,
+            delta_value: Balance,
+        ) -> PSP22Result<()> {
+            self.increase_allowance(spender, delta_value)
+                .map_err(PSP22Error::from)
+        }
+
+        /// `PSP22::decrease_allowance`: behaves like `decrease_allowance`.
+        #[ink(message, selector = "0xFECB57D5")]
+        pub fn psp22_decrease_allowance(
+            &mut self,
+            spender: AccountId,
+            delta_value: Balance,
+        ) -> PSP22Result<()> {
+            self.decrease_allowance(spender, delta_value)
+                .map_err(PSP22Error::from)
+        }
+
+        /// Executes every encoded call in `calls` in order, on behalf of the caller,
+        /// and returns the result of each.
+        ///
+        /// A call is encoded like a message call to this contract: the selector of a
+        /// PSP22 `transfer`, `transfer_from`, `approve`, `increase_allowance` or
+        /// `decrease_allowance` message followed by its SCALE encoded arguments.
+        /// The calls are dispatched within this contract rather than sent as
+        /// separate messages, so the caller stays the same for all of them.
+        ///
+        /// A failing call does not revert the calls before it. Calls that can not
+        /// be dispatched fail with `InvalidCall` error.
+        #[ink(message)]
+        pub fn multicall(&mut self, calls: Vec<Vec<u8>>) -> Vec<Result<()>> {
+            calls.iter().map(|call| self.dispatch(call)).collect()
+        }
+
+        /// Sets the allowance of `spender` over `owner`'s tokens to `value` using a
+        /// signature produced by `owner` instead of a transaction sent by `owner`.
+        ///
+        /// The signature is an ECDSA signature over the Blake2x256 hash of the
+        /// SCALE encoded `(contract, owner, spender, value, nonce, deadline)` tuple,
+        /// where `nonce` is `nonce_of(owner)`, the number of permits `owner` has used
+        /// so far. `owner` is the account derived from the signing key, as
+        /// Substrate does for ECDSA signers.
+        ///
+        /// An `Approval` event is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `Paused` error if the contract is paused.
+        ///
+        /// Returns `PermitExpired` error if the current block timestamp is past
+        /// `deadline`.
+        ///
+        /// Returns `InvalidSignature` error if `signature` was not produced by `owner`.
+        #[ink(message)]
+        pub fn permit(
+            &mut self,
+            owner: AccountId,
+            spender: AccountId,
+            value: Balance,
+            deadline: Timestamp,
+            signature: [u8; 65],
+        ) -> Result<()> {
+            self.ensure_not_paused()?;
+            if self.env().block_timestamp() > deadline {
+                return Err(Error::PermitExpired)
+            }
+            let nonce = self.nonce_of(owner);
+            let message = self.env().hash_encoded::<Blake2x256, _>(&(
+                self.env().account_id(),
+                owner,
+                spender,
+                value,
+                nonce,
+                deadline,
+            ));
+            let public_key =
+                ecdsa_recover(&signature, &message).ok_or(Error::InvalidSignature)?;
+            let signer =
+                AccountId::from(self.env().hash_bytes::<Blake2x256>(&public_key));
+            if signer != owner {
+                return Err(Error::InvalidSignature)
+            }
+            let next_nonce = nonce.checked_add(1).ok_or(Error::Overflow)?;
+            self.nonces.insert(owner, next_nonce);
+            self.allowance_expiries.take(&(owner, spender));
+            self.set_allowance(owner, spender, value);
+            Ok(())
+        }
+
+        /// Returns the nonce the next permit signed by `owner` must use.
+        #[ink(message)]
+        pub fn nonce_of(&self, owner: AccountId) -> u64 {
+            self.nonces.get(&owner).copied().unwrap_or(0)
+        }
+
+        /// Returns the current contract owner.
+        #[ink(message)]
+        pub fn owner(&self) -> AccountId {
+            self.owner
+        }
+
+        /// Returns the account proposed as the next owner, if any.
+        #[ink(message)]
+        pub fn pending_owner(&self) -> Option<AccountId> {
+            self.pending_owner
+        }
+
+        /// Proposes `new_owner` as the next contract owner.
+        ///
+        /// Ownership only moves once `new_owner` calls `accept_ownership`, so a
+        /// mistyped account can not take over. A new proposal replaces the
+        /// previous one.
+        ///
+        /// On success an `OwnershipProposed` event is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `NotOwner` error if the caller is not the contract owner.
+        #[ink(message)]
+        pub fn propose_owner(&mut self, new_owner: AccountId) -> Result<()> {
+            let owner = self.ensure_owner()?;
+            self.pending_owner = Some(new_owner);
+            self.env().emit_event(OwnershipProposed {
+                owner,
+                proposed: new_owner,
+            });
+            Ok(())
+        }
+
+        /// Makes the caller the contract owner if it was proposed by `propose_owner`.
+        ///
+        /// On success an `OwnershipTransferred` event is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `NotPendingOwner` error if the caller is not the proposed owner.
+        #[ink(message)]
+        pub fn accept_ownership(&mut self) -> Result<()> {
+            let caller = self.env().caller();
+            if self.pending_owner != Some(caller) {
+                return Err(Error::NotPendingOwner)
+            }
+            let previous = self.owner;
+            self.owner = caller;
+            self.pending_owner = None;
+            self.env().emit_event(OwnershipTransferred {
+                previous,
+                owner: caller,
+            });
+            Ok(())
+        }
+
+        /// Returns `true` if `account` holds the minter role.
+        #[ink(message)]
+        pub fn is_minter(&self, account: AccountId) -> bool {
+            self.minters.contains_key(&account)
+        }
+
+        /// Grants the minter role to `account`.
+        ///
+        /// # Errors
+        ///
+        /// Returns `NotOwner` error if the caller is not the contract owner.
+        #[ink(message)]
+        pub fn grant_minter(&mut self, account: AccountId) -> Result<()> {
+            self.ensure_owner()?;
+            self.minters.insert(account, ());
+            Ok(())
+        }
+
+        /// Revokes the minter role from `account`.
+        ///
+        /// # Errors
+        ///
+        /// Returns `NotOwner` error if the caller is not the contract owner.
+        #[ink(message)]
+        pub fn revoke_minter(&mut self, account: AccountId) -> Result<()> {
+            self.ensure_owner()?;
+            self.minters.take(&account);
+            Ok(())
+        }
+
+        /// Creates `value` new tokens and assigns them to `to`.
+        ///
+        /// On success a `Transfer` event from `None` and a `Mint` event are emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `NotMinter` error if the caller does not hold the minter role.
+        ///
+        /// Returns `Overflow` error if the new total supply would exceed `Balance::MAX`.
+        ///
+        /// Returns `CapExceeded` error if the new total supply would exceed the cap.
+        ///
+        /// Returns `ZeroRecipient` error if `to` is the zero address.
+        ///
+        /// Returns `AccountFrozen` error if `to` is frozen.
+        #[ink(message)]
+        pub fn mint(&mut self, to: AccountId, value: Balance) -> Result<()> {
+            if Self::is_zero(&to) {
+                return Err(Error::ZeroRecipient)
+            }
+            self.ensure_can_mint(value)?;
+            self.mint_to(to, value)
+        }
+
+        /// Returns the largest amount `flash_mint` can currently lend.
+        #[ink(message)]
+        pub fn max_flash_mint(&self) -> Balance {
+            let cap = self.cap().unwrap_or(Balance::MAX);
+            cap.saturating_sub(self.total_supply())
+        }
+
+        /// Returns the fee charged for flash minting `amount` tokens.
+        ///
+        /// Returns `0` while the fee collector is frozen.
+        #[ink(message)]
+        pub fn flash_fee(&self, amount: Balance) -> Balance {
+            if self.is_frozen(self.fee_collector) {
+                return 0
+            }
+            Self::bps_of(amount, self.flash_fee_bps)
+        }
+
+        /// Charges `fee_bps` basis points of every flash minted amount.
+        ///
+        /// Fees are sent to the fee collector. No fee is charged while it is frozen.
+        ///
+        /// # Errors
+        ///
+        /// Returns `NotOwner` error if the caller is not the contract owner.
+        ///
+        /// Returns `InvalidFee` error if `fee_bps` exceeds `10_000`.
+        #[ink(message)]
+        pub fn set_flash_fee(&mut self, fee_bps: u16) -> Result<()> {
+            self.ensure_owner()?;
+            if fee_bps > MAX_FEE_BPS {
+                return Err(Error::InvalidFee)
             }
+            self.flash_fee_bps = fee_bps;
+            Ok(())
+        }
+
+        /// Mints `amount` tokens to `receiver` for the duration of this call.
+        ///
+        /// After minting, the `on_flash_mint` message (see `ON_FLASH_MINT_SELECTOR`)
+        /// of `receiver` is called with the caller, `amount`, the fee and `data`.
+        /// It must return `true` and leave an allowance of `amount` plus the fee
+        /// for this contract. The amount is then burned again and the fee sent to
+        /// the fee collector.
+        ///
+        /// `receiver` sees the minted tokens while `on_flash_mint` runs, so it can
+        /// move them around with `transfer` and `transfer_from` as long as enough
+        /// of them are back for the repayment.
+        ///
+        /// # Errors
+        ///
+        /// Returns `Paused` error if the contract is paused.
+        ///
+        /// Returns `CapExceeded` error if `amount` exceeds `max_flash_mint`.
+        ///
+        /// Returns `Overflow` error if `amount` plus the fee exceeds `Balance::MAX`.
+        ///
+        /// Returns `ZeroRecipient` error if `receiver` is the zero address.
+        ///
+        /// Returns `AccountFrozen` error if `receiver` is frozen.
+        ///
+        /// Returns `FlashMintFailed` error, reverting all changes, if `receiver`
+        /// rejects the flash mint or does not approve the repayment.
+        ///
+        /// Returns `InsufficientBalance` error, reverting all changes, if
+        /// `receiver` can not repay the amount plus fee.
+        #[ink(message)]
+        pub fn flash_mint(
+            &mut self,
+            receiver: AccountId,
+            amount: Balance,
+            data: Vec<u8>,
+        ) -> Result<()> {
+            self.ensure_not_paused()?;
+            if Self::is_zero(&receiver) {
+                return Err(Error::ZeroRecipient)
+            }
+            if amount > self.max_flash_mint() {
+                return Err(Error::CapExceeded)
+            }
+            let fee = self.flash_fee(amount);
+            let repayment = amount.checked_add(fee).ok_or(Error::Overflow)?;
+            let initiator = self.env().caller();
+            self.mint_to(receiver, amount)?;
+            self.flush();
+            let accepted = build_call::<DefaultEnvironment>()
+                .callee(receiver)
+                .gas_limit(0)
+                .transferred_value(0)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(ON_FLASH_MINT_SELECTOR))
+                        .push_arg(initiator)
+                        .push_arg(amount)
+                        .push_arg(fee)
+                        .push_arg(data),
+                )
+                .returns::<ReturnType<bool>>()
+                .fire();
+            if !matches!(accepted, Ok(true)) {
+                Self::revert(Error::FlashMintFailed)
+            }
+            self.reload();
+            if let Err(error) = self.repay_flash_mint(receiver, amount, fee, repayment) {
+                Self::revert(error)
+            }
+            Ok(())
+        }
+
+        /// Creates `amount` new tokens for `to` that are locked and vest linearly
+        /// between `start` and `start + duration`.
+        ///
+        /// Nothing can be released before `start + cliff`. Vested tokens are moved
+        /// to the balance of `to` by `release`. Locked tokens count towards the
+        /// total supply but not towards any balance.
+        ///
+        /// On success a `VestingScheduleCreated` event is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `NotMinter` error if the caller does not hold the minter role.
+        ///
+        /// Returns `CapExceeded` error if the new total supply would exceed the cap.
+        ///
+        /// Returns `InvalidSchedule` error if `cliff` is greater than `duration`.
+        ///
+        /// Returns `ZeroRecipient` error if `to` is the zero address.
+        #[ink(message)]
+        pub fn mint_vested(
+            &mut self,
+            to: AccountId,
+            amount: Balance,
+            start: Timestamp,
+            cliff: Timestamp,
+            duration: Timestamp,
+        ) -> Result<()> {
+            if Self::is_zero(&to) {
+                return Err(Error::ZeroRecipient)
+            }
+            self.ensure_can_mint(amount)?;
+            if cliff > duration {
+                return Err(Error::InvalidSchedule)
+            }
+            let total_supply = self
+                .total_supply()
+                .checked_add(amount)
+                .ok_or(Error::Overflow)?;
+            self.vesting_schedules
+                .entry(to)
+                .or_insert_with(Vec::new)
+                .push(VestingSchedule {
+                    amount,
+                    released: 0,
+                    start,
+                    cliff,
+                    duration,
+                });
+            self.update_total_supply_snapshot();
+            *self.total_supply = total_supply;
+            self.env().emit_event(VestingScheduleCreated {
+                to,
+                amount,
+                start,
+                cliff,
+                duration,
+            });
+            Ok(())
+        }
+
+        /// Returns the amount of tokens that have vested for `account` but have not
+        /// been released yet.
+        #[ink(message)]
+        pub fn vested_balance_of(&self, account: AccountId) -> Balance {
+            let now = self.env().block_timestamp();
+            self.vesting_schedules
+                .get(&account)
+                .map(|schedules| {
+                    schedules
+                        .iter()
+                        .map(|schedule| schedule.vested_amount(now) - schedule.released)
+                        .sum()
+                })
+                .unwrap_or(0)
+        }
+
+        /// Returns the vesting schedules of `account`.
+        #[ink(message)]
+        pub fn vesting_schedules_of(&self, account: AccountId) -> Vec<VestingSchedule> {
+            self.vesting_schedules
+                .get(&account)
+                .cloned()
+                .unwrap_or_default()
+        }
+
+        /// Moves all vested tokens of the caller to its balance and returns their
+        /// amount.
+        ///
+        /// Fully released schedules are removed. If anything was released a
+        /// `Transfer` event from `None` and a `Released` event are emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `Overflow` error if the caller's balance would exceed
+        /// `Balance::MAX`.
+        ///
+        /// Returns `AccountFrozen` error if the caller is frozen and anything
+        /// vested. The tokens stay locked until the caller is unfrozen.
+        #[ink(message)]
+        pub fn release(&mut self) -> Result<Balance> {
+            let caller = self.env().caller();
+            let now = self.env().block_timestamp();
+            let value = self.vested_balance_of(caller);
+            if value == 0 {
+                return Ok(0)
+            }
+            self.ensure_not_frozen(caller)?;
+            let balance = self
+                .balance_of(caller)
+                .checked_add(value)
+                .ok_or(Error::Overflow)?;
+            let mut schedules = self.vesting_schedules.take(&caller).unwrap_or_default();
+            for schedule in schedules.iter_mut() {
+                schedule.released = schedule.vested_amount(now);
+            }
+            schedules.retain(|schedule| schedule.released < schedule.amount);
+            if !schedules.is_empty() {
+                self.vesting_schedules.insert(caller, schedules);
+            }
+            self.set_balance(caller, balance)?;
+            self.env().emit_event(Transfer {
+                from: None,
+                to: Some(caller),
+                value,
+            });
+            self.env().emit_event(Released {
+                account: caller,
+                value,
+            });
+            Ok(value)
+        }
+
+        /// Destroys `value` tokens from the caller's account.
+        ///
+        /// On success a `Transfer` event to `None` and a `Burn` event are emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `InsufficientBalance` error if there are not enough tokens on
+        /// the caller's account balance.
+        #[ink(message)]
+        pub fn burn(&mut self, value: Balance) -> Result<()> {
+            let from = self.env().caller();
+            self.burn_from_account(from, value)
+        }
+
+        /// Destroys `value` tokens from `owner`'s account, deducting them from the
+        /// caller's allowance.
+        ///
+        /// On success a `Transfer` event to `None`, a `Burn` event and an `Approval`
+        /// event with the remaining allowance are emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `AllowanceExpired` error if the caller's allowance has expired.
+        ///
+        /// Returns `InsufficientAllowance` error if there are not enough tokens allowed
+        /// for the caller to burn from `owner`.
+        ///
+        /// Returns `InsufficientBalance` error if there are not enough tokens on
+        /// the account balance of `owner`.
+        #[ink(message)]
+        pub fn burn_from(&mut self, owner: AccountId, value: Balance) -> Result<()> {
+            let caller = self.env().caller();
+            let allowance = self.spendable_allowance(owner, caller)?;
+            if allowance < value {
+                return Err(Error::InsufficientAllowance)
+            }
+            self.burn_from_account(owner, value)?;
+            self.set_allowance(owner, caller, allowance - value);
+            Ok(())
+        }
+
+        /// Returns `true` if transfers and approvals are halted.
+        #[ink(message)]
+        pub fn paused(&self) -> bool {
+            self.paused
+        }
+
+        /// Halts transfers and approvals.
+        ///
+        /// On success a `Paused` event is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `NotOwner` error if the caller is not the contract owner.
+        #[ink(message)]
+        pub fn pause(&mut self) -> Result<()> {
+            let caller = self.ensure_owner()?;
+            self.paused = true;
+            self.env().emit_event(Paused { account: caller });
+            Ok(())
+        }
+
+        /// Resumes transfers and approvals.
+        ///
+        /// On success an `Unpaused` event is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `NotOwner` error if the caller is not the contract owner.
+        #[ink(message)]
+        pub fn unpause(&mut self) -> Result<()> {
+            let caller = self.ensure_owner()?;
+            self.paused = false;
+            self.env().emit_event(Unpaused { account: caller });
+            Ok(())
+        }
+
+        /// Returns the fee charged on transfers in basis points.
+        #[ink(message)]
+        pub fn transfer_fee_bps(&self) -> u16 {
+            self.transfer_fee_bps
+        }
+
+        /// Returns the account receiving transfer fees.
+        #[ink(message)]
+        pub fn fee_collector(&self) -> AccountId {
+            self.fee_collector
+        }
+
+        /// Charges `fee_bps` basis points of every transferred value and sends them
+        /// to `collector`.
+        ///
+        /// The recipient of a transfer receives the value minus the fee. A
+        /// `fee_bps` of `0` disables the fee. No fee is charged while `collector`
+        /// is frozen, since it can not receive tokens then.
+        ///
+        /// # Errors
+        ///
+        /// Returns `NotOwner` error if the caller is not the contract owner.
+        ///
+        /// Returns `InvalidFee` error if `fee_bps` exceeds `10_000`.
+        #[ink(message)]
+        pub fn set_transfer_fee(
+            &mut self,
+            fee_bps: u16,
+            collector: AccountId,
+        ) -> Result<()> {
+            self.ensure_owner()?;
+            if fee_bps > MAX_FEE_BPS {
+                return Err(Error::InvalidFee)
+            }
+            self.transfer_fee_bps = fee_bps;
+            self.fee_collector = collector;
+            Ok(())
+        }
+
+        /// Returns `true` if `account` can neither send nor receive tokens.
+        #[ink(message)]
+        pub fn is_frozen(&self, account: AccountId) -> bool {
+            self.frozen.contains_key(&account)
+        }
+
+        /// Blocks all transfers to and from `account`, as well as minting tokens
+        /// to it and releasing its vested tokens.
+        ///
+        /// On success a `Frozen` event is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `NotOwner` error if the caller is not the contract owner.
+        #[ink(message)]
+        pub fn freeze(&mut self, account: AccountId) -> Result<()> {
+            self.ensure_owner()?;
+            self.frozen.insert(account, ());
+            self.env().emit_event(Frozen { account });
+            Ok(())
+        }
+
+        /// Allows transfers to and from `account` again.
+        ///
+        /// On success an `Unfrozen` event is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `NotOwner` error if the caller is not the contract owner.
+        #[ink(message)]
+        pub fn unfreeze(&mut self, account: AccountId) -> Result<()> {
+            self.ensure_owner()?;
+            self.frozen.take(&account);
+            self.env().emit_event(Unfrozen { account });
+            Ok(())
+        }
+
+        /// Returns the account `owner` delegates its voting power to, if any.
+        #[ink(message)]
+        pub fn delegates(&self, owner: AccountId) -> Option<AccountId> {
+            self.delegates.get(&owner).copied()
+        }
+
+        /// Returns the current voting power of `account`.
+        #[ink(message)]
+        pub fn get_votes(&self, account: AccountId) -> Balance {
+            self.vote_checkpoints
+                .get(&account)
+                .and_then(|checkpoints| checkpoints.last())
+                .map(|(_, votes)| *votes)
+                .unwrap_or(0)
+        }
+
+        /// Returns the voting power of `account` at the end of block `block`.
+        ///
+        /// # Errors
+        ///
+        /// Returns `BlockNotMined` error if `block` is not before the current block.
+        #[ink(message)]
+        pub fn get_past_votes(
+            &self,
+            account: AccountId,
+            block: BlockNumber,
+        ) -> Result<Balance> {
+            if block >= self.env().block_number() {
+                return Err(Error::BlockNotMined)
+            }
+            Ok(self
+                .vote_checkpoints
+                .get(&account)
+                .and_then(|checkpoints| {
+                    checkpoints
+                        .iter()
+                        .rev()
+                        .find(|(number, _)| *number <= block)
+                })
+                .map(|(_, votes)| *votes)
+                .unwrap_or(0))
+        }
+
+        /// Delegates the voting power of all current and future tokens of the
+        /// caller to `delegatee`.
+        ///
+        /// Tokens only count as votes once delegated, an account votes with its
+        /// own tokens by delegating to itself.
+        ///
+        /// A `DelegateChanged` event and a `DelegateVotesChanged` event for every
+        /// delegate whose voting power changed are emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `Overflow` error if the votes of `delegatee` would exceed
+        /// `Balance::MAX`.
+        #[ink(message)]
+        pub fn delegate(&mut self, delegatee: AccountId) -> Result<()> {
+            let delegator = self.env().caller();
+            let balance = self.balance_of(delegator);
+            let from_delegate = self.delegates(delegator);
+            // Checked up front so that a failing delegation changes nothing.
+            if from_delegate != Some(delegatee) {
+                self.get_votes(delegatee)
+                    .checked_add(balance)
+                    .ok_or(Error::Overflow)?;
+            }
+            self.delegates.insert(delegator, delegatee);
+            self.env().emit_event(DelegateChanged {
+                delegator,
+                from_delegate,
+                to_delegate: delegatee,
+            });
+            self.move_votes(from_delegate, Some(delegatee), balance)
+        }
+
+        /// Records the current balances and total supply under a new snapshot id.
+        ///
+        /// On success a `Snapshot` event is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `NotOwner` error if the caller is not the contract owner.
+        ///
+        /// Returns `Overflow` error if all snapshot ids are used up.
+        #[ink(message)]
+        pub fn snapshot(&mut self) -> Result<u32> {
+            self.ensure_owner()?;
+            let id = self
+                .current_snapshot_id
+                .checked_add(1)
+                .ok_or(Error::Overflow)?;
+            self.current_snapshot_id = id;
+            self.env().emit_event(Snapshot { id });
+            Ok(id)
+        }
+
+        /// Returns the balance of `owner` at the time `snapshot_id` was taken.
+        ///
+        /// Returns `None` if no snapshot with this id exists.
+        #[ink(message)]
+        pub fn balance_of_at(
+            &self,
+            owner: AccountId,
+            snapshot_id: u32,
+        ) -> Option<Balance> {
+            if snapshot_id == 0 || snapshot_id > self.current_snapshot_id {
+                return None
+            }
+            let recorded = self
+                .account_snapshots
+                .get(&owner)
+                .and_then(|snapshots| Self::value_at(snapshots, snapshot_id));
+            Some(recorded.unwrap_or_else(|| self.balance_of(owner)))
+        }
+
+        /// Returns the total token supply at the time `snapshot_id` was taken.
+        ///
+        /// Returns `None` if no snapshot with this id exists.
+        #[ink(message)]
+        pub fn total_supply_at(&self, snapshot_id: u32) -> Option<Balance> {
+            if snapshot_id == 0 || snapshot_id > self.current_snapshot_id {
+                return None
+            }
+            let recorded = Self::value_at(&self.total_supply_snapshots, snapshot_id);
+            Some(recorded.unwrap_or_else(|| self.total_supply()))
+        }
+
+        /// Transfers `amount` tokens of the PSP22 token contract `other_token`
+        /// held by this contract to `to`.
+        ///
+        /// Recovers tokens that were sent to this contract's address by mistake.
+        ///
+        /// # Errors
+        ///
+        /// Returns `NotOwner` error if the caller is not the contract owner.
+        ///
+        /// Returns `RescueFailed` error if `other_token` is this contract or its
+        /// transfer traps or returns an error.
+        #[ink(message)]
+        pub fn rescue_tokens(
+            &mut self,
+            other_token: AccountId,
+            to: AccountId,
+            amount: Balance,
+        ) -> Result<()> {
+            self.ensure_owner()?;
+            if other_token == self.env().account_id() {
+                return Err(Error::RescueFailed)
+            }
+            let transferred = build_call::<DefaultEnvironment>()
+                .callee(other_token)
+                .gas_limit(0)
+                .transferred_value(0)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(PSP22_TRANSFER_SELECTOR))
+                        .push_arg(to)
+                        .push_arg(amount)
+                        .push_arg(Vec::<u8>::new()),
+                )
+                .returns::<ReturnType<PSP22Result<()>>>()
+                .fire();
+            match transferred {
+                Ok(Ok(())) => Ok(()),
+                _ => Err(Error::RescueFailed),
+            }
+        }
+
+        /// Returns the value recorded by the first checkpoint taken at or after
+        /// `snapshot_id`, if any.
+        ///
+        /// A checkpoint stores the value an account had before its first change
+        /// after a snapshot, so no checkpoint means the value is unchanged since.
+        fn value_at(snapshots: &[(u32, Balance)], snapshot_id: u32) -> Option<Balance> {
+            snapshots
+                .iter()
+                .find(|(id, _)| *id >= snapshot_id)
+                .map(|(_, value)| *value)
+        }
+
+        /// Decodes a single `multicall` entry and executes it.
+        fn dispatch(&mut self, call: &[u8]) -> Result<()> {
+            fn decode<T: scale::Decode>(input: &mut &[u8]) -> Result<T> {
+                T::decode(input).map_err(|_| Error::InvalidCall)
+            }
+            let input = &mut &call[..];
+            match decode::<[u8; 4]>(input)? {
+                PSP22_TRANSFER_SELECTOR => {
+                    let (to, value, _data) =
+                        decode::<(AccountId, Balance, Vec<u8>)>(input)?;
+                    self.transfer(to, value)
+                }
+                PSP22_TRANSFER_FROM_SELECTOR => {
+                    let (from, to, value, _data) =
+                        decode::<(AccountId, AccountId, Balance, Vec<u8>)>(input)?;
+                    self.transfer_from(from, to, value)
+                }
+                PSP22_APPROVE_SELECTOR => {
+                    let (spender, value) = decode::<(AccountId, Balance)>(input)?;
+                    self.approve(spender, value)
+                }
+                PSP22_INCREASE_ALLOWANCE_SELECTOR => {
+                    let (spender, delta_value) = decode::<(AccountId, Balance)>(input)?;
+                    self.increase_allowance(spender, delta_value)
+                }
+                PSP22_DECREASE_ALLOWANCE_SELECTOR => {
+                    let (spender, delta_value) = decode::<(AccountId, Balance)>(input)?;
+                    self.decrease_allowance(spender, delta_value)
+                }
+                _ => Err(Error::InvalidCall),
+            }
+        }
+
+        /// Returns the allowance of `spender` over the tokens of `owner`.
+        ///
+        /// # Errors
+        ///
+        /// Returns `AllowanceExpired` error if the allowance has expired.
+        fn spendable_allowance(
+            &self,
+            owner: AccountId,
+            spender: AccountId,
+        ) -> Result<Balance> {
+            if let Some(expiry) = self.allowance_expiries.get(&(owner, spender)) {
+                if *expiry < self.env().block_timestamp() {
+                    return Err(Error::AllowanceExpired)
+                }
+            }
+            Ok(self.allowances.get(&(owner, spender)).copied().unwrap_or(0))
+        }
+
+        /// Sets the allowance of `spender` over the tokens of `owner` to `value`.
+        ///
+        /// An `Approval` event is emitted.
+        fn set_allowance(
+            &mut self,
+            owner: AccountId,
+            spender: AccountId,
+            value: Balance,
+        ) {
+            self.allowances.insert((owner, spender), value);
+            self.env().emit_event(Approval {
+                owner,
+                spender,
+                value,
+            });
+        }
+
+        /// Sets the balance of `owner`, checkpointing the old balance for the
+        /// current snapshot first.
+        ///
+        /// The voting power of the delegate of `owner` changes by the same amount.
+        fn set_balance(&mut self, owner: AccountId, balance: Balance) -> Result<()> {
+            let old_balance = self.balance_of(owner);
+            let current_id = self.current_snapshot_id;
+            if current_id != 0 {
+                let snapshots =
+                    self.account_snapshots.entry(owner).or_insert_with(Vec::new);
+                if snapshots.last().map(|(id, _)| *id) < Some(current_id) {
+                    snapshots.push((current_id, old_balance));
+                }
+            }
+            self.balances.insert(owner, balance);
+            let delegatee = self.delegates(owner);
+            if balance >= old_balance {
+                self.move_votes(None, delegatee, balance - old_balance)
+            } else {
+                self.move_votes(delegatee, None, old_balance - balance)
+            }
+        }
+
+        /// Moves `amount` votes from delegate `from` to delegate `to`.
+        ///
+        /// `None` stands for tokens that are not delegated, so votes are only
+        /// created or destroyed.
+        ///
+        /// # Errors
+        ///
+        /// Returns `Underflow` error if `from` has fewer than `amount` votes.
+        ///
+        /// Returns `Overflow` error if the votes of `to` would exceed `Balance::MAX`.
+        fn move_votes(
+            &mut self,
+            from: Option<AccountId>,
+            to: Option<AccountId>,
+            amount: Balance,
+        ) -> Result<()> {
+            if from == to || amount == 0 {
+                return Ok(())
+            }
+            if let Some(from) = from {
+                let votes = self
+                    .get_votes(from)
+                    .checked_sub(amount)
+                    .ok_or(Error::Underflow)?;
+                self.write_vote_checkpoint(from, votes);
+            }
+            if let Some(to) = to {
+                let votes = self
+                    .get_votes(to)
+                    .checked_add(amount)
+                    .ok_or(Error::Overflow)?;
+                self.write_vote_checkpoint(to, votes);
+            }
+            Ok(())
+        }
+
+        /// Records `votes` as the voting power of `delegatee` from the current
+        /// block on.
+        ///
+        /// A `DelegateVotesChanged` event is emitted.
+        fn write_vote_checkpoint(&mut self, delegatee: AccountId, votes: Balance) {
+            let block = self.env().block_number();
+            let previous = self.get_votes(delegatee);
+            let checkpoints = self
+                .vote_checkpoints
+                .entry(delegatee)
+                .or_insert_with(Vec::new);
+            match checkpoints.last_mut() {
+                Some(last) if last.0 == block => last.1 = votes,
+                _ => checkpoints.push((block, votes)),
+            }
+            self.env().emit_event(DelegateVotesChanged {
+                delegate: delegatee,
+                previous,
+                new: votes,
+            });
+        }
+
+        /// Checkpoints the total supply for the current snapshot before it changes.
+        fn update_total_supply_snapshot(&mut self) {
+            let current_id = self.current_snapshot_id;
+            if current_id == 0 {
+                return
+            }
+            let total_supply = self.total_supply();
+            let snapshots = &mut *self.total_supply_snapshots;
+            if snapshots.last().map(|(id, _)| *id) < Some(current_id) {
+                snapshots.push((current_id, total_supply));
+            }
+        }
+
+        /// Checks that the caller may mint `value` new tokens.
+        ///
+        /// # Errors
+        ///
+        /// Returns `NotMinter` error if the caller does not hold the minter role.
+        ///
+        /// Returns `Overflow` error if the new total supply would exceed `Balance::MAX`.
+        ///
+        /// Returns `CapExceeded` error if the new total supply would exceed the cap.
+        fn ensure_can_mint(&self, value: Balance) -> Result<()> {
+            if !self.is_minter(self.env().caller()) {
+                return Err(Error::NotMinter)
+            }
+            let total_supply = self
+                .total_supply()
+                .checked_add(value)
+                .ok_or(Error::Overflow)?;
+            if let Some(cap) = *self.cap {
+                if total_supply > cap {
+                    return Err(Error::CapExceeded)
+                }
+            }
+            Ok(())
+        }
+
+        /// Returns the caller if it is the contract owner and `NotOwner` error
+        /// otherwise.
+        fn ensure_owner(&self) -> Result<AccountId> {
+            let caller = self.env().caller();
+            if caller != self.owner {
+                return Err(Error::NotOwner)
+            }
+            Ok(caller)
+        }
+
+        /// Returns `true` if `account` is the zero address.
+        fn is_zero(account: &AccountId) -> bool {
+            *account == AccountId::from([0x00; 32])
+        }
+
+        /// Returns `Paused` error if the contract is paused.
+        fn ensure_not_paused(&self) -> Result<()> {
+            if self.paused {
+                return Err(Error::Paused)
+            }
+            Ok(())
+        }
+
+        /// Returns `AccountFrozen` error if `account` is frozen.
+        fn ensure_not_frozen(&self, account: AccountId) -> Result<()> {
+            if self.is_frozen(account) {
+                return Err(Error::AccountFrozen)
+            }
+            Ok(())
+        }
+
+        /// Destroys `value` tokens from the account `from`.
+        ///
+        /// # Errors
+        ///
+        /// Returns `InsufficientBalance` error if there are not enough tokens on
+        /// the account balance of `from`.
+        fn burn_from_account(&mut self, from: AccountId, value: Balance) -> Result<()> {
+            let from_balance = self.balance_of(from);
+            if from_balance < value {
+                return Err(Error::InsufficientBalance)
+            }
+            let total_supply = self
+                .total_supply()
+                .checked_sub(value)
+                .ok_or(Error::Underflow)?;
+            self.set_balance(from, from_balance - value)?;
+            self.update_total_supply_snapshot();
+            *self.total_supply = total_supply;
+            self.env().emit_event(Transfer {
+                from: Some(from),
+                to: None,
+                value,
+            });
+            self.env().emit_event(Burn { from, value });
+            Ok(())
+        }
+
+        /// Creates `value` new tokens and assigns them to `to`.
+        ///
+        /// A `Transfer` event from `None` and a `Mint` event are emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `AccountFrozen` error if `to` is frozen.
+        ///
+        /// Returns `Overflow` error if the balance of `to` or the total supply
+        /// would exceed `Balance::MAX`.
+        fn mint_to(&mut self, to: AccountId, value: Balance) -> Result<()> {
+            self.ensure_not_frozen(to)?;
+            let to_balance = self
+                .balance_of(to)
+                .checked_add(value)
+                .ok_or(Error::Overflow)?;
+            let total_supply = self
+                .total_supply()
+                .checked_add(value)
+                .ok_or(Error::Overflow)?;
+            self.set_balance(to, to_balance)?;
+            self.update_total_supply_snapshot();
+            *self.total_supply = total_supply;
+            self.env().emit_event(Transfer {
+                from: None,
+                to: Some(to),
+                value,
+            });
+            self.env().emit_event(Mint { to, value });
+            Ok(())
+        }
+
+        /// Transfers `value` amount of tokens from the caller's account to account `to`.
+        ///
+        /// On success a `Transfer` event is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `InsufficientBalance` error if there are not enough tokens on
+        /// the caller's account balance.
+        fn transfer_from_to(
+            &mut self,
+            from: AccountId,
+            to: AccountId,
+            value: Balance,
+        ) -> Result<()> {
+            if Self::is_zero(&from) {
+                return Err(Error::ZeroSender)
+            }
+            if Self::is_zero(&to) {
+                return Err(Error::ZeroRecipient)
+            }
+            self.ensure_not_frozen(from)?;
+            self.ensure_not_frozen(to)?;
+            let from_balance = self.balance_of(from);
+            if from_balance < value {
+                return Err(Error::InsufficientBalance)
+            }
+            let fee = self.transfer_fee_of(value);
+            self.set_balance(from, from_balance - value)?;
+            let to_balance = self
+                .balance_of(to)
+                .checked_add(value - fee)
+                .ok_or(Error::Overflow)?;
+            self.set_balance(to, to_balance)?;
+            self.env().emit_event(Transfer {
+                from: Some(from),
+                to: Some(to),
+                value: value - fee,
+            });
+            self.collect_fee(from, fee)
+        }
+
+        /// Returns the fee charged on a transfer of `value` tokens.
+        ///
+        /// Returns `0` while the fee collector is frozen.
+        fn transfer_fee_of(&self, value: Balance) -> Balance {
+            if self.is_frozen(self.fee_collector) {
+                return 0
+            }
+            Self::bps_of(value, self.transfer_fee_bps)
+        }
+
+        /// Returns `bps` basis points of `value`.
+        fn bps_of(value: Balance, bps: u16) -> Balance {
+            let (bps, max) = (bps as Balance, MAX_FEE_BPS as Balance);
+            // Split `value * bps / max` so the multiplication can not overflow.
+            value / max * bps + value % max * bps / max
+        }
+
+        /// Credits `fee` tokens already debited from `from` to the fee collector.
+        ///
+        /// A `Transfer` event from `from` to the fee collector is emitted if `fee`
+        /// is not `0`.
+        ///
+        /// # Errors
+        ///
+        /// Returns `Overflow` error if the fee collector's balance would exceed
+        /// `Balance::MAX`.
+        fn collect_fee(&mut self, from: AccountId, fee: Balance) -> Result<()> {
+            if fee == 0 {
+                return Ok(())
+            }
+            let collector = self.fee_collector;
+            let collector_balance = self
+                .balance_of(collector)
+                .checked_add(fee)
+                .ok_or(Error::Overflow)?;
+            self.set_balance(collector, collector_balance)?;
+            self.env().emit_event(Transfer {
+                from: Some(from),
+                to: Some(collector),
+                value: fee,
+            });
+            Ok(())
+        }
+
+        /// Takes back a flash minted `amount` plus `fee` with the allowance
+        /// `receiver` gave this contract and sends the fee to the fee collector.
+        ///
+        /// `repayment` is `amount` plus `fee`.
+        ///
+        /// # Errors
+        ///
+        /// Returns `FlashMintFailed` error if the allowance does not cover
+        /// `repayment`.
+        ///
+        /// Returns `InsufficientBalance` error if `receiver` can not repay.
+        fn repay_flash_mint(
+            &mut self,
+            receiver: AccountId,
+            amount: Balance,
+            fee: Balance,
+            repayment: Balance,
+        ) -> Result<()> {
+            let contract = self.env().account_id();
+            let allowance = self.allowance(receiver, contract);
+            if allowance < repayment {
+                return Err(Error::FlashMintFailed)
+            }
+            self.set_allowance(receiver, contract, allowance - repayment);
+            self.burn_from_account(receiver, amount)?;
+            let receiver_balance = self.balance_of(receiver);
+            if receiver_balance < fee {
+                return Err(Error::InsufficientBalance)
+            }
+            self.set_balance(receiver, receiver_balance - fee)?;
+            self.collect_fee(receiver, fee)
+        }
+
+        /// Writes the contract storage.
+        fn flush(&self) {
+            // ink! writes the contract storage back only when a message returns.
+            // Write it now so a contract called next already sees the changes,
+            // also when it calls back into this contract.
+            ink_storage::traits::push_spread_root::<Self>(
+                self,
+                &ink_primitives::Key::from([0x00; 32]),
+            );
+        }
+
+        /// Reads the contract storage again after a call to another contract.
+        fn reload(&mut self) {
+            // The called contract may have changed the storage by calling back
+            // into this contract. The cached state misses those changes and would
+            // overwrite them when this message returns, so it is dropped.
+            *self = ink_storage::traits::pull_spread_root::<Self>(
+                &ink_primitives::Key::from([0x00; 32]),
+            );
+        }
+
+        /// Ends the current call with `error`, undoing the tokens moved by a
+        /// rejected `transfer_and_call` or minted by a failed `flash_mint`.
+        fn revert(error: Error) -> ! {
+            ink_env::return_value::<Result<()>>(
+                ReturnFlags::default().set_reverted(true),
+                &Err(error),
+            )
+        }
+    }
+
+    /// Returns the compressed public key which produced the ECDSA `signature`
+    /// over `message`, or `None` if `signature` is malformed.
+    ///
+    /// ink! 3.0.0-rc2 can not recover ECDSA keys, so the contract does so itself,
+    /// the same way Substrate does. The last byte of `signature` is the recovery
+    /// id, which may be offset by 27 as Ethereum does.
+    #[cfg(not(feature = "ink-as-dependency"))]
+    fn ecdsa_recover(signature: &[u8; 65], message: &[u8; 32]) -> Option<[u8; 33]> {
+        let recovery_id = match signature[64] {
+            id if id > 26 => id - 27,
+            id => id,
+        };
+        let recovery_id = secp256k1::RecoveryId::parse(recovery_id).ok()?;
+        let signature = secp256k1::Signature::parse_slice(&signature[..64]).ok()?;
+        let message = secp256k1::Message::parse(message);
+        secp256k1::recover(&message, &signature, &recovery_id)
+            .ok()
+            .map(|public_key| public_key.serialize_compressed())
+    }
+
+    /// Unit tests.
+    #[cfg(test)]
+    mod tests {
+        /// Imports all the definitions from the outer scope so we can use them here.
+        use super::*;
+        use ink_env::{
+            hash::{
+                Blake2x256,
+                CryptoHash,
+                HashOutput,
+            },
+            Clear,
+        };
+
+        type Event = <Erc20 as ::ink_lang::BaseEvent>::Type;
+
+        use ink_lang as ink;
+        use test_utils::set_caller;
+
+        fn assert_transfer_event(
+            event: &ink_env::test::EmittedEvent,
+            expected_from: Option<AccountId>,
+            expected_to: Option<AccountId>,
+            expected_value: Balance,
+        ) {
+            let decoded_event = <Event as scale::Decode>::decode(&mut &event.data[..])
+                .expect("encountered invalid contract event data buffer");
+            if let Event::Transfer(Transfer { from, to, value }) = decoded_event {
+                assert_eq!(from, expected_from, "encountered invalid Transfer.from");
+                assert_eq!(to, expected_to, "encountered invalid Transfer.to");
+                assert_eq!(value, expected_value, "encountered invalid Trasfer.value");
+            } else {
+                panic!("encountered unexpected event kind: expected a Transfer event")
+            }
+            fn encoded_into_hash<T>(entity: &T) -> Hash
+            where
+                T: scale::Encode,
+            {
+                let mut result = Hash::clear();
+                let len_result = result.as_ref().len();
+                let encoded = entity.encode();
+                let len_encoded = encoded.len();
+                if len_encoded <= len_result {
+                    result.as_mut()[..len_encoded].copy_from_slice(&encoded);
+                    return result
+                }
+                let mut hash_output =
+                    <<Blake2x256 as HashOutput>::Type as Default>::default();
+                <Blake2x256 as CryptoHash>::hash(&encoded, &mut hash_output);
+                let copy_len = core::cmp::min(hash_output.len(), len_result);
+                result.as_mut()[0..copy_len].copy_from_slice(&hash_output[0..copy_len]);
+                result
+            }
+            let expected_topics = vec![
+                encoded_into_hash(&PrefixedValue {
+                    value: b"Erc20::Transfer",
+                    prefix: b"",
+                }),
+                encoded_into_hash(&PrefixedValue {
+                    prefix: b"Erc20::Transfer::from",
+                    value: &expected_from,
+                }),
+                encoded_into_hash(&PrefixedValue {
+                    prefix: b"Erc20::Transfer::to",
+                    value: &expected_to,
+                }),
+                encoded_into_hash(&PrefixedValue {
+                    prefix: b"Erc20::Transfer::value",
+                    value: &expected_value,
+                }),
+            ];
+            for (n, (actual_topic, expected_topic)) in
+                event.topics.iter().zip(expected_topics).enumerate()
+            {
+                let topic = actual_topic
+                    .decode::<Hash>()
+                    .expect("encountered invalid topic encoding");
+                assert_eq!(topic, expected_topic, "encountered invalid topic at {}", n);
+            }
+        }
+
+        fn assert_approval_event(
+            event: &ink_env::test::EmittedEvent,
+            expected_owner: AccountId,
+            expected_spender: AccountId,
+            expected_value: Balance,
+        ) {
+            let decoded_event = <Event as scale::Decode>::decode(&mut &event.data[..])
+                .expect("encountered invalid contract event data buffer");
+            if let Event::Approval(Approval {
+                owner,
+                spender,
+                value,
+            }) = decoded_event
+            {
+                assert_eq!(owner, expected_owner, "encountered invalid Approval.owner");
+                assert_eq!(
+                    spender, expected_spender,
+                    "encountered invalid Approval.spender"
+                );
+                assert_eq!(value, expected_value, "encountered invalid Approval.value");
+            } else {
+                panic!("encountered unexpected event kind: expected an Approval event")
+            }
+        }
+
+        /// The default constructor does its job.
+        #[ink::test]
+        fn new_works() {
+            // Constructor works.
+            let _erc20 = Erc20::new(100, None, None, 0);
+
+            // Transfer event triggered during initial construction.
+            let emitted_events = ink_env::test::recorded_events().collect::<Vec<_>>();
+            assert_eq!(1, emitted_events.len());
+
+            assert_transfer_event(
+                &emitted_events[0],
+                None,
+                Some(AccountId::from([0x01; 32])),
+                100,
+            );
+        }
+
+        /// The total supply was applied.
+        #[ink::test]
+        fn total_supply_works() {
+            // Constructor works.
+            let erc20 = Erc20::new(100, None, None, 0);
+            // Transfer event triggered during initial construction.
+            let emitted_events = ink_env::test::recorded_events().collect::<Vec<_>>();
+            assert_transfer_event(
+                &emitted_events[0],
+                None,
+                Some(AccountId::from([0x01; 32])),
+                100,
+            );
+            // Get the token total supply.
+            assert_eq!(erc20.total_supply(), 100);
+        }
+
+        /// The token metadata was applied.
+        #[ink::test]
+        fn token_metadata_works() {
+            let erc20 = Erc20::new(
+                100,
+                Some(String::from("Redspot")),
+                Some(String::from("RDS")),
+                10,
+            );
+            assert_eq!(erc20.token_name(), Some(String::from("Redspot")));
+            assert_eq!(erc20.token_symbol(), Some(String::from("RDS")));
+            assert_eq!(erc20.token_decimals(), 10);
+
+            let unnamed = Erc20::new(100, None, None, 0);
+            assert_eq!(unnamed.token_name(), None);
+            assert_eq!(unnamed.token_symbol(), None);
+        }
+
+        #[ink::test]
+        fn token_info_works() {
+            let mut erc20 = Erc20::new(100, None, None, 0);
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            assert_eq!(erc20.token_info(), TokenInfo::default());
+
+            let info = TokenInfo {
+                logo_uri: Some(String::from("ipfs://logo")),
+                project_url: Some(String::from("https://redspot.patract.io")),
+                description: None,
+            };
+            assert_eq!(erc20.set_token_info(info.clone()), Ok(()));
+            assert_eq!(erc20.token_info(), info);
+
+            set_caller!(accounts.bob);
+            assert_eq!(
+                erc20.set_token_info(TokenInfo::default()),
+                Err(Error::NotOwner)
+            );
+            assert_eq!(erc20.token_info(), info);
+        }
+
+        /// Get the actual balance of an account.
+        #[ink::test]
+        fn balance_of_works() {
+            // Constructor works
+            let erc20 = Erc20::new(100, None, None, 0);
+            // Transfer event triggered during initial construction
+            let emitted_events = ink_env::test::recorded_events().collect::<Vec<_>>();
+            assert_transfer_event(
+                &emitted_events[0],
+                None,
+                Some(AccountId::from([0x01; 32])),
+                100,
+            );
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            // Alice owns all the tokens on deployment
+            assert_eq!(erc20.balance_of(accounts.alice), 100);
+            // Bob does not owns tokens
+            assert_eq!(erc20.balance_of(accounts.bob), 0);
+        }
+
+        #[ink::test]
+        fn transfer_works() {
+            // Constructor works.
+            let mut erc20 = Erc20::new(100, None, None, 0);
+            // Transfer event triggered during initial construction.
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            assert_eq!(erc20.balance_of(accounts.bob), 0);
+            // Alice transfers 10 tokens to Bob.
+            assert_eq!(erc20.transfer(accounts.bob, 10), Ok(()));
+            // Bob owns 10 tokens.
+            assert_eq!(erc20.balance_of(accounts.bob), 10);
+
+            let emitted_events = ink_env::test::recorded_events().collect::<Vec<_>>();
+            assert_eq!(emitted_events.len(), 2);
+            // Check first transfer event related to ERC-20 instantiation.
+            assert_transfer_event(
+                &emitted_events[0],
+                None,
+                Some(AccountId::from([0x01; 32])),
+                100,
+            );
+            // Check the second transfer event relating to the actual trasfer.
+            assert_transfer_event(
+                &emitted_events[1],
+                Some(AccountId::from([0x01; 32])),
+                Some(AccountId::from([0x02; 32])),
+                10,
+            );
+        }
+
+        #[ink::test]
+        fn invalid_transfer_should_fail() {
+            // Constructor works.
+            let mut erc20 = Erc20::new(100, None, None, 0);
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            assert_eq!(erc20.balance_of(accounts.bob), 0);
+            // Get contract address.
+            let callee = ink_env::account_id::<ink_env::DefaultEnvironment>()
+                .unwrap_or([0x0; 32].into());
+            // Create call
+            let mut data =
+                ink_env::test::CallData::new(ink_env::call::Selector::new([0x00; 4])); // balance_of
+            data.push_arg(&accounts.bob);
+            // Push the new execution context to set Bob as caller
+            ink_env::test::push_execution_context::<ink_env::DefaultEnvironment>(
+                accounts.bob,
+                callee,
+                1000000,
+                1000000,
+                data,
+            );
+
+            // Bob fails to transfers 10 tokens to Eve.
+            assert_eq!(
+                erc20.transfer(accounts.eve, 10),
+                Err(Error::InsufficientBalance)
+            );
+            // Alice owns all the tokens.
+            assert_eq!(erc20.balance_of(accounts.alice), 100);
+            assert_eq!(erc20.balance_of(accounts.bob), 0);
+            assert_eq!(erc20.balance_of(accounts.eve), 0);
+
+            // Transfer event triggered during initial construction.
+            let emitted_events = ink_env::test::recorded_events().collect::<Vec<_>>();
+            assert_eq!(emitted_events.len(), 1);
+            assert_transfer_event(
+                &emitted_events[0],
+                None,
+                Some(AccountId::from([0x01; 32])),
+                100,
+            );
+        }
+
+        #[ink::test]
+        fn transfer_from_works() {
+            // Constructor works.
+            let mut erc20 = Erc20::new(100, None, None, 0);
+            // Transfer event triggered during initial construction.
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            // Bob fails to transfer tokens owned by Alice.
+            assert_eq!(
+                erc20.transfer_from(accounts.alice, accounts.eve, 10),
+                Err(Error::InsufficientAllowance)
+            );
+            // Alice approves Bob for token transfers on her behalf.
+            assert_eq!(erc20.approve(accounts.bob, 10), Ok(()));
+
+            // The approve event takes place.
+            assert_eq!(ink_env::test::recorded_events().count(), 2);
+
+            // Get contract address.
+            let callee = ink_env::account_id::<ink_env::DefaultEnvironment>()
+                .unwrap_or([0x0; 32].into());
+            // Create call.
+            let mut data =
+                ink_env::test::CallData::new(ink_env::call::Selector::new([0x00; 4])); // balance_of
+            data.push_arg(&accounts.bob);
+            // Push the new execution context to set Bob as caller.
+            ink_env::test::push_execution_context::<ink_env::DefaultEnvironment>(
+                accounts.bob,
+                callee,
+                1000000,
+                1000000,
+                data,
+            );
+
+            // Bob transfers tokens from Alice to Eve.
+            assert_eq!(
+                erc20.transfer_from(accounts.alice, accounts.eve, 10),
+                Ok(())
+            );
+            // Eve owns tokens.
+            assert_eq!(erc20.balance_of(accounts.eve), 10);
+
+            // Check all transfer events that happened during the previous calls:
+            let emitted_events = ink_env::test::recorded_events().collect::<Vec<_>>();
+            assert_eq!(emitted_events.len(), 4);
+            assert_transfer_event(
+                &emitted_events[0],
+                None,
+                Some(AccountId::from([0x01; 32])),
+                100,
+            );
+            assert_approval_event(&emitted_events[1], accounts.alice, accounts.bob, 10);
+            assert_transfer_event(
+                &emitted_events[2],
+                Some(AccountId::from([0x01; 32])),
+                Some(AccountId::from([0x05; 32])),
+                10,
+            );
+            // Spending the allowance reports what is left of it.
+            assert_approval_event(&emitted_events[3], accounts.alice, accounts.bob, 0);
+        }
+
+        #[ink::test]
+        fn allowance_must_not_change_on_failed_transfer() {
+            let mut erc20 = Erc20::new(100, None, None, 0);
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            // Alice approves Bob for token transfers on her behalf.
+            let alice_balance = erc20.balance_of(accounts.alice);
+            let initial_allowance = alice_balance + 2;
+            assert_eq!(erc20.approve(accounts.bob, initial_allowance), Ok(()));
+
+            // Get contract address.
+            let callee = ink_env::account_id::<ink_env::DefaultEnvironment>()
+                .unwrap_or([0x0; 32].into());
+            // Create call.
+            let mut data =
+                ink_env::test::CallData::new(ink_env::call::Selector::new([0x00; 4])); // balance_of
+            data.push_arg(&accounts.bob);
+            // Push the new execution context to set Bob as caller.
+            ink_env::test::push_execution_context::<ink_env::DefaultEnvironment>(
+                accounts.bob,
+                callee,
+                1000000,
+                1000000,
+                data,
+            );
+
+            // Bob tries to transfer tokens from Alice to Eve.
+            let emitted_events_before =
+                ink_env::test::recorded_events().collect::<Vec<_>>();
+            assert_eq!(
+                erc20.transfer_from(accounts.alice, accounts.eve, alice_balance + 1),
+                Err(Error::InsufficientBalance)
+            );
+            // Allowance must have stayed the same
+            assert_eq!(
+                erc20.allowance(accounts.alice, accounts.bob),
+                initial_allowance
+            );
+            // No more events must have been emitted
+            let emitted_events_after =
+                ink_env::test::recorded_events().collect::<Vec<_>>();
+            assert_eq!(emitted_events_before.len(), emitted_events_after.len());
+        }
+
+        /// Returns the account of the ECDSA key `secret` and its signature of the
+        /// permit for `spender` to spend `value` of its tokens until `deadline`.
+        fn sign_permit(
+            erc20: &Erc20,
+            secret: [u8; 32],
+            spender: AccountId,
+            value: Balance,
+            deadline: Timestamp,
+        ) -> (AccountId, [u8; 65]) {
+            let secret = secp256k1::SecretKey::parse(&secret).expect("Invalid key");
+            let public_key = secp256k1::PublicKey::from_secret_key(&secret);
+            let mut owner = [0x00; 32];
+            ink_env::hash_bytes::<Blake2x256>(
+                &public_key.serialize_compressed(),
+                &mut owner,
+            );
+            let owner = AccountId::from(owner);
+            let contract = ink_env::account_id::<ink_env::DefaultEnvironment>()
+                .expect("Cannot get contract");
+            let mut message = [0x00; 32];
+            ink_env::hash_encoded::<Blake2x256, _>(
+                &(
+                    contract,
+                    owner,
+                    spender,
+                    value,
+                    erc20.nonce_of(owner),
+                    deadline,
+                ),
+                &mut message,
+            );
+            let (signature, recovery_id) =
+                secp256k1::sign(&secp256k1::Message::parse(&message), &secret);
+            let mut bytes = [0x00; 65];
+            bytes[..64].copy_from_slice(&signature.serialize());
+            bytes[64] = recovery_id.serialize();
+            (owner, bytes)
+        }
+
+        #[ink::test]
+        fn mint_works() {
+            let mut erc20 = Erc20::new(100, None, None, 0);
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            // Alice is the deployer and holds the minter role.
+            assert!(erc20.is_minter(accounts.alice));
+            assert_eq!(erc20.mint(accounts.bob, 50), Ok(()));
+            assert_eq!(erc20.balance_of(accounts.bob), 50);
+            assert_eq!(erc20.total_supply(), 150);
+
+            // Constructor transfer, mint transfer and the mint event.
+            let emitted_events = ink_env::test::recorded_events().collect::<Vec<_>>();
+            assert_eq!(emitted_events.len(), 3);
+            assert_transfer_event(&emitted_events[1], None, Some(accounts.bob), 50);
+        }
+
+        #[ink::test]
+        fn mint_requires_minter_role() {
+            let mut erc20 = Erc20::new(100, None, None, 0);
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            set_caller!(accounts.bob);
+            assert_eq!(erc20.mint(accounts.bob, 50), Err(Error::NotMinter));
+            assert_eq!(erc20.total_supply(), 100);
+        }
+
+        #[ink::test]
+        fn minter_role_is_managed_by_owner() {
+            let mut erc20 = Erc20::new(100, None, None, 0);
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            assert_eq!(erc20.grant_minter(accounts.bob), Ok(()));
+            assert!(erc20.is_minter(accounts.bob));
+
+            set_caller!(accounts.bob);
+            assert_eq!(erc20.mint(accounts.bob, 5), Ok(()));
+            assert_eq!(erc20.revoke_minter(accounts.alice), Err(Error::NotOwner));
+            ink_env::test::pop_execution_context();
+
+            assert_eq!(erc20.revoke_minter(accounts.bob), Ok(()));
+            assert!(!erc20.is_minter(accounts.bob));
+        }
+
+        #[ink::test]
+        fn capped_mint_works() {
+            let mut erc20 = Erc20::new_capped(100, 150, None, None, 0);
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            assert_eq!(erc20.cap(), Some(150));
+            assert_eq!(erc20.mint(accounts.bob, 50), Ok(()));
+            assert_eq!(erc20.mint(accounts.bob, 1), Err(Error::CapExceeded));
+            assert_eq!(erc20.total_supply(), 150);
+
+            // Burned tokens free up room below the cap.
+            assert_eq!(erc20.burn(10), Ok(()));
+            assert_eq!(erc20.mint(accounts.bob, 10), Ok(()));
+            assert_eq!(erc20.balance_of(accounts.bob), 60);
+        }
+
+        #[ink::test]
+        fn uncapped_token_has_no_cap() {
+            let erc20 = Erc20::new(100, None, None, 0);
+            assert_eq!(erc20.cap(), None);
+        }
+
+        #[ink::test]
+        #[should_panic(expected = "initial supply exceeds the cap")]
+        fn initial_supply_above_cap_fails() {
+            let _ = Erc20::new_capped(200, 150, None, None, 0);
+        }
+
+        #[ink::test]
+        fn paused_contract_rejects_transfers_and_approvals() {
+            let mut erc20 = Erc20::new(100, None, None, 0);
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            assert_eq!(erc20.approve(accounts.bob, 10), Ok(()));
+            assert_eq!(erc20.pause(), Ok(()));
+            assert!(erc20.paused());
+
+            assert_eq!(erc20.transfer(accounts.bob, 10), Err(Error::Paused));
+            assert_eq!(erc20.approve(accounts.bob, 20), Err(Error::Paused));
+            set_caller!(accounts.bob);
+            assert_eq!(
+                erc20.transfer_from(accounts.alice, accounts.bob, 10),
+                Err(Error::Paused)
+            );
+            ink_env::test::pop_execution_context();
+
+            assert_eq!(erc20.unpause(), Ok(()));
+            assert_eq!(erc20.transfer(accounts.bob, 10), Ok(()));
+            assert_eq!(erc20.balance_of(accounts.bob), 10);
+        }
+
+        #[ink::test]
+        fn pause_requires_owner() {
+            let mut erc20 = Erc20::new(100, None, None, 0);
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            set_caller!(accounts.bob);
+            assert_eq!(erc20.pause(), Err(Error::NotOwner));
+            assert_eq!(erc20.unpause(), Err(Error::NotOwner));
+            assert!(!erc20.paused());
+        }
+
+        #[ink::test]
+        fn expired_permit_fails() {
+            let mut erc20 = Erc20::new(100, None, None, 0);
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            ink_env::test::advance_block::<ink_env::DefaultEnvironment>()
+                .expect("Cannot advance block");
+            assert_eq!(
+                erc20.permit(accounts.alice, accounts.bob, 10, 0, [0; 65]),
+                Err(Error::PermitExpired)
+            );
+            assert_eq!(erc20.allowance(accounts.alice, accounts.bob), 0);
+            // A rejected permit does not use up the nonce.
+            assert_eq!(erc20.nonce_of(accounts.alice), 0);
+        }
+
+        #[ink::test]
+        fn nonce_of_reports_used_permits() {
+            let mut erc20 = Erc20::new(100, None, None, 0);
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            for nonce in 0..3 {
+                let (owner, signature) =
+                    sign_permit(&erc20, [0x01; 32], accounts.bob, 10, Timestamp::MAX);
+                assert_eq!(erc20.nonce_of(owner), nonce);
+                assert_eq!(
+                    erc20.permit(owner, accounts.bob, 10, Timestamp::MAX, signature),
+                    Ok(())
+                );
+                assert_eq!(erc20.nonce_of(owner), nonce + 1);
+            }
+            assert_eq!(erc20.nonce_of(accounts.bob), 0);
+        }
+
+        #[ink::test]
+        fn permit_with_invalid_signature_fails() {
+            let mut erc20 = Erc20::new(100, None, None, 0);
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            assert_eq!(
+                erc20.permit(accounts.alice, accounts.bob, 10, Timestamp::MAX, [0; 65]),
+                Err(Error::InvalidSignature)
+            );
+            assert_eq!(erc20.allowance(accounts.alice, accounts.bob), 0);
+        }
+
+        #[ink::test]
+        fn permit_sets_the_allowance_of_the_signer() {
+            let mut erc20 = Erc20::new(100, None, None, 0);
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            let (owner, signature) =
+                sign_permit(&erc20, [0x01; 32], accounts.bob, 10, Timestamp::MAX);
+            // The permit only holds for the spender and value it was signed for.
+            assert_eq!(
+                erc20.permit(owner, accounts.charlie, 10, Timestamp::MAX, signature),
+                Err(Error::InvalidSignature)
+            );
+            assert_eq!(
+                erc20.permit(owner, accounts.bob, 11, Timestamp::MAX, signature),
+                Err(Error::InvalidSignature)
+            );
+            assert_eq!(
+                erc20.permit(owner, accounts.bob, 10, Timestamp::MAX, signature),
+                Ok(())
+            );
+            assert_eq!(erc20.allowance(owner, accounts.bob), 10);
+            // The signature used up its nonce, so it can not be replayed.
+            assert_eq!(
+                erc20.permit(owner, accounts.bob, 10, Timestamp::MAX, signature),
+                Err(Error::InvalidSignature)
+            );
+        }
+
+        #[ink::test]
+        fn increase_and_decrease_allowance_works() {
+            let mut erc20 = Erc20::new(100, None, None, 0);
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            assert_eq!(erc20.increase_allowance(accounts.bob, 10), Ok(()));
+            assert_eq!(erc20.increase_allowance(accounts.bob, 5), Ok(()));
+            assert_eq!(erc20.allowance(accounts.alice, accounts.bob), 15);
+            assert_eq!(erc20.decrease_allowance(accounts.bob, 7), Ok(()));
+            assert_eq!(erc20.allowance(accounts.alice, accounts.bob), 8);
+
+            // Decreasing by more than the allowance saturates at zero.
+            assert_eq!(erc20.decrease_allowance(accounts.bob, 100), Ok(()));
+            assert_eq!(erc20.allowance(accounts.alice, accounts.bob), 0);
+
+            // Constructor transfer plus one approval per adjustment.
+            let emitted_events = ink_env::test::recorded_events().collect::<Vec<_>>();
+            assert_eq!(emitted_events.len(), 5);
+            for (event, value) in emitted_events[1..].iter().zip(&[10, 15, 8, 0]) {
+                assert_approval_event(event, accounts.alice, accounts.bob, *value);
+            }
+        }
+
+        #[ink::test]
+        fn transfer_batch_works() {
+            let mut erc20 = Erc20::new(100, None, None, 0);
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            assert_eq!(
+                erc20.transfer_batch(vec![
+                    (accounts.bob, 10),
+                    (accounts.charlie, 20),
+                    (accounts.bob, 5),
+                ]),
+                Ok(())
+            );
+            assert_eq!(erc20.balance_of(accounts.alice), 65);
+            assert_eq!(erc20.balance_of(accounts.bob), 15);
+            assert_eq!(erc20.balance_of(accounts.charlie), 20);
+
+            // Constructor transfer plus a single aggregated event.
+            assert_eq!(ink_env::test::recorded_events().count(), 2);
+        }
+
+        #[ink::test]
+        fn transfer_batch_is_all_or_nothing() {
+            let mut erc20 = Erc20::new(100, None, None, 0);
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            assert_eq!(
+                erc20.transfer_batch(vec![(accounts.bob, 60), (accounts.charlie, 41)]),
+                Err(Error::InsufficientBalance)
+            );
+            assert_eq!(
+                erc20.transfer_batch(vec![
+                    (accounts.bob, 1),
+                    (accounts.charlie, Balance::MAX)
+                ]),
+                Err(Error::InsufficientBalance)
+            );
+            assert_eq!(erc20.balance_of(accounts.alice), 100);
+            assert_eq!(erc20.balance_of(accounts.bob), 0);
+            assert_eq!(erc20.balance_of(accounts.charlie), 0);
+        }
+
+        #[ink::test]
+        fn snapshots_record_historical_balances() {
+            let mut erc20 = Erc20::new(100, None, None, 0);
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            assert_eq!(erc20.balance_of_at(accounts.alice, 1), None);
+            assert_eq!(erc20.snapshot(), Ok(1));
+            assert_eq!(erc20.transfer(accounts.bob, 10), Ok(()));
+            assert_eq!(erc20.transfer(accounts.bob, 10), Ok(()));
+            assert_eq!(erc20.snapshot(), Ok(2));
+            assert_eq!(erc20.mint(accounts.bob, 50), Ok(()));
+            assert_eq!(erc20.snapshot(), Ok(3));
+
+            assert_eq!(erc20.balance_of_at(accounts.alice, 1), Some(100));
+            assert_eq!(erc20.balance_of_at(accounts.bob, 1), Some(0));
+            assert_eq!(erc20.balance_of_at(accounts.alice, 2), Some(80));
+            assert_eq!(erc20.balance_of_at(accounts.bob, 2), Some(20));
+            assert_eq!(erc20.balance_of_at(accounts.bob, 3), Some(70));
+            assert_eq!(erc20.balance_of_at(accounts.charlie, 3), Some(0));
+            assert_eq!(erc20.total_supply_at(1), Some(100));
+            assert_eq!(erc20.total_supply_at(2), Some(100));
+            assert_eq!(erc20.total_supply_at(3), Some(150));
+            assert_eq!(erc20.total_supply_at(4), None);
+        }
+
+        #[ink::test]
+        fn snapshot_requires_owner() {
+            let mut erc20 = Erc20::new(100, None, None, 0);
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            set_caller!(accounts.bob);
+            assert_eq!(erc20.snapshot(), Err(Error::NotOwner));
+        }
+
+        #[ink::test]
+        fn frozen_accounts_can_not_send_or_receive() {
+            let mut erc20 = Erc20::new(100, None, None, 0);
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            assert_eq!(erc20.transfer(accounts.bob, 10), Ok(()));
+            assert_eq!(erc20.freeze(accounts.bob), Ok(()));
+            assert!(erc20.is_frozen(accounts.bob));
+
+            assert_eq!(erc20.transfer(accounts.bob, 10), Err(Error::AccountFrozen));
+            assert_eq!(
+                erc20.transfer_batch(vec![(accounts.charlie, 1), (accounts.bob, 1)]),
+                Err(Error::AccountFrozen)
+            );
+            set_caller!(accounts.bob);
+            assert_eq!(
+                erc20.transfer(accounts.alice, 10),
+                Err(Error::AccountFrozen)
+            );
+            ink_env::test::pop_execution_context();
+
+            assert_eq!(erc20.unfreeze(accounts.bob), Ok(()));
+            assert!(!erc20.is_frozen(accounts.bob));
+            assert_eq!(erc20.transfer(accounts.bob, 10), Ok(()));
+            assert_eq!(erc20.balance_of(accounts.bob), 20);
+            assert_eq!(erc20.balance_of(accounts.charlie), 0);
+        }
+
+        #[ink::test]
+        fn frozen_accounts_can_not_be_minted_to_or_release() {
+            let mut erc20 = Erc20::new(100, None, None, 0);
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            assert_eq!(erc20.mint_vested(accounts.bob, 40, 0, 0, 0), Ok(()));
+            assert_eq!(erc20.freeze(accounts.bob), Ok(()));
+            assert_eq!(erc20.mint(accounts.bob, 10), Err(Error::AccountFrozen));
+            set_caller!(accounts.bob);
+            assert_eq!(erc20.release(), Err(Error::AccountFrozen));
+            assert_eq!(erc20.balance_of(accounts.bob), 0);
+            assert_eq!(erc20.vested_balance_of(accounts.bob), 40);
+            ink_env::test::pop_execution_context();
+
+            assert_eq!(erc20.unfreeze(accounts.bob), Ok(()));
+            set_caller!(accounts.bob);
+            assert_eq!(erc20.release(), Ok(40));
+            assert_eq!(erc20.balance_of(accounts.bob), 40);
+        }
+
+        #[ink::test]
+        fn freeze_requires_owner() {
+            let mut erc20 = Erc20::new(100, None, None, 0);
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            set_caller!(accounts.bob);
+            assert_eq!(erc20.freeze(accounts.charlie), Err(Error::NotOwner));
+            assert_eq!(erc20.unfreeze(accounts.charlie), Err(Error::NotOwner));
+        }
+
+        #[ink::test]
+        fn ownership_transfer_takes_two_steps() {
+            let mut erc20 = Erc20::new(100, None, None, 0);
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            assert_eq!(erc20.owner(), accounts.alice);
+            assert_eq!(erc20.propose_owner(accounts.bob), Ok(()));
+            assert_eq!(erc20.pending_owner(), Some(accounts.bob));
+            // Proposing does not hand over any rights yet.
+            assert_eq!(erc20.owner(), accounts.alice);
+
+            set_caller!(accounts.charlie);
+            assert_eq!(erc20.accept_ownership(), Err(Error::NotPendingOwner));
+            ink_env::test::pop_execution_context();
+
+            set_caller!(accounts.bob);
+            assert_eq!(erc20.accept_ownership(), Ok(()));
+            assert_eq!(erc20.owner(), accounts.bob);
+            assert_eq!(erc20.pending_owner(), None);
+            assert_eq!(erc20.pause(), Ok(()));
+            ink_env::test::pop_execution_context();
+
+            // The previous owner lost its rights.
+            assert_eq!(erc20.unpause(), Err(Error::NotOwner));
+            assert_eq!(erc20.propose_owner(accounts.alice), Err(Error::NotOwner));
+        }
+
+        #[ink::test]
+        fn vested_tokens_are_released_linearly() {
+            let mut erc20 = Erc20::new(100, None, None, 0);
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            ink_env::test::advance_block::<ink_env::DefaultEnvironment>()
+                .expect("Cannot advance block");
+            let now = ink_env::block_timestamp::<ink_env::DefaultEnvironment>()
+                .expect("Cannot get block timestamp");
+            // Half way through a schedule that started at `0`.
+            assert_eq!(erc20.mint_vested(accounts.bob, 50, 0, 0, 2 * now), Ok(()));
+            // Only starts vesting after the current block.
+            assert_eq!(erc20.mint_vested(accounts.bob, 30, now + 1, 0, 10), Ok(()));
+            assert_eq!(erc20.total_supply(), 180);
+            assert_eq!(erc20.balance_of(accounts.bob), 0);
+            assert_eq!(erc20.vested_balance_of(accounts.bob), 25);
+
+            set_caller!(accounts.bob);
+            assert_eq!(erc20.release(), Ok(25));
+            assert_eq!(erc20.release(), Ok(0));
+            assert_eq!(erc20.balance_of(accounts.bob), 25);
+            assert_eq!(erc20.vested_balance_of(accounts.bob), 0);
+            assert_eq!(erc20.vesting_schedules_of(accounts.bob).len(), 2);
+        }
+
+        #[ink::test]
+        fn vesting_respects_cliff_and_cap() {
+            let mut erc20 = Erc20::new_capped(100, 150, None, None, 0);
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            assert_eq!(
+                erc20.mint_vested(accounts.bob, 10, 0, 11, 10),
+                Err(Error::InvalidSchedule)
+            );
+            assert_eq!(
+                erc20.mint_vested(accounts.bob, 51, 0, 0, 10),
+                Err(Error::CapExceeded)
+            );
+            assert_eq!(
+                erc20.mint_vested(accounts.bob, 50, 0, Timestamp::MAX, Timestamp::MAX),
+                Ok(())
+            );
+            assert_eq!(erc20.vested_balance_of(accounts.bob), 0);
+
+            set_caller!(accounts.bob);
+            assert_eq!(
+                erc20.mint_vested(accounts.bob, 1, 0, 0, 0),
+                Err(Error::NotMinter)
+            );
+        }
+
+        #[ink::test]
+        fn transfer_fee_is_sent_to_collector() {
+            let mut erc20 = Erc20::new(100, None, None, 0);
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            assert_eq!(erc20.set_transfer_fee(1_000, accounts.charlie), Ok(()));
+            assert_eq!(erc20.transfer_fee_bps(), 1_000);
+            assert_eq!(erc20.fee_collector(), accounts.charlie);
+
+            assert_eq!(erc20.transfer(accounts.bob, 50), Ok(()));
+            assert_eq!(erc20.balance_of(accounts.alice), 50);
+            assert_eq!(erc20.balance_of(accounts.bob), 45);
+            assert_eq!(erc20.balance_of(accounts.charlie), 5);
+
+            let emitted_events = ink_env::test::recorded_events().collect::<Vec<_>>();
+            assert_transfer_event(
+                &emitted_events[1],
+                Some(accounts.alice),
+                Some(accounts.bob),
+                45,
+            );
+            assert_transfer_event(
+                &emitted_events[2],
+                Some(accounts.alice),
+                Some(accounts.charlie),
+                5,
+            );
+
+            assert_eq!(
+                erc20.transfer_batch(vec![(accounts.bob, 20), (accounts.django, 10)]),
+                Ok(())
+            );
+            assert_eq!(erc20.balance_of(accounts.bob), 63);
+            assert_eq!(erc20.balance_of(accounts.django), 9);
+            assert_eq!(erc20.balance_of(accounts.charlie), 8);
+            assert_eq!(erc20.total_supply(), 100);
+        }
+
+        #[ink::test]
+        fn no_fee_is_charged_while_the_collector_is_frozen() {
+            let mut erc20 = Erc20::new(100, None, None, 0);
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            assert_eq!(erc20.set_transfer_fee(1_000, accounts.charlie), Ok(()));
+            assert_eq!(erc20.set_flash_fee(50), Ok(()));
+            assert_eq!(erc20.freeze(accounts.charlie), Ok(()));
+            assert_eq!(erc20.flash_fee(1_000), 0);
+
+            assert_eq!(erc20.transfer(accounts.bob, 50), Ok(()));
+            assert_eq!(
+                erc20.transfer_batch(vec![(accounts.bob, 20), (accounts.django, 10)]),
+                Ok(())
+            );
+            assert_eq!(erc20.balance_of(accounts.bob), 70);
+            assert_eq!(erc20.balance_of(accounts.django), 10);
+            assert_eq!(erc20.balance_of(accounts.charlie), 0);
+
+            assert_eq!(erc20.unfreeze(accounts.charlie), Ok(()));
+            assert_eq!(erc20.flash_fee(1_000), 5);
+            assert_eq!(erc20.transfer(accounts.bob, 10), Ok(()));
+            assert_eq!(erc20.balance_of(accounts.bob), 79);
+            assert_eq!(erc20.balance_of(accounts.charlie), 1);
+        }
+
+        #[ink::test]
+        fn set_transfer_fee_validates_input() {
+            let mut erc20 = Erc20::new(100, None, None, 0);
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            assert_eq!(
+                erc20.set_transfer_fee(10_001, accounts.charlie),
+                Err(Error::InvalidFee)
+            );
+            set_caller!(accounts.bob);
+            assert_eq!(
+                erc20.set_transfer_fee(100, accounts.bob),
+                Err(Error::NotOwner)
+            );
+            ink_env::test::pop_execution_context();
+            assert_eq!(erc20.transfer_fee_bps(), 0);
+        }
+
+        #[ink::test]
+        fn psp22_messages_work() {
+            let mut erc20 = Erc20::new(100, None, None, 0);
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            assert_eq!(erc20.psp22_transfer(accounts.bob, 10, Vec::new()), Ok(()));
+            assert_eq!(erc20.psp22_approve(accounts.bob, 10), Ok(()));
+            assert_eq!(erc20.psp22_increase_allowance(accounts.bob, 5), Ok(()));
+            assert_eq!(erc20.psp22_decrease_allowance(accounts.bob, 3), Ok(()));
+
+            set_caller!(accounts.bob);
+            assert_eq!(
+                erc20.psp22_transfer_from(accounts.alice, accounts.bob, 12, Vec::new()),
+                Ok(())
+            );
+            assert_eq!(erc20.balance_of(accounts.bob), 22);
+            assert_eq!(erc20.allowance(accounts.alice, accounts.bob), 0);
+        }
+
+        #[ink::test]
+        fn psp22_queries_match_legacy_queries() {
+            let mut erc20 = Erc20::new(
+                100,
+                Some(String::from("Redspot")),
+                Some(String::from("RDS")),
+                10,
+            );
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            assert_eq!(erc20.approve(accounts.bob, 10), Ok(()));
+            assert_eq!(erc20.psp22_total_supply(), erc20.total_supply());
+            assert_eq!(erc20.psp22_token_name(), erc20.token_name());
+            assert_eq!(erc20.psp22_token_symbol(), erc20.token_symbol());
+            assert_eq!(erc20.psp22_token_decimals(), erc20.token_decimals());
+            assert_eq!(erc20.psp22_balance_of(accounts.alice), 100);
+            assert_eq!(erc20.balance_of(accounts.alice), 100);
+            assert_eq!(erc20.psp22_allowance(accounts.alice, accounts.bob), 10);
+            assert_eq!(erc20.allowance(accounts.alice, accounts.bob), 10);
+        }
+
+        #[ink::test]
+        fn psp22_messages_return_psp22_errors() {
+            let mut erc20 = Erc20::new(100, None, None, 0);
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            assert_eq!(
+                erc20.psp22_transfer(accounts.bob, 101, Vec::new()),
+                Err(PSP22Error::InsufficientBalance)
+            );
+            assert_eq!(
+                erc20.psp22_transfer_from(accounts.bob, accounts.alice, 1, Vec::new()),
+                Err(PSP22Error::InsufficientAllowance)
+            );
+            assert_eq!(erc20.pause(), Ok(()));
+            assert_eq!(
+                erc20.psp22_approve(accounts.bob, 1),
+                Err(PSP22Error::Custom(String::from("Paused")))
+            );
+        }
+
+        #[ink::test]
+        fn flash_fee_and_limit_work() {
+            let mut erc20 = Erc20::new_capped(100, 1_100, None, None, 0);
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            assert_eq!(erc20.max_flash_mint(), 1_000);
+            assert_eq!(erc20.flash_fee(1_000), 0);
+            assert_eq!(erc20.set_flash_fee(50), Ok(()));
+            assert_eq!(erc20.flash_fee(1_000), 5);
+            assert_eq!(erc20.set_flash_fee(10_001), Err(Error::InvalidFee));
+            assert_eq!(
+                erc20.flash_mint(accounts.bob, 1_001, Vec::new()),
+                Err(Error::CapExceeded)
+            );
+            assert_eq!(
+                erc20.flash_mint(AccountId::from([0x00; 32]), 10, Vec::new()),
+                Err(Error::ZeroRecipient)
+            );
+            assert_eq!(erc20.total_supply(), 100);
+
+            set_caller!(accounts.bob);
+            assert_eq!(erc20.set_flash_fee(0), Err(Error::NotOwner));
+            ink_env::test::pop_execution_context();
+
+            let uncapped = Erc20::new(100, None, None, 0);
+            assert_eq!(uncapped.max_flash_mint(), Balance::MAX - 100);
+        }
+
+        #[ink::test]
+        fn delegated_votes_follow_balances() {
+            let mut erc20 = Erc20::new(100, None, None, 0);
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            // Tokens do not count as votes until delegated.
+            assert_eq!(erc20.get_votes(accounts.alice), 0);
+            assert_eq!(erc20.delegate(accounts.alice), Ok(()));
+            assert_eq!(erc20.delegates(accounts.alice), Some(accounts.alice));
+            assert_eq!(erc20.get_votes(accounts.alice), 100);
+
+            assert_eq!(erc20.transfer(accounts.bob, 30), Ok(()));
+            assert_eq!(erc20.get_votes(accounts.alice), 70);
+            assert_eq!(erc20.get_votes(accounts.bob), 0);
+
+            set_caller!(accounts.bob);
+            assert_eq!(erc20.delegate(accounts.charlie), Ok(()));
+            assert_eq!(erc20.get_votes(accounts.charlie), 30);
+            assert_eq!(erc20.delegate(accounts.alice), Ok(()));
+            assert_eq!(erc20.get_votes(accounts.charlie), 0);
+            assert_eq!(erc20.get_votes(accounts.alice), 100);
+        }
+
+        #[ink::test]
+        fn past_votes_are_checkpointed_per_block() {
+            let mut erc20 = Erc20::new(100, None, None, 0);
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let start = ink_env::block_number::<ink_env::DefaultEnvironment>()
+                .expect("Cannot get block number");
+
+            assert_eq!(erc20.delegate(accounts.alice), Ok(()));
+            ink_env::test::advance_block::<ink_env::DefaultEnvironment>()
+                .expect("Cannot advance block");
+            assert_eq!(erc20.burn(40), Ok(()));
+            assert_eq!(erc20.burn(10), Ok(()));
+            ink_env::test::advance_block::<ink_env::DefaultEnvironment>()
+                .expect("Cannot advance block");
+
+            assert_eq!(erc20.get_past_votes(accounts.alice, start), Ok(100));
+            assert_eq!(erc20.get_past_votes(accounts.alice, start + 1), Ok(50));
+            assert_eq!(
+                erc20.get_past_votes(accounts.alice, start + 2),
+                Err(Error::BlockNotMined)
+            );
+            // Both burns in the same block share a single checkpoint.
+            assert_eq!(
+                erc20.vote_checkpoints.get(&accounts.alice).unwrap().len(),
+                2
+            );
+        }
+
+        #[ink::test]
+        fn rescue_tokens_requires_owner() {
+            let mut erc20 = Erc20::new(100, None, None, 0);
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            set_caller!(accounts.bob);
+            assert_eq!(
+                erc20.rescue_tokens(accounts.django, accounts.bob, 10),
+                Err(Error::NotOwner)
+            );
+        }
+
+        #[ink::test]
+        fn rescue_tokens_rejects_own_tokens() {
+            let mut erc20 = Erc20::new(100, None, None, 0);
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let contract = ink_env::account_id::<ink_env::DefaultEnvironment>()
+                .expect("Cannot get account id");
+
+            assert_eq!(
+                erc20.rescue_tokens(contract, accounts.bob, 10),
+                Err(Error::RescueFailed)
+            );
+        }
+
+        #[ink::test]
+        fn multicall_dispatches_every_call() {
+            use scale::Encode;
+
+            let mut erc20 = Erc20::new(100, None, None, 0);
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            let mut approve = PSP22_APPROVE_SELECTOR.to_vec();
+            (accounts.bob, 30 as Balance).encode_to(&mut approve);
+            let mut transfer = PSP22_TRANSFER_SELECTOR.to_vec();
+            (accounts.charlie, 20 as Balance, Vec::<u8>::new()).encode_to(&mut transfer);
+            let mut too_large = PSP22_TRANSFER_SELECTOR.to_vec();
+            (accounts.charlie, 1_000 as Balance, Vec::<u8>::new())
+                .encode_to(&mut too_large);
+
+            assert_eq!(
+                erc20.multicall(vec![approve, transfer, too_large]),
+                vec![Ok(()), Ok(()), Err(Error::InsufficientBalance)]
+            );
+            // The failing call does not undo the ones before it.
+            assert_eq!(erc20.allowance(accounts.alice, accounts.bob), 30);
+            assert_eq!(erc20.balance_of(accounts.charlie), 20);
+            assert_eq!(erc20.balance_of(accounts.alice), 80);
+        }
+
+        #[ink::test]
+        fn multicall_rejects_invalid_calls() {
+            let mut erc20 = Erc20::new(100, None, None, 0);
+
+            let unknown = vec![0xDE, 0xAD, 0xBE, 0xEF];
+            let truncated = PSP22_APPROVE_SELECTOR[..2].to_vec();
+            let missing_args = PSP22_APPROVE_SELECTOR.to_vec();
+            assert_eq!(
+                erc20.multicall(vec![unknown, truncated, missing_args]),
+                vec![
+                    Err(Error::InvalidCall),
+                    Err(Error::InvalidCall),
+                    Err(Error::InvalidCall)
+                ]
+            );
+            assert_eq!(erc20.multicall(Vec::new()), Vec::new());
+        }
+
+        #[ink::test]
+        fn transfers_reject_the_zero_address() {
+            let mut erc20 = Erc20::new(100, None, None, 0);
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let zero = AccountId::from([0x00; 32]);
+
+            assert_eq!(erc20.transfer(zero, 10), Err(Error::ZeroRecipient));
+            assert_eq!(
+                erc20.transfer_batch(vec![(accounts.bob, 10), (zero, 10)]),
+                Err(Error::ZeroRecipient)
+            );
+            assert_eq!(erc20.mint(zero, 10), Err(Error::ZeroRecipient));
+            assert_eq!(
+                erc20.mint_vested(zero, 10, 0, 0, 0),
+                Err(Error::ZeroRecipient)
+            );
+            assert_eq!(erc20.vesting_schedules_of(zero), Vec::new());
+            assert_eq!(erc20.total_supply(), 100);
+            assert_eq!(erc20.balance_of(accounts.alice), 100);
+
+            set_caller!(zero);
+            assert_eq!(erc20.approve(accounts.bob, 10), Ok(()));
+            set_caller!(accounts.bob);
+            assert_eq!(
+                erc20.transfer_from(zero, accounts.bob, 0),
+                Err(Error::ZeroSender)
+            );
+        }
+
+        #[ink::test]
+        fn errors_map_to_psp22_errors() {
+            assert_eq!(
+                PSP22Error::from(Error::ZeroRecipient),
+                PSP22Error::ZeroRecipientAddress
+            );
+            assert_eq!(
+                PSP22Error::from(Error::ZeroSender),
+                PSP22Error::ZeroSenderAddress
+            );
+            assert_eq!(
+                PSP22Error::from(Error::Custom(String::from("Oops"))),
+                PSP22Error::Custom(String::from("Oops"))
+            );
+            assert_eq!(
+                PSP22Error::from(Error::NotOwner),
+                PSP22Error::Custom(String::from("NotOwner"))
+            );
+        }
+
+        #[ink::test]
+        fn arithmetic_is_checked_at_balance_max() {
+            let mut erc20 = Erc20::new(Balance::MAX, None, None, 0);
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            // The whole supply can still be moved and delegated.
+            assert_eq!(erc20.transfer(accounts.bob, Balance::MAX), Ok(()));
+            assert_eq!(erc20.balance_of(accounts.bob), Balance::MAX);
+            set_caller!(accounts.bob);
+            assert_eq!(erc20.delegate(accounts.bob), Ok(()));
+            assert_eq!(erc20.get_votes(accounts.bob), Balance::MAX);
+
+            // Minting beyond `Balance::MAX` fails instead of wrapping.
+            set_caller!(accounts.alice);
+            assert_eq!(erc20.mint(accounts.alice, 1), Err(Error::Overflow));
+            assert_eq!(
+                erc20.mint_vested(accounts.alice, 1, 0, 0, 1),
+                Err(Error::Overflow)
+            );
+            assert_eq!(erc20.total_supply(), Balance::MAX);
+            assert_eq!(erc20.balance_of(accounts.alice), 0);
+        }
+
+        #[ink::test]
+        fn overflowing_batch_total_fails() {
+            let mut erc20 = Erc20::new(100, None, None, 0);
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            assert_eq!(
+                erc20.transfer_batch(vec![
+                    (accounts.bob, Balance::MAX),
+                    (accounts.charlie, 1)
+                ]),
+                Err(Error::Overflow)
+            );
+            assert_eq!(erc20.balance_of(accounts.alice), 100);
+        }
+
+        #[ink::test]
+        fn overflowing_flash_mint_repayment_fails() {
+            let mut erc20 = Erc20::new(100, None, None, 0);
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            assert_eq!(erc20.set_flash_fee(10_000), Ok(()));
+            assert_eq!(
+                erc20.flash_mint(accounts.bob, Balance::MAX - 100, Vec::new()),
+                Err(Error::Overflow)
+            );
+            assert_eq!(erc20.total_supply(), 100);
+            assert_eq!(erc20.balance_of(accounts.bob), 0);
+        }
+
+        #[ink::test]
+        fn snapshot_ids_do_not_wrap() {
+            let mut erc20 = Erc20::new(100, None, None, 0);
+
+            erc20.current_snapshot_id = u32::MAX;
+            assert_eq!(erc20.snapshot(), Err(Error::Overflow));
+            assert_eq!(erc20.current_snapshot_id, u32::MAX);
+        }
+
+        #[ink::test]
+        fn self_transfer_keeps_balance() {
+            let mut erc20 = Erc20::new(100, None, None, 0);
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            assert_eq!(erc20.transfer(accounts.alice, 60), Ok(()));
+            assert_eq!(erc20.balance_of(accounts.alice), 100);
+            assert_eq!(erc20.total_supply(), 100);
+            // A self transfer can not move more than the balance either.
+            assert_eq!(
+                erc20.transfer(accounts.alice, 101),
+                Err(Error::InsufficientBalance)
+            );
+
+            let emitted_events = ink_env::test::recorded_events().collect::<Vec<_>>();
+            assert_eq!(emitted_events.len(), 2);
+            assert_transfer_event(
+                &emitted_events[1],
+                Some(accounts.alice),
+                Some(accounts.alice),
+                60,
+            );
+        }
+
+        #[ink::test]
+        fn zero_value_transfers_work() {
+            let mut erc20 = Erc20::new(100, None, None, 0);
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            assert_eq!(erc20.transfer(accounts.bob, 0), Ok(()));
+            // No allowance is needed to move nothing.
+            set_caller!(accounts.bob);
+            assert_eq!(
+                erc20.transfer_from(accounts.alice, accounts.charlie, 0),
+                Ok(())
+            );
+            assert_eq!(erc20.balance_of(accounts.alice), 100);
+            assert_eq!(erc20.balance_of(accounts.bob), 0);
+            assert_eq!(erc20.balance_of(accounts.charlie), 0);
+
+            let emitted_events = ink_env::test::recorded_events().collect::<Vec<_>>();
+            assert_transfer_event(
+                &emitted_events[1],
+                Some(accounts.alice),
+                Some(accounts.bob),
+                0,
+            );
+            assert_transfer_event(
+                &emitted_events[2],
+                Some(accounts.alice),
+                Some(accounts.charlie),
+                0,
+            );
+        }
+
+        #[ink::test]
+        fn transfer_from_fails_once_allowance_is_spent() {
+            let mut erc20 = Erc20::new(100, None, None, 0);
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            assert_eq!(erc20.approve(accounts.bob, 10), Ok(()));
+
+            set_caller!(accounts.bob);
+            assert_eq!(erc20.transfer_from(accounts.alice, accounts.bob, 6), Ok(()));
+            assert_eq!(
+                erc20.transfer_from(accounts.alice, accounts.bob, 5),
+                Err(Error::InsufficientAllowance)
+            );
+            assert_eq!(erc20.allowance(accounts.alice, accounts.bob), 4);
+            // Allowances are per spender.
+            set_caller!(accounts.charlie);
+            assert_eq!(
+                erc20.transfer_from(accounts.alice, accounts.charlie, 1),
+                Err(Error::InsufficientAllowance)
+            );
+            assert_eq!(erc20.balance_of(accounts.bob), 6);
         }
 
-        /// The default constructor does its job.
         #[ink::test]
-        fn new_works() {
-            // Constructor works.
-            let _erc20 = Erc20::new(100);
-
-            // Transfer event triggered during initial construction.
-            let emitted_events = ink_env::test::recorded_events().collect::<Vec<_>>();
-            assert_eq!(1, emitted_events.len());
+        fn transfer_and_call_fails_before_notifying() {
+            let mut erc20 = Erc20::new(100, None, None, 0);
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
 
-            assert_transfer_event(
-                &emitted_events[0],
-                None,
-                Some(AccountId::from([0x01; 32])),
-                100,
+            assert_eq!(
+                erc20.transfer_and_call(accounts.bob, 101, Vec::new()),
+                Err(Error::InsufficientBalance)
+            );
+            assert_eq!(
+                erc20.transfer_and_call(AccountId::from([0x00; 32]), 1, Vec::new()),
+                Err(Error::ZeroRecipient)
             );
+            assert_eq!(erc20.pause(), Ok(()));
+            assert_eq!(
+                erc20.transfer_and_call(accounts.bob, 1, Vec::new()),
+                Err(Error::Paused)
+            );
+            assert_eq!(erc20.balance_of(accounts.alice), 100);
         }
 
-        /// The total supply was applied.
         #[ink::test]
-        fn total_supply_works() {
-            // Constructor works.
-            let erc20 = Erc20::new(100);
-            // Transfer event triggered during initial construction.
-            let emitted_events = ink_env::test::recorded_events().collect::<Vec<_>>();
-            assert_transfer_event(
-                &emitted_events[0],
-                None,
-                Some(AccountId::from([0x01; 32])),
-                100,
+        fn psp22_allowance_messages_work() {
+            let mut erc20 = Erc20::new(100, None, None, 0);
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            assert_eq!(erc20.psp22_increase_allowance(accounts.bob, 10), Ok(()));
+            assert_eq!(erc20.psp22_decrease_allowance(accounts.bob, 3), Ok(()));
+            assert_eq!(erc20.allowance(accounts.alice, accounts.bob), 7);
+
+            set_caller!(accounts.bob);
+            assert_eq!(
+                erc20.psp22_transfer(accounts.alice, 1, Vec::new()),
+                Err(PSP22Error::InsufficientBalance)
+            );
+            assert_eq!(
+                erc20.psp22_transfer_from(accounts.alice, accounts.bob, 8, Vec::new()),
+                Err(PSP22Error::InsufficientAllowance)
             );
-            // Get the token total supply.
-            assert_eq!(erc20.total_supply(), 100);
         }
 
-        /// Get the actual balance of an account.
         #[ink::test]
-        fn balance_of_works() {
-            // Constructor works
-            let erc20 = Erc20::new(100);
-            // Transfer event triggered during initial construction
-            let emitted_events = ink_env::test::recorded_events().collect::<Vec<_>>();
-            assert_transfer_event(
-                &emitted_events[0],
-                None,
-                Some(AccountId::from([0x01; 32])),
-                100,
-            );
+        fn defaults_of_a_new_contract() {
+            let erc20 = Erc20::new(100, None, None, 0);
             let accounts =
                 ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
                     .expect("Cannot get accounts");
-            // Alice owns all the tokens on deployment
-            assert_eq!(erc20.balance_of(accounts.alice), 100);
-            // Bob does not owns tokens
-            assert_eq!(erc20.balance_of(accounts.bob), 0);
+
+            assert_eq!(erc20.owner(), accounts.alice);
+            assert_eq!(erc20.pending_owner(), None);
+            assert_eq!(erc20.fee_collector(), accounts.alice);
+            assert_eq!(erc20.transfer_fee_bps(), 0);
+            assert_eq!(erc20.flash_fee(1_000), 0);
+            assert_eq!(erc20.delegates(accounts.alice), None);
+            assert_eq!(erc20.get_votes(accounts.alice), 0);
+            assert!(!erc20.paused());
+            assert!(!erc20.is_frozen(accounts.alice));
+            assert!(!erc20.is_minter(accounts.bob));
+            assert_eq!(erc20.vesting_schedules_of(accounts.alice), Vec::new());
+            assert_eq!(erc20.balance_of_at(accounts.alice, 1), None);
         }
 
         #[ink::test]
-        fn transfer_works() {
-            // Constructor works.
-            let mut erc20 = Erc20::new(100);
-            // Transfer event triggered during initial construction.
+        fn grant_minter_requires_owner() {
+            let mut erc20 = Erc20::new(100, None, None, 0);
             let accounts =
                 ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
                     .expect("Cannot get accounts");
 
-            assert_eq!(erc20.balance_of(accounts.bob), 0);
-            // Alice transfers 10 tokens to Bob.
-            assert_eq!(erc20.transfer(accounts.bob, 10), Ok(()));
-            // Bob owns 10 tokens.
-            assert_eq!(erc20.balance_of(accounts.bob), 10);
+            set_caller!(accounts.bob);
+            assert_eq!(erc20.grant_minter(accounts.bob), Err(Error::NotOwner));
+            assert!(!erc20.is_minter(accounts.bob));
+        }
 
-            let emitted_events = ink_env::test::recorded_events().collect::<Vec<_>>();
-            assert_eq!(emitted_events.len(), 2);
-            // Check first transfer event related to ERC-20 instantiation.
-            assert_transfer_event(
-                &emitted_events[0],
-                None,
-                Some(AccountId::from([0x01; 32])),
-                100,
-            );
-            // Check the second transfer event relating to the actual trasfer.
-            assert_transfer_event(
-                &emitted_events[1],
-                Some(AccountId::from([0x01; 32])),
-                Some(AccountId::from([0x02; 32])),
-                10,
+        #[ink::test]
+        fn fully_released_schedules_are_removed() {
+            let mut erc20 = Erc20::new(100, None, None, 0);
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            assert_eq!(erc20.mint_vested(accounts.bob, 40, 0, 0, 0), Ok(()));
+            assert_eq!(
+                erc20.vesting_schedules_of(accounts.bob),
+                vec![VestingSchedule {
+                    amount: 40,
+                    released: 0,
+                    start: 0,
+                    cliff: 0,
+                    duration: 0,
+                }]
             );
+
+            set_caller!(accounts.bob);
+            assert_eq!(erc20.release(), Ok(40));
+            assert_eq!(erc20.vesting_schedules_of(accounts.bob), Vec::new());
+            assert_eq!(erc20.balance_of(accounts.bob), 40);
         }
 
         #[ink::test]
-        fn invalid_transfer_should_fail() {
-            // Constructor works.
-            let mut erc20 = Erc20::new(100);
+        fn expired_allowances_can_not_be_used() {
+            let mut erc20 = Erc20::new(100, None, None, 0);
             let accounts =
                 ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
                     .expect("Cannot get accounts");
+            let now = ink_env::block_timestamp::<ink_env::DefaultEnvironment>()
+                .expect("Cannot get block timestamp");
 
-            assert_eq!(erc20.balance_of(accounts.bob), 0);
-            // Get contract address.
-            let callee = ink_env::account_id::<ink_env::DefaultEnvironment>()
-                .unwrap_or([0x0; 32].into());
-            // Create call
-            let mut data =
-                ink_env::test::CallData::new(ink_env::call::Selector::new([0x00; 4])); // balance_of
-            data.push_arg(&accounts.bob);
-            // Push the new execution context to set Bob as caller
-            ink_env::test::push_execution_context::<ink_env::DefaultEnvironment>(
-                accounts.bob,
-                callee,
-                1000000,
-                1000000,
-                data,
+            assert_eq!(erc20.approve_with_expiry(accounts.bob, 20, now), Ok(()));
+            assert_eq!(
+                erc20.allowance_expiry(accounts.alice, accounts.bob),
+                Some(now)
             );
 
-            // Bob fails to transfers 10 tokens to Eve.
+            // Usable up to and including the expiry.
+            set_caller!(accounts.bob);
+            assert_eq!(erc20.transfer_from(accounts.alice, accounts.bob, 5), Ok(()));
+            ink_env::test::advance_block::<ink_env::DefaultEnvironment>()
+                .expect("Cannot advance block");
             assert_eq!(
-                erc20.transfer(accounts.eve, 10),
-                Err(Error::InsufficientBalance)
+                erc20.transfer_from(accounts.alice, accounts.bob, 5),
+                Err(Error::AllowanceExpired)
             );
-            // Alice owns all the tokens.
-            assert_eq!(erc20.balance_of(accounts.alice), 100);
-            assert_eq!(erc20.balance_of(accounts.bob), 0);
-            assert_eq!(erc20.balance_of(accounts.eve), 0);
+            assert_eq!(
+                erc20.burn_from(accounts.alice, 5),
+                Err(Error::AllowanceExpired)
+            );
+            assert_eq!(erc20.allowance(accounts.alice, accounts.bob), 0);
+            assert_eq!(erc20.balance_of(accounts.bob), 5);
 
-            // Transfer event triggered during initial construction.
-            let emitted_events = ink_env::test::recorded_events().collect::<Vec<_>>();
-            assert_eq!(emitted_events.len(), 1);
-            assert_transfer_event(
-                &emitted_events[0],
-                None,
-                Some(AccountId::from([0x01; 32])),
-                100,
+            // The expired allowance can not be changed by a delta, since it would
+            // stay expired.
+            ink_env::test::pop_execution_context();
+            assert_eq!(
+                erc20.increase_allowance(accounts.bob, 10),
+                Err(Error::AllowanceExpired)
+            );
+            assert_eq!(
+                erc20.decrease_allowance(accounts.bob, 10),
+                Err(Error::AllowanceExpired)
             );
+            assert_eq!(
+                erc20.allowance_expiry(accounts.alice, accounts.bob),
+                Some(now)
+            );
+
+            // A plain approval removes the expiry.
+            assert_eq!(erc20.approve(accounts.bob, 20), Ok(()));
+            assert_eq!(erc20.allowance_expiry(accounts.alice, accounts.bob), None);
+            assert_eq!(erc20.allowance(accounts.alice, accounts.bob), 20);
         }
 
         #[ink::test]
-        fn transfer_from_works() {
-            // Constructor works.
-            let mut erc20 = Erc20::new(100);
-            // Transfer event triggered during initial construction.
+        fn purge_removes_only_expired_allowances() {
+            let mut erc20 = Erc20::new(100, None, None, 0);
             let accounts =
                 ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
                     .expect("Cannot get accounts");
+            let now = ink_env::block_timestamp::<ink_env::DefaultEnvironment>()
+                .expect("Cannot get block timestamp");
 
-            // Bob fails to transfer tokens owned by Alice.
+            assert_eq!(erc20.approve_with_expiry(accounts.bob, 10, now), Ok(()));
             assert_eq!(
-                erc20.transfer_from(accounts.alice, accounts.eve, 10),
-                Err(Error::InsufficientAllowance)
+                erc20.approve_with_expiry(accounts.charlie, 10, Timestamp::MAX),
+                Ok(())
             );
-            // Alice approves Bob for token transfers on her behalf.
-            assert_eq!(erc20.approve(accounts.bob, 10), Ok(()));
+            assert_eq!(erc20.approve(accounts.django, 10), Ok(()));
+            assert_eq!(erc20.purge_expired_allowances(), 0);
 
-            // The approve event takes place.
-            assert_eq!(ink_env::test::recorded_events().count(), 2);
+            ink_env::test::advance_block::<ink_env::DefaultEnvironment>()
+                .expect("Cannot advance block");
+            set_caller!(accounts.eve);
+            assert_eq!(erc20.purge_expired_allowances(), 1);
+            assert_eq!(erc20.allowances.get(&(accounts.alice, accounts.bob)), None);
+            assert_eq!(erc20.allowance_expiry(accounts.alice, accounts.bob), None);
+            assert_eq!(erc20.allowance(accounts.alice, accounts.charlie), 10);
+            assert_eq!(erc20.allowance(accounts.alice, accounts.django), 10);
 
-            // Get contract address.
-            let callee = ink_env::account_id::<ink_env::DefaultEnvironment>()
-                .unwrap_or([0x0; 32].into());
-            // Create call.
-            let mut data =
-                ink_env::test::CallData::new(ink_env::call::Selector::new([0x00; 4])); // balance_of
-            data.push_arg(&accounts.bob);
-            // Push the new execution context to set Bob as caller.
-            ink_env::test::push_execution_context::<ink_env::DefaultEnvironment>(
+            let emitted_events = ink_env::test::recorded_events().collect::<Vec<_>>();
+            assert_approval_event(
+                emitted_events.last().unwrap(),
+                accounts.alice,
                 accounts.bob,
-                callee,
-                1000000,
-                1000000,
-                data,
+                0,
             );
+        }
 
-            // Bob transfers tokens from Alice to Eve.
-            assert_eq!(
-                erc20.transfer_from(accounts.alice, accounts.eve, 10),
-                Ok(())
-            );
-            // Eve owns tokens.
-            assert_eq!(erc20.balance_of(accounts.eve), 10);
+        #[ink::test]
+        fn burn_works() {
+            let mut erc20 = Erc20::new(100, None, None, 0);
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
 
-            // Check all transfer events that happened during the previous calls:
+            assert_eq!(erc20.burn(30), Ok(()));
+            assert_eq!(erc20.balance_of(accounts.alice), 70);
+            assert_eq!(erc20.total_supply(), 70);
+
+            // Constructor transfer, burn transfer and the burn event.
             let emitted_events = ink_env::test::recorded_events().collect::<Vec<_>>();
             assert_eq!(emitted_events.len(), 3);
-            assert_transfer_event(
-                &emitted_events[0],
-                None,
-                Some(AccountId::from([0x01; 32])),
-                100,
-            );
-            // The second event `emitted_events[1]` is an Approve event that we skip checking.
-            assert_transfer_event(
-                &emitted_events[2],
-                Some(AccountId::from([0x01; 32])),
-                Some(AccountId::from([0x05; 32])),
-                10,
-            );
+            assert_transfer_event(&emitted_events[1], Some(accounts.alice), None, 30);
         }
 
         #[ink::test]
-        fn allowance_must_not_change_on_failed_transfer() {
-            let mut erc20 = Erc20::new(100);
+        fn burn_above_balance_fails() {
+            let mut erc20 = Erc20::new(100, None, None, 0);
+
+            assert_eq!(erc20.burn(101), Err(Error::InsufficientBalance));
+            assert_eq!(erc20.total_supply(), 100);
+            assert_eq!(ink_env::test::recorded_events().count(), 1);
+        }
+
+        #[ink::test]
+        fn burn_from_consumes_allowance() {
+            let mut erc20 = Erc20::new(100, None, None, 0);
             let accounts =
                 ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
                     .expect("Cannot get accounts");
 
-            // Alice approves Bob for token transfers on her behalf.
-            let alice_balance = erc20.balance_of(accounts.alice);
-            let initial_allowance = alice_balance + 2;
-            assert_eq!(erc20.approve(accounts.bob, initial_allowance), Ok(()));
+            assert_eq!(erc20.approve(accounts.bob, 20), Ok(()));
 
-            // Get contract address.
-            let callee = ink_env::account_id::<ink_env::DefaultEnvironment>()
-                .unwrap_or([0x0; 32].into());
-            // Create call.
-            let mut data =
-                ink_env::test::CallData::new(ink_env::call::Selector::new([0x00; 4])); // balance_of
-            data.push_arg(&accounts.bob);
-            // Push the new execution context to set Bob as caller.
-            ink_env::test::push_execution_context::<ink_env::DefaultEnvironment>(
+            set_caller!(accounts.bob);
+            assert_eq!(
+                erc20.burn_from(accounts.alice, 21),
+                Err(Error::InsufficientAllowance)
+            );
+            assert_eq!(erc20.burn_from(accounts.alice, 15), Ok(()));
+            assert_eq!(erc20.allowance(accounts.alice, accounts.bob), 5);
+            let emitted_events = ink_env::test::recorded_events().collect::<Vec<_>>();
+            assert_approval_event(
+                emitted_events.last().unwrap(),
+                accounts.alice,
                 accounts.bob,
-                callee,
-                1000000,
-                1000000,
-                data,
+                5,
             );
+            assert_eq!(erc20.balance_of(accounts.alice), 85);
+            assert_eq!(erc20.total_supply(), 85);
+        }
 
-            // Bob tries to transfer tokens from Alice to Eve.
-            let emitted_events_before =
-                ink_env::test::recorded_events().collect::<Vec<_>>();
+        #[ink::test]
+        fn allowance_must_not_change_on_failed_burn_from() {
+            let mut erc20 = Erc20::new(100, None, None, 0);
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            assert_eq!(erc20.approve(accounts.bob, 200), Ok(()));
+
+            set_caller!(accounts.bob);
             assert_eq!(
-                erc20.transfer_from(accounts.alice, accounts.eve, alice_balance + 1),
+                erc20.burn_from(accounts.alice, 101),
                 Err(Error::InsufficientBalance)
             );
-            // Allowance must have stayed the same
-            assert_eq!(
-                erc20.allowance(accounts.alice, accounts.bob),
-                initial_allowance
-            );
-            // No more events must have been emitted
-            let emitted_events_after =
-                ink_env::test::recorded_events().collect::<Vec<_>>();
-            assert_eq!(emitted_events_before.len(), emitted_events_after.len());
+            assert_eq!(erc20.allowance(accounts.alice, accounts.bob), 200);
+            assert_eq!(erc20.total_supply(), 100);
+        }
+
+        /// Stateful tests checking that tokens are neither created nor destroyed
+        /// outside of minting and burning.
+        mod invariants {
+            use super::*;
+            use test_utils::stateful;
+
+            #[derive(Debug)]
+            enum Operation {
+                Transfer(AccountId, AccountId, Balance),
+                TransferFrom(AccountId, AccountId, AccountId, Balance),
+                Mint(AccountId, Balance),
+                Burn(AccountId, Balance),
+            }
+
+            const ALICE: [u8; 32] = [0x01; 32];
+
+            /// Deploys a fresh contract from Alice's account.
+            fn deploy() -> Erc20 {
+                set_caller!(AccountId::from(ALICE));
+                Erc20::new(100, None, None, 0)
+            }
+
+            fn generate(rng: &mut stateful::Rng) -> Operation {
+                let accounts =
+                    ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                        .expect("Cannot get accounts");
+                let holders = [
+                    accounts.alice,
+                    accounts.bob,
+                    accounts.charlie,
+                    accounts.django,
+                ];
+                let value = rng.below(120) as Balance;
+                match rng.below(4) {
+                    0 => Operation::Transfer(
+                        *rng.pick(&holders),
+                        *rng.pick(&holders),
+                        value,
+                    ),
+                    1 => Operation::TransferFrom(
+                        *rng.pick(&holders),
+                        *rng.pick(&holders),
+                        *rng.pick(&holders),
+                        value,
+                    ),
+                    2 => Operation::Mint(*rng.pick(&holders), value),
+                    _ => Operation::Burn(*rng.pick(&holders), value),
+                }
+            }
+
+            fn apply(erc20: &mut Erc20, operation: &Operation) {
+                // Failing operations must leave the invariant intact as well, so
+                // their results are ignored.
+                let _ = match *operation {
+                    Operation::Transfer(from, to, value) => {
+                        set_caller!(from);
+                        erc20.transfer(to, value)
+                    }
+                    Operation::TransferFrom(spender, from, to, value) => {
+                        set_caller!(from);
+                        let _ = erc20.approve(spender, value);
+                        set_caller!(spender);
+                        erc20.transfer_from(from, to, value)
+                    }
+                    Operation::Mint(to, value) => {
+                        // Alice deployed the contract and holds the minter role.
+                        set_caller!(AccountId::from(ALICE));
+                        erc20.mint(to, value)
+                    }
+                    Operation::Burn(from, value) => {
+                        set_caller!(from);
+                        erc20.burn(value)
+                    }
+                };
+            }
+
+            fn supply_is_conserved(erc20: &Erc20) -> bool {
+                erc20.balances.values().sum::<Balance>() == erc20.total_supply()
+            }
+
+            #[ink::test]
+            fn balances_sum_up_to_total_supply() {
+                stateful::check(0..20, 50, deploy, generate, apply, supply_is_conserved);
+            }
+
+            #[ink::test]
+            fn balances_sum_up_to_total_supply_with_fees() {
+                stateful::check(
+                    0..20,
+                    50,
+                    || {
+                        let mut erc20 = deploy();
+                        let eve = AccountId::from([0x05; 32]);
+                        assert_eq!(erc20.set_transfer_fee(250, eve), Ok(()));
+                        erc20
+                    },
+                    generate,
+                    apply,
+                    supply_is_conserved,
+                );
+            }
         }
     }
 
@@ -16,10 +16,23 @@ async function run() {
 
   console.log('Balance: ', balance.toHuman());
 
-  const contract = await contractFactory.deployed('new', '1000000', {
+  const params = ['1000000', 'Redspot', 'RDS', 10];
+  const salt = '12312';
+
+  // The address only depends on the signer, the code and the salt, so it is
+  // known before deploying. `deployed` reuses a contract living there.
+  const predicted = await contractFactory.getContractAddress(
+    'new',
+    params,
+    salt
+  );
+
+  console.log('Predicted address: ', predicted.toString());
+
+  const contract = await contractFactory.deployed('new', ...params, {
     gasLimit: '200000000000',
     value: '10000000000000000',
-    salt: '12312'
+    salt
   });
 
   console.log('');
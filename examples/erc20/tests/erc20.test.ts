@@ -17,7 +17,7 @@ describe('ERC20', () => {
     const Alice = signers[0];
     const sender = await getRandomSigner(Alice, one.muln(10000));
     const contractFactory = await getContractFactory('erc20', sender);
-    const contract = await contractFactory.deploy('new', '1000');
+    const contract = await contractFactory.deploy('new', '1000', 'Redspot', 'RDS', 10);
     const abi = artifacts.readArtifact('erc20');
     const receiver = await getRandomSigner();
 
@@ -30,6 +30,38 @@ describe('ERC20', () => {
     expect(result.output).to.equal(1000);
   });
 
+  it('Deploys to the predicted salted address', async () => {
+    const { contractFactory } = await setup();
+    const params = ['1000', 'Redspot', 'RDS', 10];
+    const predicted = await contractFactory.getContractAddress(
+      'new',
+      params,
+      '42'
+    );
+    const contract = await contractFactory.deployed('new', ...params, {
+      salt: '42'
+    });
+
+    expect(contract.address.toString()).to.equal(predicted.toString());
+
+    const other = await contractFactory.getContractAddress('new', params, '43');
+    expect(other.toString()).to.not.equal(predicted.toString());
+  });
+
+  it('Exposes token metadata', async () => {
+    const { contract } = await setup();
+
+    expect((await contract.query.tokenName()).output).to.equal('Redspot');
+    expect((await contract.query.tokenSymbol()).output).to.equal('RDS');
+    expect((await contract.query.tokenDecimals()).output).to.equal(10);
+  });
+
+  it('Starts permit nonces at zero', async () => {
+    const { contract, sender } = await setup();
+
+    expect((await contract.query.nonceOf(sender.address)).output).to.equal(0);
+  });
+
   it('Transfer adds amount to destination account', async () => {
     const { contract, receiver } = await setup();
     await expect(() =>
@@ -49,6 +81,35 @@ describe('ERC20', () => {
       .withArgs(sender.address, receiver.address, 7);
   });
 
+  it('Transfer and call to an account without code acts as transfer', async () => {
+    const { contract, sender, receiver } = await setup();
+
+    await expect(contract.tx.transferAndCall(receiver.address, 7, '0x'))
+      .to.emit(contract, 'Transfer')
+      .withArgs(sender.address, receiver.address, 7);
+  });
+
+  it('Flash minted tokens can be moved during the flash mint', async () => {
+    const { contract, sender, Alice, one } = await setup();
+    const sink = await getRandomSigner(Alice, one.muln(10000));
+    const borrowerFactory = await getContractFactory('flash_borrower', sender);
+    const borrower = await borrowerFactory.deploy(
+      'new',
+      contract.address,
+      sink.address
+    );
+    await contract.tx.approve(borrower.address, 500, { signer: sink });
+
+    await expect(contract.tx.flashMint(borrower.address, 500, '0x'))
+      .to.emit(contract, 'Transfer')
+      .withArgs(borrower.address, sink.address, 500);
+    expect((await contract.query.balanceOf(borrower.address)).output).to.equal(
+      0
+    );
+    expect((await contract.query.balanceOf(sink.address)).output).to.equal(0);
+    expect((await contract.query.totalSupply()).output).to.equal(1000);
+  });
+
   it('Can not transfer above the amount', async () => {
     const { contract, receiver } = await setup();
 
@@ -69,4 +130,43 @@ describe('ERC20', () => {
       })
     ).to.not.emit(contract, 'Transfer');
   });
+
+  it('Can not transfer while paused', async () => {
+    const { contract, receiver } = await setup();
+
+    await expect(contract.tx.pause()).to.emit(contract, 'Paused');
+    await expect(contract.tx.transfer(receiver.address, 7)).to.not.emit(
+      contract,
+      'Transfer'
+    );
+
+    await expect(contract.tx.unpause()).to.emit(contract, 'Unpaused');
+    await expect(contract.tx.transfer(receiver.address, 7)).to.emit(
+      contract,
+      'Transfer'
+    );
+  });
+
+  it('Minter can mint tokens', async () => {
+    const { contract, receiver } = await setup();
+
+    await expect(() =>
+      contract.tx.mint(receiver.address, 7)
+    ).to.changeTokenBalance(contract, receiver, 7);
+
+    const result = await contract.query.totalSupply();
+    expect(result.output).to.equal(1007);
+  });
+
+  it('Can not mint without the minter role', async () => {
+    const { contract, Alice, one } = await setup();
+
+    const otherAccount = await getRandomSigner(Alice, one.muln(10000));
+
+    await expect(
+      contract.tx.mint(otherAccount.address, 7, {
+        signer: otherAccount
+      })
+    ).to.not.emit(contract, 'Mint');
+  });
 });
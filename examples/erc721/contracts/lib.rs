@@ -0,0 +1,599 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use ink_lang as ink;
+
+#[ink::contract]
+mod erc721 {
+    #[cfg(not(feature = "ink-as-dependency"))]
+    use ink_storage::collections::HashMap as StorageHashMap;
+
+    /// A token ID.
+    pub type TokenId = u32;
+
+    /// An ERC-721 non-fungible token with the enumerable extension.
+    #[ink(storage)]
+    pub struct Erc721 {
+        /// Mapping from token to owner.
+        token_owner: StorageHashMap<TokenId, AccountId>,
+        /// Mapping from token to the account approved to transfer it.
+        token_approvals: StorageHashMap<TokenId, AccountId>,
+        /// Mapping from owner to number of owned tokens.
+        owned_tokens_count: StorageHashMap<AccountId, u32>,
+        /// Mapping from `(owner, operator)` for operators allowed to transfer
+        /// all tokens of the owner.
+        operator_approvals: StorageHashMap<(AccountId, AccountId), ()>,
+        /// Mapping from `(owner, index)` to the token at that index of the
+        /// owner's token list.
+        owned_tokens: StorageHashMap<(AccountId, u32), TokenId>,
+        /// Mapping from token to its index in the owner's token list.
+        owned_tokens_index: StorageHashMap<TokenId, u32>,
+        /// Mapping from index to the token at that index of the list of all tokens.
+        all_tokens: StorageHashMap<u32, TokenId>,
+        /// Mapping from token to its index in the list of all tokens.
+        all_tokens_index: StorageHashMap<TokenId, u32>,
+    }
+
+    /// Event emitted when a token transfer occurs.
+    #[ink(event)]
+    pub struct Transfer {
+        #[ink(topic)]
+        from: Option<AccountId>,
+        #[ink(topic)]
+        to: Option<AccountId>,
+        #[ink(topic)]
+        id: TokenId,
+    }
+
+    /// Event emitted when `approved` is allowed to transfer the token `id` of
+    /// `owner`.
+    #[ink(event)]
+    pub struct Approval {
+        #[ink(topic)]
+        owner: AccountId,
+        #[ink(topic)]
+        approved: AccountId,
+        #[ink(topic)]
+        id: TokenId,
+    }
+
+    /// Event emitted when an operator is enabled or disabled for an owner.
+    /// The operator can manage all tokens of the owner.
+    #[ink(event)]
+    pub struct ApprovalForAll {
+        #[ink(topic)]
+        owner: AccountId,
+        #[ink(topic)]
+        operator: AccountId,
+        approved: bool,
+    }
+
+    /// The NFT error types.
+    #[derive(Debug, PartialEq, Eq, scale::Encode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum Error {
+        /// Returned if the account does not own the token.
+        NotOwner,
+        /// Returned if the caller is neither the owner nor approved for the token.
+        NotApproved,
+        /// Returned if the token already exists.
+        TokenExists,
+        /// Returned if the token does not exist.
+        TokenNotFound,
+        /// Returned if the zero account is used or an owner approves itself.
+        NotAllowed,
+    }
+
+    /// The NFT result type.
+    pub type Result<T> = core::result::Result<T, Error>;
+
+    impl Erc721 {
+        /// Creates a new token contract without any tokens.
+        #[ink(constructor)]
+        pub fn new() -> Self {
+            Self {
+                token_owner: StorageHashMap::new(),
+                token_approvals: StorageHashMap::new(),
+                owned_tokens_count: StorageHashMap::new(),
+                operator_approvals: StorageHashMap::new(),
+                owned_tokens: StorageHashMap::new(),
+                owned_tokens_index: StorageHashMap::new(),
+                all_tokens: StorageHashMap::new(),
+                all_tokens_index: StorageHashMap::new(),
+            }
+        }
+
+        /// Returns the number of tokens owned by `owner`.
+        ///
+        /// Returns `0` if the account is non-existent.
+        #[ink(message)]
+        pub fn balance_of(&self, owner: AccountId) -> u32 {
+            self.owned_tokens_count.get(&owner).copied().unwrap_or(0)
+        }
+
+        /// Returns the owner of the token `id`, or `None` if it does not exist.
+        #[ink(message)]
+        pub fn owner_of(&self, id: TokenId) -> Option<AccountId> {
+            self.token_owner.get(&id).copied()
+        }
+
+        /// Returns the account approved to transfer the token `id`, if any.
+        #[ink(message)]
+        pub fn get_approved(&self, id: TokenId) -> Option<AccountId> {
+            self.token_approvals.get(&id).copied()
+        }
+
+        /// Returns `true` if `operator` may transfer all tokens of `owner`.
+        #[ink(message)]
+        pub fn is_approved_for_all(&self, owner: AccountId, operator: AccountId) -> bool {
+            self.operator_approvals.contains_key(&(owner, operator))
+        }
+
+        /// Returns the number of existing tokens.
+        #[ink(message)]
+        pub fn total_supply(&self) -> u32 {
+            self.all_tokens.len()
+        }
+
+        /// Returns the token at `index` of the list of all tokens, or `None` if
+        /// `index` is not below `total_supply`.
+        ///
+        /// The order of the list changes when tokens are burned.
+        #[ink(message)]
+        pub fn token_by_index(&self, index: u32) -> Option<TokenId> {
+            self.all_tokens.get(&index).copied()
+        }
+
+        /// Returns the token at `index` of the list of tokens owned by `owner`, or
+        /// `None` if `index` is not below the balance of `owner`.
+        ///
+        /// The order of the list changes when tokens leave the account.
+        #[ink(message)]
+        pub fn token_of_owner_by_index(
+            &self,
+            owner: AccountId,
+            index: u32,
+        ) -> Option<TokenId> {
+            self.owned_tokens.get(&(owner, index)).copied()
+        }
+
+        /// Enables or disables `operator` to transfer all tokens of the caller.
+        ///
+        /// An `ApprovalForAll` event is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `NotAllowed` error if `operator` is the caller.
+        #[ink(message)]
+        pub fn set_approval_for_all(
+            &mut self,
+            operator: AccountId,
+            approved: bool,
+        ) -> Result<()> {
+            let owner = self.env().caller();
+            if operator == owner {
+                return Err(Error::NotAllowed)
+            }
+            if approved {
+                self.operator_approvals.insert((owner, operator), ());
+            } else {
+                self.operator_approvals.take(&(owner, operator));
+            }
+            self.env().emit_event(ApprovalForAll {
+                owner,
+                operator,
+                approved,
+            });
+            Ok(())
+        }
+
+        /// Allows `to` to transfer the token `id`, replacing any previous approval.
+        ///
+        /// The approval is cleared when the token is transferred.
+        ///
+        /// On success an `Approval` event is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `TokenNotFound` error if the token does not exist.
+        ///
+        /// Returns `NotApproved` error if the caller is neither the owner nor an
+        /// operator of the owner.
+        ///
+        /// Returns `NotAllowed` error if `to` is the owner or the zero account.
+        #[ink(message)]
+        pub fn approve(&mut self, to: AccountId, id: TokenId) -> Result<()> {
+            let caller = self.env().caller();
+            let owner = self.owner_of(id).ok_or(Error::TokenNotFound)?;
+            if caller != owner && !self.is_approved_for_all(owner, caller) {
+                return Err(Error::NotApproved)
+            }
+            if to == owner || Self::is_zero(&to) {
+                return Err(Error::NotAllowed)
+            }
+            self.token_approvals.insert(id, to);
+            self.env().emit_event(Approval {
+                owner,
+                approved: to,
+                id,
+            });
+            Ok(())
+        }
+
+        /// Transfers the token `id` from the caller to `to`.
+        ///
+        /// On success a `Transfer` event is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `TokenNotFound` error if the token does not exist.
+        ///
+        /// Returns `NotOwner` error if the caller does not own the token.
+        ///
+        /// Returns `NotAllowed` error if `to` is the zero account.
+        #[ink(message)]
+        pub fn transfer(&mut self, to: AccountId, id: TokenId) -> Result<()> {
+            let caller = self.env().caller();
+            self.transfer_token_from(caller, to, id)
+        }
+
+        /// Transfers the token `id` from `from` to `to` on behalf of the owner.
+        ///
+        /// On success a `Transfer` event is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `TokenNotFound` error if the token does not exist.
+        ///
+        /// Returns `NotOwner` error if `from` does not own the token.
+        ///
+        /// Returns `NotApproved` error if the caller is not the owner, not
+        /// approved for the token and not an operator of the owner.
+        ///
+        /// Returns `NotAllowed` error if `to` is the zero account.
+        #[ink(message)]
+        pub fn transfer_from(
+            &mut self,
+            from: AccountId,
+            to: AccountId,
+            id: TokenId,
+        ) -> Result<()> {
+            self.transfer_token_from(from, to, id)
+        }
+
+        /// Creates the token `id` and assigns it to the caller.
+        ///
+        /// On success a `Transfer` event from `None` is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `TokenExists` error if the token already exists.
+        #[ink(message)]
+        pub fn mint(&mut self, id: TokenId) -> Result<()> {
+            let caller = self.env().caller();
+            if self.token_owner.contains_key(&id) {
+                return Err(Error::TokenExists)
+            }
+            self.add_token_to(caller, id);
+            let index = self.all_tokens.len();
+            self.all_tokens.insert(index, id);
+            self.all_tokens_index.insert(id, index);
+            self.env().emit_event(Transfer {
+                from: None,
+                to: Some(caller),
+                id,
+            });
+            Ok(())
+        }
+
+        /// Destroys the token `id` of the caller.
+        ///
+        /// On success a `Transfer` event to `None` is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `TokenNotFound` error if the token does not exist.
+        ///
+        /// Returns `NotOwner` error if the caller does not own the token.
+        #[ink(message)]
+        pub fn burn(&mut self, id: TokenId) -> Result<()> {
+            let caller = self.env().caller();
+            let owner = self.owner_of(id).ok_or(Error::TokenNotFound)?;
+            if owner != caller {
+                return Err(Error::NotOwner)
+            }
+            self.token_approvals.take(&id);
+            self.remove_token_from(caller, id);
+            let index = self
+                .all_tokens_index
+                .take(&id)
+                .expect("existing tokens are always indexed");
+            let last_index = self.all_tokens.len() - 1;
+            let last_id = self
+                .all_tokens
+                .take(&last_index)
+                .expect("indices below the length are always occupied");
+            if index != last_index {
+                self.all_tokens.insert(index, last_id);
+                self.all_tokens_index.insert(last_id, index);
+            }
+            self.env().emit_event(Transfer {
+                from: Some(caller),
+                to: None,
+                id,
+            });
+            Ok(())
+        }
+
+        /// Transfers the token `id` from `from` to `to` if the caller is allowed to.
+        ///
+        /// On success a `Transfer` event is emitted.
+        fn transfer_token_from(
+            &mut self,
+            from: AccountId,
+            to: AccountId,
+            id: TokenId,
+        ) -> Result<()> {
+            let caller = self.env().caller();
+            let owner = self.owner_of(id).ok_or(Error::TokenNotFound)?;
+            if owner != from {
+                return Err(Error::NotOwner)
+            }
+            if !self.approved_or_owner(caller, owner, id) {
+                return Err(Error::NotApproved)
+            }
+            if Self::is_zero(&to) {
+                return Err(Error::NotAllowed)
+            }
+            self.token_approvals.take(&id);
+            self.remove_token_from(from, id);
+            self.add_token_to(to, id);
+            self.env().emit_event(Transfer {
+                from: Some(from),
+                to: Some(to),
+                id,
+            });
+            Ok(())
+        }
+
+        /// Returns `true` if `account` may transfer the token `id` of `owner`.
+        fn approved_or_owner(
+            &self,
+            account: AccountId,
+            owner: AccountId,
+            id: TokenId,
+        ) -> bool {
+            account == owner
+                || self.get_approved(id) == Some(account)
+                || self.is_approved_for_all(owner, account)
+        }
+
+        /// Appends the token `id`, which must not have an owner, to the tokens of `to`.
+        fn add_token_to(&mut self, to: AccountId, id: TokenId) {
+            let index = self.balance_of(to);
+            self.token_owner.insert(id, to);
+            self.owned_tokens.insert((to, index), id);
+            self.owned_tokens_index.insert(id, index);
+            self.owned_tokens_count.insert(to, index + 1);
+        }
+
+        /// Removes the token `id`, which must be owned by `from`, from the tokens
+        /// of `from`.
+        ///
+        /// The last token of `from` takes the place of the removed one.
+        fn remove_token_from(&mut self, from: AccountId, id: TokenId) {
+            let last_index = self.balance_of(from) - 1;
+            let index = self
+                .owned_tokens_index
+                .take(&id)
+                .expect("owned tokens are always indexed");
+            let last_id = self
+                .owned_tokens
+                .take(&(from, last_index))
+                .expect("indices below the balance are always occupied");
+            if index != last_index {
+                self.owned_tokens.insert((from, index), last_id);
+                self.owned_tokens_index.insert(last_id, index);
+            }
+            self.owned_tokens_count.insert(from, last_index);
+            self.token_owner.take(&id);
+        }
+
+        /// Returns `true` if `account` is the zero account.
+        fn is_zero(account: &AccountId) -> bool {
+            *account == AccountId::from([0x0; 32])
+        }
+    }
+
+    /// Unit tests.
+    #[cfg(test)]
+    mod tests {
+        /// Imports all the definitions from the outer scope so we can use them here.
+        use super::*;
+
+        use ink_lang as ink;
+        use test_utils::set_caller;
+
+        /// Returns the tokens of `owner` in enumeration order.
+        fn tokens_of(erc721: &Erc721, owner: AccountId) -> Vec<TokenId> {
+            (0..erc721.balance_of(owner))
+                .map(|index| {
+                    erc721
+                        .token_of_owner_by_index(owner, index)
+                        .expect("index below balance")
+                })
+                .collect()
+        }
+
+        #[ink::test]
+        fn mint_works() {
+            let mut erc721 = Erc721::new();
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            assert_eq!(erc721.owner_of(1), None);
+            assert_eq!(erc721.mint(1), Ok(()));
+            assert_eq!(erc721.owner_of(1), Some(accounts.alice));
+            assert_eq!(erc721.balance_of(accounts.alice), 1);
+            assert_eq!(erc721.total_supply(), 1);
+            assert_eq!(ink_env::test::recorded_events().count(), 1);
+        }
+
+        #[ink::test]
+        fn mint_existing_token_fails() {
+            let mut erc721 = Erc721::new();
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            assert_eq!(erc721.mint(1), Ok(()));
+            set_caller!(accounts.bob);
+            assert_eq!(erc721.mint(1), Err(Error::TokenExists));
+            assert_eq!(erc721.owner_of(1), Some(accounts.alice));
+            assert_eq!(erc721.balance_of(accounts.bob), 0);
+        }
+
+        #[ink::test]
+        fn transfer_works() {
+            let mut erc721 = Erc721::new();
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            assert_eq!(erc721.mint(1), Ok(()));
+            assert_eq!(erc721.transfer(accounts.bob, 1), Ok(()));
+            assert_eq!(erc721.owner_of(1), Some(accounts.bob));
+            assert_eq!(erc721.balance_of(accounts.alice), 0);
+            assert_eq!(erc721.balance_of(accounts.bob), 1);
+            assert_eq!(ink_env::test::recorded_events().count(), 2);
+        }
+
+        #[ink::test]
+        fn invalid_transfer_fails() {
+            let mut erc721 = Erc721::new();
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            assert_eq!(erc721.transfer(accounts.bob, 1), Err(Error::TokenNotFound));
+            assert_eq!(erc721.mint(1), Ok(()));
+            assert_eq!(
+                erc721.transfer(AccountId::from([0x0; 32]), 1),
+                Err(Error::NotAllowed)
+            );
+
+            set_caller!(accounts.bob);
+            assert_eq!(erc721.transfer(accounts.bob, 1), Err(Error::NotOwner));
+            assert_eq!(
+                erc721.transfer_from(accounts.alice, accounts.bob, 1),
+                Err(Error::NotApproved)
+            );
+            assert_eq!(erc721.owner_of(1), Some(accounts.alice));
+        }
+
+        #[ink::test]
+        fn approved_transfer_works() {
+            let mut erc721 = Erc721::new();
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            assert_eq!(erc721.mint(1), Ok(()));
+            assert_eq!(erc721.approve(accounts.alice, 1), Err(Error::NotAllowed));
+            assert_eq!(erc721.approve(accounts.bob, 1), Ok(()));
+            assert_eq!(erc721.get_approved(1), Some(accounts.bob));
+
+            set_caller!(accounts.bob);
+            assert_eq!(
+                erc721.transfer_from(accounts.alice, accounts.charlie, 1),
+                Ok(())
+            );
+            assert_eq!(erc721.owner_of(1), Some(accounts.charlie));
+            // The approval does not survive the transfer.
+            assert_eq!(erc721.get_approved(1), None);
+        }
+
+        #[ink::test]
+        fn operator_approval_works() {
+            let mut erc721 = Erc721::new();
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            assert_eq!(erc721.mint(1), Ok(()));
+            assert_eq!(erc721.mint(2), Ok(()));
+            assert_eq!(
+                erc721.set_approval_for_all(accounts.alice, true),
+                Err(Error::NotAllowed)
+            );
+            assert_eq!(erc721.set_approval_for_all(accounts.bob, true), Ok(()));
+            assert!(erc721.is_approved_for_all(accounts.alice, accounts.bob));
+
+            set_caller!(accounts.bob);
+            assert_eq!(erc721.approve(accounts.charlie, 2), Ok(()));
+            assert_eq!(
+                erc721.transfer_from(accounts.alice, accounts.charlie, 1),
+                Ok(())
+            );
+            ink_env::test::pop_execution_context();
+
+            assert_eq!(erc721.set_approval_for_all(accounts.bob, false), Ok(()));
+            assert!(!erc721.is_approved_for_all(accounts.alice, accounts.bob));
+            set_caller!(accounts.bob);
+            assert_eq!(
+                erc721.transfer_from(accounts.alice, accounts.bob, 2),
+                Err(Error::NotApproved)
+            );
+        }
+
+        #[ink::test]
+        fn burn_works() {
+            let mut erc721 = Erc721::new();
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            assert_eq!(erc721.mint(1), Ok(()));
+            assert_eq!(erc721.approve(accounts.bob, 1), Ok(()));
+
+            set_caller!(accounts.bob);
+            assert_eq!(erc721.burn(1), Err(Error::NotOwner));
+            ink_env::test::pop_execution_context();
+
+            assert_eq!(erc721.burn(1), Ok(()));
+            assert_eq!(erc721.owner_of(1), None);
+            assert_eq!(erc721.get_approved(1), None);
+            assert_eq!(erc721.balance_of(accounts.alice), 0);
+            assert_eq!(erc721.total_supply(), 0);
+            assert_eq!(erc721.burn(1), Err(Error::TokenNotFound));
+        }
+
+        #[ink::test]
+        fn enumeration_follows_transfers_and_burns() {
+            let mut erc721 = Erc721::new();
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            for id in 1..=4 {
+                assert_eq!(erc721.mint(id), Ok(()));
+            }
+            assert_eq!(tokens_of(&erc721, accounts.alice), vec![1, 2, 3, 4]);
+
+            // The last token fills the gap left by a token leaving the account.
+            assert_eq!(erc721.transfer(accounts.bob, 2), Ok(()));
+            assert_eq!(tokens_of(&erc721, accounts.alice), vec![1, 4, 3]);
+            assert_eq!(tokens_of(&erc721, accounts.bob), vec![2]);
+            assert_eq!(erc721.token_of_owner_by_index(accounts.alice, 3), None);
+
+            assert_eq!(erc721.burn(1), Ok(()));
+            assert_eq!(erc721.total_supply(), 3);
+            let all = (0..erc721.total_supply())
+                .map(|index| erc721.token_by_index(index).expect("index below supply"))
+                .collect::<Vec<_>>();
+            assert_eq!(all, vec![4, 2, 3]);
+            assert_eq!(erc721.token_by_index(3), None);
+            assert_eq!(tokens_of(&erc721, accounts.alice), vec![3, 4]);
+        }
+    }
+}
\ No newline at end of file
@@ -0,0 +1,94 @@
+import BN from 'bn.js';
+import { expect } from 'chai';
+import { patract, network } from 'redspot';
+
+const { getContractFactory, getRandomSigner } = patract;
+
+const { api, getSigners } = network;
+
+describe('ERC721', () => {
+  after(() => {
+    return api.disconnect();
+  });
+
+  async function setup() {
+    const one = new BN(10).pow(new BN(api.registry.chainDecimals[0]));
+    const signers = await getSigners();
+    const Alice = signers[0];
+    const sender = await getRandomSigner(Alice, one.muln(10000));
+    const contractFactory = await getContractFactory('erc721', sender);
+    const contract = await contractFactory.deploy('new');
+    const receiver = await getRandomSigner();
+
+    return { sender, contractFactory, contract, receiver, Alice, one };
+  }
+
+  it('Mints a token to the caller', async () => {
+    const { contract, sender } = await setup();
+
+    await expect(contract.tx.mint(1)).to.emit(contract, 'Transfer');
+
+    expect((await contract.query.ownerOf(1)).output).to.equal(sender.address);
+    expect((await contract.query.balanceOf(sender.address)).output).to.equal(
+      1
+    );
+  });
+
+  it('Transfers a token', async () => {
+    const { contract, sender, receiver } = await setup();
+
+    await contract.tx.mint(1);
+
+    await expect(contract.tx.transfer(receiver.address, 1))
+      .to.emit(contract, 'Transfer')
+      .withArgs(sender.address, receiver.address, 1);
+
+    expect((await contract.query.ownerOf(1)).output).to.equal(
+      receiver.address
+    );
+  });
+
+  it('Can not transfer a token of another account', async () => {
+    const { contract, Alice, one } = await setup();
+
+    const otherAccount = await getRandomSigner(Alice, one.muln(10000));
+
+    await contract.tx.mint(1);
+
+    await expect(
+      contract.tx.transfer(otherAccount.address, 1, {
+        signer: otherAccount
+      })
+    ).to.not.emit(contract, 'Transfer');
+  });
+
+  it('Operator can transfer tokens of the owner', async () => {
+    const { contract, sender, receiver, Alice, one } = await setup();
+
+    const operator = await getRandomSigner(Alice, one.muln(10000));
+
+    await contract.tx.mint(1);
+
+    await expect(contract.tx.setApprovalForAll(operator.address, true))
+      .to.emit(contract, 'ApprovalForAll')
+      .withArgs(sender.address, operator.address, true);
+
+    await expect(
+      contract.tx.transferFrom(sender.address, receiver.address, 1, {
+        signer: operator
+      })
+    ).to.emit(contract, 'Transfer');
+  });
+
+  it('Enumerates the tokens of an owner', async () => {
+    const { contract, sender } = await setup();
+
+    await contract.tx.mint(7);
+    await contract.tx.mint(8);
+
+    expect((await contract.query.totalSupply()).output).to.equal(2);
+    expect(
+      (await contract.query.tokenOfOwnerByIndex(sender.address, 1)).output
+    ).to.equal(8);
+  });
+});
@@ -0,0 +1,500 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use ink_lang as ink;
+
+#[ink::contract]
+mod escrow {
+    use erc20::{
+        TRANSFER_FROM_SELECTOR,
+        TRANSFER_SELECTOR,
+    };
+    use ink_env::{
+        call::{
+            build_call,
+            utils::ReturnType,
+            ExecutionInput,
+            Selector,
+        },
+        DefaultEnvironment,
+        ReturnFlags,
+    };
+    use ink_storage::traits::{
+        PackedLayout,
+        SpreadLayout,
+    };
+    #[cfg(not(feature = "ink-as-dependency"))]
+    use ink_storage::{
+        collections::HashMap as StorageHashMap,
+        lazy::Lazy,
+    };
+
+    /// An escrow ID.
+    pub type EscrowId = u32;
+
+    /// The stage an escrow is in.
+    ///
+    /// An escrow starts `Funded`. While `Funded` or `Disputed` the payer may
+    /// release the funds and the payee may refund them, and the arbiter
+    /// resolves a `Disputed` escrow either way. `Released` and `Refunded` are
+    /// final.
+    #[derive(
+        Debug,
+        Copy,
+        Clone,
+        PartialEq,
+        Eq,
+        scale::Encode,
+        scale::Decode,
+        SpreadLayout,
+        PackedLayout,
+    )]
+    #[cfg_attr(
+        feature = "std",
+        derive(::scale_info::TypeInfo, ::ink_storage::traits::StorageLayout)
+    )]
+    pub enum EscrowState {
+        /// The funds are locked.
+        Funded,
+        /// The payer or the payee disputed the escrow, waiting for the arbiter.
+        Disputed,
+        /// The funds were sent to the payee.
+        Released,
+        /// The funds were sent back to the payer.
+        Refunded,
+    }
+
+    /// Funds locked by a payer for a payee.
+    #[derive(
+        Debug,
+        Clone,
+        PartialEq,
+        Eq,
+        scale::Encode,
+        scale::Decode,
+        SpreadLayout,
+        PackedLayout,
+    )]
+    #[cfg_attr(
+        feature = "std",
+        derive(::scale_info::TypeInfo, ::ink_storage::traits::StorageLayout)
+    )]
+    pub struct Deal {
+        /// The account which locked the funds.
+        pub payer: AccountId,
+        /// The account the funds are for.
+        pub payee: AccountId,
+        /// The amount of locked tokens.
+        pub amount: Balance,
+        /// The stage the escrow is in.
+        pub state: EscrowState,
+    }
+
+    /// Locks erc20 tokens of payers for payees until either releases them to the
+    /// other, with an arbiter deciding disputes.
+    #[ink(storage)]
+    pub struct Escrow {
+        /// The erc20 token which is escrowed.
+        token: AccountId,
+        /// The account resolving disputes.
+        arbiter: AccountId,
+        /// Mapping from ID to escrow.
+        deals: StorageHashMap<EscrowId, Deal>,
+        /// The ID the next escrow gets.
+        next_escrow_id: Lazy<EscrowId>,
+    }
+
+    /// Event emitted when a payer locks funds.
+    #[ink(event)]
+    pub struct Created {
+        #[ink(topic)]
+        id: EscrowId,
+        #[ink(topic)]
+        payer: AccountId,
+        #[ink(topic)]
+        payee: AccountId,
+        amount: Balance,
+    }
+
+    /// Event emitted when the payer or the payee disputes an escrow.
+    #[ink(event)]
+    pub struct Disputed {
+        #[ink(topic)]
+        id: EscrowId,
+        #[ink(topic)]
+        by: AccountId,
+    }
+
+    /// Event emitted when the funds are sent to the payee.
+    #[ink(event)]
+    pub struct Released {
+        #[ink(topic)]
+        id: EscrowId,
+        #[ink(topic)]
+        by: AccountId,
+    }
+
+    /// Event emitted when the funds are sent back to the payer.
+    #[ink(event)]
+    pub struct Refunded {
+        #[ink(topic)]
+        id: EscrowId,
+        #[ink(topic)]
+        by: AccountId,
+    }
+
+    /// The escrow error types.
+    #[derive(Debug, PartialEq, Eq, scale::Encode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum Error {
+        /// Returned if no escrow has the given ID.
+        EscrowNotFound,
+        /// Returned if the caller is not allowed to make this transition.
+        NotAllowed,
+        /// Returned if the escrow is not in a stage allowing this transition.
+        InvalidState,
+        /// Returned if tokens could not be transferred.
+        TransferFailed,
+    }
+
+    /// The escrow result type.
+    pub type Result<T> = core::result::Result<T, Error>;
+
+    impl Escrow {
+        /// Creates an escrow for `token` with disputes resolved by `arbiter`.
+        #[ink(constructor)]
+        pub fn new(token: AccountId, arbiter: AccountId) -> Self {
+            Self {
+                token,
+                arbiter,
+                deals: StorageHashMap::new(),
+                next_escrow_id: Lazy::new(0),
+            }
+        }
+
+        /// Returns the erc20 token which is escrowed.
+        #[ink(message)]
+        pub fn token(&self) -> AccountId {
+            self.token
+        }
+
+        /// Returns the account resolving disputes.
+        #[ink(message)]
+        pub fn arbiter(&self) -> AccountId {
+            self.arbiter
+        }
+
+        /// Returns the escrow with ID `id`, if any.
+        #[ink(message)]
+        pub fn deal(&self, id: EscrowId) -> Option<Deal> {
+            self.deals.get(&id).cloned()
+        }
+
+        /// Locks `amount` tokens of the caller for `payee` and returns the escrow
+        /// ID.
+        ///
+        /// The caller has to approve this contract to spend `amount` tokens
+        /// first. On success a `Created` event is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `TransferFailed` error if the tokens could not be transferred
+        /// from the caller.
+        #[ink(message)]
+        pub fn create(&mut self, payee: AccountId, amount: Balance) -> Result<EscrowId> {
+            let payer = self.env().caller();
+            let this = self.env().account_id();
+            // Nothing was written yet, so a failed transfer leaves no trace.
+            if !self.transfer_from(payer, this, amount) {
+                return Err(Error::TransferFailed)
+            }
+            let id = *self.next_escrow_id;
+            *self.next_escrow_id += 1;
+            self.deals.insert(
+                id,
+                Deal {
+                    payer,
+                    payee,
+                    amount,
+                    state: EscrowState::Funded,
+                },
+            );
+            self.env().emit_event(Created {
+                id,
+                payer,
+                payee,
+                amount,
+            });
+            Ok(id)
+        }
+
+        /// Disputes the escrow with ID `id`, leaving the decision to the arbiter.
+        ///
+        /// On success a `Disputed` event is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `EscrowNotFound` error if there is no such escrow.
+        ///
+        /// Returns `NotAllowed` error if the caller is neither payer nor payee.
+        ///
+        /// Returns `InvalidState` error if the escrow is not `Funded`.
+        #[ink(message)]
+        pub fn dispute(&mut self, id: EscrowId) -> Result<()> {
+            let caller = self.env().caller();
+            let deal = self.deal_mut(id)?;
+            if caller != deal.payer && caller != deal.payee {
+                return Err(Error::NotAllowed)
+            }
+            if deal.state != EscrowState::Funded {
+                return Err(Error::InvalidState)
+            }
+            deal.state = EscrowState::Disputed;
+            self.env().emit_event(Disputed { id, by: caller });
+            Ok(())
+        }
+
+        /// Sends the funds of the escrow with ID `id` to the payee.
+        ///
+        /// Only the payer may release the funds, or the arbiter while the escrow
+        /// is disputed. On success a `Released` event is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `EscrowNotFound` error if there is no such escrow.
+        ///
+        /// Returns `NotAllowed` error if the caller may not release the funds.
+        ///
+        /// Returns `InvalidState` error if the escrow is `Released` or `Refunded`.
+        ///
+        /// Returns `TransferFailed` error, reverting all changes, if the tokens
+        /// could not be transferred.
+        #[ink(message)]
+        pub fn release(&mut self, id: EscrowId) -> Result<()> {
+            let caller = self.env().caller();
+            let deal = self.settle(id, EscrowState::Released, |deal| deal.payer)?;
+            if !self.transfer(deal.payee, deal.amount) {
+                Self::revert(Error::TransferFailed)
+            }
+            self.env().emit_event(Released { id, by: caller });
+            Ok(())
+        }
+
+        /// Sends the funds of the escrow with ID `id` back to the payer.
+        ///
+        /// Only the payee may refund the funds, or the arbiter while the escrow
+        /// is disputed. On success a `Refunded` event is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `EscrowNotFound` error if there is no such escrow.
+        ///
+        /// Returns `NotAllowed` error if the caller may not refund the funds.
+        ///
+        /// Returns `InvalidState` error if the escrow is `Released` or `Refunded`.
+        ///
+        /// Returns `TransferFailed` error, reverting all changes, if the tokens
+        /// could not be transferred.
+        #[ink(message)]
+        pub fn refund(&mut self, id: EscrowId) -> Result<()> {
+            let caller = self.env().caller();
+            let deal = self.settle(id, EscrowState::Refunded, |deal| deal.payee)?;
+            if !self.transfer(deal.payer, deal.amount) {
+                Self::revert(Error::TransferFailed)
+            }
+            self.env().emit_event(Refunded { id, by: caller });
+            Ok(())
+        }
+
+        /// Moves the escrow with ID `id` to the final stage `state` and returns
+        /// it, if the caller is the party returned by `party` or the escrow is
+        /// disputed and the caller is the arbiter.
+        fn settle(
+            &mut self,
+            id: EscrowId,
+            state: EscrowState,
+            party: fn(&Deal) -> AccountId,
+        ) -> Result<Deal> {
+            let caller = self.env().caller();
+            let arbiter = self.arbiter;
+            let deal = self.deal_mut(id)?;
+            let disputed = deal.state == EscrowState::Disputed;
+            if caller != party(deal) && !(disputed && caller == arbiter) {
+                return Err(Error::NotAllowed)
+            }
+            if deal.state != EscrowState::Funded && !disputed {
+                return Err(Error::InvalidState)
+            }
+            deal.state = state;
+            Ok(deal.clone())
+        }
+
+        /// Returns the escrow with ID `id` for modification.
+        fn deal_mut(&mut self, id: EscrowId) -> Result<&mut Deal> {
+            self.deals.get_mut(&id).ok_or(Error::EscrowNotFound)
+        }
+
+        /// Transfers `value` escrowed tokens to `to` and returns whether that
+        /// succeeded.
+        fn transfer(&self, to: AccountId, value: Balance) -> bool {
+            let result = build_call::<DefaultEnvironment>()
+                .callee(self.token)
+                .gas_limit(0)
+                .transferred_value(0)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(TRANSFER_SELECTOR))
+                        .push_arg(to)
+                        .push_arg(value),
+                )
+                .returns::<ReturnType<core::result::Result<(), u8>>>()
+                .fire();
+            matches!(result, Ok(Ok(())))
+        }
+
+        /// Transfers `value` tokens from `from` to `to` using the allowance of
+        /// this contract and returns whether that succeeded.
+        fn transfer_from(&self, from: AccountId, to: AccountId, value: Balance) -> bool {
+            let result = build_call::<DefaultEnvironment>()
+                .callee(self.token)
+                .gas_limit(0)
+                .transferred_value(0)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(TRANSFER_FROM_SELECTOR))
+                        .push_arg(from)
+                        .push_arg(to)
+                        .push_arg(value),
+                )
+                .returns::<ReturnType<core::result::Result<(), u8>>>()
+                .fire();
+            matches!(result, Ok(Ok(())))
+        }
+
+        /// Ends the current call with `error`, so an escrow stays open when paying
+        /// it out fails.
+        fn revert(error: Error) -> ! {
+            ink_env::return_value::<Result<()>>(
+                ReturnFlags::default().set_reverted(true),
+                &Err(error),
+            )
+        }
+    }
+
+    /// Unit tests.
+    ///
+    /// Locking and paying out tokens is left to the redspot tests. The transitions
+    /// are checked on an escrow inserted directly.
+    #[cfg(test)]
+    mod tests {
+        /// Imports all the definitions from the outer scope so we can use them here.
+        use super::*;
+
+        use ink_lang as ink;
+        use test_utils::set_caller;
+
+        /// Returns an escrow arbitrated by Eve with `100` tokens Alice locked for
+        /// Bob.
+        fn escrow() -> Escrow {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut escrow = Escrow::new(AccountId::from([0x07; 32]), accounts.eve);
+            escrow.deals.insert(
+                0,
+                Deal {
+                    payer: accounts.alice,
+                    payee: accounts.bob,
+                    amount: 100,
+                    state: EscrowState::Funded,
+                },
+            );
+            escrow
+        }
+
+        fn state(escrow: &Escrow) -> EscrowState {
+            escrow.deal(0).expect("escrow exists").state
+        }
+
+        #[ink::test]
+        fn new_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let escrow = Escrow::new(AccountId::from([0x07; 32]), accounts.eve);
+
+            assert_eq!(escrow.token(), AccountId::from([0x07; 32]));
+            assert_eq!(escrow.arbiter(), accounts.eve);
+            assert_eq!(escrow.deal(0), None);
+        }
+
+        #[ink::test]
+        fn parties_dispute_once() {
+            let mut escrow = escrow();
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            assert_eq!(escrow.dispute(1), Err(Error::EscrowNotFound));
+            set_caller!(accounts.eve);
+            assert_eq!(escrow.dispute(0), Err(Error::NotAllowed));
+            ink_env::test::pop_execution_context();
+
+            set_caller!(accounts.bob);
+            assert_eq!(escrow.dispute(0), Ok(()));
+            assert_eq!(state(&escrow), EscrowState::Disputed);
+            assert_eq!(escrow.dispute(0), Err(Error::InvalidState));
+            assert_eq!(ink_env::test::recorded_events().count(), 1);
+        }
+
+        #[ink::test]
+        fn only_parties_settle_undisputed() {
+            let mut escrow = escrow();
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            assert_eq!(escrow.refund(0), Err(Error::NotAllowed));
+            set_caller!(accounts.bob);
+            assert_eq!(escrow.release(0), Err(Error::NotAllowed));
+            ink_env::test::pop_execution_context();
+
+            set_caller!(accounts.eve);
+            assert_eq!(escrow.release(0), Err(Error::NotAllowed));
+            assert_eq!(escrow.refund(0), Err(Error::NotAllowed));
+            assert_eq!(state(&escrow), EscrowState::Funded);
+        }
+
+        #[ink::test]
+        fn arbiter_settles_disputed() {
+            let mut escrow = escrow();
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            assert_eq!(escrow.dispute(0), Ok(()));
+            set_caller!(accounts.eve);
+            assert_eq!(
+                escrow
+                    .settle(0, EscrowState::Refunded, |deal| deal.payee)
+                    .map(|deal| deal.amount),
+                Ok(100)
+            );
+            assert_eq!(state(&escrow), EscrowState::Refunded);
+        }
+
+        #[ink::test]
+        fn settled_escrows_are_final() {
+            let mut escrow = escrow();
+
+            assert_eq!(
+                escrow
+                    .settle(0, EscrowState::Released, |deal| deal.payer)
+                    .map(|deal| deal.state),
+                Ok(EscrowState::Released)
+            );
+            assert_eq!(escrow.dispute(0), Err(Error::InvalidState));
+            assert_eq!(
+                escrow.settle(0, EscrowState::Released, |deal| deal.payer),
+                Err(Error::InvalidState)
+            );
+        }
+    }
+}
\ No newline at end of file
@@ -0,0 +1,92 @@
+import BN from 'bn.js';
+import { expect } from 'chai';
+import { patract, network } from 'redspot';
+
+const { getContractFactory, getRandomSigner } = patract;
+
+const { api, getSigners } = network;
+
+describe('ESCROW', () => {
+  after(() => {
+    return api.disconnect();
+  });
+
+  async function setup() {
+    const one = new BN(10).pow(new BN(api.registry.chainDecimals[0]));
+    const signers = await getSigners();
+    const Alice = signers[0];
+    const sender = await getRandomSigner(Alice, one.muln(10000));
+    const payee = await getRandomSigner(Alice, one.muln(10000));
+    const arbiter = await getRandomSigner(Alice, one.muln(10000));
+    const tokenFactory = await getContractFactory('erc20', sender);
+    const token = await tokenFactory.deploy(
+      'new',
+      '1000',
+      'Redspot',
+      'RDS',
+      10
+    );
+    const contractFactory = await getContractFactory('escrow', sender);
+    const contract = await contractFactory.deploy(
+      'new',
+      token.address,
+      arbiter.address
+    );
+    await token.tx.approve(contract.address, 100);
+    await contract.tx.create(payee.address, 100);
+
+    return {
+      sender,
+      payee,
+      arbiter,
+      token,
+      contractFactory,
+      contract,
+      Alice,
+      one
+    };
+  }
+
+  it('Locks the funds', async () => {
+    const { contract, token } = await setup();
+
+    expect((await token.query.balanceOf(contract.address)).output).to.equal(
+      100
+    );
+  });
+
+  it('Payer releases the funds to the payee', async () => {
+    const { contract, token, payee } = await setup();
+
+    await expect(() => contract.tx.release(0)).to.changeTokenBalance(
+      token,
+      payee,
+      100
+    );
+    await expect(contract.tx.release(0)).to.not.emit(contract, 'Released');
+  });
+
+  it('Payee refunds the funds to the payer', async () => {
+    const { contract, token, sender, payee } = await setup();
+
+    await expect(() =>
+      contract.tx.refund(0, { signer: payee })
+    ).to.changeTokenBalance(token, sender, 100);
+  });
+
+  it('Arbiter resolves a dispute', async () => {
+    const { contract, token, sender, payee, arbiter } = await setup();
+
+    await expect(contract.tx.refund(0, { signer: arbiter })).to.not.emit(
+      contract,
+      'Refunded'
+    );
+
+    await expect(contract.tx.dispute(0, { signer: payee }))
+      .to.emit(contract, 'Disputed')
+      .withArgs(0, payee.address);
+    await expect(() =>
+      contract.tx.refund(0, { signer: arbiter })
+    ).to.changeTokenBalance(token, sender, 100);
+  });
+});
@@ -0,0 +1,355 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use ink_lang as ink;
+
+#[ink::contract]
+mod faucet {
+    use erc20::{
+        TRANSFER_FROM_SELECTOR,
+        TRANSFER_SELECTOR,
+    };
+    use ink_env::{
+        call::{
+            build_call,
+            utils::ReturnType,
+            ExecutionInput,
+            Selector,
+        },
+        DefaultEnvironment,
+        ReturnFlags,
+    };
+    #[cfg(not(feature = "ink-as-dependency"))]
+    use ink_storage::{
+        collections::HashMap as StorageHashMap,
+        lazy::Lazy,
+    };
+
+    /// Dispenses a fixed amount of an erc20 token to every account once per
+    /// cooldown, for onboarding accounts on local and test networks.
+    ///
+    /// Anyone may request tokens for any account, so an account without funds
+    /// to pay for the transaction can be onboarded by someone else. The limit
+    /// is kept per receiving account.
+    ///
+    /// The faucet dispenses the tokens it holds. The owner refills it with
+    /// `refill`, or anyone by transferring tokens to it.
+    #[ink(storage)]
+    pub struct Faucet {
+        /// The account allowed to change the drip and refill the faucet.
+        owner: AccountId,
+        /// The erc20 token dispensed.
+        token: AccountId,
+        /// The tokens dispensed per request.
+        drip_amount: Lazy<Balance>,
+        /// The blocks an account waits between two requests.
+        cooldown: Lazy<BlockNumber>,
+        /// Mapping from account to the block it last received tokens in.
+        last_drips: StorageHashMap<AccountId, BlockNumber>,
+    }
+
+    /// Event emitted when tokens are dispensed.
+    #[ink(event)]
+    pub struct Dripped {
+        #[ink(topic)]
+        to: AccountId,
+        amount: Balance,
+    }
+
+    /// Event emitted when the owner refills the faucet.
+    #[ink(event)]
+    pub struct Refilled {
+        amount: Balance,
+    }
+
+    /// The faucet error types.
+    #[derive(Debug, PartialEq, Eq, scale::Encode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum Error {
+        /// Returned if the account received tokens within the cooldown.
+        CooldownActive,
+        /// Returned if the drip amount is zero.
+        ZeroAmount,
+        /// Returned if the caller is not the owner.
+        NotOwner,
+        /// Returned if the tokens could not be transferred.
+        TransferFailed,
+    }
+
+    /// The faucet result type.
+    pub type Result<T> = core::result::Result<T, Error>;
+
+    impl Faucet {
+        /// Creates a faucet owned by the caller which dispenses `drip_amount`
+        /// of `token` per account every `cooldown` blocks.
+        ///
+        /// # Panics
+        ///
+        /// If `drip_amount` is zero.
+        #[ink(constructor)]
+        pub fn new(
+            token: AccountId,
+            drip_amount: Balance,
+            cooldown: BlockNumber,
+        ) -> Self {
+            assert!(drip_amount > 0, "invalid drip amount");
+            Self {
+                owner: Self::env().caller(),
+                token,
+                drip_amount: Lazy::new(drip_amount),
+                cooldown: Lazy::new(cooldown),
+                last_drips: StorageHashMap::new(),
+            }
+        }
+
+        /// Returns the account allowed to change the drip and refill the
+        /// faucet.
+        #[ink(message)]
+        pub fn owner(&self) -> AccountId {
+            self.owner
+        }
+
+        /// Returns the erc20 token dispensed.
+        #[ink(message)]
+        pub fn token(&self) -> AccountId {
+            self.token
+        }
+
+        /// Returns the tokens dispensed per request.
+        #[ink(message)]
+        pub fn drip_amount(&self) -> Balance {
+            *self.drip_amount
+        }
+
+        /// Returns the blocks an account waits between two requests.
+        #[ink(message)]
+        pub fn cooldown(&self) -> BlockNumber {
+            *self.cooldown
+        }
+
+        /// Returns the block `account` last received tokens in, or `None` if it
+        /// never did.
+        #[ink(message)]
+        pub fn last_drip_of(&self, account: AccountId) -> Option<BlockNumber> {
+            self.last_drips.get(&account).copied()
+        }
+
+        /// Returns the first block `account` can receive tokens in again.
+        #[ink(message)]
+        pub fn next_drip_of(&self, account: AccountId) -> BlockNumber {
+            self.last_drip_of(account)
+                .map_or(0, |last| last.saturating_add(*self.cooldown))
+        }
+
+        /// Sends the drip amount to `to`.
+        ///
+        /// On success a `Dripped` event is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `CooldownActive` error if `to` received tokens within the
+        /// cooldown.
+        ///
+        /// Returns `TransferFailed` error, reverting all changes, if the tokens
+        /// could not be transferred, as when the faucet ran dry.
+        #[ink(message)]
+        pub fn drip(&mut self, to: AccountId) -> Result<()> {
+            let amount = self.record_drip(to)?;
+            if !self.transfer(to, amount) {
+                Self::revert(Error::TransferFailed)
+            }
+            Ok(())
+        }
+
+        /// Sets the tokens dispensed per request to `drip_amount` and the blocks
+        /// between two requests of an account to `cooldown`.
+        ///
+        /// # Errors
+        ///
+        /// Returns `NotOwner` error if the caller is not the owner.
+        ///
+        /// Returns `ZeroAmount` error if `drip_amount` is zero.
+        #[ink(message)]
+        pub fn set_drip(
+            &mut self,
+            drip_amount: Balance,
+            cooldown: BlockNumber,
+        ) -> Result<()> {
+            self.ensure_owner()?;
+            if drip_amount == 0 {
+                return Err(Error::ZeroAmount)
+            }
+            *self.drip_amount = drip_amount;
+            *self.cooldown = cooldown;
+            Ok(())
+        }
+
+        /// Transfers `amount` tokens from the owner to the faucet.
+        ///
+        /// The owner has to approve the faucet to spend the tokens first. On
+        /// success a `Refilled` event is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `NotOwner` error if the caller is not the owner.
+        ///
+        /// Returns `TransferFailed` error if the tokens could not be
+        /// transferred.
+        #[ink(message)]
+        pub fn refill(&mut self, amount: Balance) -> Result<()> {
+            self.ensure_owner()?;
+            let this = self.env().account_id();
+            if !self.transfer_from(self.owner, this, amount) {
+                return Err(Error::TransferFailed)
+            }
+            self.env().emit_event(Refilled { amount });
+            Ok(())
+        }
+
+        /// Records that `to` receives tokens in the current block and returns
+        /// the amount it receives.
+        fn record_drip(&mut self, to: AccountId) -> Result<Balance> {
+            let now = self.env().block_number();
+            if now < self.next_drip_of(to) {
+                return Err(Error::CooldownActive)
+            }
+            self.last_drips.insert(to, now);
+            let amount = *self.drip_amount;
+            self.env().emit_event(Dripped { to, amount });
+            Ok(amount)
+        }
+
+        /// Returns `NotOwner` error if the caller is not the owner.
+        fn ensure_owner(&self) -> Result<()> {
+            if self.env().caller() != self.owner {
+                return Err(Error::NotOwner)
+            }
+            Ok(())
+        }
+
+        /// Transfers `value` tokens from the faucet to `to` and returns whether
+        /// that succeeded.
+        fn transfer(&self, to: AccountId, value: Balance) -> bool {
+            let result = build_call::<DefaultEnvironment>()
+                .callee(self.token)
+                .gas_limit(0)
+                .transferred_value(0)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(TRANSFER_SELECTOR))
+                        .push_arg(to)
+                        .push_arg(value),
+                )
+                .returns::<ReturnType<core::result::Result<(), u8>>>()
+                .fire();
+            matches!(result, Ok(Ok(())))
+        }
+
+        /// Transfers `value` tokens from `from` to `to` on behalf of the faucet
+        /// and returns whether that succeeded.
+        fn transfer_from(&self, from: AccountId, to: AccountId, value: Balance) -> bool {
+            let result = build_call::<DefaultEnvironment>()
+                .callee(self.token)
+                .gas_limit(0)
+                .transferred_value(0)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(TRANSFER_FROM_SELECTOR))
+                        .push_arg(from)
+                        .push_arg(to)
+                        .push_arg(value),
+                )
+                .returns::<ReturnType<core::result::Result<(), u8>>>()
+                .fire();
+            matches!(result, Ok(Ok(())))
+        }
+
+        /// Ends the current call with `error`, so a failed drip does not start the
+        /// cooldown of the account.
+        fn revert(error: Error) -> ! {
+            ink_env::return_value::<Result<()>>(
+                ReturnFlags::default().set_reverted(true),
+                &Err(error),
+            )
+        }
+    }
+
+    /// Unit tests.
+    ///
+    /// Dispensing tokens is left to the redspot tests.
+    #[cfg(test)]
+    mod tests {
+        /// Imports all the definitions from the outer scope so we can use them here.
+        use super::*;
+
+        use ink_lang as ink;
+        use test_utils::{
+            advance_blocks,
+            set_caller,
+        };
+
+        /// Returns a faucet owned by Alice dispensing `100` every three blocks.
+        fn faucet() -> Faucet {
+            Faucet::new(AccountId::from([0x07; 32]), 100, 3)
+        }
+
+        #[ink::test]
+        fn new_works() {
+            let faucet = faucet();
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            assert_eq!(faucet.owner(), accounts.alice);
+            assert_eq!(faucet.token(), AccountId::from([0x07; 32]));
+            assert_eq!(faucet.drip_amount(), 100);
+            assert_eq!(faucet.cooldown(), 3);
+            assert_eq!(faucet.last_drip_of(accounts.bob), None);
+            assert_eq!(faucet.next_drip_of(accounts.bob), 0);
+        }
+
+        #[ink::test]
+        #[should_panic(expected = "invalid drip amount")]
+        fn new_rejects_zero_drip_amount() {
+            Faucet::new(AccountId::from([0x07; 32]), 0, 3);
+        }
+
+        #[ink::test]
+        fn drips_respect_the_cooldown() {
+            let mut faucet = faucet();
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            assert_eq!(faucet.record_drip(accounts.bob), Ok(100));
+            assert_eq!(faucet.next_drip_of(accounts.bob), 3);
+            assert_eq!(faucet.record_drip(accounts.bob), Err(Error::CooldownActive));
+            assert_eq!(faucet.record_drip(accounts.charlie), Ok(100));
+
+            advance_blocks!(2);
+            assert_eq!(faucet.record_drip(accounts.bob), Err(Error::CooldownActive));
+            advance_blocks!(1);
+            assert_eq!(faucet.record_drip(accounts.bob), Ok(100));
+            assert_eq!(faucet.last_drip_of(accounts.bob), Some(3));
+            assert_eq!(ink_env::test::recorded_events().count(), 3);
+        }
+
+        #[ink::test]
+        fn set_drip_works() {
+            let mut faucet = faucet();
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            faucet.record_drip(accounts.bob).expect("Cannot drip");
+            assert_eq!(faucet.set_drip(0, 1), Err(Error::ZeroAmount));
+            assert_eq!(faucet.set_drip(50, 1), Ok(()));
+            assert_eq!(faucet.drip_amount(), 50);
+            assert_eq!(faucet.next_drip_of(accounts.bob), 1);
+
+            advance_blocks!(1);
+            assert_eq!(faucet.record_drip(accounts.bob), Ok(50));
+
+            set_caller!(accounts.bob);
+            assert_eq!(faucet.set_drip(1000, 0), Err(Error::NotOwner));
+            assert_eq!(faucet.refill(1000), Err(Error::NotOwner));
+        }
+    }
+}
\ No newline at end of file
@@ -0,0 +1,49 @@
+import { patract, network } from 'redspot';
+
+const { getContractFactory } = patract;
+const { createSigner, keyring, api } = network;
+
+const uri =
+  'bottom drive obey lake curtain smoke basket hold race lonely fit walk';
+
+// The development accounts onboarded besides Alice.
+const accounts = ['Bob', 'Charlie', 'Dave', 'Eve'];
+
+async function run() {
+  await api.isReady;
+
+  const signer = createSigner(keyring.createFromUri(`${uri}//Alice`));
+  const tokenFactory = await getContractFactory('erc20', signer);
+  const faucetFactory = await getContractFactory('faucet', signer);
+
+  const token = await tokenFactory.deploy(
+    'new',
+    '1000000',
+    'Redspot',
+    'RDS',
+    10
+  );
+  const faucet = await faucetFactory.deploy('new', token.address, 1000, 10);
+
+  await token.tx.approve(faucet.address, 100000);
+  await faucet.tx.refill(100000);
+
+  console.log('Token address: ', token.address.toString());
+  console.log('Faucet address: ', faucet.address.toString());
+
+  for (const name of accounts) {
+    const { address } = keyring.createFromUri(`${uri}//${name}`);
+
+    await faucet.tx.drip(address);
+
+    const balance = await token.query.balanceOf(address);
+
+    console.log(`${name} holds ${balance.output?.toString()} tokens`);
+  }
+
+  api.disconnect();
+}
+
+run().catch((err) => {
+  console.log(err);
+});
@@ -0,0 +1,92 @@
+import BN from 'bn.js';
+import { expect } from 'chai';
+import { patract, network } from 'redspot';
+
+const { getContractFactory, getRandomSigner } = patract;
+
+const { api, getSigners } = network;
+
+describe('FAUCET', () => {
+  after(() => {
+    return api.disconnect();
+  });
+
+  async function setup() {
+    const one = new BN(10).pow(new BN(api.registry.chainDecimals[0]));
+    const signers = await getSigners();
+    const Alice = signers[0];
+    const sender = await getRandomSigner(Alice, one.muln(10000));
+    const receiver = await getRandomSigner(Alice, one.muln(10000));
+    const tokenFactory = await getContractFactory('erc20', sender);
+    const token = await tokenFactory.deploy(
+      'new',
+      '1000000',
+      'Redspot',
+      'RDS',
+      10
+    );
+    const contractFactory = await getContractFactory('faucet', sender);
+    const contract = await contractFactory.deploy(
+      'new',
+      token.address,
+      100,
+      3
+    );
+    await token.tx.approve(contract.address, 150);
+    await contract.tx.refill(150);
+
+    // The development node seals a block for every transaction, so blocks pass
+    // by sending transactions which change nothing.
+    async function advanceBlocks(blocks: number) {
+      for (let i = 0; i < blocks; i++) {
+        await token.tx.approve(contract.address, 0);
+      }
+    }
+
+    return {
+      sender,
+      receiver,
+      token,
+      contractFactory,
+      contract,
+      advanceBlocks,
+      Alice,
+      one
+    };
+  }
+
+  it('Drips once per cooldown', async () => {
+    const { contract, token, receiver, advanceBlocks } = await setup();
+
+    await expect(() =>
+      contract.tx.drip(receiver.address)
+    ).to.changeTokenBalance(token, receiver, 100);
+    await expect(contract.tx.drip(receiver.address)).to.not.emit(
+      contract,
+      'Dripped'
+    );
+
+    await advanceBlocks(3);
+
+    // Only 50 tokens are left, so the faucet ran dry.
+    await expect(contract.tx.drip(receiver.address)).to.not.emit(
+      contract,
+      'Dripped'
+    );
+  });
+
+  it('Owner refills the faucet', async () => {
+    const { contract, token, receiver } = await setup();
+
+    await expect(
+      contract.tx.refill(100, { signer: receiver })
+    ).to.not.emit(contract, 'Refilled');
+    await token.tx.approve(contract.address, 100);
+    await expect(contract.tx.refill(100))
+      .to.emit(contract, 'Refilled')
+      .withArgs(100);
+    expect((await token.query.balanceOf(contract.address)).output).to.equal(
+      250
+    );
+  });
+});
@@ -0,0 +1,526 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use ink_lang as ink;
+
+#[ink::contract]
+mod governor {
+    use ink_env::{
+        call::{
+            build_call,
+            utils::ReturnType,
+            ExecutionInput,
+            Selector,
+        },
+        DefaultEnvironment,
+        ReturnFlags,
+    };
+    use ink_prelude::vec::Vec;
+    use ink_storage::traits::{
+        PackedLayout,
+        SpreadLayout,
+    };
+    #[cfg(not(feature = "ink-as-dependency"))]
+    use ink_storage::{
+        collections::HashMap as StorageHashMap,
+        lazy::Lazy,
+    };
+
+    /// A proposal ID.
+    pub type ProposalId = u32;
+
+    /// Selector of the `get_past_votes(account, block) -> Result<Balance>`
+    /// message of the erc20 example's votes extension.
+    ///
+    /// This is the selector ink! derives for a message named `get_past_votes`.
+    const GET_PAST_VOTES_SELECTOR: [u8; 4] = [0x79, 0x95, 0xD5, 0xFC];
+
+    /// A call the governor makes if the token holders vote for it.
+    #[derive(
+        Debug,
+        Clone,
+        PartialEq,
+        Eq,
+        scale::Encode,
+        scale::Decode,
+        SpreadLayout,
+        PackedLayout,
+    )]
+    #[cfg_attr(
+        feature = "std",
+        derive(::scale_info::TypeInfo, ::ink_storage::traits::StorageLayout)
+    )]
+    pub struct Proposal {
+        /// The account that made the proposal.
+        proposer: AccountId,
+        /// The contract to call.
+        target: AccountId,
+        /// The selector of the message to call.
+        selector: [u8; 4],
+        /// The SCALE encoded arguments of the message.
+        input: Vec<u8>,
+        /// The block at the end of which voting power is counted.
+        snapshot: BlockNumber,
+        /// The last block in which votes can be cast.
+        deadline: BlockNumber,
+        /// The voting power cast for the proposal.
+        for_votes: Balance,
+        /// The voting power cast against the proposal.
+        against_votes: Balance,
+        /// Whether the proposal was executed.
+        executed: bool,
+    }
+
+    /// The stage a proposal is in.
+    #[derive(Debug, Copy, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum ProposalState {
+        /// Voting starts in the block after the snapshot.
+        Pending,
+        /// Votes can be cast.
+        Active,
+        /// Voting ended without a majority for the proposal or below the quorum.
+        Defeated,
+        /// Voting ended with a majority for the proposal reaching the quorum.
+        Succeeded,
+        /// The proposal was executed.
+        Executed,
+    }
+
+    /// Lets holders of the erc20 example's token vote on calls the governor makes.
+    ///
+    /// Votes are weighted by the voting power delegated to the voter at the
+    /// snapshot block of a proposal, so tokens bought or borrowed after a
+    /// proposal was made do not count.
+    #[ink(storage)]
+    pub struct Governor {
+        /// The token whose voting power is counted.
+        token: AccountId,
+        /// The number of blocks votes can be cast for after the snapshot.
+        voting_period: BlockNumber,
+        /// The voting power that has to be cast for a proposal to succeed.
+        quorum: Balance,
+        /// Mapping from ID to proposal.
+        proposals: StorageHashMap<ProposalId, Proposal>,
+        /// Mapping from `(proposal, voter)` for every vote cast.
+        votes: StorageHashMap<(ProposalId, AccountId), ()>,
+        /// The ID the next proposal gets.
+        next_proposal_id: Lazy<ProposalId>,
+    }
+
+    /// Event emitted when a proposal is made.
+    #[ink(event)]
+    pub struct ProposalCreated {
+        #[ink(topic)]
+        id: ProposalId,
+        #[ink(topic)]
+        proposer: AccountId,
+        deadline: BlockNumber,
+    }
+
+    /// Event emitted when a vote is cast.
+    #[ink(event)]
+    pub struct VoteCast {
+        #[ink(topic)]
+        voter: AccountId,
+        #[ink(topic)]
+        id: ProposalId,
+        support: bool,
+        weight: Balance,
+    }
+
+    /// Event emitted when a proposal is executed.
+    #[ink(event)]
+    pub struct ProposalExecuted {
+        #[ink(topic)]
+        id: ProposalId,
+    }
+
+    /// The governor error types.
+    #[derive(Debug, PartialEq, Eq, scale::Encode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum Error {
+        /// Returned if no proposal has the given ID.
+        ProposalNotFound,
+        /// Returned if voting on the proposal did not start yet.
+        VotingNotStarted,
+        /// Returned if voting on the proposal ended.
+        VotingClosed,
+        /// Returned if the caller already voted on the proposal.
+        AlreadyVoted,
+        /// Returned if the caller had no voting power at the snapshot.
+        NoVotingPower,
+        /// Returned if the voting power could not be read from the token.
+        TokenCallFailed,
+        /// Returned if the proposal is not in the `Succeeded` state.
+        NotSucceeded,
+        /// Returned if the call of an executed proposal failed.
+        ExecutionFailed,
+    }
+
+    /// The governor result type.
+    pub type Result<T> = core::result::Result<T, Error>;
+
+    impl Governor {
+        /// Creates a governor counting the voting power of `token`.
+        ///
+        /// Votes can be cast for `voting_period` blocks and a proposal needs at
+        /// least `quorum` votes for it to succeed.
+        #[ink(constructor)]
+        pub fn new(
+            token: AccountId,
+            voting_period: BlockNumber,
+            quorum: Balance,
+        ) -> Self {
+            Self {
+                token,
+                voting_period,
+                quorum,
+                proposals: StorageHashMap::new(),
+                votes: StorageHashMap::new(),
+                next_proposal_id: Lazy::new(0),
+            }
+        }
+
+        /// Returns the token whose voting power is counted.
+        #[ink(message)]
+        pub fn token(&self) -> AccountId {
+            self.token
+        }
+
+        /// Returns the number of blocks votes can be cast for.
+        #[ink(message)]
+        pub fn voting_period(&self) -> BlockNumber {
+            self.voting_period
+        }
+
+        /// Returns the voting power that has to be cast for a proposal to succeed.
+        #[ink(message)]
+        pub fn quorum(&self) -> Balance {
+            self.quorum
+        }
+
+        /// Returns the proposal with ID `id`, if any.
+        #[ink(message)]
+        pub fn proposal(&self, id: ProposalId) -> Option<Proposal> {
+            self.proposals.get(&id).cloned()
+        }
+
+        /// Returns `true` if `voter` voted on the proposal with ID `id`.
+        #[ink(message)]
+        pub fn has_voted(&self, id: ProposalId, voter: AccountId) -> bool {
+            self.votes.contains_key(&(id, voter))
+        }
+
+        /// Returns the stage the proposal with ID `id` is in, if it exists.
+        #[ink(message)]
+        pub fn state(&self, id: ProposalId) -> Option<ProposalState> {
+            let proposal = self.proposals.get(&id)?;
+            let now = self.env().block_number();
+            let state = if proposal.executed {
+                ProposalState::Executed
+            } else if now <= proposal.snapshot {
+                ProposalState::Pending
+            } else if now <= proposal.deadline {
+                ProposalState::Active
+            } else if proposal.for_votes > proposal.against_votes
+                && proposal.for_votes >= self.quorum
+            {
+                ProposalState::Succeeded
+            } else {
+                ProposalState::Defeated
+            };
+            Some(state)
+        }
+
+        /// Proposes that the governor calls the message with `selector` of `target`
+        /// passing the SCALE encoded `input`, and returns the proposal ID.
+        ///
+        /// Voting power is counted at the end of the current block and votes can
+        /// be cast from the next block on. A `ProposalCreated` event is emitted.
+        #[ink(message)]
+        pub fn propose(
+            &mut self,
+            target: AccountId,
+            selector: [u8; 4],
+            input: Vec<u8>,
+        ) -> ProposalId {
+            let proposer = self.env().caller();
+            let snapshot = self.env().block_number();
+            let deadline = snapshot + self.voting_period;
+            let id = *self.next_proposal_id;
+            *self.next_proposal_id += 1;
+            self.proposals.insert(
+                id,
+                Proposal {
+                    proposer,
+                    target,
+                    selector,
+                    input,
+                    snapshot,
+                    deadline,
+                    for_votes: 0,
+                    against_votes: 0,
+                    executed: false,
+                },
+            );
+            self.env().emit_event(ProposalCreated {
+                id,
+                proposer,
+                deadline,
+            });
+            id
+        }
+
+        /// Votes for the proposal with ID `id` if `support` is `true` and against
+        /// it otherwise, with the caller's voting power at the snapshot, and
+        /// returns that voting power.
+        ///
+        /// On success a `VoteCast` event is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `ProposalNotFound` error if there is no such proposal.
+        ///
+        /// Returns `VotingNotStarted` or `VotingClosed` error if the proposal is
+        /// not `Active`.
+        ///
+        /// Returns `AlreadyVoted` error if the caller already voted on it.
+        ///
+        /// Returns `TokenCallFailed` error if the voting power could not be read.
+        ///
+        /// Returns `NoVotingPower` error if the caller had no voting power.
+        #[ink(message)]
+        pub fn cast_vote(&mut self, id: ProposalId, support: bool) -> Result<Balance> {
+            let voter = self.env().caller();
+            match self.state(id) {
+                None => return Err(Error::ProposalNotFound),
+                Some(ProposalState::Pending) => return Err(Error::VotingNotStarted),
+                Some(ProposalState::Active) => {}
+                Some(_) => return Err(Error::VotingClosed),
+            }
+            if self.has_voted(id, voter) {
+                return Err(Error::AlreadyVoted)
+            }
+            let snapshot = self.proposals[&id].snapshot;
+            let weight = self.past_votes(voter, snapshot)?;
+            if weight == 0 {
+                return Err(Error::NoVotingPower)
+            }
+            self.votes.insert((id, voter), ());
+            let proposal = self
+                .proposals
+                .get_mut(&id)
+                .expect("the proposal exists as it is active");
+            // Tallies are bounded by the token supply, which is a `Balance`.
+            if support {
+                proposal.for_votes = proposal.for_votes.saturating_add(weight);
+            } else {
+                proposal.against_votes = proposal.against_votes.saturating_add(weight);
+            }
+            self.env().emit_event(VoteCast {
+                voter,
+                id,
+                support,
+                weight,
+            });
+            Ok(weight)
+        }
+
+        /// Executes the succeeded proposal with ID `id`.
+        ///
+        /// Anyone may execute a succeeded proposal. The value returned by the call
+        /// is not interpreted. On success a `ProposalExecuted` event is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `ProposalNotFound` error if there is no such proposal.
+        ///
+        /// Returns `NotSucceeded` error if the proposal is not `Succeeded`.
+        ///
+        /// Returns `ExecutionFailed` error, reverting all changes, if the call fails.
+        #[ink(message)]
+        pub fn execute(&mut self, id: ProposalId) -> Result<()> {
+            match self.state(id) {
+                None => return Err(Error::ProposalNotFound),
+                Some(ProposalState::Succeeded) => {}
+                Some(_) => return Err(Error::NotSucceeded),
+            }
+            let proposal = self
+                .proposals
+                .get_mut(&id)
+                .expect("the proposal exists as it succeeded");
+            proposal.executed = true;
+            let (target, selector, input) =
+                (proposal.target, proposal.selector, proposal.input.clone());
+            // ink! writes the contract storage back only when a message returns.
+            // Write it now so the target can not execute the proposal a second
+            // time by calling back into the governor.
+            ink_storage::traits::push_spread_root::<Self>(
+                self,
+                &ink_primitives::Key::from([0x00; 32]),
+            );
+            let result = build_call::<DefaultEnvironment>()
+                .callee(target)
+                .gas_limit(0)
+                .transferred_value(0)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(selector))
+                        .push_arg(CallInput(&input)),
+                )
+                .returns::<()>()
+                .fire();
+            if result.is_err() {
+                Self::revert(Error::ExecutionFailed)
+            }
+            self.env().emit_event(ProposalExecuted { id });
+            Ok(())
+        }
+
+        /// Returns the voting power `account` had at the end of block `block`.
+        fn past_votes(&self, account: AccountId, block: BlockNumber) -> Result<Balance> {
+            // The token's error is not interpreted, decoding its variant index is
+            // enough to tell it apart from a balance.
+            let votes = build_call::<DefaultEnvironment>()
+                .callee(self.token)
+                .gas_limit(0)
+                .transferred_value(0)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(GET_PAST_VOTES_SELECTOR))
+                        .push_arg(account)
+                        .push_arg(block),
+                )
+                .returns::<ReturnType<core::result::Result<Balance, u8>>>()
+                .fire();
+            match votes {
+                Ok(Ok(votes)) => Ok(votes),
+                _ => Err(Error::TokenCallFailed),
+            }
+        }
+
+        /// Ends the current call with `error`, so a proposal whose call fails is
+        /// not marked as executed.
+        fn revert(error: Error) -> ! {
+            ink_env::return_value::<Result<()>>(
+                ReturnFlags::default().set_reverted(true),
+                &Err(error),
+            )
+        }
+    }
+
+    /// Passes already encoded arguments on to a call without encoding them again.
+    struct CallInput<'a>(&'a [u8]);
+
+    impl<'a> scale::Encode for CallInput<'a> {
+        fn encode_to<T: scale::Output + ?Sized>(&self, dest: &mut T) {
+            dest.write(self.0);
+        }
+    }
+
+    /// Unit tests.
+    ///
+    /// Reading voting power and executing proposals are left to the redspot tests.
+    #[cfg(test)]
+    mod tests {
+        /// Imports all the definitions from the outer scope so we can use them here.
+        use super::*;
+
+        use ink_lang as ink;
+        use test_utils::advance_blocks;
+
+        /// Returns a governor with a voting period of three blocks and a quorum
+        /// of `10`, with one proposal.
+        fn governor() -> Governor {
+            let mut governor = Governor::new(AccountId::from([0x07; 32]), 3, 10);
+            assert_eq!(
+                governor.propose(AccountId::from([0x08; 32]), [0x00; 4], Vec::new()),
+                0
+            );
+            governor
+        }
+
+        #[ink::test]
+        fn propose_works() {
+            let governor = governor();
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            let proposal = governor.proposal(0).expect("proposal exists");
+            assert_eq!(proposal.proposer, accounts.alice);
+            assert_eq!(proposal.deadline, proposal.snapshot + 3);
+            assert_eq!(governor.state(0), Some(ProposalState::Pending));
+            assert_eq!(governor.state(1), None);
+            assert_eq!(ink_env::test::recorded_events().count(), 1);
+        }
+
+        #[ink::test]
+        fn proposal_moves_through_voting() {
+            let governor = governor();
+
+            advance_blocks!(1);
+            assert_eq!(governor.state(0), Some(ProposalState::Active));
+            advance_blocks!(2);
+            assert_eq!(governor.state(0), Some(ProposalState::Active));
+            advance_blocks!(1);
+            assert_eq!(governor.state(0), Some(ProposalState::Defeated));
+        }
+
+        #[ink::test]
+        fn outcome_needs_majority_and_quorum() {
+            let mut governor = governor();
+            advance_blocks!(4);
+
+            let proposal = governor.proposals.get_mut(&0).expect("proposal exists");
+            proposal.for_votes = 9;
+            assert_eq!(governor.state(0), Some(ProposalState::Defeated));
+
+            let proposal = governor.proposals.get_mut(&0).expect("proposal exists");
+            proposal.for_votes = 10;
+            proposal.against_votes = 10;
+            assert_eq!(governor.state(0), Some(ProposalState::Defeated));
+
+            let proposal = governor.proposals.get_mut(&0).expect("proposal exists");
+            proposal.against_votes = 9;
+            assert_eq!(governor.state(0), Some(ProposalState::Succeeded));
+        }
+
+        #[ink::test]
+        fn votes_are_only_counted_while_active() {
+            let mut governor = governor();
+
+            assert_eq!(governor.cast_vote(0, true), Err(Error::VotingNotStarted));
+            assert_eq!(governor.cast_vote(1, true), Err(Error::ProposalNotFound));
+            advance_blocks!(4);
+            assert_eq!(governor.cast_vote(0, true), Err(Error::VotingClosed));
+        }
+
+        #[ink::test]
+        fn votes_are_cast_once() {
+            let mut governor = governor();
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            governor.votes.insert((0, accounts.alice), ());
+            advance_blocks!(1);
+            assert_eq!(governor.cast_vote(0, false), Err(Error::AlreadyVoted));
+            assert!(governor.has_voted(0, accounts.alice));
+            assert!(!governor.has_voted(0, accounts.bob));
+        }
+
+        #[ink::test]
+        fn only_succeeded_proposals_are_executed() {
+            let mut governor = governor();
+
+            assert_eq!(governor.execute(0), Err(Error::NotSucceeded));
+            assert_eq!(governor.execute(1), Err(Error::ProposalNotFound));
+            advance_blocks!(4);
+            assert_eq!(governor.execute(0), Err(Error::NotSucceeded));
+
+            let proposal = governor.proposals.get_mut(&0).expect("proposal exists");
+            proposal.executed = true;
+            assert_eq!(governor.state(0), Some(ProposalState::Executed));
+            assert_eq!(governor.execute(0), Err(Error::NotSucceeded));
+        }
+    }
+}
\ No newline at end of file
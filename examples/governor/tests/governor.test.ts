@@ -0,0 +1,116 @@
+import BN from 'bn.js';
+import { expect } from 'chai';
+import { patract, network } from 'redspot';
+
+const { getContractFactory, getRandomSigner } = patract;
+
+const { api, getSigners } = network;
+
+// Selector of the erc20 `accept_ownership` message.
+const ACCEPT_OWNERSHIP_SELECTOR = '0xb55be9f0';
+
+const VOTING_PERIOD = 2;
+
+describe('GOVERNOR', () => {
+  after(() => {
+    return api.disconnect();
+  });
+
+  async function setup() {
+    const one = new BN(10).pow(new BN(api.registry.chainDecimals[0]));
+    const signers = await getSigners();
+    const Alice = signers[0];
+    const sender = await getRandomSigner(Alice, one.muln(10000));
+    const tokenFactory = await getContractFactory('erc20', sender);
+    const token = await tokenFactory.deploy(
+      'new',
+      '1000',
+      'Redspot',
+      'RDS',
+      10
+    );
+    await token.tx.delegate(sender.address);
+    const contractFactory = await getContractFactory('governor', sender);
+    const contract = await contractFactory.deploy(
+      'new',
+      token.address,
+      VOTING_PERIOD,
+      500
+    );
+    // A proposal making the governor the owner of the token.
+    await token.tx.proposeOwner(contract.address);
+    await contract.tx.propose(token.address, ACCEPT_OWNERSHIP_SELECTOR, '0x');
+
+    // The development node seals a block for every transaction, so blocks
+    // pass by sending transactions which change nothing.
+    async function advanceBlocks(blocks: number) {
+      for (let i = 0; i < blocks; i++) {
+        await token.tx.approve(contract.address, 0);
+      }
+    }
+
+    return {
+      sender,
+      token,
+      contractFactory,
+      contract,
+      advanceBlocks,
+      Alice,
+      one
+    };
+  }
+
+  it('Executes a proposal voted for', async () => {
+    const { contract, token, sender, advanceBlocks } = await setup();
+
+    await expect(contract.tx.castVote(0, true))
+      .to.emit(contract, 'VoteCast')
+      .withArgs(sender.address, 0, true, 1000);
+    await expect(contract.tx.execute(0)).to.not.emit(
+      contract,
+      'ProposalExecuted'
+    );
+
+    await advanceBlocks(VOTING_PERIOD);
+    await expect(contract.tx.execute(0))
+      .to.emit(contract, 'ProposalExecuted')
+      .withArgs(0);
+
+    expect((await token.query.owner()).output).to.equal(contract.address);
+  });
+
+  it('Does not execute a proposal voted against', async () => {
+    const { contract, token, sender, advanceBlocks } = await setup();
+
+    await contract.tx.castVote(0, false);
+    await advanceBlocks(VOTING_PERIOD);
+
+    await expect(contract.tx.execute(0)).to.not.emit(
+      contract,
+      'ProposalExecuted'
+    );
+    expect((await token.query.owner()).output).to.equal(sender.address);
+  });
+
+  it('Can not vote twice', async () => {
+    const { contract } = await setup();
+
+    await contract.tx.castVote(0, true);
+    await expect(contract.tx.castVote(0, true)).to.not.emit(
+      contract,
+      'VoteCast'
+    );
+  });
+
+  it('Can not vote without voting power', async () => {
+    const { contract, Alice, one } = await setup();
+
+    const otherAccount = await getRandomSigner(Alice, one.muln(10000));
+
+    await expect(
+      contract.tx.castVote(0, true, {
+        signer: otherAccount
+      })
+    ).to.not.emit(contract, 'VoteCast');
+  });
+});
@@ -0,0 +1,780 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use ink_lang as ink;
+
+#[ink::contract]
+mod lending {
+    use erc20::{
+        TRANSFER_FROM_SELECTOR,
+        TRANSFER_SELECTOR,
+    };
+    use ink_env::{
+        call::{
+            build_call,
+            utils::ReturnType,
+            ExecutionInput,
+            Selector,
+        },
+        DefaultEnvironment,
+        ReturnFlags,
+    };
+    use ink_storage::traits::{
+        PackedLayout,
+        SpreadLayout,
+    };
+    #[cfg(not(feature = "ink-as-dependency"))]
+    use ink_storage::{
+        collections::HashMap as StorageHashMap,
+        lazy::Lazy,
+    };
+
+    /// Selector of the price oracle's `price(token, max_age) -> Result<Balance>`.
+    const PRICE_SELECTOR: [u8; 4] = [0xD4, 0xBD, 0x7B, 0xC1];
+
+    /// The factor the borrow index and the interest rate are scaled by to keep
+    /// their fractional part.
+    pub const PRECISION: Balance = 1_000_000_000_000;
+
+    /// The collateral and the debt of a borrower.
+    #[derive(
+        Debug,
+        Copy,
+        Clone,
+        PartialEq,
+        Eq,
+        scale::Encode,
+        scale::Decode,
+        SpreadLayout,
+        PackedLayout,
+    )]
+    #[cfg_attr(
+        feature = "std",
+        derive(::scale_info::TypeInfo, ::ink_storage::traits::StorageLayout)
+    )]
+    pub struct Position {
+        /// The collateral tokens supplied.
+        pub collateral: Balance,
+        /// The debt at the time `index` was the borrow index.
+        pub principal: Balance,
+        /// The borrow index `principal` was last updated at.
+        pub index: Balance,
+    }
+
+    /// Lends an erc20 token against another erc20 token as collateral.
+    ///
+    /// Borrowers supply collateral and borrow up to `collateral_factor` percent
+    /// of its value, both valued at the prices of an oracle contract. Debts grow
+    /// by `interest_rate` every block. Once a debt is worth more than the
+    /// collateral allows, anyone may repay it and takes collateral worth the
+    /// repaid amount plus `liquidation_bonus` percent in return.
+    ///
+    /// The market lends out the tokens it holds, which anyone can transfer to
+    /// it. Repaid debts and interest stay in the market, and the owner can take
+    /// tokens out again with `withdraw_liquidity`.
+    ///
+    /// Prices are read from the oracle with `price(token, max_age)`, which
+    /// returns the price of `token` in a unit of the oracle's choice, or an
+    /// error if it has none updated within the last `max_age` milliseconds.
+    /// The oracle example implements it.
+    #[ink(storage)]
+    pub struct Lending {
+        /// The account allowed to withdraw the lent out tokens.
+        owner: AccountId,
+        /// The erc20 token borrowers supply as collateral.
+        collateral_token: AccountId,
+        /// The erc20 token borrowers borrow.
+        borrow_token: AccountId,
+        /// The contract prices are read from.
+        oracle: AccountId,
+        /// The percentage of the collateral value which can be borrowed.
+        collateral_factor: u8,
+        /// The percentage of the repaid value liquidators get on top in
+        /// collateral.
+        liquidation_bonus: u8,
+        /// The interest per block, scaled by `PRECISION`.
+        interest_rate: Balance,
+        /// The oldest price, in milliseconds, the market accepts.
+        max_price_age: Timestamp,
+        /// The growth of a debt since the market was created, scaled by
+        /// `PRECISION`, accumulated until `accrued_at`.
+        borrow_index: Lazy<Balance>,
+        /// The block `borrow_index` was last updated in.
+        accrued_at: Lazy<BlockNumber>,
+        /// Mapping from borrower to its position.
+        positions: StorageHashMap<AccountId, Position>,
+    }
+
+    /// Event emitted when collateral is supplied.
+    #[ink(event)]
+    pub struct Supplied {
+        #[ink(topic)]
+        account: AccountId,
+        amount: Balance,
+    }
+
+    /// Event emitted when collateral is withdrawn.
+    #[ink(event)]
+    pub struct Withdrawn {
+        #[ink(topic)]
+        account: AccountId,
+        amount: Balance,
+    }
+
+    /// Event emitted when tokens are borrowed.
+    #[ink(event)]
+    pub struct Borrowed {
+        #[ink(topic)]
+        account: AccountId,
+        amount: Balance,
+    }
+
+    /// Event emitted when a debt is repaid by its borrower.
+    #[ink(event)]
+    pub struct Repaid {
+        #[ink(topic)]
+        account: AccountId,
+        amount: Balance,
+    }
+
+    /// Event emitted when an undercollateralized debt is repaid by a liquidator.
+    #[ink(event)]
+    pub struct Liquidated {
+        #[ink(topic)]
+        borrower: AccountId,
+        #[ink(topic)]
+        liquidator: AccountId,
+        repaid: Balance,
+        seized: Balance,
+    }
+
+    /// The lending error types.
+    #[derive(Debug, PartialEq, Eq, scale::Encode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum Error {
+        /// Returned if zero tokens would be supplied, withdrawn, borrowed or
+        /// repaid.
+        ZeroAmount,
+        /// Returned if more collateral would be withdrawn than was supplied.
+        InsufficientCollateral,
+        /// Returned if the debt of the caller would be worth more than its
+        /// collateral allows.
+        Undercollateralized,
+        /// Returned if a position which is not undercollateralized would be
+        /// liquidated.
+        PositionHealthy,
+        /// Returned if the caller is not the owner.
+        NotOwner,
+        /// Returned if the oracle has no recent price for a token.
+        PriceUnavailable,
+        /// Returned if the value of a position does not fit into a `Balance`.
+        Overflow,
+        /// Returned if tokens could not be transferred.
+        TransferFailed,
+    }
+
+    /// The lending result type.
+    pub type Result<T> = core::result::Result<T, Error>;
+
+    impl Lending {
+        /// Creates a market lending `borrow_token` against `collateral_token` at
+        /// the prices of `oracle`.
+        ///
+        /// `interest_rate` is the interest per block scaled by `PRECISION`, and
+        /// prices older than `max_price_age` milliseconds are rejected.
+        ///
+        /// # Panics
+        ///
+        /// If `collateral_factor` is zero or above `100`.
+        #[ink(constructor)]
+        pub fn new(
+            collateral_token: AccountId,
+            borrow_token: AccountId,
+            oracle: AccountId,
+            collateral_factor: u8,
+            liquidation_bonus: u8,
+            interest_rate: Balance,
+            max_price_age: Timestamp,
+        ) -> Self {
+            assert!(
+                (1..=100).contains(&collateral_factor),
+                "invalid collateral factor"
+            );
+            Self {
+                owner: Self::env().caller(),
+                collateral_token,
+                borrow_token,
+                oracle,
+                collateral_factor,
+                liquidation_bonus,
+                interest_rate,
+                max_price_age,
+                borrow_index: Lazy::new(PRECISION),
+                accrued_at: Lazy::new(Self::env().block_number()),
+                positions: StorageHashMap::new(),
+            }
+        }
+
+        /// Returns the collateral and the borrowed token.
+        #[ink(message)]
+        pub fn tokens(&self) -> (AccountId, AccountId) {
+            (self.collateral_token, self.borrow_token)
+        }
+
+        /// Returns the contract prices are read from.
+        #[ink(message)]
+        pub fn oracle(&self) -> AccountId {
+            self.oracle
+        }
+
+        /// Returns the collateral factor and the liquidation bonus in percent.
+        #[ink(message)]
+        pub fn risk_parameters(&self) -> (u8, u8) {
+            (self.collateral_factor, self.liquidation_bonus)
+        }
+
+        /// Returns the interest per block, scaled by `PRECISION`.
+        #[ink(message)]
+        pub fn interest_rate(&self) -> Balance {
+            self.interest_rate
+        }
+
+        /// Returns the growth of a debt since the market was created, scaled by
+        /// `PRECISION`.
+        #[ink(message)]
+        pub fn borrow_index(&self) -> Balance {
+            let blocks = self.env().block_number() - *self.accrued_at;
+            let interest = self
+                .borrow_index
+                .saturating_mul(self.interest_rate)
+                .saturating_mul(Balance::from(blocks))
+                / PRECISION;
+            self.borrow_index.saturating_add(interest)
+        }
+
+        /// Returns the collateral `account` supplied.
+        #[ink(message)]
+        pub fn collateral_of(&self, account: AccountId) -> Balance {
+            self.position(account).collateral
+        }
+
+        /// Returns the debt of `account` including the interest until now.
+        #[ink(message)]
+        pub fn debt_of(&self, account: AccountId) -> Balance {
+            self.debt(self.position(account))
+        }
+
+        /// Supplies `amount` collateral tokens of the caller.
+        ///
+        /// The caller has to approve this contract to spend `amount` collateral
+        /// tokens first. On success a `Supplied` event is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `ZeroAmount` error if `amount` is zero.
+        ///
+        /// Returns `TransferFailed` error, reverting all changes, if the tokens
+        /// can not be transferred from the caller.
+        #[ink(message)]
+        pub fn supply(&mut self, amount: Balance) -> Result<()> {
+            if amount == 0 {
+                return Err(Error::ZeroAmount)
+            }
+            let account = self.env().caller();
+            let mut position = self.position(account);
+            position.collateral = position
+                .collateral
+                .checked_add(amount)
+                .ok_or(Error::Overflow)?;
+            self.positions.insert(account, position);
+            let this = self.env().account_id();
+            if !self.transfer_from(self.collateral_token, account, this, amount) {
+                Self::revert(Error::TransferFailed)
+            }
+            self.env().emit_event(Supplied { account, amount });
+            Ok(())
+        }
+
+        /// Withdraws `amount` collateral tokens of the caller.
+        ///
+        /// On success a `Withdrawn` event is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `ZeroAmount` error if `amount` is zero.
+        ///
+        /// Returns `InsufficientCollateral` error if the caller supplied less
+        /// than `amount` collateral tokens.
+        ///
+        /// Returns `Undercollateralized` error if the remaining collateral would
+        /// not cover the debt of the caller.
+        ///
+        /// Returns `PriceUnavailable` error if the caller has a debt and the
+        /// oracle has no recent price.
+        ///
+        /// Returns `TransferFailed` error, reverting all changes, if the tokens
+        /// can not be transferred to the caller.
+        #[ink(message)]
+        pub fn withdraw(&mut self, amount: Balance) -> Result<()> {
+            if amount == 0 {
+                return Err(Error::ZeroAmount)
+            }
+            let account = self.env().caller();
+            let mut position = self.position(account);
+            if position.collateral < amount {
+                return Err(Error::InsufficientCollateral)
+            }
+            self.accrue();
+            position.collateral -= amount;
+            self.ensure_healthy(position)?;
+            self.positions.insert(account, position);
+            if !self.transfer(self.collateral_token, account, amount) {
+                Self::revert(Error::TransferFailed)
+            }
+            self.env().emit_event(Withdrawn { account, amount });
+            Ok(())
+        }
+
+        /// Borrows `amount` tokens against the collateral of the caller.
+        ///
+        /// On success a `Borrowed` event is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `ZeroAmount` error if `amount` is zero.
+        ///
+        /// Returns `Undercollateralized` error if the collateral of the caller
+        /// would not cover its debt.
+        ///
+        /// Returns `PriceUnavailable` error if the oracle has no recent price.
+        ///
+        /// Returns `TransferFailed` error, reverting all changes, if the tokens
+        /// can not be transferred to the caller, for example because the market
+        /// does not hold enough of them.
+        #[ink(message)]
+        pub fn borrow(&mut self, amount: Balance) -> Result<()> {
+            if amount == 0 {
+                return Err(Error::ZeroAmount)
+            }
+            let account = self.env().caller();
+            self.accrue();
+            let debt = self
+                .debt(self.position(account))
+                .checked_add(amount)
+                .ok_or(Error::Overflow)?;
+            let position = self.update_debt(account, debt);
+            self.ensure_healthy(position)?;
+            self.positions.insert(account, position);
+            if !self.transfer(self.borrow_token, account, amount) {
+                Self::revert(Error::TransferFailed)
+            }
+            self.env().emit_event(Borrowed { account, amount });
+            Ok(())
+        }
+
+        /// Repays up to `amount` of the debt of the caller and returns the
+        /// amount repaid.
+        ///
+        /// The caller has to approve this contract to spend the borrowed tokens
+        /// first. On success a `Repaid` event is emitted if there was a debt.
+        ///
+        /// # Errors
+        ///
+        /// Returns `ZeroAmount` error if `amount` is zero.
+        ///
+        /// Returns `TransferFailed` error, reverting all changes, if the tokens
+        /// can not be transferred from the caller.
+        #[ink(message)]
+        pub fn repay(&mut self, amount: Balance) -> Result<Balance> {
+            if amount == 0 {
+                return Err(Error::ZeroAmount)
+            }
+            let account = self.env().caller();
+            self.accrue();
+            let debt = self.debt(self.position(account));
+            let repaid = amount.min(debt);
+            if repaid == 0 {
+                return Ok(0)
+            }
+            let position = self.update_debt(account, debt - repaid);
+            self.positions.insert(account, position);
+            let this = self.env().account_id();
+            if !self.transfer_from(self.borrow_token, account, this, repaid) {
+                Self::revert(Error::TransferFailed)
+            }
+            self.env().emit_event(Repaid {
+                account,
+                amount: repaid,
+            });
+            Ok(repaid)
+        }
+
+        /// Repays up to `amount` of the debt of the undercollateralized
+        /// `borrower` and returns the collateral the caller gets for it.
+        ///
+        /// The caller gets collateral worth the repaid amount plus the
+        /// liquidation bonus, at most all collateral of `borrower`. The caller
+        /// has to approve this contract to spend the borrowed tokens first. On
+        /// success a `Liquidated` event is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `ZeroAmount` error if `amount` is zero.
+        ///
+        /// Returns `PositionHealthy` error if the collateral of `borrower` covers
+        /// its debt.
+        ///
+        /// Returns `PriceUnavailable` error if the oracle has no recent price.
+        ///
+        /// Returns `TransferFailed` error, reverting all changes, if the tokens
+        /// can not be transferred.
+        #[ink(message)]
+        pub fn liquidate(
+            &mut self,
+            borrower: AccountId,
+            amount: Balance,
+        ) -> Result<Balance> {
+            if amount == 0 {
+                return Err(Error::ZeroAmount)
+            }
+            self.accrue();
+            let position = self.position(borrower);
+            let debt = self.debt(position);
+            if debt == 0 {
+                return Err(Error::PositionHealthy)
+            }
+            let prices = self.prices()?;
+            if self.is_healthy(position.collateral, debt, prices)? {
+                return Err(Error::PositionHealthy)
+            }
+            let repaid = amount.min(debt);
+            let seized = self
+                .seized_collateral(repaid, prices)?
+                .min(position.collateral);
+            let mut position = self.update_debt(borrower, debt - repaid);
+            position.collateral -= seized;
+            self.positions.insert(borrower, position);
+            let liquidator = self.env().caller();
+            let this = self.env().account_id();
+            if !self.transfer_from(self.borrow_token, liquidator, this, repaid)
+                || !self.transfer(self.collateral_token, liquidator, seized)
+            {
+                Self::revert(Error::TransferFailed)
+            }
+            self.env().emit_event(Liquidated {
+                borrower,
+                liquidator,
+                repaid,
+                seized,
+            });
+            Ok(seized)
+        }
+
+        /// Sends `amount` of the borrowed token held by the market to the owner.
+        ///
+        /// # Errors
+        ///
+        /// Returns `NotOwner` error if the caller is not the owner.
+        ///
+        /// Returns `TransferFailed` error if the tokens can not be transferred,
+        /// for example because the market does not hold enough of them.
+        #[ink(message)]
+        pub fn withdraw_liquidity(&mut self, amount: Balance) -> Result<()> {
+            if self.env().caller() != self.owner {
+                return Err(Error::NotOwner)
+            }
+            // Nothing was written yet, so a failed transfer leaves no trace.
+            if !self.transfer(self.borrow_token, self.owner, amount) {
+                return Err(Error::TransferFailed)
+            }
+            Ok(())
+        }
+
+        /// Returns the position of `account`, empty if it has none.
+        fn position(&self, account: AccountId) -> Position {
+            self.positions.get(&account).copied().unwrap_or(Position {
+                collateral: 0,
+                principal: 0,
+                index: PRECISION,
+            })
+        }
+
+        /// Returns the debt of `position` at the current borrow index.
+        fn debt(&self, position: Position) -> Balance {
+            if position.principal == 0 {
+                return 0
+            }
+            position.principal.saturating_mul(self.borrow_index()) / position.index
+        }
+
+        /// Returns the position of `account` with its debt set to `debt` at the
+        /// current borrow index.
+        fn update_debt(&self, account: AccountId, debt: Balance) -> Position {
+            Position {
+                principal: debt,
+                index: *self.borrow_index,
+                ..self.position(account)
+            }
+        }
+
+        /// Accounts the interest until the current block.
+        ///
+        /// Has to be called before any debt changes.
+        fn accrue(&mut self) {
+            *self.borrow_index = self.borrow_index();
+            *self.accrued_at = self.env().block_number();
+        }
+
+        /// Returns `Undercollateralized` error if `position` has a debt which
+        /// its collateral does not cover.
+        fn ensure_healthy(&self, position: Position) -> Result<()> {
+            let debt = self.debt(position);
+            if debt == 0 {
+                return Ok(())
+            }
+            if !self.is_healthy(position.collateral, debt, self.prices()?)? {
+                return Err(Error::Undercollateralized)
+            }
+            Ok(())
+        }
+
+        /// Returns whether `collateral` covers `debt` at `prices`, the prices of
+        /// the collateral and the borrowed token.
+        fn is_healthy(
+            &self,
+            collateral: Balance,
+            debt: Balance,
+            (collateral_price, borrow_price): (Balance, Balance),
+        ) -> Result<bool> {
+            let limit = collateral
+                .checked_mul(collateral_price)
+                .and_then(|value| {
+                    value.checked_mul(Balance::from(self.collateral_factor))
+                })
+                .ok_or(Error::Overflow)?;
+            let value = debt
+                .checked_mul(borrow_price)
+                .and_then(|value| value.checked_mul(100))
+                .ok_or(Error::Overflow)?;
+            Ok(value <= limit)
+        }
+
+        /// Returns the collateral worth `repaid` borrowed tokens plus the
+        /// liquidation bonus at `prices`.
+        fn seized_collateral(
+            &self,
+            repaid: Balance,
+            (collateral_price, borrow_price): (Balance, Balance),
+        ) -> Result<Balance> {
+            repaid
+                .checked_mul(borrow_price)
+                .and_then(|value| {
+                    value.checked_mul(100 + Balance::from(self.liquidation_bonus))
+                })
+                .and_then(|value| value.checked_div(collateral_price.checked_mul(100)?))
+                .ok_or(Error::Overflow)
+        }
+
+        /// Returns the prices of the collateral and the borrowed token.
+        fn prices(&self) -> Result<(Balance, Balance)> {
+            Ok((
+                self.price(self.collateral_token)?,
+                self.price(self.borrow_token)?,
+            ))
+        }
+
+        /// Returns the price of `token` from the oracle.
+        fn price(&self, token: AccountId) -> Result<Balance> {
+            let result = build_call::<DefaultEnvironment>()
+                .callee(self.oracle)
+                .gas_limit(0)
+                .transferred_value(0)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(PRICE_SELECTOR))
+                        .push_arg(token)
+                        .push_arg(self.max_price_age),
+                )
+                .returns::<ReturnType<core::result::Result<Balance, u8>>>()
+                .fire();
+            match result {
+                Ok(Ok(price)) => Ok(price),
+                _ => Err(Error::PriceUnavailable),
+            }
+        }
+
+        /// Transfers `value` tokens of `token` from this contract to `to` and
+        /// returns whether that succeeded.
+        fn transfer(&self, token: AccountId, to: AccountId, value: Balance) -> bool {
+            let result = build_call::<DefaultEnvironment>()
+                .callee(token)
+                .gas_limit(0)
+                .transferred_value(0)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(TRANSFER_SELECTOR))
+                        .push_arg(to)
+                        .push_arg(value),
+                )
+                .returns::<ReturnType<core::result::Result<(), u8>>>()
+                .fire();
+            matches!(result, Ok(Ok(())))
+        }
+
+        /// Transfers `value` tokens of `token` from `from` to `to` and returns
+        /// whether that succeeded.
+        fn transfer_from(
+            &self,
+            token: AccountId,
+            from: AccountId,
+            to: AccountId,
+            value: Balance,
+        ) -> bool {
+            let result = build_call::<DefaultEnvironment>()
+                .callee(token)
+                .gas_limit(0)
+                .transferred_value(0)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(TRANSFER_FROM_SELECTOR))
+                        .push_arg(from)
+                        .push_arg(to)
+                        .push_arg(value),
+                )
+                .returns::<ReturnType<core::result::Result<(), u8>>>()
+                .fire();
+            matches!(result, Ok(Ok(())))
+        }
+
+        /// Ends the current call with `error`, so the accounts of the market stay
+        /// as they were when moving the tokens fails.
+        fn revert(error: Error) -> ! {
+            ink_env::return_value::<Result<()>>(
+                ReturnFlags::default().set_reverted(true),
+                &Err(error),
+            )
+        }
+    }
+
+    /// Unit tests.
+    ///
+    /// Moving tokens and reading prices are left to the redspot tests. Positions
+    /// are written directly to check the accounting.
+    #[cfg(test)]
+    mod tests {
+        /// Imports all the definitions from the outer scope so we can use them here.
+        use super::*;
+
+        use ink_lang as ink;
+        use test_utils::advance_blocks;
+
+        /// Returns a market with a collateral factor of 75%, a liquidation bonus
+        /// of 5% and an interest of 1% per block.
+        fn market() -> Lending {
+            Lending::new(
+                AccountId::from([0x07; 32]),
+                AccountId::from([0x08; 32]),
+                AccountId::from([0x09; 32]),
+                75,
+                5,
+                PRECISION / 100,
+                60_000,
+            )
+        }
+
+        #[ink::test]
+        fn new_works() {
+            let market = market();
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            assert_eq!(
+                market.tokens(),
+                (AccountId::from([0x07; 32]), AccountId::from([0x08; 32]))
+            );
+            assert_eq!(market.oracle(), AccountId::from([0x09; 32]));
+            assert_eq!(market.risk_parameters(), (75, 5));
+            assert_eq!(market.interest_rate(), PRECISION / 100);
+            assert_eq!(market.borrow_index(), PRECISION);
+            assert_eq!(market.collateral_of(accounts.bob), 0);
+            assert_eq!(market.debt_of(accounts.bob), 0);
+        }
+
+        #[ink::test]
+        #[should_panic(expected = "invalid collateral factor")]
+        fn new_rejects_collateral_factor_above_100() {
+            Lending::new(
+                AccountId::from([0x07; 32]),
+                AccountId::from([0x08; 32]),
+                AccountId::from([0x09; 32]),
+                101,
+                5,
+                0,
+                60_000,
+            );
+        }
+
+        #[ink::test]
+        fn debts_accrue_interest() {
+            let mut market = market();
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            market.positions.insert(
+                accounts.bob,
+                Position {
+                    collateral: 1000,
+                    principal: 100,
+                    index: PRECISION,
+                },
+            );
+
+            advance_blocks!(2);
+            assert_eq!(market.debt_of(accounts.bob), 102);
+            market.accrue();
+            advance_blocks!(1);
+            // The interest of the first two blocks earns interest as well.
+            assert_eq!(market.borrow_index(), PRECISION / 10000 * 10302);
+            assert_eq!(market.debt_of(accounts.bob), 103);
+            assert_eq!(market.collateral_of(accounts.bob), 1000);
+        }
+
+        #[ink::test]
+        fn health_follows_collateral_factor() {
+            let market = market();
+
+            // 100 collateral at a price of 2 allow a debt worth 150.
+            assert_eq!(market.is_healthy(100, 150, (2, 1)), Ok(true));
+            assert_eq!(market.is_healthy(100, 151, (2, 1)), Ok(false));
+            assert_eq!(market.is_healthy(100, 75, (2, 2)), Ok(true));
+            assert_eq!(
+                market.is_healthy(Balance::MAX, 1, (2, 1)),
+                Err(Error::Overflow)
+            );
+        }
+
+        #[ink::test]
+        fn liquidators_get_a_bonus() {
+            let market = market();
+
+            // 100 repaid at a price of 1 are worth 105 with the bonus, which
+            // is 52 collateral at a price of 2 rounded down.
+            assert_eq!(market.seized_collateral(100, (2, 1)), Ok(52));
+            assert_eq!(market.seized_collateral(100, (0, 1)), Err(Error::Overflow));
+        }
+
+        #[ink::test]
+        fn checks_come_before_calls() {
+            let mut market = market();
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            assert_eq!(market.supply(0), Err(Error::ZeroAmount));
+            assert_eq!(market.withdraw(1), Err(Error::InsufficientCollateral));
+            assert_eq!(market.borrow(0), Err(Error::ZeroAmount));
+            assert_eq!(market.repay(10), Ok(0));
+            assert_eq!(
+                market.liquidate(accounts.bob, 10),
+                Err(Error::PositionHealthy)
+            );
+        }
+    }
+}
\ No newline at end of file
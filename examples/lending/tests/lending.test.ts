@@ -0,0 +1,145 @@
+import BN from 'bn.js';
+import { expect } from 'chai';
+import { patract, network } from 'redspot';
+
+const { getContractFactory, getRandomSigner } = patract;
+
+const { api, getSigners } = network;
+
+describe('LENDING', () => {
+  after(() => {
+    return api.disconnect();
+  });
+
+  async function setup() {
+    const one = new BN(10).pow(new BN(api.registry.chainDecimals[0]));
+    const signers = await getSigners();
+    const Alice = signers[0];
+    const sender = await getRandomSigner(Alice, one.muln(10000));
+    const tokenFactory = await getContractFactory('erc20', sender);
+    const collateral = await tokenFactory.deploy(
+      'new',
+      '1000000',
+      'Redspot',
+      'RDS',
+      10
+    );
+    const borrowed = await tokenFactory.deploy(
+      'new',
+      '1000000',
+      'Patract',
+      'PAT',
+      10
+    );
+    const oracleFactory = await getContractFactory('oracle', sender);
+    const oracle = await oracleFactory.deploy('new', [sender.address]);
+    const contractFactory = await getContractFactory('lending', sender);
+    const contract = await contractFactory.deploy(
+      'new',
+      collateral.address,
+      borrowed.address,
+      oracle.address,
+      75,
+      5,
+      0,
+      60000
+    );
+    await collateral.tx.approve(contract.address, 1000);
+    await borrowed.tx.transfer(contract.address, 100000);
+    const borrower = await getRandomSigner(Alice, one.muln(100));
+    await collateral.tx.transfer(borrower.address, 1000);
+    await collateral.tx.approve(contract.address, 1000, { signer: borrower });
+
+    return {
+      sender,
+      borrower,
+      collateral,
+      borrowed,
+      oracle,
+      contractFactory,
+      contract,
+      Alice,
+      one
+    };
+  }
+
+  it('Supplies and withdraws collateral', async () => {
+    const { contract, collateral, sender } = await setup();
+
+    await expect(() => contract.tx.supply(1000)).to.changeTokenBalance(
+      collateral,
+      sender,
+      -1000
+    );
+    expect((await contract.query.collateralOf(sender.address)).output).to.equal(
+      1000
+    );
+
+    await expect(contract.tx.withdraw(1001)).to.not.emit(contract, 'Withdrawn');
+    await expect(() => contract.tx.withdraw(400)).to.changeTokenBalance(
+      collateral,
+      sender,
+      400
+    );
+  });
+
+  it('Does not lend without a price', async () => {
+    const { contract } = await setup();
+
+    await contract.tx.supply(1000);
+
+    await expect(contract.tx.borrow(100)).to.not.emit(contract, 'Borrowed');
+  });
+
+  it('Lends up to the collateral factor', async () => {
+    const { contract, oracle, collateral, borrowed, borrower } = await setup();
+
+    await oracle.tx.setPrices([
+      [collateral.address, 2],
+      [borrowed.address, 1]
+    ]);
+    await contract.tx.supply(1000, { signer: borrower });
+
+    await expect(
+      contract.tx.borrow(1501, { signer: borrower })
+    ).to.not.emit(contract, 'Borrowed');
+    await expect(() =>
+      contract.tx.borrow(1500, { signer: borrower })
+    ).to.changeTokenBalance(borrowed, borrower, 1500);
+    expect((await contract.query.debtOf(borrower.address)).output).to.equal(
+      1500
+    );
+  });
+
+  it('Liquidates undercollateralized positions', async () => {
+    const {
+      contract,
+      oracle,
+      collateral,
+      borrowed,
+      sender,
+      borrower
+    } = await setup();
+
+    await oracle.tx.setPrices([
+      [collateral.address, 2],
+      [borrowed.address, 1]
+    ]);
+    await contract.tx.supply(1000, { signer: borrower });
+    await contract.tx.borrow(1500, { signer: borrower });
+    await borrowed.tx.approve(contract.address, 500);
+
+    await expect(
+      contract.tx.liquidate(borrower.address, 500)
+    ).to.not.emit(contract, 'Liquidated');
+
+    await oracle.tx.setPrice(collateral.address, 1);
+
+    await expect(contract.tx.liquidate(borrower.address, 500))
+      .to.emit(contract, 'Liquidated')
+      .withArgs(borrower.address, sender.address, 500, 525);
+    expect(
+      (await contract.query.collateralOf(borrower.address)).output
+    ).to.equal(475);
+  });
+});
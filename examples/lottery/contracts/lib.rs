@@ -0,0 +1,630 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use ink_lang as ink;
+
+#[ink::contract]
+mod lottery {
+    use erc20::{
+        TRANSFER_FROM_SELECTOR,
+        TRANSFER_SELECTOR,
+    };
+    use ink_env::{
+        call::{
+            build_call,
+            utils::ReturnType,
+            ExecutionInput,
+            Selector,
+        },
+        DefaultEnvironment,
+        ReturnFlags,
+    };
+    use ink_storage::traits::{
+        PackedLayout,
+        SpreadLayout,
+    };
+    #[cfg(not(feature = "ink-as-dependency"))]
+    use ink_storage::{
+        collections::{
+            HashMap as StorageHashMap,
+            Vec as StorageVec,
+        },
+        lazy::Lazy,
+    };
+
+    /// The subject the randomness of the draw is requested for.
+    const RANDOM_SUBJECT: &[u8] = b"lottery";
+
+    /// The tickets bought by an account in one purchase.
+    #[derive(
+        Debug,
+        Copy,
+        Clone,
+        PartialEq,
+        Eq,
+        scale::Encode,
+        scale::Decode,
+        SpreadLayout,
+        PackedLayout,
+    )]
+    #[cfg_attr(
+        feature = "std",
+        derive(::scale_info::TypeInfo, ::ink_storage::traits::StorageLayout)
+    )]
+    pub struct Entry {
+        /// The account holding the tickets.
+        pub account: AccountId,
+        /// The number of tickets sold up to and including this entry. The entry
+        /// holds the tickets from the end of the previous entry up to this one.
+        pub end: u32,
+    }
+
+    /// Sells tickets for an erc20 token and pays all of them to the holder of
+    /// a randomly drawn ticket.
+    ///
+    /// Tickets are sold until the `end` block. The winner is drawn from the
+    /// on-chain randomness of the chain, which is derived from recent block
+    /// hashes, so the draw waits `draw_delay` more blocks. By then the
+    /// randomness stems from blocks sealed after the last ticket was sold, so
+    /// no entrant knows it when entering.
+    ///
+    /// The randomness changes with every block, so the winner can only be
+    /// drawn in the single draw block. Otherwise anyone could dry-run the draw
+    /// block after block and draw once it favours them. If the draw block
+    /// passes without a draw, the entrants get the price of their tickets
+    /// back with `refund`. The author of the draw block may still leave the
+    /// draw out of it, which is fine for an example but not for real prizes.
+    ///
+    /// Buying tickets transfers the tokens with `transfer_from`, so entrants
+    /// approve the lottery to spend them first.
+    #[ink(storage)]
+    pub struct Lottery {
+        /// The erc20 token tickets are paid in.
+        token: AccountId,
+        /// The tokens one ticket costs.
+        ticket_price: Balance,
+        /// The last block tickets are sold in.
+        end: BlockNumber,
+        /// The blocks to wait after `end` before the draw.
+        draw_delay: BlockNumber,
+        /// The purchases of tickets in the order they were made.
+        entries: StorageVec<Entry>,
+        /// Mapping from account to the number of tickets it holds.
+        tickets: StorageHashMap<AccountId, u32>,
+        /// Whether the winner was drawn.
+        drawn: Lazy<bool>,
+        /// The holder of the drawn ticket.
+        winner: Lazy<Option<AccountId>>,
+    }
+
+    /// Event emitted when tickets are bought.
+    #[ink(event)]
+    pub struct Entered {
+        #[ink(topic)]
+        account: AccountId,
+        tickets: u32,
+    }
+
+    /// Event emitted when the winner is drawn, which is `None` if no ticket
+    /// was sold.
+    #[ink(event)]
+    pub struct Drawn {
+        winner: Option<AccountId>,
+        ticket: u32,
+        prize: Balance,
+    }
+
+    /// The lottery error types.
+    #[derive(Debug, PartialEq, Eq, scale::Encode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum Error {
+        /// Returned if tickets are bought after the end block or the draw.
+        EntriesClosed,
+        /// Returned if zero tickets are bought.
+        ZeroTickets,
+        /// Returned if the draw delay did not pass yet.
+        DrawNotOpen,
+        /// Returned if the draw block passed without a draw.
+        DrawMissed,
+        /// Returned if tickets are refunded although the draw was not missed.
+        DrawNotMissed,
+        /// Returned if the caller holds no tickets to refund.
+        NoTickets,
+        /// Returned if the winner was drawn already.
+        AlreadyDrawn,
+        /// Returned if the number or the price of the tickets overflows.
+        Overflow,
+        /// Returned if the tokens could not be transferred.
+        TransferFailed,
+    }
+
+    /// The lottery result type.
+    pub type Result<T> = core::result::Result<T, Error>;
+
+    impl Lottery {
+        /// Creates a lottery selling tickets for `ticket_price` of `token` for
+        /// `duration` blocks, which draws its winner `draw_delay` blocks after.
+        ///
+        /// # Panics
+        ///
+        /// If `ticket_price` or `draw_delay` is zero.
+        #[ink(constructor)]
+        pub fn new(
+            token: AccountId,
+            ticket_price: Balance,
+            duration: BlockNumber,
+            draw_delay: BlockNumber,
+        ) -> Self {
+            assert!(ticket_price > 0, "invalid ticket price");
+            assert!(draw_delay > 0, "invalid draw delay");
+            Self {
+                token,
+                ticket_price,
+                end: Self::env().block_number() + duration,
+                draw_delay,
+                entries: StorageVec::new(),
+                tickets: StorageHashMap::new(),
+                drawn: Lazy::new(false),
+                winner: Lazy::new(None),
+            }
+        }
+
+        /// Returns the erc20 token tickets are paid in.
+        #[ink(message)]
+        pub fn token(&self) -> AccountId {
+            self.token
+        }
+
+        /// Returns the tokens one ticket costs.
+        #[ink(message)]
+        pub fn ticket_price(&self) -> Balance {
+            self.ticket_price
+        }
+
+        /// Returns the last block tickets are sold in.
+        #[ink(message)]
+        pub fn end(&self) -> BlockNumber {
+            self.end
+        }
+
+        /// Returns the block the winner has to be drawn in.
+        #[ink(message)]
+        pub fn draw_block(&self) -> BlockNumber {
+            self.end + self.draw_delay + 1
+        }
+
+        /// Returns the number of tickets sold.
+        #[ink(message)]
+        pub fn total_tickets(&self) -> u32 {
+            self.entries.last().map_or(0, |entry| entry.end)
+        }
+
+        /// Returns the number of tickets `account` holds.
+        #[ink(message)]
+        pub fn tickets_of(&self, account: AccountId) -> u32 {
+            self.tickets.get(&account).copied().unwrap_or(0)
+        }
+
+        /// Returns whether the winner was drawn.
+        #[ink(message)]
+        pub fn is_drawn(&self) -> bool {
+            *self.drawn
+        }
+
+        /// Returns the holder of the drawn ticket, or `None` if the winner was
+        /// not drawn or no ticket was sold.
+        #[ink(message)]
+        pub fn winner(&self) -> Option<AccountId> {
+            *self.winner
+        }
+
+        /// Buys `tickets` tickets for the caller.
+        ///
+        /// The caller has to approve the lottery to spend the price of the
+        /// tickets first. On success an `Entered` event is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `EntriesClosed` error if the end block passed.
+        ///
+        /// Returns `ZeroTickets` error if `tickets` is zero.
+        ///
+        /// Returns `Overflow` error if the number or the price of the tickets
+        /// overflows.
+        ///
+        /// Returns `TransferFailed` error, reverting all changes, if the tokens
+        /// could not be transferred.
+        #[ink(message)]
+        pub fn enter(&mut self, tickets: u32) -> Result<()> {
+            let account = self.env().caller();
+            let price = self.add_entry(account, tickets)?;
+            let this = self.env().account_id();
+            if !self.transfer_from(account, this, price) {
+                Self::revert(Error::TransferFailed)
+            }
+            Ok(())
+        }
+
+        /// Draws the winner and pays all tickets sold to it.
+        ///
+        /// Anyone may draw the winner. On success a `Drawn` event is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `AlreadyDrawn` error if the winner was drawn.
+        ///
+        /// Returns `DrawNotOpen` error if the draw block was not reached.
+        ///
+        /// Returns `DrawMissed` error if the draw block passed.
+        ///
+        /// Returns `TransferFailed` error, reverting all changes, if the prize
+        /// could not be transferred.
+        #[ink(message)]
+        pub fn draw(&mut self) -> Result<Option<AccountId>> {
+            if *self.drawn {
+                return Err(Error::AlreadyDrawn)
+            }
+            let block = self.env().block_number();
+            if block < self.draw_block() {
+                return Err(Error::DrawNotOpen)
+            }
+            if block > self.draw_block() {
+                return Err(Error::DrawMissed)
+            }
+            let seed = self.env().random(RANDOM_SUBJECT);
+            let winner = self.settle(seed)?;
+            if let Some((winner, prize)) = winner {
+                if !self.transfer(winner, prize) {
+                    Self::revert(Error::TransferFailed)
+                }
+            }
+            Ok(winner.map(|(winner, _)| winner))
+        }
+
+        /// Pays the price of the tickets the caller holds back to it, which is
+        /// only possible if the draw block passed without a draw.
+        ///
+        /// # Errors
+        ///
+        /// Returns `DrawNotMissed` error if the winner was drawn or the draw
+        /// block did not pass yet.
+        ///
+        /// Returns `NoTickets` error if the caller holds no tickets.
+        ///
+        /// Returns `TransferFailed` error, reverting all changes, if the tokens
+        /// could not be transferred.
+        #[ink(message)]
+        pub fn refund(&mut self) -> Result<()> {
+            let account = self.env().caller();
+            let refund = self.remove_tickets(account)?;
+            if !self.transfer(account, refund) {
+                Self::revert(Error::TransferFailed)
+            }
+            Ok(())
+        }
+
+        /// Records the purchase of `tickets` tickets by `account` and returns
+        /// their price.
+        fn add_entry(&mut self, account: AccountId, tickets: u32) -> Result<Balance> {
+            if *self.drawn || self.env().block_number() > self.end {
+                return Err(Error::EntriesClosed)
+            }
+            if tickets == 0 {
+                return Err(Error::ZeroTickets)
+            }
+            let end = self
+                .total_tickets()
+                .checked_add(tickets)
+                .ok_or(Error::Overflow)?;
+            let price = self
+                .ticket_price
+                .checked_mul(Balance::from(tickets))
+                .ok_or(Error::Overflow)?;
+            self.entries.push(Entry { account, end });
+            let held = self.tickets_of(account);
+            self.tickets.insert(account, held + tickets);
+            self.env().emit_event(Entered { account, tickets });
+            Ok(price)
+        }
+
+        /// Removes the tickets `account` holds for a refund and returns their
+        /// price.
+        fn remove_tickets(&mut self, account: AccountId) -> Result<Balance> {
+            if *self.drawn || self.env().block_number() <= self.draw_block() {
+                return Err(Error::DrawNotMissed)
+            }
+            let tickets = self.tickets.take(&account).unwrap_or(0);
+            if tickets == 0 {
+                return Err(Error::NoTickets)
+            }
+            // The price was checked for overflows when the tickets were bought.
+            Ok(self.ticket_price * Balance::from(tickets))
+        }
+
+        /// Draws the winning ticket from `seed` and returns its holder and the
+        /// prize, or `None` if no ticket was sold.
+        fn settle(&mut self, seed: Hash) -> Result<Option<(AccountId, Balance)>> {
+            *self.drawn = true;
+            let total = self.total_tickets();
+            if total == 0 {
+                self.env().emit_event(Drawn {
+                    winner: None,
+                    ticket: 0,
+                    prize: 0,
+                });
+                return Ok(None)
+            }
+            let ticket = winning_ticket(seed, total);
+            let winner = self.holder_of(ticket);
+            let prize = self
+                .ticket_price
+                .checked_mul(Balance::from(total))
+                .ok_or(Error::Overflow)?;
+            *self.winner = Some(winner);
+            self.env().emit_event(Drawn {
+                winner: Some(winner),
+                ticket,
+                prize,
+            });
+            Ok(Some((winner, prize)))
+        }
+
+        /// Returns the holder of `ticket`, which has to be below the number of
+        /// tickets sold.
+        fn holder_of(&self, ticket: u32) -> AccountId {
+            // The ends of the entries grow with every entry, so the holder is
+            // found by a binary search for the first entry ending above it.
+            let (mut low, mut high) = (0, self.entries.len() - 1);
+            while low < high {
+                let middle = low + (high - low) / 2;
+                if self.entries[middle].end > ticket {
+                    high = middle;
+                } else {
+                    low = middle + 1;
+                }
+            }
+            self.entries[low].account
+        }
+
+        /// Transfers `value` tokens from the lottery to `to` and returns whether
+        /// that succeeded.
+        fn transfer(&self, to: AccountId, value: Balance) -> bool {
+            let result = build_call::<DefaultEnvironment>()
+                .callee(self.token)
+                .gas_limit(0)
+                .transferred_value(0)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(TRANSFER_SELECTOR))
+                        .push_arg(to)
+                        .push_arg(value),
+                )
+                .returns::<ReturnType<core::result::Result<(), u8>>>()
+                .fire();
+            matches!(result, Ok(Ok(())))
+        }
+
+        /// Transfers `value` tokens from `from` to `to` on behalf of the lottery
+        /// and returns whether that succeeded.
+        fn transfer_from(&self, from: AccountId, to: AccountId, value: Balance) -> bool {
+            let result = build_call::<DefaultEnvironment>()
+                .callee(self.token)
+                .gas_limit(0)
+                .transferred_value(0)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(TRANSFER_FROM_SELECTOR))
+                        .push_arg(from)
+                        .push_arg(to)
+                        .push_arg(value),
+                )
+                .returns::<ReturnType<core::result::Result<(), u8>>>()
+                .fire();
+            matches!(result, Ok(Ok(())))
+        }
+
+        /// Ends the current call with `error`, so no tickets are sold or paid out
+        /// when moving the tokens fails.
+        fn revert(error: Error) -> ! {
+            ink_env::return_value::<Result<()>>(
+                ReturnFlags::default().set_reverted(true),
+                &Err(error),
+            )
+        }
+    }
+
+    /// Returns the ticket `seed` draws out of `total` tickets.
+    ///
+    /// The first 16 bytes of `seed` are taken as a little endian number, so a
+    /// seed is as likely to draw any ticket as the next, give or take the
+    /// bias of the modulo, which is negligible for a `u32` number of tickets.
+    fn winning_ticket(seed: Hash, total: u32) -> u32 {
+        let mut bytes = [0x00; 16];
+        bytes.copy_from_slice(&seed.as_ref()[..16]);
+        (u128::from_le_bytes(bytes) % u128::from(total)) as u32
+    }
+
+    /// Unit tests.
+    ///
+    /// Paying for tickets and prizes is left to the redspot tests. The draw gets
+    /// fixed seeds instead of the randomness of the chain.
+    #[cfg(test)]
+    mod tests {
+        /// Imports all the definitions from the outer scope so we can use them here.
+        use super::*;
+
+        use ink_lang as ink;
+        use test_utils::advance_blocks;
+
+        /// Returns a seed which draws the ticket `value` modulo the number of
+        /// tickets sold.
+        fn seed(value: u128) -> Hash {
+            let mut bytes = [0x00; 32];
+            bytes[..16].copy_from_slice(&value.to_le_bytes());
+            Hash::from(bytes)
+        }
+
+        /// Returns a lottery selling tickets at `10` for two blocks and
+        /// drawing one block after.
+        fn lottery() -> Lottery {
+            Lottery::new(AccountId::from([0x07; 32]), 10, 2, 1)
+        }
+
+        #[ink::test]
+        fn new_works() {
+            let lottery = lottery();
+
+            assert_eq!(lottery.token(), AccountId::from([0x07; 32]));
+            assert_eq!(lottery.ticket_price(), 10);
+            assert_eq!(lottery.end(), 2);
+            assert_eq!(lottery.draw_block(), 4);
+            assert_eq!(lottery.total_tickets(), 0);
+            assert!(!lottery.is_drawn());
+            assert_eq!(lottery.winner(), None);
+        }
+
+        #[ink::test]
+        #[should_panic(expected = "invalid draw delay")]
+        fn new_rejects_zero_draw_delay() {
+            Lottery::new(AccountId::from([0x07; 32]), 10, 2, 0);
+        }
+
+        #[ink::test]
+        fn entries_are_recorded() {
+            let mut lottery = lottery();
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            assert_eq!(lottery.add_entry(accounts.bob, 0), Err(Error::ZeroTickets));
+            assert_eq!(lottery.add_entry(accounts.bob, 2), Ok(20));
+            assert_eq!(lottery.add_entry(accounts.charlie, 3), Ok(30));
+            assert_eq!(lottery.add_entry(accounts.bob, 1), Ok(10));
+            assert_eq!(lottery.tickets_of(accounts.bob), 3);
+            assert_eq!(lottery.tickets_of(accounts.charlie), 3);
+            assert_eq!(lottery.total_tickets(), 6);
+            assert_eq!(
+                lottery.add_entry(accounts.bob, u32::MAX),
+                Err(Error::Overflow)
+            );
+            assert_eq!(ink_env::test::recorded_events().count(), 3);
+
+            advance_blocks!(3);
+            assert_eq!(
+                lottery.add_entry(accounts.bob, 1),
+                Err(Error::EntriesClosed)
+            );
+        }
+
+        #[ink::test]
+        fn seeds_draw_the_holder_of_the_ticket() {
+            let mut lottery = lottery();
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            lottery.add_entry(accounts.bob, 2).expect("Cannot enter");
+            lottery
+                .add_entry(accounts.charlie, 3)
+                .expect("Cannot enter");
+            lottery.add_entry(accounts.django, 1).expect("Cannot enter");
+
+            let holders = [
+                accounts.bob,
+                accounts.bob,
+                accounts.charlie,
+                accounts.charlie,
+                accounts.charlie,
+                accounts.django,
+            ];
+            for (ticket, holder) in holders.iter().enumerate() {
+                assert_eq!(winning_ticket(seed(ticket as u128), 6), ticket as u32);
+                assert_eq!(lottery.holder_of(ticket as u32), *holder);
+            }
+            assert_eq!(winning_ticket(seed(8), 6), 2);
+        }
+
+        #[ink::test]
+        fn settle_pays_all_tickets_to_the_winner() {
+            let mut lottery = lottery();
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            lottery.add_entry(accounts.bob, 2).expect("Cannot enter");
+            lottery
+                .add_entry(accounts.charlie, 3)
+                .expect("Cannot enter");
+            advance_blocks!(2);
+            assert_eq!(lottery.draw(), Err(Error::DrawNotOpen));
+
+            assert_eq!(lottery.settle(seed(4)), Ok(Some((accounts.charlie, 50))));
+            assert!(lottery.is_drawn());
+            assert_eq!(lottery.winner(), Some(accounts.charlie));
+            assert_eq!(lottery.draw(), Err(Error::AlreadyDrawn));
+            assert_eq!(
+                lottery.add_entry(accounts.bob, 1),
+                Err(Error::EntriesClosed)
+            );
+        }
+
+        #[ink::test]
+        fn draw_without_tickets_has_no_winner() {
+            let mut lottery = lottery();
+
+            advance_blocks!(4);
+            assert_eq!(lottery.draw(), Ok(None));
+            assert!(lottery.is_drawn());
+            assert_eq!(lottery.winner(), None);
+        }
+
+        #[ink::test]
+        fn draw_is_only_open_in_the_draw_block() {
+            let mut lottery = lottery();
+
+            advance_blocks!(3);
+            assert_eq!(lottery.draw(), Err(Error::DrawNotOpen));
+            advance_blocks!(2);
+            assert_eq!(lottery.draw(), Err(Error::DrawMissed));
+            assert!(!lottery.is_drawn());
+        }
+
+        #[ink::test]
+        fn missed_draws_refund_the_tickets() {
+            let mut lottery = lottery();
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            lottery.add_entry(accounts.bob, 2).expect("Cannot enter");
+            lottery.add_entry(accounts.bob, 1).expect("Cannot enter");
+            advance_blocks!(4);
+            assert_eq!(
+                lottery.remove_tickets(accounts.bob),
+                Err(Error::DrawNotMissed)
+            );
+
+            advance_blocks!(1);
+            assert_eq!(lottery.remove_tickets(accounts.bob), Ok(30));
+            assert_eq!(lottery.tickets_of(accounts.bob), 0);
+            assert_eq!(lottery.remove_tickets(accounts.bob), Err(Error::NoTickets));
+            assert_eq!(
+                lottery.remove_tickets(accounts.charlie),
+                Err(Error::NoTickets)
+            );
+        }
+
+        #[ink::test]
+        fn drawn_lotteries_refund_nothing() {
+            let mut lottery = lottery();
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            lottery.add_entry(accounts.bob, 2).expect("Cannot enter");
+            lottery.settle(seed(0)).expect("Cannot settle");
+            advance_blocks!(5);
+            assert_eq!(
+                lottery.remove_tickets(accounts.bob),
+                Err(Error::DrawNotMissed)
+            );
+        }
+    }
+}
\ No newline at end of file
@@ -0,0 +1,132 @@
+import BN from 'bn.js';
+import { expect } from 'chai';
+import { patract, network } from 'redspot';
+
+const { getContractFactory, getRandomSigner } = patract;
+
+const { api, getSigners } = network;
+
+describe('LOTTERY', () => {
+  after(() => {
+    return api.disconnect();
+  });
+
+  async function setup() {
+    const one = new BN(10).pow(new BN(api.registry.chainDecimals[0]));
+    const signers = await getSigners();
+    const Alice = signers[0];
+    const sender = await getRandomSigner(Alice, one.muln(10000));
+    const entrant = await getRandomSigner(Alice, one.muln(10000));
+    const tokenFactory = await getContractFactory('erc20', sender);
+    const token = await tokenFactory.deploy(
+      'new',
+      '1000000',
+      'Redspot',
+      'RDS',
+      10
+    );
+    const contractFactory = await getContractFactory('lottery', sender);
+    const contract = await contractFactory.deploy(
+      'new',
+      token.address,
+      100,
+      5,
+      2
+    );
+    await token.tx.transfer(entrant.address, 1000);
+    await token.tx.approve(contract.address, 1000);
+    await token.tx.approve(contract.address, 1000, { signer: entrant });
+
+    // The development node seals a block for every transaction, so blocks pass
+    // by sending transactions which change nothing.
+    async function advanceBlocks(blocks: number) {
+      for (let i = 0; i < blocks; i++) {
+        await token.tx.approve(contract.address, 1000);
+      }
+    }
+
+    // The winner can only be drawn in the draw block, so this stops one block
+    // before it and the next transaction lands in it.
+    async function advanceToDrawBlock() {
+      const drawBlock = (await contract.query.drawBlock()).output as any;
+      const block = (await api.query.system.number()) as any;
+      await advanceBlocks(drawBlock.toNumber() - block.toNumber() - 1);
+    }
+
+    return {
+      sender,
+      entrant,
+      token,
+      contractFactory,
+      contract,
+      advanceBlocks,
+      advanceToDrawBlock,
+      Alice,
+      one
+    };
+  }
+
+  it('Sells tickets', async () => {
+    const { contract, token, entrant } = await setup();
+
+    await expect(() =>
+      contract.tx.enter(3, { signer: entrant })
+    ).to.changeTokenBalance(token, entrant, -300);
+    await expect(contract.tx.enter(11, { signer: entrant })).to.not.emit(
+      contract,
+      'Entered'
+    );
+    expect((await contract.query.ticketsOf(entrant.address)).output).to.equal(
+      3
+    );
+  });
+
+  it('Pays all tickets to the winner', async () => {
+    const {
+      contract,
+      token,
+      sender,
+      entrant,
+      advanceToDrawBlock
+    } = await setup();
+
+    await contract.tx.enter(2);
+    await contract.tx.enter(2, { signer: entrant });
+    await expect(contract.tx.draw()).to.not.emit(contract, 'Drawn');
+
+    await expect(contract.tx.enter(1)).to.not.emit(contract, 'Entered');
+    await advanceToDrawBlock();
+    await expect(contract.tx.draw()).to.emit(contract, 'Drawn');
+
+    const winner = (await contract.query.winner()).output?.toString();
+    expect([sender.address, entrant.address]).to.include(winner);
+    expect((await token.query.balanceOf(contract.address)).output).to.equal(0);
+  });
+
+  it('Refunds the tickets if the draw block is missed', async () => {
+    const {
+      contract,
+      token,
+      entrant,
+      advanceBlocks,
+      advanceToDrawBlock
+    } = await setup();
+
+    await contract.tx.enter(3, { signer: entrant });
+    await expect(contract.tx.refund({ signer: entrant })).to.not.emit(
+      token,
+      'Transfer'
+    );
+
+    await advanceToDrawBlock();
+    await advanceBlocks(1);
+
+    await expect(contract.tx.draw()).to.not.emit(contract, 'Drawn');
+    await expect(() =>
+      contract.tx.refund({ signer: entrant })
+    ).to.changeTokenBalance(token, entrant, 300);
+    expect((await contract.query.ticketsOf(entrant.address)).output).to.equal(
+      0
+    );
+  });
+});
@@ -0,0 +1,332 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use ink_lang as ink;
+
+#[ink::contract]
+mod merkle_airdrop {
+    use erc20::TRANSFER_SELECTOR;
+    use ink_env::{
+        call::{
+            build_call,
+            utils::ReturnType,
+            ExecutionInput,
+            Selector,
+        },
+        hash::Blake2x256,
+        DefaultEnvironment,
+        ReturnFlags,
+    };
+    use ink_prelude::vec::Vec;
+    #[cfg(not(feature = "ink-as-dependency"))]
+    use ink_storage::collections::HashMap as StorageHashMap;
+
+    /// Releases erc20 tokens to the accounts listed in a Merkle tree.
+    ///
+    /// Every allocation is a leaf of the tree, the Blake2x256 hash of the
+    /// SCALE encoded `(index, account, amount)`. Inner nodes hash the
+    /// concatenation of their two children, the smaller one first, so a
+    /// proof is just the list of siblings from the leaf up to the root.
+    /// `scripts/merkle.ts` builds the tree and its proofs.
+    ///
+    /// The contract only stores the root, so the list of allocations can be
+    /// of any size. Claimed allocations are marked in a bitmap of 128
+    /// indices per storage cell.
+    ///
+    /// The contract has to hold the tokens it releases, so they are
+    /// transferred to it after instantiation.
+    #[ink(storage)]
+    pub struct MerkleAirdrop {
+        /// The erc20 token released.
+        token: AccountId,
+        /// The root of the Merkle tree of the allocations.
+        merkle_root: Hash,
+        /// Mapping from the index of a word to the bits of the claimed
+        /// allocations in it.
+        claimed: StorageHashMap<u32, u128>,
+    }
+
+    /// Event emitted when an allocation is claimed.
+    #[ink(event)]
+    pub struct Claimed {
+        index: u32,
+        #[ink(topic)]
+        account: AccountId,
+        amount: Balance,
+    }
+
+    /// The Merkle airdrop error types.
+    #[derive(Debug, PartialEq, Eq, scale::Encode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum Error {
+        /// Returned if the allocation was claimed already.
+        AlreadyClaimed,
+        /// Returned if the proof does not lead from the allocation to the root.
+        InvalidProof,
+        /// Returned if the tokens could not be transferred.
+        TransferFailed,
+    }
+
+    /// The Merkle airdrop result type.
+    pub type Result<T> = core::result::Result<T, Error>;
+
+    impl MerkleAirdrop {
+        /// Creates an airdrop of `token` to the allocations of the Merkle tree
+        /// with the root `merkle_root`.
+        #[ink(constructor)]
+        pub fn new(token: AccountId, merkle_root: Hash) -> Self {
+            Self {
+                token,
+                merkle_root,
+                claimed: StorageHashMap::new(),
+            }
+        }
+
+        /// Returns the erc20 token released.
+        #[ink(message)]
+        pub fn token(&self) -> AccountId {
+            self.token
+        }
+
+        /// Returns the root of the Merkle tree of the allocations.
+        #[ink(message)]
+        pub fn merkle_root(&self) -> Hash {
+            self.merkle_root
+        }
+
+        /// Returns `true` if the allocation at `index` was claimed.
+        #[ink(message)]
+        pub fn is_claimed(&self, index: u32) -> bool {
+            let (word, bit) = Self::bit_position(index);
+            (self.claimed.get(&word).copied().unwrap_or(0) & bit) != 0
+        }
+
+        /// Sends `amount` tokens to `account`, which the allocation at `index`
+        /// with the Merkle `proof` grants it.
+        ///
+        /// Anyone may claim an allocation, the tokens are sent to `account`
+        /// either way. On success a `Claimed` event is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `AlreadyClaimed` error if the allocation was claimed.
+        ///
+        /// Returns `InvalidProof` error if `proof` does not lead from the
+        /// allocation to the root.
+        ///
+        /// Returns `TransferFailed` error, reverting all changes, if the tokens
+        /// could not be transferred.
+        #[ink(message)]
+        pub fn claim(
+            &mut self,
+            index: u32,
+            account: AccountId,
+            amount: Balance,
+            proof: Vec<Hash>,
+        ) -> Result<()> {
+            self.record_claim(index, account, amount, &proof)?;
+            if !self.transfer(account, amount) {
+                Self::revert(Error::TransferFailed)
+            }
+            Ok(())
+        }
+
+        /// Checks the allocation at `index` against the root and marks it as
+        /// claimed.
+        fn record_claim(
+            &mut self,
+            index: u32,
+            account: AccountId,
+            amount: Balance,
+            proof: &[Hash],
+        ) -> Result<()> {
+            if self.is_claimed(index) {
+                return Err(Error::AlreadyClaimed)
+            }
+            if !verify(proof, self.merkle_root, leaf_hash(index, account, amount)) {
+                return Err(Error::InvalidProof)
+            }
+            let (word, bit) = Self::bit_position(index);
+            let bits = self.claimed.get(&word).copied().unwrap_or(0);
+            self.claimed.insert(word, bits | bit);
+            self.env().emit_event(Claimed {
+                index,
+                account,
+                amount,
+            });
+            Ok(())
+        }
+
+        /// Returns the word of the bitmap `index` is kept in and its bit there.
+        fn bit_position(index: u32) -> (u32, u128) {
+            (index / 128, 1 << (index % 128))
+        }
+
+        /// Transfers `value` tokens from the airdrop to `to` and returns whether
+        /// that succeeded.
+        fn transfer(&self, to: AccountId, value: Balance) -> bool {
+            let result = build_call::<DefaultEnvironment>()
+                .callee(self.token)
+                .gas_limit(0)
+                .transferred_value(0)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(TRANSFER_SELECTOR))
+                        .push_arg(to)
+                        .push_arg(value),
+                )
+                .returns::<ReturnType<core::result::Result<(), u8>>>()
+                .fire();
+            matches!(result, Ok(Ok(())))
+        }
+
+        /// Ends the current call with `error`, so a claim is not marked as claimed
+        /// when paying it out fails.
+        fn revert(error: Error) -> ! {
+            ink_env::return_value::<Result<()>>(
+                ReturnFlags::default().set_reverted(true),
+                &Err(error),
+            )
+        }
+    }
+
+    /// Returns the leaf of the allocation of `amount` tokens to `account` at
+    /// `index`.
+    fn leaf_hash(index: u32, account: AccountId, amount: Balance) -> Hash {
+        let mut output = [0x00; 32];
+        ink_env::hash_encoded::<Blake2x256, _>(&(index, account, amount), &mut output);
+        Hash::from(output)
+    }
+
+    /// Returns the parent node of the nodes `a` and `b`.
+    fn hash_pair(a: Hash, b: Hash) -> Hash {
+        let (first, second) = if a.as_ref() <= b.as_ref() {
+            (a, b)
+        } else {
+            (b, a)
+        };
+        let mut input = [0x00; 64];
+        input[..32].copy_from_slice(first.as_ref());
+        input[32..].copy_from_slice(second.as_ref());
+        let mut output = [0x00; 32];
+        ink_env::hash_bytes::<Blake2x256>(&input, &mut output);
+        Hash::from(output)
+    }
+
+    /// Returns `true` if hashing `leaf` with the siblings in `proof` leads to
+    /// `root`.
+    fn verify(proof: &[Hash], root: Hash, leaf: Hash) -> bool {
+        proof
+            .iter()
+            .fold(leaf, |node, sibling| hash_pair(node, *sibling))
+            == root
+    }
+
+    /// Unit tests.
+    ///
+    /// Paying out claims is left to the redspot tests.
+    #[cfg(test)]
+    mod tests {
+        /// Imports all the definitions from the outer scope so we can use them here.
+        use super::*;
+
+        use ink_lang as ink;
+
+        /// Returns the leaves of four allocations and the root of their tree.
+        fn tree() -> ([Hash; 4], Hash) {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let leaves = [
+                leaf_hash(0, accounts.alice, 100),
+                leaf_hash(1, accounts.bob, 200),
+                leaf_hash(2, accounts.charlie, 300),
+                leaf_hash(200, accounts.django, 400),
+            ];
+            let root = hash_pair(
+                hash_pair(leaves[0], leaves[1]),
+                hash_pair(leaves[2], leaves[3]),
+            );
+            (leaves, root)
+        }
+
+        #[ink::test]
+        fn new_works() {
+            let (_, root) = tree();
+            let airdrop = MerkleAirdrop::new(AccountId::from([0x07; 32]), root);
+
+            assert_eq!(airdrop.token(), AccountId::from([0x07; 32]));
+            assert_eq!(airdrop.merkle_root(), root);
+            assert!(!airdrop.is_claimed(0));
+        }
+
+        #[ink::test]
+        fn hash_pair_is_symmetric() {
+            let (leaves, _) = tree();
+
+            assert_eq!(
+                hash_pair(leaves[0], leaves[1]),
+                hash_pair(leaves[1], leaves[0])
+            );
+            assert_ne!(
+                hash_pair(leaves[0], leaves[1]),
+                hash_pair(leaves[0], leaves[2])
+            );
+        }
+
+        #[ink::test]
+        fn valid_proofs_are_claimed_once() {
+            let (leaves, root) = tree();
+            let mut airdrop = MerkleAirdrop::new(AccountId::from([0x07; 32]), root);
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let bob_proof = [leaves[0], hash_pair(leaves[2], leaves[3])];
+            let django_proof = [leaves[2], hash_pair(leaves[0], leaves[1])];
+
+            assert_eq!(
+                airdrop.record_claim(1, accounts.bob, 200, &bob_proof),
+                Ok(())
+            );
+            assert!(airdrop.is_claimed(1));
+            assert_eq!(
+                airdrop.record_claim(1, accounts.bob, 200, &bob_proof),
+                Err(Error::AlreadyClaimed)
+            );
+            assert_eq!(
+                airdrop.record_claim(200, accounts.django, 400, &django_proof),
+                Ok(())
+            );
+            assert!(airdrop.is_claimed(200));
+            assert!(!airdrop.is_claimed(0));
+            assert!(!airdrop.is_claimed(72));
+            assert_eq!(ink_env::test::recorded_events().count(), 2);
+        }
+
+        #[ink::test]
+        fn invalid_proofs_are_rejected() {
+            let (leaves, root) = tree();
+            let mut airdrop = MerkleAirdrop::new(AccountId::from([0x07; 32]), root);
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let bob_proof = [leaves[0], hash_pair(leaves[2], leaves[3])];
+
+            assert_eq!(
+                airdrop.record_claim(1, accounts.bob, 201, &bob_proof),
+                Err(Error::InvalidProof)
+            );
+            assert_eq!(
+                airdrop.record_claim(1, accounts.charlie, 200, &bob_proof),
+                Err(Error::InvalidProof)
+            );
+            assert_eq!(
+                airdrop.record_claim(0, accounts.bob, 200, &bob_proof),
+                Err(Error::InvalidProof)
+            );
+            assert_eq!(
+                airdrop.record_claim(1, accounts.bob, 200, &bob_proof[..1]),
+                Err(Error::InvalidProof)
+            );
+            assert!(!airdrop.is_claimed(1));
+        }
+    }
+}
\ No newline at end of file
@@ -0,0 +1,87 @@
+import type { ApiPromise } from '@polkadot/api';
+import type BN from 'bn.js';
+import { u8aConcat, u8aToHex } from '@polkadot/util';
+import { blake2AsU8a } from '@polkadot/util-crypto';
+
+/**
+ * An allocation of `amount` tokens to `account` at `index` of the airdrop.
+ */
+export interface Allocation {
+  index: number;
+  account: string;
+  amount: number | string | BN;
+}
+
+/**
+ * Returns the leaf of `allocation`, the hash of its SCALE encoded
+ * `(index, account, amount)`.
+ *
+ * @param api Api of the chain the airdrop lives on
+ * @param allocation Allocation to hash
+ * @returns Leaf of the allocation
+ */
+export function leafHash(api: ApiPromise, allocation: Allocation) {
+  const { index, account, amount } = allocation;
+  const encoded = api
+    .createType('(u32, AccountId, Balance)', [index, account, amount])
+    .toU8a();
+
+  return blake2AsU8a(encoded, 256);
+}
+
+/**
+ * Returns the parent node of the nodes `a` and `b`, the hash of both
+ * concatenated with the smaller one first.
+ *
+ * @param a Node of the tree
+ * @param b Sibling of `a`
+ * @returns Parent node
+ */
+export function hashPair(a: Uint8Array, b: Uint8Array) {
+  const [first, second] = u8aToHex(a) <= u8aToHex(b) ? [a, b] : [b, a];
+
+  return blake2AsU8a(u8aConcat(first, second), 256);
+}
+
+/**
+ * Builds the Merkle tree of `allocations` as the airdrop contract expects it.
+ *
+ * A node without a sibling is moved up to the next level unchanged.
+ *
+ * @param api Api of the chain the airdrop lives on
+ * @param allocations Allocations of the airdrop
+ * @returns Root of the tree and the proof of every allocation, in order
+ */
+export function merkleTree(api: ApiPromise, allocations: Allocation[]) {
+  if (allocations.length === 0) {
+    throw new Error('An airdrop needs at least one allocation');
+  }
+
+  let level = allocations.map((allocation) => leafHash(api, allocation));
+  const positions = allocations.map((_, index) => index);
+  const proofs: string[][] = allocations.map(() => []);
+
+  while (level.length > 1) {
+    positions.forEach((position, leaf) => {
+      const sibling = position ^ 1;
+
+      if (sibling < level.length) {
+        proofs[leaf].push(u8aToHex(level[sibling]));
+      }
+
+      positions[leaf] = position >> 1;
+    });
+
+    const next: Uint8Array[] = [];
+
+    for (let i = 0; i < level.length; i += 2) {
+      next.push(
+        i + 1 < level.length ? hashPair(level[i], level[i + 1]) : level[i]
+      );
+    }
+
+    level = next;
+  }
+
+  return { root: u8aToHex(level[0]), proofs };
+}
@@ -0,0 +1,77 @@
+import BN from 'bn.js';
+import { expect } from 'chai';
+import { patract, network } from 'redspot';
+import { merkleTree } from '../scripts/merkle';
+
+const { getContractFactory, getRandomSigner } = patract;
+
+const { api, getSigners } = network;
+
+describe('MERKLE AIRDROP', () => {
+  after(() => {
+    return api.disconnect();
+  });
+
+  async function setup() {
+    const one = new BN(10).pow(new BN(api.registry.chainDecimals[0]));
+    const signers = await getSigners();
+    const Alice = signers[0];
+    const sender = await getRandomSigner(Alice, one.muln(10000));
+    const claimant = await getRandomSigner(Alice, one.muln(10000));
+    const tokenFactory = await getContractFactory('erc20', sender);
+    const token = await tokenFactory.deploy(
+      'new',
+      '1000000',
+      'Redspot',
+      'RDS',
+      10
+    );
+    const allocations = [
+      { index: 0, account: sender.address, amount: 100 },
+      { index: 1, account: claimant.address, amount: 200 },
+      { index: 2, account: Alice.address, amount: 300 }
+    ];
+    const { root, proofs } = merkleTree(api, allocations);
+    const contractFactory = await getContractFactory('merkle_airdrop', sender);
+    const contract = await contractFactory.deploy('new', token.address, root);
+    await token.tx.transfer(contract.address, 600);
+
+    return {
+      sender,
+      claimant,
+      token,
+      proofs,
+      contractFactory,
+      contract,
+      Alice,
+      one
+    };
+  }
+
+  it('Releases allocations with a valid proof', async () => {
+    const { contract, token, claimant, proofs } = await setup();
+
+    await expect(() =>
+      contract.tx.claim(1, claimant.address, 200, proofs[1])
+    ).to.changeTokenBalance(token, claimant, 200);
+    expect((await contract.query.isClaimed(1)).output).to.equal(true);
+
+    await expect(
+      contract.tx.claim(1, claimant.address, 200, proofs[1])
+    ).to.not.emit(contract, 'Claimed');
+  });
+
+  it('Rejects invalid proofs', async () => {
+    const { contract, claimant, Alice, proofs } = await setup();
+
+    await expect(
+      contract.tx.claim(1, claimant.address, 300, proofs[1])
+    ).to.not.emit(contract, 'Claimed');
+    await expect(
+      contract.tx.claim(2, Alice.address, 300, proofs[1])
+    ).to.not.emit(contract, 'Claimed');
+    await expect(contract.tx.claim(2, Alice.address, 300, proofs[2]))
+      .to.emit(contract, 'Claimed')
+      .withArgs(2, Alice.address, 300);
+  });
+});
@@ -0,0 +1,220 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use ink_lang as ink;
+
+#[ink::contract]
+mod multicall {
+    use ink_env::{
+        call::{
+            build_call,
+            utils::ReturnType,
+            ExecutionInput,
+            Selector,
+        },
+        DefaultEnvironment,
+    };
+    use ink_prelude::vec::Vec;
+
+    /// A call to another contract: the contract called and the call data, which
+    /// is the selector of the message followed by its SCALE encoded arguments.
+    pub type Call = (AccountId, Vec<u8>);
+
+    /// Executes a batch of read calls against other contracts and returns their
+    /// SCALE encoded results, so a front-end reads many values with a single
+    /// dry-run.
+    ///
+    /// The results come with the number of the block they were read at, so
+    /// they are known to be consistent with each other. The aggregator does not
+    /// keep any state and forwards no funds. The contracts called see the
+    /// aggregator as their caller, so it is no use for messages checking the
+    /// caller.
+    #[ink(storage)]
+    #[derive(Default)]
+    pub struct Multicall {}
+
+    /// The multicall error types.
+    #[derive(Debug, PartialEq, Eq, scale::Encode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum Error {
+        /// Returned if the call data at the index is shorter than a selector.
+        InvalidCall(u32),
+        /// Returned if the call at the index traps or can not be made.
+        CallFailed(u32),
+    }
+
+    /// The multicall result type.
+    pub type Result<T> = core::result::Result<T, Error>;
+
+    /// Arguments which are SCALE encoded already, so they are passed on as
+    /// they are.
+    struct EncodedArgs<'a>(&'a [u8]);
+
+    impl scale::Encode for EncodedArgs<'_> {
+        fn size_hint(&self) -> usize {
+            self.0.len()
+        }
+
+        fn encode_to<T: scale::Output + ?Sized>(&self, dest: &mut T) {
+            dest.write(self.0);
+        }
+    }
+
+    /// A return value which is kept SCALE encoded.
+    struct EncodedOutput(Vec<u8>);
+
+    impl scale::Decode for EncodedOutput {
+        fn decode<I: scale::Input>(
+            input: &mut I,
+        ) -> core::result::Result<Self, scale::Error> {
+            let len = input.remaining_len()?.unwrap_or(0);
+            let mut output = ink_prelude::vec![0x00; len];
+            input.read(&mut output)?;
+            Ok(Self(output))
+        }
+    }
+
+    impl Multicall {
+        /// Creates an aggregator.
+        #[ink(constructor)]
+        pub fn new() -> Self {
+            Default::default()
+        }
+
+        /// Executes `calls` in order and returns the current block number with
+        /// the SCALE encoded result of every call.
+        ///
+        /// # Errors
+        ///
+        /// Returns `InvalidCall` error with the index of the first call whose
+        /// call data is shorter than a selector.
+        ///
+        /// Returns `CallFailed` error with the index of the first call which
+        /// traps or can not be made.
+        #[ink(message)]
+        pub fn aggregate(&self, calls: Vec<Call>) -> Result<(BlockNumber, Vec<Vec<u8>>)> {
+            let mut results = Vec::with_capacity(calls.len());
+            for (index, (callee, data)) in (0u32..).zip(calls) {
+                let (selector, input) =
+                    split_call(&data).ok_or(Error::InvalidCall(index))?;
+                let output = Self::execute(callee, selector, input)
+                    .ok_or(Error::CallFailed(index))?;
+                results.push(output);
+            }
+            Ok((self.env().block_number(), results))
+        }
+
+        /// Executes `calls` in order and returns the current block number with
+        /// the SCALE encoded result of every call, or `None` for every call
+        /// whose call data is shorter than a selector or which fails.
+        #[ink(message)]
+        pub fn try_aggregate(
+            &self,
+            calls: Vec<Call>,
+        ) -> (BlockNumber, Vec<Option<Vec<u8>>>) {
+            let results = calls
+                .iter()
+                .map(|(callee, data)| {
+                    let (selector, input) = split_call(data)?;
+                    Self::execute(*callee, selector, input)
+                })
+                .collect();
+            (self.env().block_number(), results)
+        }
+
+        /// Returns the current block number, which the results of a batch are
+        /// read at.
+        #[ink(message)]
+        pub fn block_number(&self) -> BlockNumber {
+            self.env().block_number()
+        }
+
+        /// Calls the message with `selector` of `callee` with the SCALE encoded
+        /// `input` and returns its SCALE encoded result, or `None` if the call
+        /// failed.
+        fn execute(
+            callee: AccountId,
+            selector: [u8; 4],
+            input: &[u8],
+        ) -> Option<Vec<u8>> {
+            build_call::<DefaultEnvironment>()
+                .callee(callee)
+                .gas_limit(0)
+                .transferred_value(0)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(selector))
+                        .push_arg(EncodedArgs(input)),
+                )
+                .returns::<ReturnType<EncodedOutput>>()
+                .fire()
+                .map(|output| output.0)
+                .ok()
+        }
+    }
+
+    /// Splits call data into the selector and the SCALE encoded arguments, or
+    /// returns `None` if it is shorter than a selector.
+    fn split_call(data: &[u8]) -> Option<([u8; 4], &[u8])> {
+        if data.len() < 4 {
+            return None
+        }
+        let (selector, input) = data.split_at(4);
+        let mut bytes = [0x00; 4];
+        bytes.copy_from_slice(selector);
+        Some((bytes, input))
+    }
+
+    /// Unit tests.
+    ///
+    /// Aggregating calls is left to the redspot tests.
+    #[cfg(test)]
+    mod tests {
+        /// Imports all the definitions from the outer scope so we can use them here.
+        use super::*;
+
+        use ink_lang as ink;
+        use ink_prelude::vec;
+        use scale::{
+            Decode,
+            Encode,
+        };
+
+        #[test]
+        fn calls_are_split_into_selector_and_input() {
+            assert_eq!(split_call(&[0x01, 0x02, 0x03]), None);
+            assert_eq!(
+                split_call(&[0x01, 0x02, 0x03, 0x04]),
+                Some(([0x01, 0x02, 0x03, 0x04], &[][..]))
+            );
+            assert_eq!(
+                split_call(&[0x01, 0x02, 0x03, 0x04, 0x05]),
+                Some(([0x01, 0x02, 0x03, 0x04], &[0x05][..]))
+            );
+        }
+
+        #[test]
+        fn encoded_values_are_kept_as_they_are() {
+            let input = (7u32, true).encode();
+
+            assert_eq!(EncodedArgs(&input).encode(), input);
+            let decoded =
+                EncodedOutput::decode(&mut &input[..]).expect("Cannot decode output");
+            assert_eq!(decoded.0, input);
+        }
+
+        #[ink::test]
+        fn invalid_calls_are_rejected() {
+            let multicall = Multicall::new();
+            let callee = AccountId::from([0x07; 32]);
+
+            assert_eq!(
+                multicall.aggregate(vec![(callee, vec![0x01])]),
+                Err(Error::InvalidCall(0))
+            );
+            assert_eq!(multicall.aggregate(Vec::new()), Ok((0, Vec::new())));
+            assert_eq!(
+                multicall.try_aggregate(vec![(callee, Vec::new())]),
+                (0, vec![None])
+            );
+        }
+    }
+}
\ No newline at end of file
@@ -0,0 +1,112 @@
+import BN from 'bn.js';
+import { expect } from 'chai';
+import { patract, network } from 'redspot';
+import { u8aConcat, u8aToHex } from '@polkadot/util';
+
+const { getContractFactory, getRandomSigner } = patract;
+
+const { api, getSigners } = network;
+
+// Selectors of the erc20 example's `psp22_total_supply` and
+// `psp22_balance_of`.
+const TOTAL_SUPPLY_SELECTOR = '0x162df8c2';
+const BALANCE_OF_SELECTOR = '0x6568382f';
+
+describe('MULTICALL', () => {
+  after(() => {
+    return api.disconnect();
+  });
+
+  async function setup() {
+    const one = new BN(10).pow(new BN(api.registry.chainDecimals[0]));
+    const signers = await getSigners();
+    const Alice = signers[0];
+    const sender = await getRandomSigner(Alice, one.muln(10000));
+    const receiver = await getRandomSigner();
+    const tokenFactory = await getContractFactory('erc20', sender);
+    const token = await tokenFactory.deploy(
+      'new',
+      '1000000',
+      'Redspot',
+      'RDS',
+      10
+    );
+    const contractFactory = await getContractFactory('multicall', sender);
+    const contract = await contractFactory.deploy('new');
+    await token.tx.transfer(receiver.address, 300);
+
+    // Returns the call data of `balance_of(owner)`.
+    function balanceOf(owner: string) {
+      const input = api.createType('AccountId', owner).toU8a();
+
+      return u8aToHex(u8aConcat(BALANCE_OF_SELECTOR, input));
+    }
+
+    // Decodes the SCALE encoded results of a batch as balances.
+    function balances(results: any[]) {
+      return results.map((result) =>
+        api.createType('Balance', result.toU8a(true)).toNumber()
+      );
+    }
+
+    return {
+      sender,
+      receiver,
+      token,
+      contract,
+      balanceOf,
+      balances,
+      Alice,
+      one
+    };
+  }
+
+  it('Aggregates the results of read calls', async () => {
+    const {
+      contract,
+      token,
+      sender,
+      receiver,
+      balanceOf,
+      balances
+    } = await setup();
+
+    const result = await contract.query.aggregate([
+      [token.address, TOTAL_SUPPLY_SELECTOR],
+      [token.address, balanceOf(sender.address)],
+      [token.address, balanceOf(receiver.address)]
+    ]);
+    const [blockNumber, results] = (result.output as any).asOk;
+
+    expect(blockNumber.toNumber()).to.be.greaterThan(0);
+    expect(balances(results)).to.deep.equal([1000000, 999700, 300]);
+  });
+
+  it('Fails the batch if a call fails', async () => {
+    const { contract, token, receiver, balanceOf } = await setup();
+
+    const result = await contract.query.aggregate([
+      [token.address, balanceOf(receiver.address)],
+      [receiver.address, balanceOf(receiver.address)]
+    ]);
+
+    expect((result.output as any).asErr.toJSON()).to.deep.equal({
+      CallFailed: 1
+    });
+  });
+
+  it('Skips failed calls when trying to aggregate', async () => {
+    const { contract, token, receiver, balanceOf, balances } = await setup();
+
+    const result = await contract.query.tryAggregate([
+      [token.address, '0x01'],
+      [receiver.address, balanceOf(receiver.address)],
+      [token.address, balanceOf(receiver.address)]
+    ]);
+    const [, results] = result.output as any;
+
+    expect(results[0].isNone).to.equal(true);
+    expect(results[1].isNone).to.equal(true);
+    expect(balances([results[2].unwrap()])).to.deep.equal([300]);
+  });
+});
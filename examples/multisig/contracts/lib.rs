@@ -0,0 +1,794 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use ink_lang as ink;
+
+#[ink::contract]
+mod multisig {
+    use ink_env::{
+        call::{
+            build_call,
+            ExecutionInput,
+            Selector,
+        },
+        DefaultEnvironment,
+        ReturnFlags,
+    };
+    use ink_prelude::vec::Vec;
+    use ink_storage::traits::{
+        PackedLayout,
+        SpreadLayout,
+    };
+    #[cfg(not(feature = "ink-as-dependency"))]
+    use ink_storage::{
+        collections::HashMap as StorageHashMap,
+        lazy::Lazy,
+    };
+
+    /// A transaction ID.
+    pub type TransactionId = u32;
+
+    /// A call the wallet makes once enough owners confirmed it.
+    #[derive(
+        Debug,
+        Clone,
+        PartialEq,
+        Eq,
+        scale::Encode,
+        scale::Decode,
+        SpreadLayout,
+        PackedLayout,
+    )]
+    #[cfg_attr(
+        feature = "std",
+        derive(::scale_info::TypeInfo, ::ink_storage::traits::StorageLayout)
+    )]
+    pub struct Transaction {
+        /// The contract to call.
+        pub callee: AccountId,
+        /// The selector of the message to call.
+        pub selector: [u8; 4],
+        /// The SCALE encoded arguments of the message.
+        pub input: Vec<u8>,
+        /// The native funds the wallet sends along.
+        pub transferred_value: Balance,
+        /// The gas limit of the call, `0` forwards all remaining gas.
+        pub gas_limit: u64,
+    }
+
+    /// A submitted transaction with the owners that confirmed it.
+    #[derive(Debug, Clone, scale::Encode, scale::Decode, SpreadLayout, PackedLayout)]
+    #[cfg_attr(
+        feature = "std",
+        derive(::scale_info::TypeInfo, ::ink_storage::traits::StorageLayout)
+    )]
+    struct TransactionState {
+        transaction: Transaction,
+        confirmations: Vec<AccountId>,
+        executed: bool,
+    }
+
+    /// Passes already encoded arguments on to a call without encoding them again.
+    struct CallInput<'a>(&'a [u8]);
+
+    impl<'a> scale::Encode for CallInput<'a> {
+        fn encode_to<T: scale::Output + ?Sized>(&self, dest: &mut T) {
+            dest.write(self.0);
+        }
+    }
+
+    /// A wallet executing calls once `requirement` of its owners confirmed them.
+    ///
+    /// Owners and the requirement can only be changed by the wallet itself, that
+    /// is by a confirmed transaction calling the wallet.
+    #[ink(storage)]
+    pub struct Multisig {
+        /// The accounts allowed to submit and confirm transactions.
+        owners: Lazy<Vec<AccountId>>,
+        /// The number of confirmations a transaction needs to be executed.
+        requirement: Lazy<u32>,
+        /// Mapping from ID to submitted transaction.
+        transactions: StorageHashMap<TransactionId, TransactionState>,
+        /// The ID the next submitted transaction gets.
+        next_transaction_id: Lazy<TransactionId>,
+    }
+
+    /// Event emitted when an owner submits a transaction.
+    #[ink(event)]
+    pub struct Submission {
+        #[ink(topic)]
+        transaction: TransactionId,
+    }
+
+    /// Event emitted when an owner confirms a transaction.
+    #[ink(event)]
+    pub struct Confirmation {
+        #[ink(topic)]
+        transaction: TransactionId,
+        #[ink(topic)]
+        from: AccountId,
+    }
+
+    /// Event emitted when an owner revokes its confirmation of a transaction.
+    #[ink(event)]
+    pub struct Revocation {
+        #[ink(topic)]
+        transaction: TransactionId,
+        #[ink(topic)]
+        from: AccountId,
+    }
+
+    /// Event emitted when the wallet cancels a transaction.
+    #[ink(event)]
+    pub struct Cancellation {
+        #[ink(topic)]
+        transaction: TransactionId,
+    }
+
+    /// Event emitted when a transaction is executed.
+    #[ink(event)]
+    pub struct Execution {
+        #[ink(topic)]
+        transaction: TransactionId,
+    }
+
+    /// Event emitted when an owner is added.
+    #[ink(event)]
+    pub struct OwnerAddition {
+        #[ink(topic)]
+        owner: AccountId,
+    }
+
+    /// Event emitted when an owner is removed.
+    #[ink(event)]
+    pub struct OwnerRemoval {
+        #[ink(topic)]
+        owner: AccountId,
+    }
+
+    /// Event emitted when the requirement changes.
+    #[ink(event)]
+    pub struct RequirementChange {
+        requirement: u32,
+    }
+
+    /// The wallet error types.
+    #[derive(Debug, PartialEq, Eq, scale::Encode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum Error {
+        /// Returned if the caller or the given account is not an owner.
+        NotOwner,
+        /// Returned if the caller is not the wallet itself.
+        NotWallet,
+        /// Returned if the given account already is an owner.
+        AlreadyOwner,
+        /// Returned if the requirement is zero or above the number of owners.
+        InvalidRequirement,
+        /// Returned if no pending transaction has the given ID.
+        TransactionNotFound,
+        /// Returned if the transaction was already executed.
+        AlreadyExecuted,
+        /// Returned if the caller already confirmed the transaction.
+        AlreadyConfirmed,
+        /// Returned if the caller did not confirm the transaction.
+        NotConfirmed,
+        /// Returned if fewer owners than required confirmed the transaction.
+        NotEnoughConfirmations,
+        /// Returned if the call of an executed transaction failed.
+        TransactionFailed,
+    }
+
+    /// The wallet result type.
+    pub type Result<T> = core::result::Result<T, Error>;
+
+    impl Multisig {
+        /// Creates a wallet with the given `owners` that executes transactions
+        /// confirmed by `requirement` of them.
+        ///
+        /// Duplicate owners are ignored.
+        ///
+        /// # Panics
+        ///
+        /// If `requirement` is zero or above the number of owners.
+        #[ink(constructor)]
+        pub fn new(requirement: u32, owners: Vec<AccountId>) -> Self {
+            let mut unique = Vec::new();
+            for owner in owners {
+                if !unique.contains(&owner) {
+                    unique.push(owner);
+                }
+            }
+            assert!(
+                Self::is_valid_requirement(unique.len(), requirement),
+                "invalid requirement"
+            );
+            Self {
+                owners: Lazy::new(unique),
+                requirement: Lazy::new(requirement),
+                transactions: StorageHashMap::new(),
+                next_transaction_id: Lazy::new(0),
+            }
+        }
+
+        /// Returns the owners of the wallet.
+        #[ink(message)]
+        pub fn owners(&self) -> Vec<AccountId> {
+            (*self.owners).clone()
+        }
+
+        /// Returns `true` if `account` is an owner of the wallet.
+        #[ink(message)]
+        pub fn is_owner(&self, account: AccountId) -> bool {
+            self.owners.contains(&account)
+        }
+
+        /// Returns the number of confirmations a transaction needs to be executed.
+        #[ink(message)]
+        pub fn requirement(&self) -> u32 {
+            *self.requirement
+        }
+
+        /// Returns the transaction with ID `id`, or `None` if it was never
+        /// submitted or is cancelled.
+        #[ink(message)]
+        pub fn transaction(&self, id: TransactionId) -> Option<Transaction> {
+            self.transactions
+                .get(&id)
+                .map(|state| state.transaction.clone())
+        }
+
+        /// Returns the owners that confirmed the transaction with ID `id`.
+        #[ink(message)]
+        pub fn confirmations(&self, id: TransactionId) -> Vec<AccountId> {
+            self.transactions
+                .get(&id)
+                .map(|state| state.confirmations.clone())
+                .unwrap_or_default()
+        }
+
+        /// Returns `true` if the transaction with ID `id` was executed.
+        #[ink(message)]
+        pub fn is_executed(&self, id: TransactionId) -> bool {
+            self.transactions
+                .get(&id)
+                .map_or(false, |state| state.executed)
+        }
+
+        /// Adds `owner` to the owners of the wallet.
+        ///
+        /// On success an `OwnerAddition` event is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `NotWallet` error if the caller is not the wallet itself.
+        ///
+        /// Returns `AlreadyOwner` error if `owner` already is an owner.
+        #[ink(message)]
+        pub fn add_owner(&mut self, owner: AccountId) -> Result<()> {
+            self.ensure_from_wallet()?;
+            if self.is_owner(owner) {
+                return Err(Error::AlreadyOwner)
+            }
+            self.owners.push(owner);
+            self.env().emit_event(OwnerAddition { owner });
+            Ok(())
+        }
+
+        /// Removes `owner` from the owners of the wallet and drops its
+        /// confirmations of pending transactions.
+        ///
+        /// The requirement is lowered to the number of remaining owners if it
+        /// exceeds it.
+        ///
+        /// On success an `OwnerRemoval` event is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `NotWallet` error if the caller is not the wallet itself.
+        ///
+        /// Returns `NotOwner` error if `owner` is not an owner.
+        ///
+        /// Returns `InvalidRequirement` error if `owner` is the last owner.
+        #[ink(message)]
+        pub fn remove_owner(&mut self, owner: AccountId) -> Result<()> {
+            self.ensure_from_wallet()?;
+            if !self.is_owner(owner) {
+                return Err(Error::NotOwner)
+            }
+            let remaining = self.owners.len() as u32 - 1;
+            if remaining == 0 {
+                return Err(Error::InvalidRequirement)
+            }
+            self.owners.retain(|account| *account != owner);
+            self.drop_confirmations_of(owner);
+            self.env().emit_event(OwnerRemoval { owner });
+            if *self.requirement > remaining {
+                *self.requirement = remaining;
+                self.env().emit_event(RequirementChange {
+                    requirement: remaining,
+                });
+            }
+            Ok(())
+        }
+
+        /// Replaces the owner `owner` with `new_owner` and drops the confirmations
+        /// `owner` gave to pending transactions.
+        ///
+        /// On success an `OwnerRemoval` and an `OwnerAddition` event are emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `NotWallet` error if the caller is not the wallet itself.
+        ///
+        /// Returns `NotOwner` error if `owner` is not an owner.
+        ///
+        /// Returns `AlreadyOwner` error if `new_owner` already is an owner.
+        #[ink(message)]
+        pub fn replace_owner(
+            &mut self,
+            owner: AccountId,
+            new_owner: AccountId,
+        ) -> Result<()> {
+            self.ensure_from_wallet()?;
+            if self.is_owner(new_owner) {
+                return Err(Error::AlreadyOwner)
+            }
+            let index = self
+                .owners
+                .iter()
+                .position(|account| *account == owner)
+                .ok_or(Error::NotOwner)?;
+            self.owners[index] = new_owner;
+            self.drop_confirmations_of(owner);
+            self.env().emit_event(OwnerRemoval { owner });
+            self.env().emit_event(OwnerAddition { owner: new_owner });
+            Ok(())
+        }
+
+        /// Sets the number of confirmations a transaction needs to `requirement`.
+        ///
+        /// On success a `RequirementChange` event is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `NotWallet` error if the caller is not the wallet itself.
+        ///
+        /// Returns `InvalidRequirement` error if `requirement` is zero or above the
+        /// number of owners.
+        #[ink(message)]
+        pub fn change_requirement(&mut self, requirement: u32) -> Result<()> {
+            self.ensure_from_wallet()?;
+            if !Self::is_valid_requirement(self.owners.len(), requirement) {
+                return Err(Error::InvalidRequirement)
+            }
+            *self.requirement = requirement;
+            self.env().emit_event(RequirementChange { requirement });
+            Ok(())
+        }
+
+        /// Submits `transaction` for confirmation and confirms it for the caller.
+        ///
+        /// On success a `Submission` and a `Confirmation` event are emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `NotOwner` error if the caller is not an owner.
+        #[ink(message)]
+        pub fn submit_transaction(
+            &mut self,
+            transaction: Transaction,
+        ) -> Result<TransactionId> {
+            let caller = self.ensure_owner()?;
+            let id = *self.next_transaction_id;
+            *self.next_transaction_id += 1;
+            let mut confirmations = Vec::new();
+            confirmations.push(caller);
+            self.transactions.insert(
+                id,
+                TransactionState {
+                    transaction,
+                    confirmations,
+                    executed: false,
+                },
+            );
+            self.env().emit_event(Submission { transaction: id });
+            self.env().emit_event(Confirmation {
+                transaction: id,
+                from: caller,
+            });
+            Ok(id)
+        }
+
+        /// Confirms the transaction with ID `id` for the caller.
+        ///
+        /// On success a `Confirmation` event is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `NotOwner` error if the caller is not an owner.
+        ///
+        /// Returns `TransactionNotFound` error if there is no such transaction.
+        ///
+        /// Returns `AlreadyExecuted` error if the transaction was executed.
+        ///
+        /// Returns `AlreadyConfirmed` error if the caller already confirmed it.
+        #[ink(message)]
+        pub fn confirm_transaction(&mut self, id: TransactionId) -> Result<()> {
+            let caller = self.ensure_owner()?;
+            let state = self.pending_transaction(id)?;
+            if state.confirmations.contains(&caller) {
+                return Err(Error::AlreadyConfirmed)
+            }
+            state.confirmations.push(caller);
+            self.env().emit_event(Confirmation {
+                transaction: id,
+                from: caller,
+            });
+            Ok(())
+        }
+
+        /// Revokes the caller's confirmation of the transaction with ID `id`.
+        ///
+        /// On success a `Revocation` event is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `NotOwner` error if the caller is not an owner.
+        ///
+        /// Returns `TransactionNotFound` error if there is no such transaction.
+        ///
+        /// Returns `AlreadyExecuted` error if the transaction was executed.
+        ///
+        /// Returns `NotConfirmed` error if the caller did not confirm it.
+        #[ink(message)]
+        pub fn revoke_confirmation(&mut self, id: TransactionId) -> Result<()> {
+            let caller = self.ensure_owner()?;
+            let state = self.pending_transaction(id)?;
+            if !state.confirmations.contains(&caller) {
+                return Err(Error::NotConfirmed)
+            }
+            state.confirmations.retain(|owner| *owner != caller);
+            self.env().emit_event(Revocation {
+                transaction: id,
+                from: caller,
+            });
+            Ok(())
+        }
+
+        /// Cancels the pending transaction with ID `id`.
+        ///
+        /// On success a `Cancellation` event is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `NotWallet` error if the caller is not the wallet itself.
+        ///
+        /// Returns `TransactionNotFound` error if there is no such transaction.
+        ///
+        /// Returns `AlreadyExecuted` error if the transaction was executed.
+        #[ink(message)]
+        pub fn cancel_transaction(&mut self, id: TransactionId) -> Result<()> {
+            self.ensure_from_wallet()?;
+            self.pending_transaction(id)?;
+            self.transactions.take(&id);
+            self.env().emit_event(Cancellation { transaction: id });
+            Ok(())
+        }
+
+        /// Executes the transaction with ID `id` once enough owners confirmed it.
+        ///
+        /// Anyone may execute a confirmed transaction. On success an `Execution`
+        /// event is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `TransactionNotFound` error if there is no such transaction.
+        ///
+        /// Returns `AlreadyExecuted` error if the transaction was executed.
+        ///
+        /// Returns `NotEnoughConfirmations` error if fewer owners than required
+        /// confirmed it.
+        ///
+        /// Returns `TransactionFailed` error, reverting all changes, if the call
+        /// fails.
+        #[ink(message)]
+        pub fn invoke_transaction(&mut self, id: TransactionId) -> Result<()> {
+            let requirement = *self.requirement;
+            let state = self.pending_transaction(id)?;
+            if (state.confirmations.len() as u32) < requirement {
+                return Err(Error::NotEnoughConfirmations)
+            }
+            state.executed = true;
+            let transaction = state.transaction.clone();
+            // ink! writes the contract storage back only when a message returns.
+            // Write it now so the callee, which may be this wallet, already sees
+            // the transaction as executed and can not execute it a second time.
+            // Everything else the callee may change is kept in `Lazy` cells and
+            // mapping entries, which are only written back again if modified.
+            ink_storage::traits::push_spread_root::<Self>(
+                self,
+                &ink_primitives::Key::from([0x00; 32]),
+            );
+            let result = build_call::<DefaultEnvironment>()
+                .callee(transaction.callee)
+                .gas_limit(transaction.gas_limit)
+                .transferred_value(transaction.transferred_value)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(transaction.selector))
+                        .push_arg(CallInput(&transaction.input)),
+                )
+                .returns::<()>()
+                .fire();
+            if result.is_err() {
+                Self::revert(Error::TransactionFailed)
+            }
+            self.env().emit_event(Execution { transaction: id });
+            Ok(())
+        }
+
+        /// Returns `true` if `requirement` confirmations out of `owners` owners
+        /// are a valid threshold.
+        fn is_valid_requirement(owners: usize, requirement: u32) -> bool {
+            requirement > 0 && requirement as usize <= owners
+        }
+
+        /// Returns the caller if it is an owner and `NotOwner` error otherwise.
+        fn ensure_owner(&self) -> Result<AccountId> {
+            let caller = self.env().caller();
+            if !self.is_owner(caller) {
+                return Err(Error::NotOwner)
+            }
+            Ok(caller)
+        }
+
+        /// Returns `NotWallet` error if the caller is not the wallet itself.
+        fn ensure_from_wallet(&self) -> Result<()> {
+            if self.env().caller() != self.env().account_id() {
+                return Err(Error::NotWallet)
+            }
+            Ok(())
+        }
+
+        /// Returns the transaction with ID `id` if it is neither cancelled nor
+        /// executed.
+        fn pending_transaction(
+            &mut self,
+            id: TransactionId,
+        ) -> Result<&mut TransactionState> {
+            let state = self
+                .transactions
+                .get_mut(&id)
+                .ok_or(Error::TransactionNotFound)?;
+            if state.executed {
+                return Err(Error::AlreadyExecuted)
+            }
+            Ok(state)
+        }
+
+        /// Drops the confirmations `owner` gave to pending transactions.
+        fn drop_confirmations_of(&mut self, owner: AccountId) {
+            let ids = self.transactions.keys().copied().collect::<Vec<_>>();
+            for id in ids {
+                if let Ok(state) = self.pending_transaction(id) {
+                    if state.confirmations.contains(&owner) {
+                        state.confirmations.retain(|account| *account != owner);
+                    }
+                }
+            }
+        }
+
+        /// Ends the current call with `error`, so a transaction whose call fails is
+        /// not marked as executed.
+        fn revert(error: Error) -> ! {
+            ink_env::return_value::<Result<()>>(
+                ReturnFlags::default().set_reverted(true),
+                &Err(error),
+            )
+        }
+    }
+
+    /// Unit tests.
+    ///
+    /// Executing a transaction is left to the redspot tests.
+    #[cfg(test)]
+    mod tests {
+        /// Imports all the definitions from the outer scope so we can use them here.
+        use super::*;
+
+        use ink_lang as ink;
+        use test_utils::set_caller;
+
+        /// Pushes a new execution context so that the wallet calls itself.
+        fn set_wallet_caller() {
+            let wallet = ink_env::account_id::<ink_env::DefaultEnvironment>()
+                .expect("Cannot get contract id");
+            set_caller!(wallet);
+        }
+
+        /// Returns a wallet owned by Alice, Bob and Eve requiring two
+        /// confirmations.
+        fn wallet() -> Multisig {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            Multisig::new(2, vec![accounts.alice, accounts.bob, accounts.eve])
+        }
+
+        fn transaction() -> Transaction {
+            Transaction {
+                callee: AccountId::from([0x07; 32]),
+                selector: [0x00; 4],
+                input: Vec::new(),
+                transferred_value: 0,
+                gas_limit: 0,
+            }
+        }
+
+        #[ink::test]
+        fn new_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let wallet =
+                Multisig::new(1, vec![accounts.alice, accounts.bob, accounts.alice]);
+            assert_eq!(wallet.owners(), vec![accounts.alice, accounts.bob]);
+            assert_eq!(wallet.requirement(), 1);
+            assert!(wallet.is_owner(accounts.bob));
+            assert!(!wallet.is_owner(accounts.eve));
+        }
+
+        #[ink::test]
+        #[should_panic(expected = "invalid requirement")]
+        fn new_rejects_unreachable_requirement() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            Multisig::new(2, vec![accounts.alice, accounts.alice]);
+        }
+
+        #[ink::test]
+        fn submit_and_confirm_works() {
+            let mut wallet = wallet();
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            assert_eq!(wallet.submit_transaction(transaction()), Ok(0));
+            assert_eq!(wallet.transaction(0), Some(transaction()));
+            assert_eq!(wallet.confirmations(0), vec![accounts.alice]);
+            assert_eq!(wallet.confirm_transaction(0), Err(Error::AlreadyConfirmed));
+
+            set_caller!(accounts.bob);
+            assert_eq!(wallet.confirm_transaction(0), Ok(()));
+            assert_eq!(wallet.confirmations(0), vec![accounts.alice, accounts.bob]);
+            assert_eq!(
+                wallet.confirm_transaction(1),
+                Err(Error::TransactionNotFound)
+            );
+            assert_eq!(ink_env::test::recorded_events().count(), 3);
+        }
+
+        #[ink::test]
+        fn only_owners_can_submit_and_confirm() {
+            let mut wallet = wallet();
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            assert_eq!(wallet.submit_transaction(transaction()), Ok(0));
+            set_caller!(accounts.charlie);
+            assert_eq!(
+                wallet.submit_transaction(transaction()),
+                Err(Error::NotOwner)
+            );
+            assert_eq!(wallet.confirm_transaction(0), Err(Error::NotOwner));
+            assert_eq!(wallet.revoke_confirmation(0), Err(Error::NotOwner));
+        }
+
+        #[ink::test]
+        fn revoke_confirmation_works() {
+            let mut wallet = wallet();
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            assert_eq!(wallet.submit_transaction(transaction()), Ok(0));
+            assert_eq!(wallet.revoke_confirmation(0), Ok(()));
+            assert_eq!(wallet.confirmations(0), Vec::new());
+            assert_eq!(wallet.revoke_confirmation(0), Err(Error::NotConfirmed));
+
+            set_caller!(accounts.bob);
+            assert_eq!(wallet.confirm_transaction(0), Ok(()));
+            assert_eq!(
+                wallet.invoke_transaction(0),
+                Err(Error::NotEnoughConfirmations)
+            );
+            assert!(!wallet.is_executed(0));
+        }
+
+        #[ink::test]
+        fn owner_management_requires_the_wallet() {
+            let mut wallet = wallet();
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            assert_eq!(wallet.add_owner(accounts.charlie), Err(Error::NotWallet));
+            assert_eq!(wallet.remove_owner(accounts.bob), Err(Error::NotWallet));
+            assert_eq!(
+                wallet.replace_owner(accounts.bob, accounts.charlie),
+                Err(Error::NotWallet)
+            );
+            assert_eq!(wallet.change_requirement(1), Err(Error::NotWallet));
+            assert_eq!(wallet.submit_transaction(transaction()), Ok(0));
+            assert_eq!(wallet.cancel_transaction(0), Err(Error::NotWallet));
+        }
+
+        #[ink::test]
+        fn wallet_manages_owners() {
+            let mut wallet = wallet();
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            set_wallet_caller();
+            assert_eq!(wallet.add_owner(accounts.bob), Err(Error::AlreadyOwner));
+            assert_eq!(wallet.add_owner(accounts.charlie), Ok(()));
+            assert_eq!(
+                wallet.replace_owner(accounts.charlie, accounts.django),
+                Ok(())
+            );
+            assert_eq!(
+                wallet.owners(),
+                vec![accounts.alice, accounts.bob, accounts.eve, accounts.django]
+            );
+            assert_eq!(wallet.change_requirement(5), Err(Error::InvalidRequirement));
+            assert_eq!(wallet.change_requirement(0), Err(Error::InvalidRequirement));
+            assert_eq!(wallet.change_requirement(4), Ok(()));
+            assert_eq!(wallet.requirement(), 4);
+        }
+
+        #[ink::test]
+        fn remove_owner_drops_confirmations_and_lowers_requirement() {
+            let mut wallet = wallet();
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            assert_eq!(wallet.submit_transaction(transaction()), Ok(0));
+            set_caller!(accounts.bob);
+            assert_eq!(wallet.confirm_transaction(0), Ok(()));
+            ink_env::test::pop_execution_context();
+
+            set_wallet_caller();
+            assert_eq!(wallet.remove_owner(accounts.charlie), Err(Error::NotOwner));
+            assert_eq!(wallet.remove_owner(accounts.bob), Ok(()));
+            assert_eq!(wallet.confirmations(0), vec![accounts.alice]);
+            assert_eq!(wallet.requirement(), 2);
+            assert_eq!(wallet.remove_owner(accounts.eve), Ok(()));
+            assert_eq!(wallet.requirement(), 1);
+            assert_eq!(
+                wallet.remove_owner(accounts.alice),
+                Err(Error::InvalidRequirement)
+            );
+            assert_eq!(wallet.owners(), vec![accounts.alice]);
+        }
+
+        #[ink::test]
+        fn cancel_transaction_works() {
+            let mut wallet = wallet();
+
+            assert_eq!(wallet.submit_transaction(transaction()), Ok(0));
+            set_wallet_caller();
+            assert_eq!(wallet.cancel_transaction(0), Ok(()));
+            assert_eq!(wallet.transaction(0), None);
+            assert_eq!(
+                wallet.cancel_transaction(0),
+                Err(Error::TransactionNotFound)
+            );
+            assert_eq!(
+                wallet.invoke_transaction(0),
+                Err(Error::TransactionNotFound)
+            );
+        }
+    }
+}
\ No newline at end of file
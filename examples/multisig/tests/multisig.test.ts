@@ -0,0 +1,121 @@
+import BN from 'bn.js';
+import { expect } from 'chai';
+import { patract, network } from 'redspot';
+
+const { getContractFactory, getRandomSigner } = patract;
+
+const { api, getSigners } = network;
+
+// Selector of the wallet's own `change_requirement` message.
+const CHANGE_REQUIREMENT_SELECTOR = '0x9448dea5';
+
+describe('MULTISIG', () => {
+  after(() => {
+    return api.disconnect();
+  });
+
+  async function setup() {
+    const one = new BN(10).pow(new BN(api.registry.chainDecimals[0]));
+    const signers = await getSigners();
+    const Alice = signers[0];
+    const sender = await getRandomSigner(Alice, one.muln(10000));
+    const second = await getRandomSigner(Alice, one.muln(10000));
+    const contractFactory = await getContractFactory('multisig', sender);
+    const contract = await contractFactory.deploy('new', 2, [
+      sender.address,
+      second.address
+    ]);
+
+    // A transaction lowering the requirement of the wallet to one.
+    const transaction = {
+      callee: contract.address,
+      selector: CHANGE_REQUIREMENT_SELECTOR,
+      input: api.createType('u32', 1).toHex(),
+      transferredValue: 0,
+      gasLimit: 0
+    };
+
+    return {
+      sender,
+      second,
+      contractFactory,
+      contract,
+      transaction,
+      Alice,
+      one
+    };
+  }
+
+  it('Assigns owners and requirement', async () => {
+    const { contract, sender, second } = await setup();
+
+    expect((await contract.query.requirement()).output).to.equal(2);
+    expect((await contract.query.isOwner(sender.address)).output).to.equal(
+      true
+    );
+    expect((await contract.query.isOwner(second.address)).output).to.equal(
+      true
+    );
+  });
+
+  it('Executes a transaction once enough owners confirmed it', async () => {
+    const { contract, second, transaction } = await setup();
+
+    await expect(contract.tx.submitTransaction(transaction)).to.emit(
+      contract,
+      'Submission'
+    );
+    await expect(contract.tx.invokeTransaction(0)).to.not.emit(
+      contract,
+      'Execution'
+    );
+
+    await expect(
+      contract.tx.confirmTransaction(0, {
+        signer: second
+      })
+    )
+      .to.emit(contract, 'Confirmation')
+      .withArgs(0, second.address);
+    await expect(contract.tx.invokeTransaction(0))
+      .to.emit(contract, 'Execution')
+      .withArgs(0);
+
+    expect((await contract.query.requirement()).output).to.equal(1);
+    expect((await contract.query.isExecuted(0)).output).to.equal(true);
+  });
+
+  it('Can not execute a transaction twice', async () => {
+    const { contract, second, transaction } = await setup();
+
+    await contract.tx.submitTransaction(transaction);
+    await contract.tx.confirmTransaction(0, { signer: second });
+    await contract.tx.invokeTransaction(0);
+
+    await expect(contract.tx.invokeTransaction(0)).to.not.emit(
+      contract,
+      'Execution'
+    );
+  });
+
+  it('Can not submit without being an owner', async () => {
+    const { contract, transaction, Alice, one } = await setup();
+
+    const otherAccount = await getRandomSigner(Alice, one.muln(10000));
+
+    await expect(
+      contract.tx.submitTransaction(transaction, {
+        signer: otherAccount
+      })
+    ).to.not.emit(contract, 'Submission');
+  });
+
+  it('Can not change the requirement directly', async () => {
+    const { contract } = await setup();
+
+    await expect(contract.tx.changeRequirement(1)).to.not.emit(
+      contract,
+      'RequirementChange'
+    );
+  });
+});
@@ -0,0 +1,686 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use ink_lang as ink;
+
+#[ink::contract]
+mod name_registry {
+    use ink_env::{
+        hash::Blake2x256,
+        ReturnFlags,
+    };
+    use ink_prelude::string::String;
+    use ink_storage::traits::{
+        PackedLayout,
+        SpreadLayout,
+    };
+    #[cfg(not(feature = "ink-as-dependency"))]
+    use ink_storage::{
+        collections::HashMap as StorageHashMap,
+        lazy::Lazy,
+    };
+
+    /// The longest name in bytes that can be registered.
+    pub const MAX_NAME_LENGTH: usize = 64;
+
+    /// The registration of a name.
+    #[derive(
+        Debug,
+        Copy,
+        Clone,
+        PartialEq,
+        Eq,
+        scale::Encode,
+        scale::Decode,
+        SpreadLayout,
+        PackedLayout,
+    )]
+    #[cfg_attr(
+        feature = "std",
+        derive(::scale_info::TypeInfo, ::ink_storage::traits::StorageLayout)
+    )]
+    pub struct Record {
+        /// The account allowed to change and renew the registration.
+        pub owner: AccountId,
+        /// The account the name resolves to.
+        pub target: AccountId,
+        /// The first block the registration is no longer valid in.
+        pub expires_at: BlockNumber,
+    }
+
+    /// Maps human-readable names to accounts.
+    ///
+    /// A name is registered for a number of periods of `period` blocks, paying
+    /// `fee` native funds per period, and can be renewed by paying for more
+    /// periods before it expires. Once expired it resolves to nothing and
+    /// anyone may register it again.
+    ///
+    /// Records are keyed by the Blake2x256 hash of the name, so every entry
+    /// has the same size however long the name is, and no message iterates
+    /// over the registered names. The cost of a call therefore does not grow
+    /// with the number of names, and anyone may `prune` an expired record to
+    /// free its storage.
+    #[ink(storage)]
+    pub struct NameRegistry {
+        /// The account that sets the fee and collects the fees.
+        owner: AccountId,
+        /// The native funds to pay per registration period.
+        fee: Lazy<Balance>,
+        /// The length of a registration period in blocks.
+        period: BlockNumber,
+        /// The fees paid which were not withdrawn yet.
+        collected_fees: Lazy<Balance>,
+        /// Mapping from the hash of a name to its registration.
+        records: StorageHashMap<Hash, Record>,
+    }
+
+    /// Event emitted when a name is registered.
+    #[ink(event)]
+    pub struct Registered {
+        name: String,
+        #[ink(topic)]
+        owner: AccountId,
+        target: AccountId,
+        expires_at: BlockNumber,
+    }
+
+    /// Event emitted when the registration of a name is extended.
+    #[ink(event)]
+    pub struct Renewed {
+        name: String,
+        expires_at: BlockNumber,
+    }
+
+    /// Event emitted when the account a name resolves to changes.
+    #[ink(event)]
+    pub struct TargetChanged {
+        name: String,
+        #[ink(topic)]
+        target: AccountId,
+    }
+
+    /// Event emitted when a name is transferred to a new owner.
+    #[ink(event)]
+    pub struct Transfer {
+        name: String,
+        #[ink(topic)]
+        from: AccountId,
+        #[ink(topic)]
+        to: AccountId,
+    }
+
+    /// Event emitted when the record of an expired name is removed.
+    #[ink(event)]
+    pub struct Pruned {
+        name: String,
+    }
+
+    /// The name registry error types.
+    #[derive(Debug, PartialEq, Eq, scale::Encode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum Error {
+        /// Returned if the name is empty or longer than `MAX_NAME_LENGTH` bytes.
+        InvalidName,
+        /// Returned if zero periods are paid for.
+        ZeroPeriods,
+        /// Returned if the funds sent along are not the fee for the periods.
+        WrongFee,
+        /// Returned if the name is registered and not expired.
+        NameTaken,
+        /// Returned if the name is not registered or expired.
+        NameNotRegistered,
+        /// Returned if the registration of the name did not expire.
+        NotExpired,
+        /// Returned if the caller does not own the name.
+        NotNameOwner,
+        /// Returned if the caller is not the owner of the registry.
+        NotOwner,
+        /// Returned if the fee or the expiry overflows.
+        Overflow,
+        /// Returned if the fees could not be transferred.
+        TransferFailed,
+    }
+
+    /// The name registry result type.
+    pub type Result<T> = core::result::Result<T, Error>;
+
+    impl NameRegistry {
+        /// Creates a registry owned by the caller which registers names for
+        /// `fee` native funds per `period` blocks.
+        ///
+        /// # Panics
+        ///
+        /// If `period` is zero.
+        #[ink(constructor)]
+        pub fn new(fee: Balance, period: BlockNumber) -> Self {
+            assert!(period > 0, "invalid period");
+            Self {
+                owner: Self::env().caller(),
+                fee: Lazy::new(fee),
+                period,
+                collected_fees: Lazy::new(0),
+                records: StorageHashMap::new(),
+            }
+        }
+
+        /// Returns the account that sets the fee and collects the fees.
+        #[ink(message)]
+        pub fn owner(&self) -> AccountId {
+            self.owner
+        }
+
+        /// Returns the native funds to pay per registration period.
+        #[ink(message)]
+        pub fn fee(&self) -> Balance {
+            *self.fee
+        }
+
+        /// Returns the length of a registration period in blocks.
+        #[ink(message)]
+        pub fn period(&self) -> BlockNumber {
+            self.period
+        }
+
+        /// Returns the fees paid which were not withdrawn yet.
+        #[ink(message)]
+        pub fn collected_fees(&self) -> Balance {
+            *self.collected_fees
+        }
+
+        /// Returns the record of `name`, even if it expired but was not pruned.
+        #[ink(message)]
+        pub fn record(&self, name: String) -> Option<Record> {
+            self.records.get(&self.name_hash(&name)).copied()
+        }
+
+        /// Returns the account `name` resolves to, or `None` if it is not
+        /// registered or expired.
+        #[ink(message)]
+        pub fn resolve(&self, name: String) -> Option<AccountId> {
+            self.live_record(&name).ok().map(|record| record.target)
+        }
+
+        /// Registers `name` for the caller for `periods` periods, resolving to
+        /// `target`, and returns the block it expires in.
+        ///
+        /// The fee for all periods has to be sent along. On success a
+        /// `Registered` event is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `InvalidName` error if `name` is empty or longer than
+        /// `MAX_NAME_LENGTH` bytes.
+        ///
+        /// Returns `ZeroPeriods` error if `periods` is zero.
+        ///
+        /// Returns `WrongFee` error if the funds sent along are not the fee for
+        /// `periods` periods.
+        ///
+        /// Returns `NameTaken` error if `name` is registered and not expired.
+        ///
+        /// All errors revert the call, so the funds sent along are kept by the
+        /// caller.
+        #[ink(message, payable)]
+        pub fn register(
+            &mut self,
+            name: String,
+            target: AccountId,
+            periods: u32,
+        ) -> Result<BlockNumber> {
+            let owner = self.env().caller();
+            let value = self.env().transferred_balance();
+            match self.register_name(owner, name, target, periods, value) {
+                Ok(expires_at) => Ok(expires_at),
+                Err(error) => Self::revert(error),
+            }
+        }
+
+        /// Extends the registration of `name` by `periods` periods and returns
+        /// the block it expires in.
+        ///
+        /// Anyone may renew a name by sending along the fee for all periods. On
+        /// success a `Renewed` event is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `NameNotRegistered` error if `name` is not registered or
+        /// expired.
+        ///
+        /// Returns `ZeroPeriods` error if `periods` is zero.
+        ///
+        /// Returns `WrongFee` error if the funds sent along are not the fee for
+        /// `periods` periods.
+        ///
+        /// All errors revert the call, so the funds sent along are kept by the
+        /// caller.
+        #[ink(message, payable)]
+        pub fn renew(&mut self, name: String, periods: u32) -> Result<BlockNumber> {
+            let value = self.env().transferred_balance();
+            match self.renew_name(name, periods, value) {
+                Ok(expires_at) => Ok(expires_at),
+                Err(error) => Self::revert(error),
+            }
+        }
+
+        /// Makes `name` resolve to `target`.
+        ///
+        /// On success a `TargetChanged` event is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `NameNotRegistered` error if `name` is not registered or
+        /// expired.
+        ///
+        /// Returns `NotNameOwner` error if the caller does not own `name`.
+        #[ink(message)]
+        pub fn set_target(&mut self, name: String, target: AccountId) -> Result<()> {
+            let mut record = self.owned_record(&name)?;
+            record.target = target;
+            self.records.insert(self.name_hash(&name), record);
+            self.env().emit_event(TargetChanged { name, target });
+            Ok(())
+        }
+
+        /// Transfers `name` to `to`, which can change and renew it from then on.
+        ///
+        /// The account the name resolves to does not change. On success a
+        /// `Transfer` event is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `NameNotRegistered` error if `name` is not registered or
+        /// expired.
+        ///
+        /// Returns `NotNameOwner` error if the caller does not own `name`.
+        #[ink(message)]
+        pub fn transfer(&mut self, name: String, to: AccountId) -> Result<()> {
+            let mut record = self.owned_record(&name)?;
+            let from = record.owner;
+            record.owner = to;
+            self.records.insert(self.name_hash(&name), record);
+            self.env().emit_event(Transfer { name, from, to });
+            Ok(())
+        }
+
+        /// Removes the record of the expired `name`.
+        ///
+        /// Anyone may prune an expired name. On success a `Pruned` event is
+        /// emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `NameNotRegistered` error if there is no record of `name`.
+        ///
+        /// Returns `NotExpired` error if the registration of `name` did not
+        /// expire.
+        #[ink(message)]
+        pub fn prune(&mut self, name: String) -> Result<()> {
+            let hash = self.name_hash(&name);
+            let record = self.records.get(&hash).ok_or(Error::NameNotRegistered)?;
+            if !self.is_expired(record) {
+                return Err(Error::NotExpired)
+            }
+            self.records.take(&hash);
+            self.env().emit_event(Pruned { name });
+            Ok(())
+        }
+
+        /// Sets the native funds to pay per registration period to `fee`.
+        ///
+        /// Names registered before keep their expiry.
+        ///
+        /// # Errors
+        ///
+        /// Returns `NotOwner` error if the caller is not the owner.
+        #[ink(message)]
+        pub fn set_fee(&mut self, fee: Balance) -> Result<()> {
+            if self.env().caller() != self.owner {
+                return Err(Error::NotOwner)
+            }
+            *self.fee = fee;
+            Ok(())
+        }
+
+        /// Sends the collected fees to the owner and returns their amount.
+        ///
+        /// # Errors
+        ///
+        /// Returns `NotOwner` error if the caller is not the owner.
+        ///
+        /// Returns `TransferFailed` error if the fees could not be transferred.
+        #[ink(message)]
+        pub fn withdraw_fees(&mut self) -> Result<Balance> {
+            if self.env().caller() != self.owner {
+                return Err(Error::NotOwner)
+            }
+            let fees = *self.collected_fees;
+            // The fees are cleared once they reached the owner, who runs no code on
+            // receiving them.
+            if fees > 0 && self.env().transfer(self.owner, fees).is_err() {
+                return Err(Error::TransferFailed)
+            }
+            *self.collected_fees = 0;
+            Ok(fees)
+        }
+
+        /// Registers `name` for `owner` for `periods` periods, paid with `value`
+        /// native funds, and returns the block it expires in.
+        fn register_name(
+            &mut self,
+            owner: AccountId,
+            name: String,
+            target: AccountId,
+            periods: u32,
+            value: Balance,
+        ) -> Result<BlockNumber> {
+            if name.is_empty() || name.len() > MAX_NAME_LENGTH {
+                return Err(Error::InvalidName)
+            }
+            let hash = self.name_hash(&name);
+            if matches!(self.records.get(&hash), Some(record) if !self.is_expired(record))
+            {
+                return Err(Error::NameTaken)
+            }
+            let expires_at = self.extend(self.env().block_number(), periods, value)?;
+            self.records.insert(
+                hash,
+                Record {
+                    owner,
+                    target,
+                    expires_at,
+                },
+            );
+            self.env().emit_event(Registered {
+                name,
+                owner,
+                target,
+                expires_at,
+            });
+            Ok(expires_at)
+        }
+
+        /// Extends the registration of `name` by `periods` periods, paid with
+        /// `value` native funds, and returns the block it expires in.
+        fn renew_name(
+            &mut self,
+            name: String,
+            periods: u32,
+            value: Balance,
+        ) -> Result<BlockNumber> {
+            let mut record = self.live_record(&name)?;
+            record.expires_at = self.extend(record.expires_at, periods, value)?;
+            self.records.insert(self.name_hash(&name), record);
+            self.env().emit_event(Renewed {
+                name,
+                expires_at: record.expires_at,
+            });
+            Ok(record.expires_at)
+        }
+
+        /// Collects `value` as the fee for `periods` periods and returns the
+        /// block `periods` periods after `from`.
+        fn extend(
+            &mut self,
+            from: BlockNumber,
+            periods: u32,
+            value: Balance,
+        ) -> Result<BlockNumber> {
+            if periods == 0 {
+                return Err(Error::ZeroPeriods)
+            }
+            let fee = self
+                .fee
+                .checked_mul(Balance::from(periods))
+                .ok_or(Error::Overflow)?;
+            if value != fee {
+                return Err(Error::WrongFee)
+            }
+            let expires_at = self
+                .period
+                .checked_mul(periods)
+                .and_then(|blocks| from.checked_add(blocks))
+                .ok_or(Error::Overflow)?;
+            *self.collected_fees += fee;
+            Ok(expires_at)
+        }
+
+        /// Returns the record of `name` if it is registered and not expired.
+        fn live_record(&self, name: &str) -> Result<Record> {
+            self.records
+                .get(&self.name_hash(name))
+                .filter(|record| !self.is_expired(record))
+                .copied()
+                .ok_or(Error::NameNotRegistered)
+        }
+
+        /// Returns the record of `name` if it is registered, not expired and
+        /// owned by the caller.
+        fn owned_record(&self, name: &str) -> Result<Record> {
+            let record = self.live_record(name)?;
+            if record.owner != self.env().caller() {
+                return Err(Error::NotNameOwner)
+            }
+            Ok(record)
+        }
+
+        /// Returns `true` if the registration of `record` expired.
+        fn is_expired(&self, record: &Record) -> bool {
+            self.env().block_number() >= record.expires_at
+        }
+
+        /// Returns the key of `name` in the records.
+        fn name_hash(&self, name: &str) -> Hash {
+            Hash::from(self.env().hash_bytes::<Blake2x256>(name.as_bytes()))
+        }
+
+        /// Ends the current call with `error`, so the fee sent along goes back to
+        /// the caller.
+        fn revert(error: Error) -> ! {
+            ink_env::return_value::<Result<()>>(
+                ReturnFlags::default().set_reverted(true),
+                &Err(error),
+            )
+        }
+    }
+
+    /// Unit tests.
+    ///
+    /// The off-chain environment does not support reverting, so registrations
+    /// and renewals are checked with the funds passed in directly.
+    #[cfg(test)]
+    mod tests {
+        /// Imports all the definitions from the outer scope so we can use them here.
+        use super::*;
+
+        use ink_lang as ink;
+        use test_utils::{
+            advance_blocks,
+            set_caller,
+        };
+
+        fn native_balance_of(account: AccountId) -> Balance {
+            ink_env::test::get_account_balance::<ink_env::DefaultEnvironment>(account)
+                .expect("Cannot get account balance")
+        }
+
+        fn name(name: &str) -> String {
+            String::from(name)
+        }
+
+        /// Returns a registry owned by Alice charging `10` per two blocks.
+        fn registry() -> NameRegistry {
+            NameRegistry::new(10, 2)
+        }
+
+        #[ink::test]
+        fn new_works() {
+            let registry = registry();
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            assert_eq!(registry.owner(), accounts.alice);
+            assert_eq!(registry.fee(), 10);
+            assert_eq!(registry.period(), 2);
+            assert_eq!(registry.collected_fees(), 0);
+            assert_eq!(registry.resolve(name("alice")), None);
+        }
+
+        #[ink::test]
+        #[should_panic(expected = "invalid period")]
+        fn new_rejects_zero_period() {
+            NameRegistry::new(10, 0);
+        }
+
+        #[ink::test]
+        fn register_works() {
+            let mut registry = registry();
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let (alice, bob) = (accounts.alice, accounts.bob);
+
+            assert_eq!(
+                registry.register_name(alice, name(""), bob, 1, 10),
+                Err(Error::InvalidName)
+            );
+            assert_eq!(
+                registry.register_name(alice, "a".repeat(65), bob, 1, 10),
+                Err(Error::InvalidName)
+            );
+            assert_eq!(
+                registry.register_name(alice, name("alice"), bob, 0, 0),
+                Err(Error::ZeroPeriods)
+            );
+            assert_eq!(
+                registry.register_name(alice, name("alice"), bob, 2, 10),
+                Err(Error::WrongFee)
+            );
+            assert_eq!(
+                registry.register_name(alice, name("alice"), bob, 2, 20),
+                Ok(4)
+            );
+            assert_eq!(
+                registry.register_name(bob, name("alice"), bob, 1, 10),
+                Err(Error::NameTaken)
+            );
+            assert_eq!(registry.resolve(name("alice")), Some(bob));
+            assert_eq!(
+                registry.record(name("alice")),
+                Some(Record {
+                    owner: alice,
+                    target: bob,
+                    expires_at: 4,
+                })
+            );
+            assert_eq!(registry.collected_fees(), 20);
+            assert_eq!(ink_env::test::recorded_events().count(), 1);
+        }
+
+        #[ink::test]
+        fn names_expire_and_are_renewed() {
+            let mut registry = registry();
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let (alice, bob) = (accounts.alice, accounts.bob);
+
+            registry
+                .register_name(alice, name("alice"), alice, 1, 10)
+                .expect("Cannot register name");
+            advance_blocks!(1);
+            assert_eq!(
+                registry.renew_name(name("alice"), 1, 5),
+                Err(Error::WrongFee)
+            );
+            assert_eq!(registry.renew_name(name("alice"), 1, 10), Ok(4));
+            assert_eq!(registry.prune(name("alice")), Err(Error::NotExpired));
+
+            advance_blocks!(3);
+            assert_eq!(registry.resolve(name("alice")), None);
+            assert_eq!(
+                registry.renew_name(name("alice"), 1, 10),
+                Err(Error::NameNotRegistered)
+            );
+            assert_eq!(
+                registry.register_name(bob, name("alice"), bob, 1, 10),
+                Ok(6)
+            );
+            assert_eq!(registry.resolve(name("alice")), Some(bob));
+            assert_eq!(registry.collected_fees(), 30);
+        }
+
+        #[ink::test]
+        fn expired_names_are_pruned() {
+            let mut registry = registry();
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            registry
+                .register_name(accounts.alice, name("alice"), accounts.alice, 1, 10)
+                .expect("Cannot register name");
+            assert_eq!(registry.prune(name("bob")), Err(Error::NameNotRegistered));
+            advance_blocks!(2);
+
+            set_caller!(accounts.bob);
+            assert_eq!(registry.prune(name("alice")), Ok(()));
+            assert_eq!(registry.record(name("alice")), None);
+        }
+
+        #[ink::test]
+        fn owners_change_and_transfer_names() {
+            let mut registry = registry();
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let (alice, bob) = (accounts.alice, accounts.bob);
+
+            registry
+                .register_name(alice, name("alice"), alice, 1, 10)
+                .expect("Cannot register name");
+            assert_eq!(registry.set_target(name("alice"), bob), Ok(()));
+            assert_eq!(registry.resolve(name("alice")), Some(bob));
+            assert_eq!(registry.transfer(name("alice"), bob), Ok(()));
+            assert_eq!(
+                registry.set_target(name("alice"), alice),
+                Err(Error::NotNameOwner)
+            );
+            assert_eq!(
+                registry.transfer(name("bob"), bob),
+                Err(Error::NameNotRegistered)
+            );
+
+            set_caller!(bob);
+            assert_eq!(registry.transfer(name("alice"), alice), Ok(()));
+            assert_eq!(registry.record(name("alice")).map(|r| r.owner), Some(alice));
+        }
+
+        #[ink::test]
+        fn owner_withdraws_fees() {
+            let mut registry = registry();
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let contract = ink_env::account_id::<ink_env::DefaultEnvironment>()
+                .expect("Cannot get contract id");
+
+            registry
+                .register_name(accounts.bob, name("bob"), accounts.bob, 3, 30)
+                .expect("Cannot register name");
+            ink_env::test::set_account_balance::<ink_env::DefaultEnvironment>(
+                contract, 30,
+            )
+            .expect("Cannot set contract balance");
+            assert_eq!(registry.set_fee(20), Ok(()));
+            assert_eq!(registry.fee(), 20);
+            let alice_native = native_balance_of(accounts.alice);
+            assert_eq!(registry.withdraw_fees(), Ok(30));
+            assert_eq!(native_balance_of(accounts.alice), alice_native + 30);
+            assert_eq!(registry.collected_fees(), 0);
+
+            set_caller!(accounts.bob);
+            assert_eq!(registry.set_fee(0), Err(Error::NotOwner));
+            assert_eq!(registry.withdraw_fees(), Err(Error::NotOwner));
+        }
+    }
+}
\ No newline at end of file
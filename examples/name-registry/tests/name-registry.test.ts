@@ -0,0 +1,96 @@
+import BN from 'bn.js';
+import { expect } from 'chai';
+import { patract, network } from 'redspot';
+
+const { getContractFactory, getRandomSigner } = patract;
+
+const { api, getSigners } = network;
+
+describe('NAME REGISTRY', () => {
+  after(() => {
+    return api.disconnect();
+  });
+
+  async function setup() {
+    const one = new BN(10).pow(new BN(api.registry.chainDecimals[0]));
+    const signers = await getSigners();
+    const Alice = signers[0];
+    const sender = await getRandomSigner(Alice, one.muln(10000));
+    const receiver = await getRandomSigner(Alice, one.muln(10000));
+    const contractFactory = await getContractFactory('name_registry', sender);
+    const contract = await contractFactory.deploy('new', one, 3);
+
+    // The development node seals a block for every transaction, so blocks pass
+    // by sending transactions which change nothing.
+    async function advanceBlocks(blocks: number) {
+      for (let i = 0; i < blocks; i++) {
+        await contract.tx.setFee(one);
+      }
+    }
+
+    return {
+      sender,
+      receiver,
+      contractFactory,
+      contract,
+      advanceBlocks,
+      Alice,
+      one
+    };
+  }
+
+  it('Registers and resolves names', async () => {
+    const { contract, receiver, one } = await setup();
+
+    await expect(
+      contract.tx.register('redspot', receiver.address, 2, { value: one })
+    ).to.not.emit(contract, 'Registered');
+    await expect(
+      contract.tx.register('redspot', receiver.address, 2, {
+        value: one.muln(2)
+      })
+    ).to.emit(contract, 'Registered');
+    await expect(
+      contract.tx.register('redspot', receiver.address, 1, {
+        signer: receiver,
+        value: one
+      })
+    ).to.not.emit(contract, 'Registered');
+
+    expect((await contract.query.resolve('redspot')).output).to.equal(
+      receiver.address
+    );
+  });
+
+  it('Owners transfer names', async () => {
+    const { contract, sender, receiver, one } = await setup();
+
+    await contract.tx.register('redspot', sender.address, 1, { value: one });
+
+    await expect(contract.tx.transfer('redspot', receiver.address))
+      .to.emit(contract, 'Transfer')
+      .withArgs('redspot', sender.address, receiver.address);
+    await expect(
+      contract.tx.setTarget('redspot', sender.address)
+    ).to.not.emit(contract, 'TargetChanged');
+    await expect(
+      contract.tx.setTarget('redspot', receiver.address, { signer: receiver })
+    ).to.emit(contract, 'TargetChanged');
+  });
+
+  it('Expired names can be registered again', async () => {
+    const { contract, receiver, advanceBlocks, one } = await setup();
+
+    await contract.tx.register('redspot', receiver.address, 1, { value: one });
+    await expect(contract.tx.prune('redspot')).to.not.emit(contract, 'Pruned');
+
+    await advanceBlocks(3);
+
+    await expect(
+      contract.tx.register('redspot', receiver.address, 1, {
+        signer: receiver,
+        value: one
+      })
+    ).to.emit(contract, 'Registered');
+  });
+});
@@ -0,0 +1,344 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use ink_lang as ink;
+
+#[ink::contract]
+mod oracle {
+    use ink_prelude::vec::Vec;
+    #[cfg(not(feature = "ink-as-dependency"))]
+    use ink_storage::collections::HashMap as StorageHashMap;
+    use ink_storage::traits::{
+        PackedLayout,
+        SpreadLayout,
+    };
+
+    /// A price reported for a token.
+    #[derive(
+        Debug,
+        Copy,
+        Clone,
+        PartialEq,
+        Eq,
+        scale::Encode,
+        scale::Decode,
+        SpreadLayout,
+        PackedLayout,
+    )]
+    #[cfg_attr(
+        feature = "std",
+        derive(::scale_info::TypeInfo, ::ink_storage::traits::StorageLayout)
+    )]
+    pub struct PriceData {
+        /// The price of the token.
+        pub price: Balance,
+        /// The block timestamp the price was reported at.
+        pub updated_at: Timestamp,
+    }
+
+    /// Reports prices of tokens to other contracts.
+    ///
+    /// Prices are set by updaters, which the owner grants and revokes, and are
+    /// given in a unit of the updaters' choice, so only prices of the same
+    /// oracle can be compared. Readers pass the oldest price they accept to
+    /// `price`, so a feed which stopped being updated is not used silently.
+    ///
+    /// The lending and the AMM pair examples read their prices with `price`.
+    #[ink(storage)]
+    pub struct Oracle {
+        /// The account allowed to grant and revoke the updater role.
+        owner: AccountId,
+        /// The accounts allowed to set prices.
+        updaters: StorageHashMap<AccountId, ()>,
+        /// Mapping from token to its last reported price.
+        prices: StorageHashMap<AccountId, PriceData>,
+    }
+
+    /// Event emitted when the price of a token is set.
+    #[ink(event)]
+    pub struct PriceUpdated {
+        #[ink(topic)]
+        token: AccountId,
+        price: Balance,
+        updated_at: Timestamp,
+    }
+
+    /// Event emitted when an account is granted the updater role.
+    #[ink(event)]
+    pub struct UpdaterGranted {
+        #[ink(topic)]
+        account: AccountId,
+    }
+
+    /// Event emitted when the updater role is revoked from an account.
+    #[ink(event)]
+    pub struct UpdaterRevoked {
+        #[ink(topic)]
+        account: AccountId,
+    }
+
+    /// The oracle error types.
+    #[derive(Debug, PartialEq, Eq, scale::Encode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum Error {
+        /// Returned if no price was ever reported for the token.
+        PriceNotSet,
+        /// Returned if the price of the token is older than the reader accepts.
+        StalePrice,
+        /// Returned if the caller is not the owner.
+        NotOwner,
+        /// Returned if the caller does not hold the updater role.
+        NotUpdater,
+    }
+
+    /// The oracle result type.
+    pub type Result<T> = core::result::Result<T, Error>;
+
+    impl Oracle {
+        /// Creates an oracle owned by the caller with `updaters` holding the
+        /// updater role.
+        #[ink(constructor)]
+        pub fn new(updaters: Vec<AccountId>) -> Self {
+            Self {
+                owner: Self::env().caller(),
+                updaters: updaters.into_iter().map(|account| (account, ())).collect(),
+                prices: StorageHashMap::new(),
+            }
+        }
+
+        /// Returns the account allowed to grant and revoke the updater role.
+        #[ink(message)]
+        pub fn owner(&self) -> AccountId {
+            self.owner
+        }
+
+        /// Returns `true` if `account` holds the updater role.
+        #[ink(message)]
+        pub fn is_updater(&self, account: AccountId) -> bool {
+            self.updaters.contains_key(&account)
+        }
+
+        /// Returns the last reported price of `token` and when it was reported,
+        /// however old it is.
+        #[ink(message)]
+        pub fn latest(&self, token: AccountId) -> Option<PriceData> {
+            self.prices.get(&token).copied()
+        }
+
+        /// Returns the price of `token` if it was reported within the last
+        /// `max_age` milliseconds.
+        ///
+        /// # Errors
+        ///
+        /// Returns `PriceNotSet` error if no price was ever reported for `token`.
+        ///
+        /// Returns `StalePrice` error if the price is older than `max_age`.
+        #[ink(message)]
+        pub fn price(&self, token: AccountId, max_age: Timestamp) -> Result<Balance> {
+            let data = self.latest(token).ok_or(Error::PriceNotSet)?;
+            if self.env().block_timestamp().saturating_sub(data.updated_at) > max_age {
+                return Err(Error::StalePrice)
+            }
+            Ok(data.price)
+        }
+
+        /// Sets the price of `token` to `price` as of the current block.
+        ///
+        /// On success a `PriceUpdated` event is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `NotUpdater` error if the caller does not hold the updater
+        /// role.
+        #[ink(message)]
+        pub fn set_price(&mut self, token: AccountId, price: Balance) -> Result<()> {
+            self.ensure_updater()?;
+            self.store_price(token, price);
+            Ok(())
+        }
+
+        /// Sets the prices of several tokens at once, each given with its price.
+        ///
+        /// On success a `PriceUpdated` event is emitted for every token.
+        ///
+        /// # Errors
+        ///
+        /// Returns `NotUpdater` error if the caller does not hold the updater
+        /// role.
+        #[ink(message)]
+        pub fn set_prices(&mut self, prices: Vec<(AccountId, Balance)>) -> Result<()> {
+            self.ensure_updater()?;
+            for (token, price) in prices {
+                self.store_price(token, price);
+            }
+            Ok(())
+        }
+
+        /// Grants the updater role to `account`.
+        ///
+        /// On success an `UpdaterGranted` event is emitted if `account` did not
+        /// hold the role yet.
+        ///
+        /// # Errors
+        ///
+        /// Returns `NotOwner` error if the caller is not the owner.
+        #[ink(message)]
+        pub fn grant_updater(&mut self, account: AccountId) -> Result<()> {
+            self.ensure_owner()?;
+            if self.updaters.insert(account, ()).is_none() {
+                self.env().emit_event(UpdaterGranted { account });
+            }
+            Ok(())
+        }
+
+        /// Revokes the updater role from `account`.
+        ///
+        /// Prices it set stay until they are updated. On success an
+        /// `UpdaterRevoked` event is emitted if `account` held the role.
+        ///
+        /// # Errors
+        ///
+        /// Returns `NotOwner` error if the caller is not the owner.
+        #[ink(message)]
+        pub fn revoke_updater(&mut self, account: AccountId) -> Result<()> {
+            self.ensure_owner()?;
+            if self.updaters.take(&account).is_some() {
+                self.env().emit_event(UpdaterRevoked { account });
+            }
+            Ok(())
+        }
+
+        /// Stores `price` for `token` as of the current block.
+        fn store_price(&mut self, token: AccountId, price: Balance) {
+            let updated_at = self.env().block_timestamp();
+            self.prices.insert(token, PriceData { price, updated_at });
+            self.env().emit_event(PriceUpdated {
+                token,
+                price,
+                updated_at,
+            });
+        }
+
+        /// Returns `NotOwner` error if the caller is not the owner.
+        fn ensure_owner(&self) -> Result<()> {
+            if self.env().caller() != self.owner {
+                return Err(Error::NotOwner)
+            }
+            Ok(())
+        }
+
+        /// Returns `NotUpdater` error if the caller does not hold the updater
+        /// role.
+        fn ensure_updater(&self) -> Result<()> {
+            if !self.is_updater(self.env().caller()) {
+                return Err(Error::NotUpdater)
+            }
+            Ok(())
+        }
+    }
+
+    /// Unit tests.
+    #[cfg(test)]
+    mod tests {
+        /// Imports all the definitions from the outer scope so we can use them here.
+        use super::*;
+
+        use ink_lang as ink;
+        use test_utils::set_caller;
+
+        fn now() -> Timestamp {
+            ink_env::block_timestamp::<ink_env::DefaultEnvironment>()
+                .expect("Cannot get block timestamp")
+        }
+
+        /// Returns an oracle owned by Alice with Bob as updater.
+        fn oracle() -> Oracle {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            Oracle::new(vec![accounts.bob])
+        }
+
+        #[ink::test]
+        fn new_works() {
+            let oracle = oracle();
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            assert_eq!(oracle.owner(), accounts.alice);
+            assert!(oracle.is_updater(accounts.bob));
+            assert!(!oracle.is_updater(accounts.alice));
+            assert_eq!(oracle.latest(AccountId::from([0x07; 32])), None);
+        }
+
+        #[ink::test]
+        fn only_updaters_set_prices() {
+            let mut oracle = oracle();
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let token = AccountId::from([0x07; 32]);
+
+            assert_eq!(oracle.set_price(token, 100), Err(Error::NotUpdater));
+            assert_eq!(oracle.price(token, 0), Err(Error::PriceNotSet));
+
+            set_caller!(accounts.bob);
+            assert_eq!(oracle.set_price(token, 100), Ok(()));
+            assert_eq!(
+                oracle.latest(token),
+                Some(PriceData {
+                    price: 100,
+                    updated_at: now(),
+                })
+            );
+            assert_eq!(oracle.price(token, 0), Ok(100));
+            assert_eq!(
+                oracle.set_prices(vec![(token, 120), (AccountId::from([0x08; 32]), 5)]),
+                Ok(())
+            );
+            assert_eq!(oracle.price(token, 0), Ok(120));
+            assert_eq!(oracle.price(AccountId::from([0x08; 32]), 0), Ok(5));
+            assert_eq!(ink_env::test::recorded_events().count(), 3);
+        }
+
+        #[ink::test]
+        fn reads_reject_stale_prices() {
+            let mut oracle = oracle();
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let token = AccountId::from([0x07; 32]);
+
+            set_caller!(accounts.bob);
+            oracle.set_price(token, 100).expect("Cannot set price");
+            let updated_at = now();
+            ink_env::test::advance_block::<ink_env::DefaultEnvironment>()
+                .expect("Cannot advance block");
+            let age = now() - updated_at;
+
+            assert_eq!(oracle.price(token, age), Ok(100));
+            assert_eq!(oracle.price(token, age - 1), Err(Error::StalePrice));
+        }
+
+        #[ink::test]
+        fn owner_grants_and_revokes_updaters() {
+            let mut oracle = oracle();
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            assert_eq!(oracle.grant_updater(accounts.charlie), Ok(()));
+            assert!(oracle.is_updater(accounts.charlie));
+            assert_eq!(oracle.revoke_updater(accounts.bob), Ok(()));
+            assert!(!oracle.is_updater(accounts.bob));
+            assert_eq!(ink_env::test::recorded_events().count(), 2);
+
+            set_caller!(accounts.charlie);
+            assert_eq!(oracle.grant_updater(accounts.django), Err(Error::NotOwner));
+            assert_eq!(
+                oracle.revoke_updater(accounts.charlie),
+                Err(Error::NotOwner)
+            );
+        }
+    }
+}
\ No newline at end of file
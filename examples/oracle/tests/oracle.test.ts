@@ -0,0 +1,56 @@
+import BN from 'bn.js';
+import { expect } from 'chai';
+import { patract, network } from 'redspot';
+
+const { getContractFactory, getRandomSigner } = patract;
+
+const { api, getSigners } = network;
+
+describe('ORACLE', () => {
+  after(() => {
+    return api.disconnect();
+  });
+
+  async function setup() {
+    const one = new BN(10).pow(new BN(api.registry.chainDecimals[0]));
+    const signers = await getSigners();
+    const Alice = signers[0];
+    const sender = await getRandomSigner(Alice, one.muln(10000));
+    const updater = await getRandomSigner(Alice, one.muln(10000));
+    const contractFactory = await getContractFactory('oracle', sender);
+    const contract = await contractFactory.deploy('new', [updater.address]);
+
+    return { sender, updater, contractFactory, contract, Alice, one };
+  }
+
+  it('Updaters set prices', async () => {
+    const { contract, updater, Alice } = await setup();
+
+    await expect(
+      contract.tx.setPrice(Alice.address, 100, { signer: updater })
+    ).to.emit(contract, 'PriceUpdated');
+    const latest = await contract.query.latest(Alice.address);
+    expect(latest.output?.toString()).to.contain('100');
+  });
+
+  it('Others can not set prices', async () => {
+    const { contract, Alice } = await setup();
+
+    await expect(contract.tx.setPrice(Alice.address, 100)).to.not.emit(
+      contract,
+      'PriceUpdated'
+    );
+  });
+
+  it('Owner grants the updater role', async () => {
+    const { contract, sender, Alice } = await setup();
+
+    await expect(contract.tx.grantUpdater(sender.address))
+      .to.emit(contract, 'UpdaterGranted')
+      .withArgs(sender.address);
+    await expect(contract.tx.setPrice(Alice.address, 100)).to.emit(
+      contract,
+      'PriceUpdated'
+    );
+  });
+});
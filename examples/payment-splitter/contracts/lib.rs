@@ -0,0 +1,472 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use ink_lang as ink;
+
+#[ink::contract]
+mod payment_splitter {
+    use erc20::{
+        BALANCE_OF_SELECTOR,
+        TRANSFER_SELECTOR,
+    };
+    use ink_env::{
+        call::{
+            build_call,
+            utils::ReturnType,
+            ExecutionInput,
+            Selector,
+        },
+        DefaultEnvironment,
+        ReturnFlags,
+    };
+    use ink_prelude::vec::Vec;
+    #[cfg(not(feature = "ink-as-dependency"))]
+    use ink_storage::{
+        collections::HashMap as StorageHashMap,
+        lazy::Lazy,
+    };
+
+    /// Splits the native funds and erc20 tokens it receives between a fixed set
+    /// of payees in proportion to their shares.
+    ///
+    /// The splitter is funded by the endowment, plain native transfers, `receive`
+    /// and erc20 transfers to it. Payees are not paid on arrival of the funds,
+    /// instead anyone may release the part owed to a payee, which is its share
+    /// of everything ever received minus what was released to it already.
+    #[ink(storage)]
+    pub struct PaymentSplitter {
+        /// The accounts funds are split between, in the order they were given.
+        payees: Lazy<Vec<AccountId>>,
+        /// Mapping from payee to its shares.
+        shares: StorageHashMap<AccountId, Balance>,
+        /// The sum of the shares of all payees.
+        total_shares: Balance,
+        /// The native funds released so far.
+        total_released: Lazy<Balance>,
+        /// Mapping from payee to the native funds released to it so far.
+        released: StorageHashMap<AccountId, Balance>,
+        /// Mapping from token to the amount of it released so far.
+        token_total_released: StorageHashMap<AccountId, Balance>,
+        /// Mapping from token and payee to the amount of the token released to
+        /// the payee so far.
+        token_released: StorageHashMap<(AccountId, AccountId), Balance>,
+    }
+
+    /// Event emitted when native funds are sent to `receive`.
+    #[ink(event)]
+    pub struct PaymentReceived {
+        #[ink(topic)]
+        from: AccountId,
+        value: Balance,
+    }
+
+    /// Event emitted when funds are released to a payee.
+    ///
+    /// `token` is `None` for native funds.
+    #[ink(event)]
+    pub struct PaymentReleased {
+        #[ink(topic)]
+        token: Option<AccountId>,
+        #[ink(topic)]
+        to: AccountId,
+        amount: Balance,
+    }
+
+    /// The payment splitter error types.
+    #[derive(Debug, PartialEq, Eq, scale::Encode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum Error {
+        /// Returned if the account is not a payee.
+        NotPayee,
+        /// Returned if the token balance of the splitter could not be read.
+        TokenCallFailed,
+        /// Returned if funds could not be transferred.
+        TransferFailed,
+    }
+
+    /// The payment splitter result type.
+    pub type Result<T> = core::result::Result<T, Error>;
+
+    impl PaymentSplitter {
+        /// Creates a splitter between `payees`, each given with its shares.
+        ///
+        /// # Panics
+        ///
+        /// If `payees` is empty, lists an account twice or gives an account no
+        /// shares.
+        #[ink(constructor)]
+        pub fn new(payees: Vec<(AccountId, Balance)>) -> Self {
+            assert!(!payees.is_empty(), "invalid payees");
+            let mut accounts = Vec::new();
+            let mut shares = StorageHashMap::new();
+            let mut total_shares: Balance = 0;
+            for (payee, payee_shares) in payees {
+                assert!(
+                    payee_shares > 0 && shares.insert(payee, payee_shares).is_none(),
+                    "invalid payees"
+                );
+                accounts.push(payee);
+                total_shares = total_shares
+                    .checked_add(payee_shares)
+                    .expect("invalid payees");
+            }
+            Self {
+                payees: Lazy::new(accounts),
+                shares,
+                total_shares,
+                total_released: Lazy::new(0),
+                released: StorageHashMap::new(),
+                token_total_released: StorageHashMap::new(),
+                token_released: StorageHashMap::new(),
+            }
+        }
+
+        /// Returns the accounts funds are split between.
+        #[ink(message)]
+        pub fn payees(&self) -> Vec<AccountId> {
+            (*self.payees).clone()
+        }
+
+        /// Returns the shares of `account`, which are zero if it is not a payee.
+        #[ink(message)]
+        pub fn shares(&self, account: AccountId) -> Balance {
+            self.shares.get(&account).copied().unwrap_or(0)
+        }
+
+        /// Returns the sum of the shares of all payees.
+        #[ink(message)]
+        pub fn total_shares(&self) -> Balance {
+            self.total_shares
+        }
+
+        /// Returns the native funds released so far.
+        #[ink(message)]
+        pub fn total_released(&self) -> Balance {
+            *self.total_released
+        }
+
+        /// Returns the native funds released to `account` so far.
+        #[ink(message)]
+        pub fn released(&self, account: AccountId) -> Balance {
+            self.released.get(&account).copied().unwrap_or(0)
+        }
+
+        /// Returns the amount of `token` released so far.
+        #[ink(message)]
+        pub fn token_total_released(&self, token: AccountId) -> Balance {
+            self.token_total_released.get(&token).copied().unwrap_or(0)
+        }
+
+        /// Returns the amount of `token` released to `account` so far.
+        #[ink(message)]
+        pub fn token_released(&self, token: AccountId, account: AccountId) -> Balance {
+            self.token_released
+                .get(&(token, account))
+                .copied()
+                .unwrap_or(0)
+        }
+
+        /// Returns the native funds owed to `account` which were not released
+        /// yet.
+        ///
+        /// The splitter's minimum balance is never released.
+        #[ink(message)]
+        pub fn releasable(&self, account: AccountId) -> Balance {
+            let received = self.native_balance().saturating_add(*self.total_released);
+            self.owed(received, account)
+                .saturating_sub(self.released(account))
+        }
+
+        /// Returns the amount of `token` owed to `account` which was not released
+        /// yet.
+        ///
+        /// # Errors
+        ///
+        /// Returns `TokenCallFailed` error if the balance of the splitter could
+        /// not be read from `token`.
+        #[ink(message)]
+        pub fn token_releasable(
+            &self,
+            token: AccountId,
+            account: AccountId,
+        ) -> Result<Balance> {
+            let received = self
+                .token_balance(token)?
+                .saturating_add(self.token_total_released(token));
+            Ok(self
+                .owed(received, account)
+                .saturating_sub(self.token_released(token, account)))
+        }
+
+        /// Records native funds sent along as a payment.
+        ///
+        /// Plain native transfers are split all the same, this only emits a
+        /// `PaymentReceived` event.
+        #[ink(message, payable)]
+        pub fn receive(&mut self) {
+            self.env().emit_event(PaymentReceived {
+                from: self.env().caller(),
+                value: self.env().transferred_balance(),
+            });
+        }
+
+        /// Sends the releasable native funds to `account` and returns their
+        /// amount.
+        ///
+        /// Anyone may release the funds of a payee. On success a
+        /// `PaymentReleased` event is emitted if there were funds to release.
+        ///
+        /// # Errors
+        ///
+        /// Returns `NotPayee` error if `account` is not a payee.
+        ///
+        /// Returns `TransferFailed` error if the funds could not be sent.
+        #[ink(message)]
+        pub fn release(&mut self, account: AccountId) -> Result<Balance> {
+            self.ensure_payee(account)?;
+            let amount = self.releasable(account);
+            if amount == 0 {
+                return Ok(0)
+            }
+            // The payee runs no code on receiving the funds, so the release is
+            // recorded after it.
+            self.env()
+                .transfer(account, amount)
+                .map_err(|_| Error::TransferFailed)?;
+            *self.total_released += amount;
+            let released = self.released(account);
+            self.released.insert(account, released + amount);
+            self.env().emit_event(PaymentReleased {
+                token: None,
+                to: account,
+                amount,
+            });
+            Ok(amount)
+        }
+
+        /// Sends the releasable amount of `token` to `account` and returns it.
+        ///
+        /// Anyone may release the tokens of a payee. On success a
+        /// `PaymentReleased` event is emitted if there were tokens to release.
+        ///
+        /// # Errors
+        ///
+        /// Returns `NotPayee` error if `account` is not a payee.
+        ///
+        /// Returns `TokenCallFailed` error if the balance of the splitter could
+        /// not be read from `token`.
+        ///
+        /// Returns `TransferFailed` error, reverting all changes, if the tokens
+        /// could not be sent.
+        #[ink(message)]
+        pub fn token_release(
+            &mut self,
+            token: AccountId,
+            account: AccountId,
+        ) -> Result<Balance> {
+            self.ensure_payee(account)?;
+            let amount = self.token_releasable(token, account)?;
+            if amount == 0 {
+                return Ok(0)
+            }
+            let total_released = self.token_total_released(token);
+            self.token_total_released
+                .insert(token, total_released + amount);
+            let released = self.token_released(token, account);
+            self.token_released
+                .insert((token, account), released + amount);
+            if !self.token_transfer(token, account, amount) {
+                Self::revert(Error::TransferFailed)
+            }
+            self.env().emit_event(PaymentReleased {
+                token: Some(token),
+                to: account,
+                amount,
+            });
+            Ok(amount)
+        }
+
+        /// Returns the part of `received` owed to `account` by its shares.
+        fn owed(&self, received: Balance, account: AccountId) -> Balance {
+            let shares = self.shares(account);
+            let total = self.total_shares;
+            // Split `received * shares / total` so the multiplication can not
+            // overflow.
+            received / total * shares + received % total * shares / total
+        }
+
+        /// Returns the native funds of the splitter above its minimum balance.
+        fn native_balance(&self) -> Balance {
+            self.env()
+                .balance()
+                .saturating_sub(self.env().minimum_balance())
+        }
+
+        /// Returns the balance of the splitter in `token`.
+        fn token_balance(&self, token: AccountId) -> Result<Balance> {
+            build_call::<DefaultEnvironment>()
+                .callee(token)
+                .gas_limit(0)
+                .transferred_value(0)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(BALANCE_OF_SELECTOR))
+                        .push_arg(self.env().account_id()),
+                )
+                .returns::<ReturnType<Balance>>()
+                .fire()
+                .map_err(|_| Error::TokenCallFailed)
+        }
+
+        /// Transfers `value` of `token` from the splitter to `to` and returns
+        /// whether that succeeded.
+        fn token_transfer(
+            &self,
+            token: AccountId,
+            to: AccountId,
+            value: Balance,
+        ) -> bool {
+            let result = build_call::<DefaultEnvironment>()
+                .callee(token)
+                .gas_limit(0)
+                .transferred_value(0)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(TRANSFER_SELECTOR))
+                        .push_arg(to)
+                        .push_arg(value),
+                )
+                .returns::<ReturnType<core::result::Result<(), u8>>>()
+                .fire();
+            matches!(result, Ok(Ok(())))
+        }
+
+        /// Returns `NotPayee` error if `account` is not a payee.
+        fn ensure_payee(&self, account: AccountId) -> Result<()> {
+            if !self.shares.contains_key(&account) {
+                return Err(Error::NotPayee)
+            }
+            Ok(())
+        }
+
+        /// Ends the current call with `error`, so the share of a payee is not
+        /// counted as released when paying it out fails.
+        fn revert(error: Error) -> ! {
+            ink_env::return_value::<Result<()>>(
+                ReturnFlags::default().set_reverted(true),
+                &Err(error),
+            )
+        }
+    }
+
+    /// Unit tests.
+    ///
+    /// Releasing tokens is left to the redspot tests. Tokens are split the same way
+    /// as the native funds checked here.
+    #[cfg(test)]
+    mod tests {
+        /// Imports all the definitions from the outer scope so we can use them here.
+        use super::*;
+
+        use ink_lang as ink;
+
+        fn native_balance_of(account: AccountId) -> Balance {
+            ink_env::test::get_account_balance::<ink_env::DefaultEnvironment>(account)
+                .expect("Cannot get account balance")
+        }
+
+        /// Sets the native funds of the contract above its minimum balance.
+        fn set_native_balance(value: Balance) {
+            let contract = ink_env::account_id::<ink_env::DefaultEnvironment>()
+                .expect("Cannot get contract id");
+            let minimum_balance =
+                ink_env::minimum_balance::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get minimum balance");
+            ink_env::test::set_account_balance::<ink_env::DefaultEnvironment>(
+                contract,
+                minimum_balance + value,
+            )
+            .expect("Cannot set contract balance");
+        }
+
+        /// Returns a splitter giving Bob one and Charlie three shares.
+        fn splitter() -> PaymentSplitter {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            PaymentSplitter::new(vec![(accounts.bob, 1), (accounts.charlie, 3)])
+        }
+
+        #[ink::test]
+        fn new_works() {
+            let splitter = splitter();
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            assert_eq!(splitter.payees(), vec![accounts.bob, accounts.charlie]);
+            assert_eq!(splitter.shares(accounts.bob), 1);
+            assert_eq!(splitter.shares(accounts.charlie), 3);
+            assert_eq!(splitter.shares(accounts.alice), 0);
+            assert_eq!(splitter.total_shares(), 4);
+            assert_eq!(splitter.total_released(), 0);
+        }
+
+        #[ink::test]
+        #[should_panic(expected = "invalid payees")]
+        fn new_rejects_duplicate_payees() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            PaymentSplitter::new(vec![(accounts.bob, 1), (accounts.bob, 3)]);
+        }
+
+        #[ink::test]
+        #[should_panic(expected = "invalid payees")]
+        fn new_rejects_payees_without_shares() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            PaymentSplitter::new(vec![(accounts.bob, 1), (accounts.charlie, 0)]);
+        }
+
+        #[ink::test]
+        fn native_funds_are_split_by_shares() {
+            let mut splitter = splitter();
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            set_native_balance(100);
+            assert_eq!(splitter.releasable(accounts.bob), 25);
+            assert_eq!(splitter.releasable(accounts.charlie), 75);
+
+            let bob_native = native_balance_of(accounts.bob);
+            assert_eq!(splitter.release(accounts.bob), Ok(25));
+            assert_eq!(splitter.release(accounts.bob), Ok(0));
+            assert_eq!(native_balance_of(accounts.bob), bob_native + 25);
+            assert_eq!(splitter.released(accounts.bob), 25);
+            assert_eq!(splitter.releasable(accounts.charlie), 75);
+
+            // Funds arriving later are split the same way.
+            set_native_balance(75 + 40);
+            assert_eq!(splitter.releasable(accounts.bob), 10);
+            assert_eq!(splitter.releasable(accounts.charlie), 105);
+            assert_eq!(splitter.release(accounts.charlie), Ok(105));
+            assert_eq!(splitter.total_released(), 130);
+        }
+
+        #[ink::test]
+        fn only_payees_are_released_to() {
+            let mut splitter = splitter();
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            set_native_balance(100);
+            assert_eq!(splitter.releasable(accounts.alice), 0);
+            assert_eq!(splitter.release(accounts.alice), Err(Error::NotPayee));
+            assert_eq!(
+                splitter.token_release(AccountId::from([0x07; 32]), accounts.alice),
+                Err(Error::NotPayee)
+            );
+        }
+    }
+}
\ No newline at end of file
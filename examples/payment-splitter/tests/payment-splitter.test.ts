@@ -0,0 +1,102 @@
+import BN from 'bn.js';
+import { expect } from 'chai';
+import { patract, network } from 'redspot';
+
+const { getContractFactory, getRandomSigner } = patract;
+
+const { api, getSigners } = network;
+
+describe('PAYMENT SPLITTER', () => {
+  after(() => {
+    return api.disconnect();
+  });
+
+  async function setup() {
+    const one = new BN(10).pow(new BN(api.registry.chainDecimals[0]));
+    const signers = await getSigners();
+    const Alice = signers[0];
+    const sender = await getRandomSigner(Alice, one.muln(10000));
+    const first = await getRandomSigner(Alice, one.muln(10000));
+    const second = await getRandomSigner(Alice, one.muln(10000));
+    const tokenFactory = await getContractFactory('erc20', sender);
+    const token = await tokenFactory.deploy(
+      'new',
+      '1000',
+      'Redspot',
+      'RDS',
+      10
+    );
+    const contractFactory = await getContractFactory(
+      'payment_splitter',
+      sender
+    );
+    const contract = await contractFactory.deploy('new', [
+      [first.address, 1],
+      [second.address, 3]
+    ]);
+
+    return {
+      sender,
+      first,
+      second,
+      token,
+      contractFactory,
+      contract,
+      Alice,
+      one
+    };
+  }
+
+  it('Splits tokens by shares', async () => {
+    const { contract, token, first, second } = await setup();
+
+    await token.tx.transfer(contract.address, 100);
+
+    await expect(() =>
+      contract.tx.tokenRelease(token.address, first.address)
+    ).to.changeTokenBalance(token, first, 25);
+    await expect(() =>
+      contract.tx.tokenRelease(token.address, first.address)
+    ).to.changeTokenBalance(token, first, 0);
+
+    // Tokens arriving later are split the same way.
+    await token.tx.transfer(contract.address, 40);
+
+    await expect(() =>
+      contract.tx.tokenRelease(token.address, first.address)
+    ).to.changeTokenBalance(token, first, 10);
+    await expect(() =>
+      contract.tx.tokenRelease(token.address, second.address)
+    ).to.changeTokenBalance(token, second, 105);
+    expect(
+      (await contract.query.tokenTotalReleased(token.address)).output
+    ).to.equal(140);
+  });
+
+  it('Splits native funds by shares', async () => {
+    const { contract, sender, first, one } = await setup();
+
+    await expect(contract.tx.receive({ value: one.muln(100) }))
+      .to.emit(contract, 'PaymentReceived')
+      .withArgs(sender.address, one.muln(100));
+
+    const releasable = await contract.query.releasable(first.address);
+    expect(new BN(releasable.output?.toString()).gte(one.muln(25))).to.equal(
+      true
+    );
+    await expect(contract.tx.release(first.address)).to.emit(
+      contract,
+      'PaymentReleased'
+    );
+  });
+
+  it('Only releases to payees', async () => {
+    const { contract, token, sender } = await setup();
+
+    await token.tx.transfer(contract.address, 100);
+
+    await expect(
+      contract.tx.tokenRelease(token.address, sender.address)
+    ).to.not.emit(contract, 'PaymentReleased');
+  });
+});
@@ -0,0 +1,162 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use ink_lang as ink;
+
+#[ink::contract]
+mod counter_v1 {
+    use ink_env::{
+        call::{
+            build_call,
+            utils::ReturnType,
+            ExecutionInput,
+            Selector,
+        },
+        DefaultEnvironment,
+    };
+    use ink_prelude::vec::Vec;
+    use scale::{
+        Decode,
+        Encode,
+    };
+
+    /// Selector of the proxy example's `read(key) -> Option<Vec<u8>>`.
+    const READ_SELECTOR: [u8; 4] = [0x21, 0x74, 0x66, 0xDF];
+
+    /// Selector of the proxy example's `write(key, value) -> Result<()>`.
+    const WRITE_SELECTOR: [u8; 4] = [0x19, 0x8E, 0x34, 0xA3];
+
+    /// The key the count is kept under in the proxy.
+    const COUNT_KEY: [u8; 32] = [0x01; 32];
+
+    /// The first version of a counter behind the proxy example.
+    ///
+    /// The count is kept in the proxy, so it stays when the proxy is upgraded
+    /// to `counter-v2`. Every message takes the caller of the proxy as its
+    /// first argument, as the proxy forwards it.
+    #[ink(storage)]
+    pub struct CounterV1 {
+        /// The proxy calls are accepted from and the count is kept in.
+        proxy: AccountId,
+    }
+
+    /// The counter error types.
+    #[derive(Debug, PartialEq, Eq, scale::Encode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum Error {
+        /// Returned if the counter is not called through the proxy.
+        NotProxy,
+        /// Returned if the count overflows.
+        Overflow,
+        /// Returned if the count could not be stored in the proxy.
+        WriteFailed,
+    }
+
+    /// The counter result type.
+    pub type Result<T> = core::result::Result<T, Error>;
+
+    impl CounterV1 {
+        /// Creates a counter behind `proxy`.
+        #[ink(constructor)]
+        pub fn new(proxy: AccountId) -> Self {
+            Self { proxy }
+        }
+
+        /// Returns the proxy calls are accepted from.
+        #[ink(message)]
+        pub fn proxy(&self) -> AccountId {
+            self.proxy
+        }
+
+        /// Returns the count.
+        #[ink(message)]
+        pub fn get(&self, _caller: AccountId) -> u64 {
+            self.read(COUNT_KEY).unwrap_or(0)
+        }
+
+        /// Increments the count by one and returns it.
+        ///
+        /// # Errors
+        ///
+        /// Returns `NotProxy` error if the counter is not called through the
+        /// proxy.
+        ///
+        /// Returns `Overflow` error if the count overflows.
+        ///
+        /// Returns `WriteFailed` error if the count could not be stored.
+        #[ink(message)]
+        pub fn increment(&mut self, caller: AccountId) -> Result<u64> {
+            self.ensure_proxy()?;
+            let count = self.get(caller).checked_add(1).ok_or(Error::Overflow)?;
+            self.write(COUNT_KEY, &count)?;
+            Ok(count)
+        }
+
+        /// Returns `NotProxy` error if the counter is not called through the
+        /// proxy.
+        fn ensure_proxy(&self) -> Result<()> {
+            if self.env().caller() != self.proxy {
+                return Err(Error::NotProxy)
+            }
+            Ok(())
+        }
+
+        /// Returns the value kept under `key` in the proxy.
+        fn read<T: Decode>(&self, key: [u8; 32]) -> Option<T> {
+            build_call::<DefaultEnvironment>()
+                .callee(self.proxy)
+                .gas_limit(0)
+                .transferred_value(0)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(READ_SELECTOR))
+                        .push_arg(Hash::from(key)),
+                )
+                .returns::<ReturnType<Option<Vec<u8>>>>()
+                .fire()
+                .ok()
+                .flatten()
+                .and_then(|value| T::decode(&mut &value[..]).ok())
+        }
+
+        /// Keeps `value` under `key` in the proxy.
+        fn write<T: Encode>(&self, key: [u8; 32], value: &T) -> Result<()> {
+            let result = build_call::<DefaultEnvironment>()
+                .callee(self.proxy)
+                .gas_limit(0)
+                .transferred_value(0)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(WRITE_SELECTOR))
+                        .push_arg(Hash::from(key))
+                        .push_arg(value.encode()),
+                )
+                .returns::<ReturnType<core::result::Result<(), u8>>>()
+                .fire();
+            if !matches!(result, Ok(Ok(()))) {
+                return Err(Error::WriteFailed)
+            }
+            Ok(())
+        }
+    }
+
+    /// Unit tests.
+    ///
+    /// The count is kept in the proxy, so counting is left to the redspot tests of
+    /// the proxy.
+    #[cfg(test)]
+    mod tests {
+        /// Imports all the definitions from the outer scope so we can use them here.
+        use super::*;
+
+        use ink_lang as ink;
+
+        #[ink::test]
+        fn only_the_proxy_increments() {
+            let mut counter = CounterV1::new(AccountId::from([0x07; 32]));
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            assert_eq!(counter.proxy(), AccountId::from([0x07; 32]));
+            assert_eq!(counter.increment(accounts.alice), Err(Error::NotProxy));
+        }
+    }
+}
\ No newline at end of file
@@ -0,0 +1,314 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use ink_lang as ink;
+
+#[ink::contract]
+mod proxy {
+    use ink_env::{
+        call::{
+            build_call,
+            build_create,
+            utils::ReturnType,
+            ExecutionInput,
+            FromAccountId,
+            Selector,
+        },
+        hash::Blake2x256,
+        DefaultEnvironment,
+    };
+    use ink_prelude::vec::Vec;
+    use ink_primitives::Key;
+    use ink_storage::Lazy;
+
+    /// Selector of the `new(proxy)` constructor every implementation provides.
+    const NEW_SELECTOR: [u8; 4] = [0x9B, 0xAE, 0x9D, 0x5E];
+
+    /// Forwards calls to an implementation which keeps its state in the proxy,
+    /// so the implementation can be upgraded while the state stays.
+    ///
+    /// ink! 3.0.0-rc2 has no delegate call, so the implementation runs as a
+    /// contract of its own, instantiated by the proxy from a code hash. It
+    /// keeps its state in the proxy with `read` and `write`, which are keyed
+    /// by hashes of its choice. Upgrading with `upgrade_to` instantiates a new
+    /// implementation from another code hash, which finds the state the
+    /// previous one left.
+    ///
+    /// `forward` sends any message to the implementation with the caller as
+    /// its first argument, followed by the SCALE encoded arguments passed in,
+    /// and passes back the SCALE encoded return value. Implementations take
+    /// the caller as the first argument of every message and only accept
+    /// calls from the proxy, since their own caller is always the proxy.
+    ///
+    /// The `counter-v1` and `counter-v2` contracts are two versions of such an
+    /// implementation.
+    #[ink(storage)]
+    pub struct Proxy {
+        /// The account allowed to upgrade the implementation.
+        admin: AccountId,
+        /// The contract calls are forwarded to.
+        implementation: Lazy<AccountId>,
+        /// The code hash the implementation was instantiated from.
+        code_hash: Lazy<Hash>,
+        /// The number of upgrades so far.
+        version: Lazy<u32>,
+    }
+
+    /// Event emitted when the implementation is replaced.
+    #[ink(event)]
+    pub struct Upgraded {
+        #[ink(topic)]
+        implementation: AccountId,
+        code_hash: Hash,
+        version: u32,
+    }
+
+    /// The proxy error types.
+    #[derive(Debug, PartialEq, Eq, scale::Encode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum Error {
+        /// Returned if the caller is not the admin.
+        NotAdmin,
+        /// Returned if the caller is not the implementation.
+        NotImplementation,
+        /// Returned if the implementation could not be instantiated.
+        InstantiationFailed,
+        /// Returned if the forwarded call failed.
+        CallFailed,
+        /// Returned if the version overflows.
+        Overflow,
+    }
+
+    /// The proxy result type.
+    pub type Result<T> = core::result::Result<T, Error>;
+
+    /// An implementation instantiated by the proxy.
+    struct Implementation(AccountId);
+
+    impl FromAccountId<DefaultEnvironment> for Implementation {
+        fn from_account_id(account_id: AccountId) -> Self {
+            Self(account_id)
+        }
+    }
+
+    /// Arguments which are SCALE encoded already, so they are passed on as
+    /// they are.
+    struct EncodedArgs<'a>(&'a [u8]);
+
+    impl scale::Encode for EncodedArgs<'_> {
+        fn size_hint(&self) -> usize {
+            self.0.len()
+        }
+
+        fn encode_to<T: scale::Output + ?Sized>(&self, dest: &mut T) {
+            dest.write(self.0);
+        }
+    }
+
+    /// A return value which is kept SCALE encoded.
+    struct EncodedOutput(Vec<u8>);
+
+    impl scale::Decode for EncodedOutput {
+        fn decode<I: scale::Input>(
+            input: &mut I,
+        ) -> core::result::Result<Self, scale::Error> {
+            let len = input.remaining_len()?.unwrap_or(0);
+            let mut output = ink_prelude::vec![0x00; len];
+            input.read(&mut output)?;
+            Ok(Self(output))
+        }
+    }
+
+    impl Proxy {
+        /// Creates a proxy administered by the caller, instantiating its first
+        /// implementation from `code_hash`.
+        ///
+        /// A quarter of the funds sent along endows the implementation.
+        ///
+        /// # Panics
+        ///
+        /// If the implementation can not be instantiated.
+        #[ink(constructor)]
+        pub fn new(code_hash: Hash) -> Self {
+            let implementation =
+                Self::instantiate(code_hash, 0).expect("invalid implementation");
+            Self {
+                admin: Self::env().caller(),
+                implementation: Lazy::new(implementation),
+                code_hash: Lazy::new(code_hash),
+                version: Lazy::new(0),
+            }
+        }
+
+        /// Returns the account allowed to upgrade the implementation.
+        #[ink(message)]
+        pub fn admin(&self) -> AccountId {
+            self.admin
+        }
+
+        /// Returns the contract calls are forwarded to.
+        #[ink(message)]
+        pub fn implementation(&self) -> AccountId {
+            *self.implementation
+        }
+
+        /// Returns the code hash the implementation was instantiated from.
+        #[ink(message)]
+        pub fn code_hash(&self) -> Hash {
+            *self.code_hash
+        }
+
+        /// Returns the number of upgrades so far.
+        #[ink(message)]
+        pub fn version(&self) -> u32 {
+            *self.version
+        }
+
+        /// Sends the message with `selector` to the implementation, with the
+        /// caller and the SCALE encoded `input` as its arguments, and returns
+        /// the SCALE encoded return value.
+        ///
+        /// # Errors
+        ///
+        /// Returns `CallFailed` error if the implementation traps or the call
+        /// can not be made.
+        #[ink(message)]
+        pub fn forward(&self, selector: [u8; 4], input: Vec<u8>) -> Result<Vec<u8>> {
+            // Taking `&self` keeps the proxy from writing its fields back after
+            // the call, which the implementation may `write` to during it.
+            build_call::<DefaultEnvironment>()
+                .callee(*self.implementation)
+                .gas_limit(0)
+                .transferred_value(0)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(selector))
+                        .push_arg(self.env().caller())
+                        .push_arg(EncodedArgs(&input)),
+                )
+                .returns::<ReturnType<EncodedOutput>>()
+                .fire()
+                .map(|output| output.0)
+                .map_err(|_| Error::CallFailed)
+        }
+
+        /// Returns the value the implementation stored under `key`.
+        #[ink(message)]
+        pub fn read(&self, key: Hash) -> Option<Vec<u8>> {
+            ink_env::get_contract_storage(&Self::state_key(key)).unwrap_or(None)
+        }
+
+        /// Stores `value` under `key`.
+        ///
+        /// # Errors
+        ///
+        /// Returns `NotImplementation` error if the caller is not the
+        /// implementation.
+        #[ink(message)]
+        pub fn write(&mut self, key: Hash, value: Vec<u8>) -> Result<()> {
+            if self.env().caller() != *self.implementation {
+                return Err(Error::NotImplementation)
+            }
+            ink_env::set_contract_storage(&Self::state_key(key), &value);
+            Ok(())
+        }
+
+        /// Replaces the implementation with one instantiated from `code_hash`,
+        /// which works on the state the previous one left.
+        ///
+        /// A quarter of the balance of the proxy endows the new implementation.
+        /// On success an `Upgraded` event is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `NotAdmin` error if the caller is not the admin.
+        ///
+        /// Returns `InstantiationFailed` error if the implementation can not be
+        /// instantiated from `code_hash`.
+        #[ink(message)]
+        pub fn upgrade_to(&mut self, code_hash: Hash) -> Result<()> {
+            if self.env().caller() != self.admin {
+                return Err(Error::NotAdmin)
+            }
+            let version = self.version.checked_add(1).ok_or(Error::Overflow)?;
+            let implementation = Self::instantiate(code_hash, version)?;
+            *self.implementation = implementation;
+            *self.code_hash = code_hash;
+            *self.version = version;
+            self.env().emit_event(Upgraded {
+                implementation,
+                code_hash,
+                version,
+            });
+            Ok(())
+        }
+
+        /// Instantiates an implementation from `code_hash` for the proxy, salted
+        /// with `version` so every upgrade gets a new address.
+        fn instantiate(code_hash: Hash, version: u32) -> Result<AccountId> {
+            build_create::<DefaultEnvironment, Implementation>()
+                .code_hash(code_hash)
+                .gas_limit(0)
+                .endowment(Self::env().balance() / 4)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(NEW_SELECTOR))
+                        .push_arg(Self::env().account_id()),
+                )
+                .salt_bytes(version.to_le_bytes())
+                .instantiate()
+                .map(|implementation| implementation.0)
+                .map_err(|_| Error::InstantiationFailed)
+        }
+
+        /// Returns the storage key the value under `key` is kept at.
+        ///
+        /// The key is hashed, so the values of the implementation can not
+        /// overwrite the fields of the proxy.
+        fn state_key(key: Hash) -> Key {
+            let mut output = [0x00; 32];
+            ink_env::hash_encoded::<Blake2x256, _>(&(b"proxy", key), &mut output);
+            Key::from(output)
+        }
+    }
+
+    /// Unit tests.
+    ///
+    /// Forwarding and upgrading are left to the redspot tests.
+    #[cfg(test)]
+    mod tests {
+        /// Imports all the definitions from the outer scope so we can use them here.
+        use super::*;
+
+        use scale::{
+            Decode,
+            Encode,
+        };
+
+        #[test]
+        fn encoded_args_are_passed_on_as_they_are() {
+            let input = (7u32, true).encode();
+
+            assert_eq!(EncodedArgs(&input).encode(), input);
+            assert_eq!((1u8, EncodedArgs(&input)).encode()[1..], input[..]);
+        }
+
+        #[test]
+        fn encoded_output_keeps_all_bytes() {
+            let output = (7u32, true).encode();
+
+            let decoded =
+                EncodedOutput::decode(&mut &output[..]).expect("Cannot decode output");
+            assert_eq!(decoded.0, output);
+        }
+
+        #[test]
+        fn state_keys_are_distinct() {
+            let key = Hash::from([0x01; 32]);
+
+            assert_eq!(Proxy::state_key(key), Proxy::state_key(key));
+            assert_ne!(
+                Proxy::state_key(key),
+                Proxy::state_key(Hash::from([0x02; 32]))
+            );
+            assert_ne!(Proxy::state_key(key), Key::from([0x01; 32]));
+        }
+    }
+}
\ No newline at end of file
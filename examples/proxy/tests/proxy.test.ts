@@ -0,0 +1,101 @@
+import BN from 'bn.js';
+import { expect } from 'chai';
+import { patract, network } from 'redspot';
+
+const { getContractFactory, getRandomSigner } = patract;
+
+const { api, getSigners } = network;
+
+// Selectors of the counter messages forwarded by the proxy.
+const GET_SELECTOR = '0x2f865bd9';
+const INCREMENT_SELECTOR = '0x12bd51d3';
+const INCREMENT_BY_SELECTOR = '0xe0d2a996';
+const LAST_CALLER_SELECTOR = '0x29a8459d';
+
+describe('PROXY', () => {
+  after(() => {
+    return api.disconnect();
+  });
+
+  async function setup() {
+    const one = new BN(10).pow(new BN(api.registry.chainDecimals[0]));
+    const signers = await getSigners();
+    const Alice = signers[0];
+    const sender = await getRandomSigner(Alice, one.muln(10000));
+    const v1Factory = await getContractFactory('counter_v1', sender);
+    const v2Factory = await getContractFactory('counter_v2', sender);
+    const proxyFactory = await getContractFactory('proxy', sender);
+
+    // The proxy instantiates its implementations from code already on
+    // chain, deploying them once on their own puts it there.
+    await v1Factory.deploy('new', sender.address);
+    await v2Factory.deploy('new', sender.address);
+
+    const v1Hash = (v1Factory.abi.json as any).source.hash;
+    const v2Hash = (v2Factory.abi.json as any).source.hash;
+    const contract = await proxyFactory.deploy('new', v1Hash, {
+      value: one.muln(100)
+    });
+    const receiver = await getRandomSigner(Alice, one.muln(10000));
+
+    // Queries the forwarded message with `selector` and decodes its SCALE
+    // encoded return value as `type`.
+    async function query(type: string, selector: string, input = '0x') {
+      const result = await contract.query.forward(selector, input);
+      const output = (result.output as any).asOk.toU8a(true);
+
+      return api.createType(type, output);
+    }
+
+    return { sender, contract, receiver, v1Hash, v2Hash, query, Alice, one };
+  }
+
+  it('Forwards calls to the implementation', async () => {
+    const { contract, v1Hash, query } = await setup();
+
+    expect((await contract.query.codeHash()).output?.toString()).to.equal(
+      v1Hash
+    );
+    await contract.tx.forward(INCREMENT_SELECTOR, '0x');
+    await contract.tx.forward(INCREMENT_SELECTOR, '0x');
+    expect((await query('u64', GET_SELECTOR)).toNumber()).to.equal(2);
+  });
+
+  it('Upgrade keeps the state', async () => {
+    const { contract, sender, v2Hash, query } = await setup();
+
+    await contract.tx.forward(INCREMENT_SELECTOR, '0x');
+    await contract.tx.forward(INCREMENT_SELECTOR, '0x');
+    await expect(contract.tx.upgradeTo(v2Hash)).to.emit(contract, 'Upgraded');
+    expect((await contract.query.version()).output).to.equal(1);
+    expect((await query('u64', GET_SELECTOR)).toNumber()).to.equal(2);
+
+    await contract.tx.forward(
+      INCREMENT_BY_SELECTOR,
+      api.createType('u64', 3).toHex()
+    );
+    expect((await query('u64', GET_SELECTOR)).toNumber()).to.equal(5);
+    expect(
+      (await query('Option<AccountId>', LAST_CALLER_SELECTOR)).toString()
+    ).to.equal(sender.address);
+  });
+
+  it('Only the admin upgrades', async () => {
+    const { contract, receiver, v2Hash } = await setup();
+
+    await expect(
+      contract.tx.upgradeTo(v2Hash, {
+        signer: receiver
+      })
+    ).to.not.emit(contract, 'Upgraded');
+    expect((await contract.query.version()).output).to.equal(0);
+  });
+
+  it('Only the implementation writes to the state', async () => {
+    const { contract } = await setup();
+    const key = `0x${'01'.repeat(32)}`;
+
+    await contract.tx.write(key, api.createType('u64', 7).toHex());
+    expect((await contract.query.read(key)).output?.isEmpty).to.equal(true);
+  });
+});
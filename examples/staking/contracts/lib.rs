@@ -0,0 +1,534 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use ink_lang as ink;
+
+#[ink::contract]
+mod staking {
+    use erc20::{
+        TRANSFER_FROM_SELECTOR,
+        TRANSFER_SELECTOR,
+    };
+    use ink_env::{
+        call::{
+            build_call,
+            utils::ReturnType,
+            ExecutionInput,
+            Selector,
+        },
+        hash::Blake2x256,
+        DefaultEnvironment,
+        ReturnFlags,
+    };
+    #[cfg(not(feature = "ink-as-dependency"))]
+    use ink_storage::{
+        collections::HashMap as StorageHashMap,
+        lazy::Lazy,
+    };
+
+    /// Selector of the access-control example's `has_role(role, account) -> bool`.
+    const HAS_ROLE_SELECTOR: [u8; 4] = [0x8D, 0x19, 0x4A, 0x68];
+
+    /// The name of the role allowed to change the reward rate. The role is the
+    /// Blake2x256 hash of the name, as the access-control example expects.
+    const REWARD_MANAGER: &[u8] = b"reward_manager";
+
+    /// The factor rewards per staked token are scaled by to keep their
+    /// fractional part.
+    const PRECISION: Balance = 1_000_000_000_000;
+
+    /// Pays out a reward token to the stakers of an erc20 token.
+    ///
+    /// `reward_rate` reward tokens are emitted every block and split between
+    /// the stakers by their share of the staked tokens. The contract pays the
+    /// rewards out of its own balance of the reward token, which has to be
+    /// funded by transferring reward tokens to it.
+    ///
+    /// The reward rate is changed by the holders of the reward manager role in
+    /// an access-control registry, which is asked on every change, so the role
+    /// can be granted and revoked there without touching this contract.
+    #[ink(storage)]
+    pub struct Staking {
+        /// The access-control registry granting the reward manager role.
+        roles: AccountId,
+        /// The erc20 token which is staked.
+        staking_token: AccountId,
+        /// The erc20 token rewards are paid in.
+        reward_token: AccountId,
+        /// The reward tokens emitted per block.
+        reward_rate: Lazy<Balance>,
+        /// The total amount of staked tokens.
+        total_staked: Lazy<Balance>,
+        /// The rewards per staked token, scaled by `PRECISION`, accumulated
+        /// until `last_update_block`.
+        reward_per_token_stored: Lazy<Balance>,
+        /// The block `reward_per_token_stored` was last updated in.
+        last_update_block: Lazy<BlockNumber>,
+        /// Mapping from staker to the amount of staked tokens.
+        balances: StorageHashMap<AccountId, Balance>,
+        /// Mapping from staker to the rewards per staked token at the time
+        /// its rewards were last updated.
+        reward_per_token_paid: StorageHashMap<AccountId, Balance>,
+        /// Mapping from staker to the rewards not claimed yet.
+        rewards: StorageHashMap<AccountId, Balance>,
+    }
+
+    /// Event emitted when tokens are staked.
+    #[ink(event)]
+    pub struct Staked {
+        #[ink(topic)]
+        staker: AccountId,
+        amount: Balance,
+    }
+
+    /// Event emitted when tokens are unstaked.
+    #[ink(event)]
+    pub struct Unstaked {
+        #[ink(topic)]
+        staker: AccountId,
+        amount: Balance,
+    }
+
+    /// Event emitted when rewards are claimed.
+    #[ink(event)]
+    pub struct RewardPaid {
+        #[ink(topic)]
+        staker: AccountId,
+        reward: Balance,
+    }
+
+    /// Event emitted when the reward rate changes.
+    #[ink(event)]
+    pub struct RewardRateChange {
+        old_rate: Balance,
+        new_rate: Balance,
+    }
+
+    /// The staking error types.
+    #[derive(Debug, PartialEq, Eq, scale::Encode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum Error {
+        /// Returned if zero tokens would be staked or unstaked.
+        ZeroAmount,
+        /// Returned if more tokens would be unstaked than the caller staked.
+        InsufficientStake,
+        /// Returned if the caller does not hold the reward manager role.
+        NotRewardManager,
+        /// Returned if a token transfer failed.
+        TransferFailed,
+    }
+
+    /// The staking result type.
+    pub type Result<T> = core::result::Result<T, Error>;
+
+    impl Staking {
+        /// Creates a staking contract for `staking_token` emitting `reward_rate`
+        /// tokens of `reward_token` per block, whose reward managers are kept
+        /// in the `roles` registry.
+        #[ink(constructor)]
+        pub fn new(
+            staking_token: AccountId,
+            reward_token: AccountId,
+            reward_rate: Balance,
+            roles: AccountId,
+        ) -> Self {
+            Self {
+                roles,
+                staking_token,
+                reward_token,
+                reward_rate: Lazy::new(reward_rate),
+                total_staked: Lazy::new(0),
+                reward_per_token_stored: Lazy::new(0),
+                last_update_block: Lazy::new(Self::env().block_number()),
+                balances: StorageHashMap::new(),
+                reward_per_token_paid: StorageHashMap::new(),
+                rewards: StorageHashMap::new(),
+            }
+        }
+
+        /// Returns the token which is staked.
+        #[ink(message)]
+        pub fn staking_token(&self) -> AccountId {
+            self.staking_token
+        }
+
+        /// Returns the token rewards are paid in.
+        #[ink(message)]
+        pub fn reward_token(&self) -> AccountId {
+            self.reward_token
+        }
+
+        /// Returns the reward tokens emitted per block.
+        #[ink(message)]
+        pub fn reward_rate(&self) -> Balance {
+            *self.reward_rate
+        }
+
+        /// Returns the total amount of staked tokens.
+        #[ink(message)]
+        pub fn total_staked(&self) -> Balance {
+            *self.total_staked
+        }
+
+        /// Returns the amount of tokens `staker` staked.
+        ///
+        /// Returns `0` if the account is non-existent.
+        #[ink(message)]
+        pub fn balance_of(&self, staker: AccountId) -> Balance {
+            self.balances.get(&staker).copied().unwrap_or(0)
+        }
+
+        /// Returns the rewards per staked token accumulated so far, scaled by
+        /// `10^12`.
+        #[ink(message)]
+        pub fn reward_per_token(&self) -> Balance {
+            let total_staked = *self.total_staked;
+            if total_staked == 0 {
+                return *self.reward_per_token_stored
+            }
+            let blocks = self.env().block_number() - *self.last_update_block;
+            let emitted = Balance::from(blocks)
+                .saturating_mul(*self.reward_rate)
+                .saturating_mul(PRECISION);
+            self.reward_per_token_stored
+                .saturating_add(emitted / total_staked)
+        }
+
+        /// Returns the rewards `staker` can claim.
+        #[ink(message)]
+        pub fn earned(&self, staker: AccountId) -> Balance {
+            let paid = self
+                .reward_per_token_paid
+                .get(&staker)
+                .copied()
+                .unwrap_or(0);
+            let pending = self
+                .balance_of(staker)
+                .saturating_mul(self.reward_per_token() - paid)
+                / PRECISION;
+            self.rewards
+                .get(&staker)
+                .copied()
+                .unwrap_or(0)
+                .saturating_add(pending)
+        }
+
+        /// Stakes `amount` tokens of the caller.
+        ///
+        /// The caller has to approve this contract to spend `amount` tokens of
+        /// the staking token first. On success a `Staked` event is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `ZeroAmount` error if `amount` is zero.
+        ///
+        /// Returns `TransferFailed` error, reverting all changes, if the tokens
+        /// can not be transferred from the caller.
+        #[ink(message)]
+        pub fn stake(&mut self, amount: Balance) -> Result<()> {
+            if amount == 0 {
+                return Err(Error::ZeroAmount)
+            }
+            let staker = self.env().caller();
+            self.add_stake(staker, amount);
+            let this = self.env().account_id();
+            if !self.transfer_from(self.staking_token, staker, this, amount) {
+                Self::revert(Error::TransferFailed)
+            }
+            self.env().emit_event(Staked { staker, amount });
+            Ok(())
+        }
+
+        /// Unstakes `amount` tokens of the caller and sends them back.
+        ///
+        /// Rewards earned so far stay claimable. On success an `Unstaked` event
+        /// is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `ZeroAmount` error if `amount` is zero.
+        ///
+        /// Returns `InsufficientStake` error if the caller staked less than
+        /// `amount` tokens.
+        ///
+        /// Returns `TransferFailed` error, reverting all changes, if the tokens
+        /// can not be transferred to the caller.
+        #[ink(message)]
+        pub fn unstake(&mut self, amount: Balance) -> Result<()> {
+            if amount == 0 {
+                return Err(Error::ZeroAmount)
+            }
+            let staker = self.env().caller();
+            self.remove_stake(staker, amount)?;
+            if !self.transfer(self.staking_token, staker, amount) {
+                Self::revert(Error::TransferFailed)
+            }
+            self.env().emit_event(Unstaked { staker, amount });
+            Ok(())
+        }
+
+        /// Sends the rewards earned by the caller and returns their amount.
+        ///
+        /// On success a `RewardPaid` event is emitted if there were rewards.
+        ///
+        /// # Errors
+        ///
+        /// Returns `TransferFailed` error, reverting all changes, if the rewards
+        /// can not be transferred to the caller, for example because the
+        /// contract does not hold enough reward tokens.
+        #[ink(message)]
+        pub fn claim(&mut self) -> Result<Balance> {
+            let staker = self.env().caller();
+            self.update_reward(staker);
+            let reward = self.rewards.take(&staker).unwrap_or(0);
+            if reward == 0 {
+                return Ok(0)
+            }
+            if !self.transfer(self.reward_token, staker, reward) {
+                Self::revert(Error::TransferFailed)
+            }
+            self.env().emit_event(RewardPaid { staker, reward });
+            Ok(reward)
+        }
+
+        /// Changes the reward tokens emitted per block to `new_rate`.
+        ///
+        /// Rewards emitted until now are accounted at the old rate. On success a
+        /// `RewardRateChange` event is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `NotRewardManager` error if the caller does not hold the
+        /// reward manager role in the registry.
+        #[ink(message)]
+        pub fn set_reward_rate(&mut self, new_rate: Balance) -> Result<()> {
+            if !self.is_reward_manager(self.env().caller()) {
+                return Err(Error::NotRewardManager)
+            }
+            self.change_reward_rate(new_rate);
+            Ok(())
+        }
+
+        /// Returns the access-control registry granting the reward manager role.
+        #[ink(message)]
+        pub fn roles(&self) -> AccountId {
+            self.roles
+        }
+
+        /// Changes the reward rate to `new_rate` after accounting the rewards
+        /// emitted at the old rate.
+        fn change_reward_rate(&mut self, new_rate: Balance) {
+            self.update_reward_per_token();
+            let old_rate = core::mem::replace(&mut *self.reward_rate, new_rate);
+            self.env()
+                .emit_event(RewardRateChange { old_rate, new_rate });
+        }
+
+        /// Returns whether `account` holds the reward manager role, asking the
+        /// registry. A failing call counts as not holding it.
+        fn is_reward_manager(&self, account: AccountId) -> bool {
+            let role = self.env().hash_bytes::<Blake2x256>(REWARD_MANAGER);
+            let result = build_call::<DefaultEnvironment>()
+                .callee(self.roles)
+                .gas_limit(0)
+                .transferred_value(0)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(HAS_ROLE_SELECTOR))
+                        .push_arg(Hash::from(role))
+                        .push_arg(account),
+                )
+                .returns::<ReturnType<bool>>()
+                .fire();
+            matches!(result, Ok(true))
+        }
+
+        /// Accounts the rewards emitted until the current block.
+        fn update_reward_per_token(&mut self) {
+            *self.reward_per_token_stored = self.reward_per_token();
+            *self.last_update_block = self.env().block_number();
+        }
+
+        /// Moves the rewards `staker` earned until the current block to its
+        /// claimable rewards.
+        ///
+        /// Has to be called before the stake of `staker` changes.
+        fn update_reward(&mut self, staker: AccountId) {
+            self.update_reward_per_token();
+            let earned = self.earned(staker);
+            self.rewards.insert(staker, earned);
+            self.reward_per_token_paid
+                .insert(staker, *self.reward_per_token_stored);
+        }
+
+        /// Adds `amount` tokens to the stake of `staker`.
+        fn add_stake(&mut self, staker: AccountId, amount: Balance) {
+            self.update_reward(staker);
+            let balance = self.balance_of(staker);
+            self.balances.insert(staker, balance + amount);
+            *self.total_staked += amount;
+        }
+
+        /// Removes `amount` tokens from the stake of `staker`.
+        ///
+        /// Returns `InsufficientStake` error if `staker` staked less than
+        /// `amount` tokens.
+        fn remove_stake(&mut self, staker: AccountId, amount: Balance) -> Result<()> {
+            let balance = self.balance_of(staker);
+            if balance < amount {
+                return Err(Error::InsufficientStake)
+            }
+            self.update_reward(staker);
+            self.balances.insert(staker, balance - amount);
+            *self.total_staked -= amount;
+            Ok(())
+        }
+
+        /// Transfers `value` tokens of `token` from this contract to `to` and
+        /// returns whether that succeeded.
+        fn transfer(&self, token: AccountId, to: AccountId, value: Balance) -> bool {
+            let result = build_call::<DefaultEnvironment>()
+                .callee(token)
+                .gas_limit(0)
+                .transferred_value(0)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(TRANSFER_SELECTOR))
+                        .push_arg(to)
+                        .push_arg(value),
+                )
+                .returns::<ReturnType<core::result::Result<(), u8>>>()
+                .fire();
+            matches!(result, Ok(Ok(())))
+        }
+
+        /// Transfers `value` tokens of `token` from `from` to `to` using the
+        /// allowance of this contract and returns whether that succeeded.
+        fn transfer_from(
+            &self,
+            token: AccountId,
+            from: AccountId,
+            to: AccountId,
+            value: Balance,
+        ) -> bool {
+            let result = build_call::<DefaultEnvironment>()
+                .callee(token)
+                .gas_limit(0)
+                .transferred_value(0)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(TRANSFER_FROM_SELECTOR))
+                        .push_arg(from)
+                        .push_arg(to)
+                        .push_arg(value),
+                )
+                .returns::<ReturnType<core::result::Result<(), u8>>>()
+                .fire();
+            matches!(result, Ok(Ok(())))
+        }
+
+        /// Ends the current call with `error`, so stakes and rewards stay as they
+        /// were when moving the tokens fails.
+        fn revert(error: Error) -> ! {
+            ink_env::return_value::<Result<()>>(
+                ReturnFlags::default().set_reverted(true),
+                &Err(error),
+            )
+        }
+    }
+
+    /// Unit tests.
+    ///
+    /// Moving tokens and asking the registry for reward managers are left to the
+    /// redspot tests. The tests here change stakes through `add_stake` and
+    /// `remove_stake`, which `stake` and `unstake` use.
+    #[cfg(test)]
+    mod tests {
+        /// Imports all the definitions from the outer scope so we can use them here.
+        use super::*;
+
+        use ink_lang as ink;
+        use test_utils::advance_blocks;
+
+        /// Returns a staking contract emitting ten reward tokens per block.
+        fn staking() -> Staking {
+            Staking::new(
+                AccountId::from([0x07; 32]),
+                AccountId::from([0x08; 32]),
+                10,
+                AccountId::from([0x09; 32]),
+            )
+        }
+
+        #[ink::test]
+        fn new_works() {
+            let staking = staking();
+
+            assert_eq!(staking.staking_token(), AccountId::from([0x07; 32]));
+            assert_eq!(staking.reward_token(), AccountId::from([0x08; 32]));
+            assert_eq!(staking.roles(), AccountId::from([0x09; 32]));
+            assert_eq!(staking.reward_rate(), 10);
+            assert_eq!(staking.total_staked(), 0);
+            assert_eq!(staking.reward_per_token(), 0);
+        }
+
+        #[ink::test]
+        fn rewards_are_split_by_stake() {
+            let mut staking = staking();
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            // Nothing is emitted while nobody stakes.
+            advance_blocks!(1);
+            staking.add_stake(accounts.alice, 100);
+            assert_eq!(staking.earned(accounts.alice), 0);
+
+            advance_blocks!(2);
+            assert_eq!(staking.earned(accounts.alice), 20);
+
+            staking.add_stake(accounts.bob, 400);
+            advance_blocks!(2);
+            assert_eq!(staking.earned(accounts.alice), 24);
+            assert_eq!(staking.earned(accounts.bob), 16);
+
+            assert_eq!(staking.remove_stake(accounts.alice, 100), Ok(()));
+            advance_blocks!(1);
+            assert_eq!(staking.earned(accounts.alice), 24);
+            assert_eq!(staking.earned(accounts.bob), 26);
+            assert_eq!(staking.total_staked(), 400);
+        }
+
+        #[ink::test]
+        fn stake_and_unstake_check_amount() {
+            let mut staking = staking();
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            assert_eq!(staking.stake(0), Err(Error::ZeroAmount));
+            assert_eq!(staking.unstake(0), Err(Error::ZeroAmount));
+            staking.add_stake(accounts.alice, 100);
+            assert_eq!(staking.unstake(101), Err(Error::InsufficientStake));
+        }
+
+        #[ink::test]
+        fn reward_rate_changes_apply_from_the_current_block() {
+            let mut staking = staking();
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            staking.add_stake(accounts.alice, 100);
+            advance_blocks!(1);
+            staking.change_reward_rate(20);
+            assert_eq!(staking.reward_rate(), 20);
+            advance_blocks!(1);
+            assert_eq!(staking.earned(accounts.alice), 30);
+        }
+
+        #[ink::test]
+        fn claim_without_rewards_returns_zero() {
+            let mut staking = staking();
+
+            assert_eq!(staking.claim(), Ok(0));
+            assert_eq!(ink_env::test::recorded_events().count(), 0);
+        }
+    }
+}
\ No newline at end of file
@@ -0,0 +1,158 @@
+import BN from 'bn.js';
+import { expect } from 'chai';
+import { patract, network } from 'redspot';
+import { blake2AsHex } from '@polkadot/util-crypto';
+
+const { getContractFactory, getRandomSigner } = patract;
+
+const { api, getSigners } = network;
+
+const REWARD_RATE = 10;
+
+const REWARD_MANAGER_ROLE = blake2AsHex('reward_manager');
+
+describe('STAKING', () => {
+  after(() => {
+    return api.disconnect();
+  });
+
+  async function setup() {
+    const one = new BN(10).pow(new BN(api.registry.chainDecimals[0]));
+    const signers = await getSigners();
+    const Alice = signers[0];
+    const sender = await getRandomSigner(Alice, one.muln(10000));
+    const tokenFactory = await getContractFactory('erc20', sender);
+    const stakingToken = await tokenFactory.deploy(
+      'new',
+      '1000',
+      'Stake',
+      'STK',
+      10
+    );
+    const rewardToken = await tokenFactory.deploy(
+      'new',
+      '1000000',
+      'Reward',
+      'RWD',
+      10
+    );
+    const rolesFactory = await getContractFactory('access_control', sender);
+    const roles = await rolesFactory.deploy('new');
+    const contractFactory = await getContractFactory('staking', sender);
+    const contract = await contractFactory.deploy(
+      'new',
+      stakingToken.address,
+      rewardToken.address,
+      REWARD_RATE,
+      roles.address
+    );
+    await rewardToken.tx.transfer(contract.address, 100000);
+    await stakingToken.tx.approve(contract.address, 100);
+
+    return {
+      sender,
+      stakingToken,
+      rewardToken,
+      roles,
+      contractFactory,
+      contract,
+      Alice,
+      one
+    };
+  }
+
+  it('Stakes approved tokens', async () => {
+    const { contract, stakingToken, sender } = await setup();
+
+    await expect(() =>
+      contract.tx.stake(100)
+    ).to.changeTokenBalance(stakingToken, sender, -100);
+
+    expect((await contract.query.balanceOf(sender.address)).output).to.equal(
+      100
+    );
+    expect((await contract.query.totalStaked()).output).to.equal(100);
+    expect(
+      (await stakingToken.query.balanceOf(contract.address)).output
+    ).to.equal(100);
+  });
+
+  it('Pays out rewards per block', async () => {
+    const { contract, rewardToken, sender } = await setup();
+
+    await contract.tx.stake(100);
+
+    // The development node seals a block for every transaction, so the claim
+    // comes one block after the stake.
+    await expect(() => contract.tx.claim()).to.changeTokenBalance(
+      rewardToken,
+      sender,
+      REWARD_RATE
+    );
+    await expect(contract.tx.claim())
+      .to.emit(contract, 'RewardPaid')
+      .withArgs(sender.address, REWARD_RATE);
+  });
+
+  it('Unstakes tokens', async () => {
+    const { contract, stakingToken, sender } = await setup();
+
+    await contract.tx.stake(100);
+
+    await expect(() =>
+      contract.tx.unstake(100)
+    ).to.changeTokenBalance(stakingToken, sender, 100);
+    await expect(contract.tx.unstake(1)).to.not.emit(contract, 'Unstaked');
+    expect((await contract.query.totalStaked()).output).to.equal(0);
+  });
+
+  it('Can not stake without allowance', async () => {
+    const { contract, stakingToken, Alice, one } = await setup();
+
+    const otherAccount = await getRandomSigner(Alice, one.muln(10000));
+    await stakingToken.tx.transfer(otherAccount.address, 100);
+
+    await expect(
+      contract.tx.stake(100, {
+        signer: otherAccount
+      })
+    ).to.not.emit(contract, 'Staked');
+    expect((await contract.query.totalStaked()).output).to.equal(0);
+  });
+
+  it('Reward managers of the registry change the reward rate', async () => {
+    const { contract, roles, Alice, one } = await setup();
+
+    const manager = await getRandomSigner(Alice, one.muln(10000));
+    await roles.tx.grantRole(REWARD_MANAGER_ROLE, manager.address);
+
+    await expect(
+      contract.tx.setRewardRate(20, {
+        signer: manager
+      })
+    )
+      .to.emit(contract, 'RewardRateChange')
+      .withArgs(REWARD_RATE, 20);
+    expect((await contract.query.rewardRate()).output).to.equal(20);
+
+    await roles.tx.revokeRole(REWARD_MANAGER_ROLE, manager.address);
+    await expect(
+      contract.tx.setRewardRate(30, {
+        signer: manager
+      })
+    ).to.not.emit(contract, 'RewardRateChange');
+  });
+
+  it('Can not change the reward rate without the role', async () => {
+    const { contract, Alice, one } = await setup();
+
+    const otherAccount = await getRandomSigner(Alice, one.muln(10000));
+
+    await expect(
+      contract.tx.setRewardRate(0, {
+        signer: otherAccount
+      })
+    ).to.not.emit(contract, 'RewardRateChange');
+    expect((await contract.query.rewardRate()).output).to.equal(REWARD_RATE);
+  });
+});
@@ -0,0 +1,514 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use ink_lang as ink;
+
+#[ink::contract]
+mod subscriptions {
+    use erc20::TRANSFER_FROM_SELECTOR;
+    use ink_env::{
+        call::{
+            build_call,
+            utils::ReturnType,
+            ExecutionInput,
+            Selector,
+        },
+        DefaultEnvironment,
+    };
+    use ink_storage::traits::{
+        PackedLayout,
+        SpreadLayout,
+    };
+    #[cfg(not(feature = "ink-as-dependency"))]
+    use ink_storage::{
+        collections::HashMap as StorageHashMap,
+        lazy::Lazy,
+    };
+
+    /// A plan subscribers pay a merchant for.
+    #[derive(
+        Debug,
+        Copy,
+        Clone,
+        PartialEq,
+        Eq,
+        scale::Encode,
+        scale::Decode,
+        SpreadLayout,
+        PackedLayout,
+    )]
+    #[cfg_attr(
+        feature = "std",
+        derive(::scale_info::TypeInfo, ::ink_storage::traits::StorageLayout)
+    )]
+    pub struct Plan {
+        /// The account paid by the subscribers.
+        pub merchant: AccountId,
+        /// The erc20 token the plan is paid in.
+        pub token: AccountId,
+        /// The tokens charged every period.
+        pub amount: Balance,
+        /// The number of blocks a charge pays for.
+        pub period: BlockNumber,
+    }
+
+    /// The subscription of an account to a plan.
+    #[derive(
+        Debug,
+        Copy,
+        Clone,
+        PartialEq,
+        Eq,
+        scale::Encode,
+        scale::Decode,
+        SpreadLayout,
+        PackedLayout,
+    )]
+    #[cfg_attr(
+        feature = "std",
+        derive(::scale_info::TypeInfo, ::ink_storage::traits::StorageLayout)
+    )]
+    pub struct Subscription {
+        /// The plan subscribed to.
+        pub plan_id: u32,
+        /// The first block at which the subscriber can be charged again.
+        pub next_charge: BlockNumber,
+    }
+
+    /// Recurring payments in erc20 tokens from subscribers to merchants.
+    ///
+    /// Merchants register plans charging an amount of a token every period.
+    /// Subscribers authorize the recurring pulls by subscribing to a plan and
+    /// approving the contract to spend the token for them. The merchant then
+    /// pulls the amount with `charge` once per period, starting right after the
+    /// subscription.
+    ///
+    /// An account subscribes to at most one plan of every merchant, so
+    /// subscribing to another plan of the same merchant switches to it. A
+    /// subscription ends when the subscriber cancels it, or automatically as
+    /// soon as a charge fails, for instance because the allowance or the
+    /// balance of the subscriber ran out.
+    #[ink(storage)]
+    pub struct Subscriptions {
+        /// The plans by their id.
+        plans: StorageHashMap<u32, Plan>,
+        /// The id of the next plan.
+        next_plan_id: Lazy<u32>,
+        /// The subscriptions by merchant and subscriber.
+        subscriptions: StorageHashMap<(AccountId, AccountId), Subscription>,
+    }
+
+    /// Event emitted when a merchant registers a plan.
+    #[ink(event)]
+    pub struct PlanCreated {
+        plan_id: u32,
+        #[ink(topic)]
+        merchant: AccountId,
+        #[ink(topic)]
+        token: AccountId,
+        amount: Balance,
+        period: BlockNumber,
+    }
+
+    /// Event emitted when an account subscribes to a plan.
+    #[ink(event)]
+    pub struct Subscribed {
+        #[ink(topic)]
+        merchant: AccountId,
+        #[ink(topic)]
+        subscriber: AccountId,
+        plan_id: u32,
+    }
+
+    /// Event emitted when a subscriber is charged for a period.
+    #[ink(event)]
+    pub struct Charged {
+        #[ink(topic)]
+        merchant: AccountId,
+        #[ink(topic)]
+        subscriber: AccountId,
+        plan_id: u32,
+        amount: Balance,
+    }
+
+    /// Event emitted when a subscription ends.
+    #[ink(event)]
+    pub struct Cancelled {
+        #[ink(topic)]
+        merchant: AccountId,
+        #[ink(topic)]
+        subscriber: AccountId,
+        plan_id: u32,
+    }
+
+    /// The subscriptions error types.
+    #[derive(Debug, PartialEq, Eq, scale::Encode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum Error {
+        /// Returned if a plan charges nothing.
+        ZeroAmount,
+        /// Returned if a plan has an empty period.
+        ZeroPeriod,
+        /// Returned if the plan does not exist.
+        UnknownPlan,
+        /// Returned if the account is not subscribed to a plan of the merchant.
+        NotSubscribed,
+        /// Returned if the subscriber was charged for the current period already.
+        NotDue,
+        /// Returned if the charge failed, which cancelled the subscription.
+        PaymentFailed,
+        /// Returned if the plan id or the block number overflows.
+        Overflow,
+    }
+
+    /// The subscriptions result type.
+    pub type Result<T> = core::result::Result<T, Error>;
+
+    impl Subscriptions {
+        /// Creates a contract without any plans.
+        #[ink(constructor)]
+        pub fn new() -> Self {
+            Self {
+                plans: StorageHashMap::new(),
+                next_plan_id: Lazy::new(0),
+                subscriptions: StorageHashMap::new(),
+            }
+        }
+
+        /// Returns the plan with `plan_id`, if it exists.
+        #[ink(message)]
+        pub fn plan(&self, plan_id: u32) -> Option<Plan> {
+            self.plans.get(&plan_id).copied()
+        }
+
+        /// Returns the id of the next plan, which is the number of plans so
+        /// far.
+        #[ink(message)]
+        pub fn next_plan_id(&self) -> u32 {
+            *self.next_plan_id
+        }
+
+        /// Returns the subscription of `subscriber` to a plan of `merchant`, if
+        /// any.
+        #[ink(message)]
+        pub fn subscription(
+            &self,
+            merchant: AccountId,
+            subscriber: AccountId,
+        ) -> Option<Subscription> {
+            self.subscriptions.get(&(merchant, subscriber)).copied()
+        }
+
+        /// Registers a plan of the caller charging `amount` of `token` every
+        /// `period` blocks and returns its id.
+        ///
+        /// On success a `PlanCreated` event is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `ZeroAmount` error if `amount` is zero.
+        ///
+        /// Returns `ZeroPeriod` error if `period` is zero.
+        ///
+        /// Returns `Overflow` error if the plan id overflows.
+        #[ink(message)]
+        pub fn create_plan(
+            &mut self,
+            token: AccountId,
+            amount: Balance,
+            period: BlockNumber,
+        ) -> Result<u32> {
+            if amount == 0 {
+                return Err(Error::ZeroAmount)
+            }
+            if period == 0 {
+                return Err(Error::ZeroPeriod)
+            }
+            let merchant = self.env().caller();
+            let plan_id = *self.next_plan_id;
+            let next_plan_id = plan_id.checked_add(1).ok_or(Error::Overflow)?;
+            self.plans.insert(
+                plan_id,
+                Plan {
+                    merchant,
+                    token,
+                    amount,
+                    period,
+                },
+            );
+            *self.next_plan_id = next_plan_id;
+            self.env().emit_event(PlanCreated {
+                plan_id,
+                merchant,
+                token,
+                amount,
+                period,
+            });
+            Ok(plan_id)
+        }
+
+        /// Subscribes the caller to the plan with `plan_id`, replacing any
+        /// subscription to another plan of the same merchant.
+        ///
+        /// The caller has to approve the contract to spend the token of the
+        /// plan as well. The merchant can charge the first period right away.
+        /// On success a `Subscribed` event is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `UnknownPlan` error if the plan does not exist.
+        #[ink(message)]
+        pub fn subscribe(&mut self, plan_id: u32) -> Result<()> {
+            let plan = self.plan(plan_id).ok_or(Error::UnknownPlan)?;
+            let subscriber = self.env().caller();
+            self.subscriptions.insert(
+                (plan.merchant, subscriber),
+                Subscription {
+                    plan_id,
+                    next_charge: self.env().block_number(),
+                },
+            );
+            self.env().emit_event(Subscribed {
+                merchant: plan.merchant,
+                subscriber,
+                plan_id,
+            });
+            Ok(())
+        }
+
+        /// Cancels the subscription of the caller to a plan of `merchant`.
+        ///
+        /// On success a `Cancelled` event is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `NotSubscribed` error if the caller is not subscribed to a
+        /// plan of `merchant`.
+        #[ink(message)]
+        pub fn cancel(&mut self, merchant: AccountId) -> Result<()> {
+            let subscriber = self.env().caller();
+            self.end_subscription(merchant, subscriber)
+        }
+
+        /// Charges `subscriber` for the current period of its plan with the
+        /// caller, paying the amount of the plan to the caller.
+        ///
+        /// On success a `Charged` event is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `NotSubscribed` error if `subscriber` is not subscribed to a
+        /// plan of the caller.
+        ///
+        /// Returns `NotDue` error if `subscriber` was charged for the current
+        /// period already.
+        ///
+        /// Returns `Overflow` error if the block of the next charge overflows.
+        ///
+        /// Returns `PaymentFailed` error if the tokens could not be transferred.
+        /// This cancels the subscription and emits a `Cancelled` event, which
+        /// stays so since returning an error does not revert the changes made.
+        #[ink(message)]
+        pub fn charge(&mut self, subscriber: AccountId) -> Result<()> {
+            let merchant = self.env().caller();
+            let (plan_id, plan) = self.start_charge(merchant, subscriber)?;
+            if !self.transfer_from(plan.token, subscriber, merchant, plan.amount) {
+                self.end_subscription(merchant, subscriber)?;
+                return Err(Error::PaymentFailed)
+            }
+            self.env().emit_event(Charged {
+                merchant,
+                subscriber,
+                plan_id,
+                amount: plan.amount,
+            });
+            Ok(())
+        }
+
+        /// Moves the next charge of the subscription of `subscriber` to a plan
+        /// of `merchant` one period on and returns the plan.
+        ///
+        /// The next charge is counted from the current block, so a subscriber
+        /// charged late is not charged for the periods missed.
+        fn start_charge(
+            &mut self,
+            merchant: AccountId,
+            subscriber: AccountId,
+        ) -> Result<(u32, Plan)> {
+            let subscription = self
+                .subscription(merchant, subscriber)
+                .ok_or(Error::NotSubscribed)?;
+            let now = self.env().block_number();
+            if now < subscription.next_charge {
+                return Err(Error::NotDue)
+            }
+            let plan = self.plan(subscription.plan_id).ok_or(Error::UnknownPlan)?;
+            let next_charge = now.checked_add(plan.period).ok_or(Error::Overflow)?;
+            self.subscriptions.insert(
+                (merchant, subscriber),
+                Subscription {
+                    next_charge,
+                    ..subscription
+                },
+            );
+            Ok((subscription.plan_id, plan))
+        }
+
+        /// Ends the subscription of `subscriber` to a plan of `merchant`.
+        fn end_subscription(
+            &mut self,
+            merchant: AccountId,
+            subscriber: AccountId,
+        ) -> Result<()> {
+            let subscription = self
+                .subscriptions
+                .take(&(merchant, subscriber))
+                .ok_or(Error::NotSubscribed)?;
+            self.env().emit_event(Cancelled {
+                merchant,
+                subscriber,
+                plan_id: subscription.plan_id,
+            });
+            Ok(())
+        }
+
+        /// Transfers `value` tokens of `token` from `from` to `to` on behalf of
+        /// the contract and returns whether that succeeded.
+        fn transfer_from(
+            &self,
+            token: AccountId,
+            from: AccountId,
+            to: AccountId,
+            value: Balance,
+        ) -> bool {
+            let result = build_call::<DefaultEnvironment>()
+                .callee(token)
+                .gas_limit(0)
+                .transferred_value(0)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(TRANSFER_FROM_SELECTOR))
+                        .push_arg(from)
+                        .push_arg(to)
+                        .push_arg(value),
+                )
+                .returns::<ReturnType<core::result::Result<(), u8>>>()
+                .fire();
+            matches!(result, Ok(Ok(())))
+        }
+    }
+
+    /// Unit tests.
+    ///
+    /// Charging subscribers is left to the redspot tests.
+    #[cfg(test)]
+    mod tests {
+        /// Imports all the definitions from the outer scope so we can use them here.
+        use super::*;
+
+        use ink_lang as ink;
+        use test_utils::{
+            advance_blocks,
+            set_caller,
+        };
+
+        /// Returns a contract with a plan of Alice charging `100` every three
+        /// blocks.
+        fn subscriptions() -> Subscriptions {
+            let mut subscriptions = Subscriptions::new();
+            assert_eq!(
+                subscriptions.create_plan(AccountId::from([0x07; 32]), 100, 3),
+                Ok(0)
+            );
+            subscriptions
+        }
+
+        #[ink::test]
+        fn plans_are_created() {
+            let mut subscriptions = subscriptions();
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            assert_eq!(
+                subscriptions.plan(0),
+                Some(Plan {
+                    merchant: accounts.alice,
+                    token: AccountId::from([0x07; 32]),
+                    amount: 100,
+                    period: 3,
+                })
+            );
+            assert_eq!(subscriptions.next_plan_id(), 1);
+            assert_eq!(
+                subscriptions.create_plan(AccountId::from([0x07; 32]), 0, 3),
+                Err(Error::ZeroAmount)
+            );
+            assert_eq!(
+                subscriptions.create_plan(AccountId::from([0x07; 32]), 100, 0),
+                Err(Error::ZeroPeriod)
+            );
+            assert_eq!(subscriptions.next_plan_id(), 1);
+        }
+
+        #[ink::test]
+        fn subscribers_can_be_charged_once_per_period() {
+            let mut subscriptions = subscriptions();
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            assert_eq!(
+                subscriptions.start_charge(accounts.alice, accounts.bob),
+                Err(Error::NotSubscribed)
+            );
+            set_caller!(accounts.bob);
+            assert_eq!(subscriptions.subscribe(1), Err(Error::UnknownPlan));
+            assert_eq!(subscriptions.subscribe(0), Ok(()));
+
+            let plan = subscriptions.plan(0).unwrap();
+            assert_eq!(
+                subscriptions.start_charge(accounts.alice, accounts.bob),
+                Ok((0, plan))
+            );
+            assert_eq!(
+                subscriptions.start_charge(accounts.alice, accounts.bob),
+                Err(Error::NotDue)
+            );
+            advance_blocks!(3);
+            assert_eq!(
+                subscriptions.start_charge(accounts.alice, accounts.bob),
+                Ok((0, plan))
+            );
+            assert_eq!(
+                subscriptions
+                    .subscription(accounts.alice, accounts.bob)
+                    .map(|subscription| subscription.next_charge),
+                Some(6)
+            );
+        }
+
+        #[ink::test]
+        fn subscribers_cancel() {
+            let mut subscriptions = subscriptions();
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            set_caller!(accounts.bob);
+            assert_eq!(
+                subscriptions.cancel(accounts.alice),
+                Err(Error::NotSubscribed)
+            );
+            subscriptions.subscribe(0).unwrap();
+            assert_eq!(subscriptions.cancel(accounts.alice), Ok(()));
+            assert_eq!(
+                subscriptions.subscription(accounts.alice, accounts.bob),
+                None
+            );
+            assert_eq!(
+                subscriptions.start_charge(accounts.alice, accounts.bob),
+                Err(Error::NotSubscribed)
+            );
+            assert_eq!(ink_env::test::recorded_events().count(), 3);
+        }
+    }
+}
\ No newline at end of file
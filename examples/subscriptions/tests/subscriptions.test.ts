@@ -0,0 +1,116 @@
+import BN from 'bn.js';
+import { expect } from 'chai';
+import { patract, network } from 'redspot';
+
+const { getContractFactory, getRandomSigner } = patract;
+
+const { api, getSigners } = network;
+
+describe('SUBSCRIPTIONS', () => {
+  after(() => {
+    return api.disconnect();
+  });
+
+  async function setup() {
+    const one = new BN(10).pow(new BN(api.registry.chainDecimals[0]));
+    const signers = await getSigners();
+    const Alice = signers[0];
+    const sender = await getRandomSigner(Alice, one.muln(10000));
+    const subscriber = await getRandomSigner(Alice, one.muln(10000));
+    const tokenFactory = await getContractFactory('erc20', sender);
+    const token = await tokenFactory.deploy(
+      'new',
+      '1000000',
+      'Redspot',
+      'RDS',
+      10
+    );
+    const contractFactory = await getContractFactory('subscriptions', sender);
+    const contract = await contractFactory.deploy('new');
+    await token.tx.transfer(subscriber.address, 1000);
+    await contract.tx.createPlan(token.address, 100, 3);
+
+    // The development node seals a block for every transaction, so blocks pass
+    // by sending transactions which change nothing.
+    async function advanceBlocks(blocks: number) {
+      for (let i = 0; i < blocks; i++) {
+        await token.tx.approve(contract.address, 0);
+      }
+    }
+
+    return {
+      sender,
+      subscriber,
+      token,
+      contract,
+      advanceBlocks,
+      Alice,
+      one
+    };
+  }
+
+  it('Charges subscribers once per period', async () => {
+    const {
+      contract,
+      token,
+      sender,
+      subscriber,
+      advanceBlocks
+    } = await setup();
+
+    await token.tx.approve(contract.address, 1000, { signer: subscriber });
+    await expect(contract.tx.subscribe(0, { signer: subscriber }))
+      .to.emit(contract, 'Subscribed')
+      .withArgs(sender.address, subscriber.address, 0);
+
+    await expect(() =>
+      contract.tx.charge(subscriber.address)
+    ).to.changeTokenBalances(token, [subscriber, sender], [-100, 100]);
+    await expect(contract.tx.charge(subscriber.address)).to.not.emit(
+      contract,
+      'Charged'
+    );
+
+    await advanceBlocks(3);
+    await expect(contract.tx.charge(subscriber.address))
+      .to.emit(contract, 'Charged')
+      .withArgs(sender.address, subscriber.address, 0, 100);
+  });
+
+  it('Cancels subscriptions whose charge fails', async () => {
+    const {
+      contract,
+      token,
+      sender,
+      subscriber,
+      advanceBlocks
+    } = await setup();
+
+    await token.tx.approve(contract.address, 100, { signer: subscriber });
+    await contract.tx.subscribe(0, { signer: subscriber });
+    await contract.tx.charge(subscriber.address);
+
+    await advanceBlocks(3);
+    await expect(contract.tx.charge(subscriber.address))
+      .to.emit(contract, 'Cancelled')
+      .withArgs(sender.address, subscriber.address, 0);
+    expect(
+      (await contract.query.subscription(sender.address, subscriber.address))
+        .output?.isEmpty
+    ).to.equal(true);
+  });
+
+  it('Subscribers cancel', async () => {
+    const { contract, token, sender, subscriber } = await setup();
+
+    await token.tx.approve(contract.address, 1000, { signer: subscriber });
+    await contract.tx.subscribe(0, { signer: subscriber });
+    await expect(contract.tx.cancel(sender.address, { signer: subscriber }))
+      .to.emit(contract, 'Cancelled')
+      .withArgs(sender.address, subscriber.address, 0);
+
+    await expect(() =>
+      contract.tx.charge(subscriber.address)
+    ).to.changeTokenBalance(token, subscriber, 0);
+  });
+});
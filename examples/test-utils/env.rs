@@ -0,0 +1,33 @@
+//! Helpers driving the off-chain environment of ink!.
+//!
+//! They are macros, so they expand to the `ink_env` the contract under test
+//! links instead of pulling in a copy of their own that would have to match it.
+
+/// Pushes a new execution context so that `caller` calls the contract, sending
+/// `value` native funds along, or none if it is left out.
+#[macro_export]
+macro_rules! set_caller {
+    ($caller:expr) => {
+        $crate::set_caller!($caller, 0)
+    };
+    ($caller:expr, $value:expr) => {{
+        let callee = ::ink_env::account_id::<::ink_env::DefaultEnvironment>()
+            .unwrap_or([0x0; 32].into());
+        let data =
+            ::ink_env::test::CallData::new(::ink_env::call::Selector::new([0x00; 4]));
+        ::ink_env::test::push_execution_context::<::ink_env::DefaultEnvironment>(
+            $caller, callee, 1000000, $value, data,
+        );
+    }};
+}
+
+/// Advances the off-chain environment by `blocks` blocks.
+#[macro_export]
+macro_rules! advance_blocks {
+    ($blocks:expr) => {
+        for _ in 0..$blocks {
+            ::ink_env::test::advance_block::<::ink_env::DefaultEnvironment>()
+                .expect("Cannot advance block");
+        }
+    };
+}
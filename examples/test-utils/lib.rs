@@ -0,0 +1,10 @@
+//! Helpers shared by the unit tests of the examples.
+//!
+//! Examples use this crate as a dev-dependency, so it never ends up in a
+//! contract and can rely on `std`.
+//!
+//! The off-chain environment of ink! can not call other contracts, so the unit
+//! tests leave those calls to the redspot tests of the examples.
+
+mod env;
+pub mod stateful;
\ No newline at end of file
@@ -0,0 +1,84 @@
+//! A small helper for stateful property tests.
+//!
+//! [`check`] drives a state through pseudo random sequences of operations and
+//! asserts an invariant after every operation.
+
+use core::{
+    fmt::Debug,
+    ops::Range,
+};
+
+/// A xorshift pseudo random number generator.
+///
+/// The generated sequence only depends on the seed, so a failing run can be
+/// replayed.
+pub struct Rng(u64);
+
+impl Rng {
+    /// Creates a generator for `seed`.
+    pub fn new(seed: u64) -> Self {
+        // Xorshift never leaves the all zero state.
+        Self(seed.wrapping_mul(0x9E37_79B9_7F4A_7C15) | 1)
+    }
+
+    /// Returns the next pseudo random number.
+    pub fn next_u64(&mut self) -> u64 {
+        let mut x = self.0;
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        self.0 = x;
+        x
+    }
+
+    /// Returns a number in `0..bound`.
+    pub fn below(&mut self, bound: u64) -> u64 {
+        self.next_u64() % bound
+    }
+
+    /// Returns one of `items`.
+    pub fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.below(items.len() as u64) as usize]
+    }
+}
+
+/// Runs `steps` operations for every seed in `seeds`.
+///
+/// Each run starts from the state returned by `init`. Operations are drawn by
+/// `generate` and applied by `apply`.
+///
+/// # Panics
+///
+/// If `invariant` does not hold after an operation, with the seed and the
+/// operations applied so far.
+pub fn check<S, O, I, G, A, V>(
+    seeds: Range<u64>,
+    steps: usize,
+    mut init: I,
+    mut generate: G,
+    mut apply: A,
+    invariant: V,
+) where
+    O: Debug,
+    I: FnMut() -> S,
+    G: FnMut(&mut Rng) -> O,
+    A: FnMut(&mut S, &O),
+    V: Fn(&S) -> bool,
+{
+    for seed in seeds {
+        let mut rng = Rng::new(seed);
+        let mut state = init();
+        let mut history = Vec::with_capacity(steps);
+        for _ in 0..steps {
+            let operation = generate(&mut rng);
+            apply(&mut state, &operation);
+            history.push(operation);
+            assert!(
+                invariant(&state),
+                "invariant violated for seed {} after {:?}",
+                seed,
+                history
+            );
+        }
+    }
+}
@@ -0,0 +1,613 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use ink_lang as ink;
+
+#[ink::contract]
+mod timelock {
+    use ink_env::{
+        call::{
+            build_call,
+            ExecutionInput,
+            Selector,
+        },
+        DefaultEnvironment,
+        ReturnFlags,
+    };
+    use ink_prelude::vec::Vec;
+    use ink_storage::traits::{
+        PackedLayout,
+        SpreadLayout,
+    };
+    #[cfg(not(feature = "ink-as-dependency"))]
+    use ink_storage::{
+        collections::HashMap as StorageHashMap,
+        lazy::Lazy,
+    };
+
+    /// An operation ID.
+    pub type OperationId = u32;
+
+    /// A call the timelock makes once its delay passed.
+    #[derive(
+        Debug,
+        Clone,
+        PartialEq,
+        Eq,
+        scale::Encode,
+        scale::Decode,
+        SpreadLayout,
+        PackedLayout,
+    )]
+    #[cfg_attr(
+        feature = "std",
+        derive(::scale_info::TypeInfo, ::ink_storage::traits::StorageLayout)
+    )]
+    pub struct Operation {
+        /// The contract to call.
+        pub target: AccountId,
+        /// The selector of the message to call.
+        pub selector: [u8; 4],
+        /// The SCALE encoded arguments of the message.
+        pub input: Vec<u8>,
+        /// The first block in which the call can be executed.
+        pub ready_at: BlockNumber,
+        /// Whether the call was executed.
+        pub executed: bool,
+    }
+
+    /// The stage an operation is in.
+    #[derive(Debug, Copy, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum OperationState {
+        /// The delay did not pass yet.
+        Waiting,
+        /// The operation can be executed.
+        Ready,
+        /// The operation was executed.
+        Done,
+    }
+
+    /// The roles accounts can be granted.
+    #[derive(Debug, Copy, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum Role {
+        /// May schedule and cancel operations.
+        Proposer,
+        /// May execute operations once they are ready.
+        Executor,
+    }
+
+    /// Passes already encoded arguments on to a call without encoding them again.
+    struct CallInput<'a>(&'a [u8]);
+
+    impl<'a> scale::Encode for CallInput<'a> {
+        fn encode_to<T: scale::Output + ?Sized>(&self, dest: &mut T) {
+            dest.write(self.0);
+        }
+    }
+
+    /// Delays the calls its proposers schedule by at least `min_delay` blocks
+    /// before its executors can make them.
+    ///
+    /// Made the owner of a contract such as the erc20 example, it gives everyone
+    /// time to react to admin calls before they take effect. A governor like the
+    /// governor example is usually its only proposer.
+    ///
+    /// Roles and the minimum delay can only be changed by the timelock itself,
+    /// that is by an operation calling the timelock.
+    #[ink(storage)]
+    pub struct Timelock {
+        /// The minimum number of blocks between scheduling and executing a call.
+        min_delay: Lazy<BlockNumber>,
+        /// The accounts holding the `Proposer` role.
+        proposers: StorageHashMap<AccountId, ()>,
+        /// The accounts holding the `Executor` role.
+        executors: StorageHashMap<AccountId, ()>,
+        /// Mapping from ID to scheduled operation.
+        operations: StorageHashMap<OperationId, Operation>,
+        /// The ID the next scheduled operation gets.
+        next_operation_id: Lazy<OperationId>,
+    }
+
+    /// Event emitted when a proposer schedules an operation.
+    #[ink(event)]
+    pub struct CallScheduled {
+        #[ink(topic)]
+        id: OperationId,
+        #[ink(topic)]
+        target: AccountId,
+        ready_at: BlockNumber,
+    }
+
+    /// Event emitted when an operation is executed.
+    #[ink(event)]
+    pub struct CallExecuted {
+        #[ink(topic)]
+        id: OperationId,
+    }
+
+    /// Event emitted when a proposer cancels an operation.
+    #[ink(event)]
+    pub struct Cancelled {
+        #[ink(topic)]
+        id: OperationId,
+    }
+
+    /// Event emitted when a role is granted to an account.
+    #[ink(event)]
+    pub struct RoleGranted {
+        role: Role,
+        #[ink(topic)]
+        account: AccountId,
+    }
+
+    /// Event emitted when a role is revoked from an account.
+    #[ink(event)]
+    pub struct RoleRevoked {
+        role: Role,
+        #[ink(topic)]
+        account: AccountId,
+    }
+
+    /// Event emitted when the minimum delay changes.
+    #[ink(event)]
+    pub struct MinDelayChange {
+        old_delay: BlockNumber,
+        new_delay: BlockNumber,
+    }
+
+    /// The timelock error types.
+    #[derive(Debug, PartialEq, Eq, scale::Encode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum Error {
+        /// Returned if the caller does not hold the `Proposer` role.
+        NotProposer,
+        /// Returned if the caller does not hold the `Executor` role.
+        NotExecutor,
+        /// Returned if the caller is not the timelock itself.
+        NotTimelock,
+        /// Returned if an operation would be delayed less than the minimum delay.
+        InsufficientDelay,
+        /// Returned if no operation has the given ID.
+        OperationNotFound,
+        /// Returned if the delay of the operation did not pass yet.
+        NotReady,
+        /// Returned if the operation was executed.
+        AlreadyExecuted,
+        /// Returned if the call of an operation failed.
+        ExecutionFailed,
+    }
+
+    /// The timelock result type.
+    pub type Result<T> = core::result::Result<T, Error>;
+
+    impl Timelock {
+        /// Creates a timelock delaying calls by at least `min_delay` blocks,
+        /// granting the `Proposer` role to `proposers` and the `Executor` role to
+        /// `executors`.
+        #[ink(constructor)]
+        pub fn new(
+            min_delay: BlockNumber,
+            proposers: Vec<AccountId>,
+            executors: Vec<AccountId>,
+        ) -> Self {
+            Self {
+                min_delay: Lazy::new(min_delay),
+                proposers: proposers.into_iter().map(|account| (account, ())).collect(),
+                executors: executors.into_iter().map(|account| (account, ())).collect(),
+                operations: StorageHashMap::new(),
+                next_operation_id: Lazy::new(0),
+            }
+        }
+
+        /// Returns the minimum number of blocks calls are delayed by.
+        #[ink(message)]
+        pub fn min_delay(&self) -> BlockNumber {
+            *self.min_delay
+        }
+
+        /// Returns `true` if `account` holds `role`.
+        #[ink(message)]
+        pub fn has_role(&self, role: Role, account: AccountId) -> bool {
+            self.members(role).contains_key(&account)
+        }
+
+        /// Returns the operation with ID `id`, if any.
+        #[ink(message)]
+        pub fn operation(&self, id: OperationId) -> Option<Operation> {
+            self.operations.get(&id).cloned()
+        }
+
+        /// Returns the stage the operation with ID `id` is in, if it exists.
+        #[ink(message)]
+        pub fn state(&self, id: OperationId) -> Option<OperationState> {
+            let operation = self.operations.get(&id)?;
+            let state = if operation.executed {
+                OperationState::Done
+            } else if self.env().block_number() < operation.ready_at {
+                OperationState::Waiting
+            } else {
+                OperationState::Ready
+            };
+            Some(state)
+        }
+
+        /// Schedules a call of the message with `selector` of `target` passing the
+        /// SCALE encoded `input`, which can be executed after `delay` blocks, and
+        /// returns the operation ID.
+        ///
+        /// On success a `CallScheduled` event is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `NotProposer` error if the caller does not hold the `Proposer`
+        /// role.
+        ///
+        /// Returns `InsufficientDelay` error if `delay` is less than the minimum
+        /// delay.
+        #[ink(message)]
+        pub fn schedule(
+            &mut self,
+            target: AccountId,
+            selector: [u8; 4],
+            input: Vec<u8>,
+            delay: BlockNumber,
+        ) -> Result<OperationId> {
+            self.ensure_role(Role::Proposer)?;
+            if delay < *self.min_delay {
+                return Err(Error::InsufficientDelay)
+            }
+            let ready_at = self.env().block_number().saturating_add(delay);
+            let id = *self.next_operation_id;
+            *self.next_operation_id += 1;
+            self.operations.insert(
+                id,
+                Operation {
+                    target,
+                    selector,
+                    input,
+                    ready_at,
+                    executed: false,
+                },
+            );
+            self.env().emit_event(CallScheduled {
+                id,
+                target,
+                ready_at,
+            });
+            Ok(id)
+        }
+
+        /// Cancels the operation with ID `id` before it is executed.
+        ///
+        /// On success a `Cancelled` event is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `NotProposer` error if the caller does not hold the `Proposer`
+        /// role.
+        ///
+        /// Returns `OperationNotFound` error if there is no such operation.
+        ///
+        /// Returns `AlreadyExecuted` error if the operation was executed.
+        #[ink(message)]
+        pub fn cancel(&mut self, id: OperationId) -> Result<()> {
+            self.ensure_role(Role::Proposer)?;
+            self.pending_operation(id)?;
+            self.operations.take(&id);
+            self.env().emit_event(Cancelled { id });
+            Ok(())
+        }
+
+        /// Executes the operation with ID `id` once its delay passed.
+        ///
+        /// The value returned by the call is not interpreted. On success a
+        /// `CallExecuted` event is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `NotExecutor` error if the caller does not hold the `Executor`
+        /// role.
+        ///
+        /// Returns `OperationNotFound` error if there is no such operation.
+        ///
+        /// Returns `AlreadyExecuted` error if the operation was executed.
+        ///
+        /// Returns `NotReady` error if the delay of the operation did not pass yet.
+        ///
+        /// Returns `ExecutionFailed` error, reverting all changes, if the call
+        /// fails.
+        #[ink(message)]
+        pub fn execute(&mut self, id: OperationId) -> Result<()> {
+            self.ensure_role(Role::Executor)?;
+            let now = self.env().block_number();
+            let operation = self.pending_operation(id)?;
+            if now < operation.ready_at {
+                return Err(Error::NotReady)
+            }
+            operation.executed = true;
+            let (target, selector, input) = (
+                operation.target,
+                operation.selector,
+                operation.input.clone(),
+            );
+            // ink! writes the contract storage back only when a message returns.
+            // Write it now so the target, which may be this timelock, already sees
+            // the operation as executed and can not execute it a second time.
+            // Everything else the target may change is kept in `Lazy` cells and
+            // mapping entries, which are only written back again if modified.
+            ink_storage::traits::push_spread_root::<Self>(
+                self,
+                &ink_primitives::Key::from([0x00; 32]),
+            );
+            let result = build_call::<DefaultEnvironment>()
+                .callee(target)
+                .gas_limit(0)
+                .transferred_value(0)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(selector))
+                        .push_arg(CallInput(&input)),
+                )
+                .returns::<()>()
+                .fire();
+            if result.is_err() {
+                Self::revert(Error::ExecutionFailed)
+            }
+            self.env().emit_event(CallExecuted { id });
+            Ok(())
+        }
+
+        /// Grants `role` to `account`.
+        ///
+        /// On success a `RoleGranted` event is emitted if `account` did not hold
+        /// `role` yet.
+        ///
+        /// # Errors
+        ///
+        /// Returns `NotTimelock` error if the caller is not the timelock itself.
+        #[ink(message)]
+        pub fn grant_role(&mut self, role: Role, account: AccountId) -> Result<()> {
+            self.ensure_from_timelock()?;
+            if self.members_mut(role).insert(account, ()).is_none() {
+                self.env().emit_event(RoleGranted { role, account });
+            }
+            Ok(())
+        }
+
+        /// Revokes `role` from `account`.
+        ///
+        /// On success a `RoleRevoked` event is emitted if `account` held `role`.
+        ///
+        /// # Errors
+        ///
+        /// Returns `NotTimelock` error if the caller is not the timelock itself.
+        #[ink(message)]
+        pub fn revoke_role(&mut self, role: Role, account: AccountId) -> Result<()> {
+            self.ensure_from_timelock()?;
+            if self.members_mut(role).take(&account).is_some() {
+                self.env().emit_event(RoleRevoked { role, account });
+            }
+            Ok(())
+        }
+
+        /// Changes the minimum delay to `new_delay` blocks.
+        ///
+        /// Operations scheduled before keep their delay. On success a
+        /// `MinDelayChange` event is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `NotTimelock` error if the caller is not the timelock itself.
+        #[ink(message)]
+        pub fn update_delay(&mut self, new_delay: BlockNumber) -> Result<()> {
+            self.ensure_from_timelock()?;
+            let old_delay = core::mem::replace(&mut *self.min_delay, new_delay);
+            self.env().emit_event(MinDelayChange {
+                old_delay,
+                new_delay,
+            });
+            Ok(())
+        }
+
+        /// Returns the accounts holding `role`.
+        fn members(&self, role: Role) -> &StorageHashMap<AccountId, ()> {
+            match role {
+                Role::Proposer => &self.proposers,
+                Role::Executor => &self.executors,
+            }
+        }
+
+        /// Returns the accounts holding `role` for modification.
+        fn members_mut(&mut self, role: Role) -> &mut StorageHashMap<AccountId, ()> {
+            match role {
+                Role::Proposer => &mut self.proposers,
+                Role::Executor => &mut self.executors,
+            }
+        }
+
+        /// Returns `NotProposer` or `NotExecutor` error if the caller does not hold
+        /// `role`.
+        fn ensure_role(&self, role: Role) -> Result<()> {
+            if self.has_role(role, self.env().caller()) {
+                return Ok(())
+            }
+            match role {
+                Role::Proposer => Err(Error::NotProposer),
+                Role::Executor => Err(Error::NotExecutor),
+            }
+        }
+
+        /// Returns `NotTimelock` error if the caller is not the timelock itself.
+        fn ensure_from_timelock(&self) -> Result<()> {
+            if self.env().caller() != self.env().account_id() {
+                return Err(Error::NotTimelock)
+            }
+            Ok(())
+        }
+
+        /// Returns the operation with ID `id` if it was not executed yet.
+        fn pending_operation(&mut self, id: OperationId) -> Result<&mut Operation> {
+            let operation = self
+                .operations
+                .get_mut(&id)
+                .ok_or(Error::OperationNotFound)?;
+            if operation.executed {
+                return Err(Error::AlreadyExecuted)
+            }
+            Ok(operation)
+        }
+
+        /// Ends the current call with `error`, so an operation whose call fails is
+        /// not `Done`.
+        fn revert(error: Error) -> ! {
+            ink_env::return_value::<Result<()>>(
+                ReturnFlags::default().set_reverted(true),
+                &Err(error),
+            )
+        }
+    }
+
+    /// Unit tests.
+    ///
+    /// Executing operations is left to the redspot tests.
+    #[cfg(test)]
+    mod tests {
+        /// Imports all the definitions from the outer scope so we can use them here.
+        use super::*;
+
+        use ink_lang as ink;
+        use test_utils::{
+            advance_blocks,
+            set_caller,
+        };
+
+        /// Pushes a new execution context so that the timelock calls itself.
+        fn set_timelock_caller() {
+            let timelock = ink_env::account_id::<ink_env::DefaultEnvironment>()
+                .expect("Cannot get contract id");
+            set_caller!(timelock);
+        }
+
+        /// Returns a timelock with a minimum delay of two blocks, Alice as
+        /// proposer and Bob as executor.
+        fn timelock() -> Timelock {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            Timelock::new(2, vec![accounts.alice], vec![accounts.bob])
+        }
+
+        fn schedule(timelock: &mut Timelock, delay: BlockNumber) -> Result<OperationId> {
+            timelock.schedule(AccountId::from([0x07; 32]), [0x00; 4], Vec::new(), delay)
+        }
+
+        #[ink::test]
+        fn new_works() {
+            let timelock = timelock();
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            assert_eq!(timelock.min_delay(), 2);
+            assert!(timelock.has_role(Role::Proposer, accounts.alice));
+            assert!(!timelock.has_role(Role::Executor, accounts.alice));
+            assert!(timelock.has_role(Role::Executor, accounts.bob));
+            assert!(!timelock.has_role(Role::Proposer, accounts.bob));
+        }
+
+        #[ink::test]
+        fn schedule_works() {
+            let mut timelock = timelock();
+            let start = ink_env::block_number::<ink_env::DefaultEnvironment>()
+                .expect("Cannot get block number");
+
+            assert_eq!(schedule(&mut timelock, 1), Err(Error::InsufficientDelay));
+            assert_eq!(schedule(&mut timelock, 3), Ok(0));
+            let operation = timelock.operation(0).expect("operation exists");
+            assert_eq!(operation.ready_at, start + 3);
+            assert_eq!(timelock.state(0), Some(OperationState::Waiting));
+            assert_eq!(timelock.state(1), None);
+            assert_eq!(ink_env::test::recorded_events().count(), 1);
+
+            advance_blocks!(2);
+            assert_eq!(timelock.state(0), Some(OperationState::Waiting));
+            advance_blocks!(1);
+            assert_eq!(timelock.state(0), Some(OperationState::Ready));
+        }
+
+        #[ink::test]
+        fn roles_are_separated() {
+            let mut timelock = timelock();
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            assert_eq!(schedule(&mut timelock, 2), Ok(0));
+            advance_blocks!(2);
+            assert_eq!(timelock.execute(0), Err(Error::NotExecutor));
+
+            set_caller!(accounts.bob);
+            assert_eq!(schedule(&mut timelock, 2), Err(Error::NotProposer));
+            assert_eq!(timelock.cancel(0), Err(Error::NotProposer));
+        }
+
+        #[ink::test]
+        fn execute_waits_for_delay() {
+            let mut timelock = timelock();
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            assert_eq!(schedule(&mut timelock, 2), Ok(0));
+            set_caller!(accounts.bob);
+            assert_eq!(timelock.execute(0), Err(Error::NotReady));
+            assert_eq!(timelock.execute(1), Err(Error::OperationNotFound));
+        }
+
+        #[ink::test]
+        fn cancel_works() {
+            let mut timelock = timelock();
+
+            assert_eq!(schedule(&mut timelock, 2), Ok(0));
+            assert_eq!(schedule(&mut timelock, 2), Ok(1));
+            assert_eq!(timelock.cancel(0), Ok(()));
+            assert_eq!(timelock.operation(0), None);
+            assert_eq!(timelock.cancel(0), Err(Error::OperationNotFound));
+
+            timelock
+                .operations
+                .get_mut(&1)
+                .expect("operation exists")
+                .executed = true;
+            assert_eq!(timelock.state(1), Some(OperationState::Done));
+            assert_eq!(timelock.cancel(1), Err(Error::AlreadyExecuted));
+        }
+
+        #[ink::test]
+        fn only_timelock_manages_itself() {
+            let mut timelock = timelock();
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            assert_eq!(
+                timelock.grant_role(Role::Proposer, accounts.eve),
+                Err(Error::NotTimelock)
+            );
+            assert_eq!(
+                timelock.revoke_role(Role::Proposer, accounts.alice),
+                Err(Error::NotTimelock)
+            );
+            assert_eq!(timelock.update_delay(0), Err(Error::NotTimelock));
+
+            set_timelock_caller();
+            assert_eq!(timelock.grant_role(Role::Proposer, accounts.eve), Ok(()));
+            assert!(timelock.has_role(Role::Proposer, accounts.eve));
+            assert_eq!(timelock.revoke_role(Role::Executor, accounts.bob), Ok(()));
+            assert!(!timelock.has_role(Role::Executor, accounts.bob));
+            assert_eq!(timelock.update_delay(5), Ok(()));
+            assert_eq!(timelock.min_delay(), 5);
+            assert_eq!(ink_env::test::recorded_events().count(), 3);
+        }
+    }
+}
\ No newline at end of file
@@ -0,0 +1,130 @@
+import BN from 'bn.js';
+import { expect } from 'chai';
+import { patract, network } from 'redspot';
+
+const { getContractFactory, getRandomSigner } = patract;
+
+const { api, getSigners } = network;
+
+// Selector of the erc20 `accept_ownership` message.
+const ACCEPT_OWNERSHIP_SELECTOR = '0xb55be9f0';
+
+// Selector of the timelock's own `update_delay` message.
+const UPDATE_DELAY_SELECTOR = '0x43e492f5';
+
+const MIN_DELAY = 2;
+
+describe('TIMELOCK', () => {
+  after(() => {
+    return api.disconnect();
+  });
+
+  async function setup() {
+    const one = new BN(10).pow(new BN(api.registry.chainDecimals[0]));
+    const signers = await getSigners();
+    const Alice = signers[0];
+    const sender = await getRandomSigner(Alice, one.muln(10000));
+    const executor = await getRandomSigner(Alice, one.muln(10000));
+    const tokenFactory = await getContractFactory('erc20', sender);
+    const token = await tokenFactory.deploy(
+      'new',
+      '1000',
+      'Redspot',
+      'RDS',
+      10
+    );
+    const contractFactory = await getContractFactory('timelock', sender);
+    const contract = await contractFactory.deploy(
+      'new',
+      MIN_DELAY,
+      [sender.address],
+      [executor.address]
+    );
+    // An operation making the timelock the owner of the token.
+    await token.tx.proposeOwner(contract.address);
+    await contract.tx.schedule(
+      token.address,
+      ACCEPT_OWNERSHIP_SELECTOR,
+      '0x',
+      MIN_DELAY
+    );
+
+    // The development node seals a block for every transaction, so blocks
+    // pass by sending transactions which change nothing.
+    async function advanceBlocks(blocks: number) {
+      for (let i = 0; i < blocks; i++) {
+        await token.tx.approve(contract.address, 0);
+      }
+    }
+
+    return {
+      sender,
+      executor,
+      token,
+      contractFactory,
+      contract,
+      advanceBlocks,
+      Alice,
+      one
+    };
+  }
+
+  it('Executes an operation after the delay', async () => {
+    const { contract, token, executor, advanceBlocks } = await setup();
+
+    await expect(
+      contract.tx.execute(0, { signer: executor })
+    ).to.not.emit(contract, 'CallExecuted');
+
+    await advanceBlocks(1);
+    await expect(contract.tx.execute(0, { signer: executor }))
+      .to.emit(contract, 'CallExecuted')
+      .withArgs(0);
+
+    expect((await token.query.owner()).output).to.equal(contract.address);
+  });
+
+  it('Can not execute without the executor role', async () => {
+    const { contract, advanceBlocks } = await setup();
+
+    await advanceBlocks(MIN_DELAY);
+    await expect(contract.tx.execute(0)).to.not.emit(contract, 'CallExecuted');
+  });
+
+  it('Does not execute a cancelled operation', async () => {
+    const { contract, token, sender, executor, advanceBlocks } = await setup();
+
+    await expect(contract.tx.cancel(0))
+      .to.emit(contract, 'Cancelled')
+      .withArgs(0);
+    await advanceBlocks(MIN_DELAY);
+
+    await expect(
+      contract.tx.execute(0, { signer: executor })
+    ).to.not.emit(contract, 'CallExecuted');
+    expect((await token.query.owner()).output).to.equal(sender.address);
+  });
+
+  it('Changes its delay through an operation', async () => {
+    const { contract, executor, advanceBlocks } = await setup();
+
+    await expect(contract.tx.updateDelay(5)).to.not.emit(
+      contract,
+      'MinDelayChange'
+    );
+
+    await contract.tx.schedule(
+      contract.address,
+      UPDATE_DELAY_SELECTOR,
+      api.createType('u32', 5).toHex(),
+      MIN_DELAY
+    );
+    await advanceBlocks(MIN_DELAY);
+    await expect(contract.tx.execute(1, { signer: executor })).to.emit(
+      contract,
+      'MinDelayChange'
+    );
+
+    expect((await contract.query.minDelay()).output).to.equal(5);
+  });
+});
@@ -0,0 +1,318 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use ink_lang as ink;
+
+#[ink::contract]
+mod token_factory {
+    use erc20::TRANSFER_SELECTOR;
+    use ink_env::{
+        call::{
+            build_call,
+            build_create,
+            utils::ReturnType,
+            ExecutionInput,
+            FromAccountId,
+            Selector,
+        },
+        DefaultEnvironment,
+        ReturnFlags,
+    };
+    use ink_prelude::{
+        string::String,
+        vec::Vec,
+    };
+    #[cfg(not(feature = "ink-as-dependency"))]
+    use ink_storage::collections::{
+        HashMap as StorageHashMap,
+        Vec as StorageVec,
+    };
+
+    /// Selector of the erc20 example's `new(initial_supply, name, symbol, decimals)`.
+    const NEW_SELECTOR: [u8; 4] = [0x9B, 0xAE, 0x9D, 0x5E];
+
+    /// Selector of the erc20 example's `propose_owner(new_owner) -> Result<()>`.
+    const PROPOSE_OWNER_SELECTOR: [u8; 4] = [0x1F, 0x4B, 0x98, 0x6A];
+
+    /// Instantiates tokens of the erc20 example and keeps a registry of them.
+    ///
+    /// The factory instantiates every token from the code hash of the erc20
+    /// example, which has to be on chain already. It mints the initial supply
+    /// of the token, so the factory transfers it on to the creator and
+    /// proposes the creator as the owner of the token. The creator takes over
+    /// with `accept_ownership` on the token and may then revoke the minter
+    /// role the factory still holds.
+    ///
+    /// The tokens are numbered in the order they were created, so they can be
+    /// enumerated with `token_count` and `token_at`, or page by page with
+    /// `tokens`.
+    #[ink(storage)]
+    pub struct TokenFactory {
+        /// The code hash tokens are instantiated from.
+        token_code_hash: Hash,
+        /// The tokens in the order they were created.
+        tokens: StorageVec<AccountId>,
+        /// The creator of every token.
+        creators: StorageHashMap<AccountId, AccountId>,
+    }
+
+    /// Event emitted when a token is created.
+    #[ink(event)]
+    pub struct TokenCreated {
+        #[ink(topic)]
+        token: AccountId,
+        #[ink(topic)]
+        creator: AccountId,
+        index: u32,
+    }
+
+    /// The token factory error types.
+    #[derive(Debug, PartialEq, Eq, scale::Encode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum Error {
+        /// Returned if the token could not be instantiated.
+        InstantiationFailed,
+        /// Returned if the initial supply could not be transferred to the
+        /// creator.
+        TransferFailed,
+        /// Returned if the creator could not be proposed as the owner.
+        HandoverFailed,
+        /// Returned if the registry is full.
+        Overflow,
+    }
+
+    /// The token factory result type.
+    pub type Result<T> = core::result::Result<T, Error>;
+
+    /// A token instantiated by the factory.
+    struct Token(AccountId);
+
+    impl FromAccountId<DefaultEnvironment> for Token {
+        fn from_account_id(account_id: AccountId) -> Self {
+            Self(account_id)
+        }
+    }
+
+    impl TokenFactory {
+        /// Creates a factory instantiating tokens from `token_code_hash`.
+        #[ink(constructor)]
+        pub fn new(token_code_hash: Hash) -> Self {
+            Self {
+                token_code_hash,
+                tokens: StorageVec::new(),
+                creators: StorageHashMap::new(),
+            }
+        }
+
+        /// Returns the code hash tokens are instantiated from.
+        #[ink(message)]
+        pub fn token_code_hash(&self) -> Hash {
+            self.token_code_hash
+        }
+
+        /// Returns the number of tokens created.
+        #[ink(message)]
+        pub fn token_count(&self) -> u32 {
+            self.tokens.len()
+        }
+
+        /// Returns the token created as the `index`th, counting from zero.
+        #[ink(message)]
+        pub fn token_at(&self, index: u32) -> Option<AccountId> {
+            self.tokens.get(index).copied()
+        }
+
+        /// Returns up to `limit` tokens, starting with the `start`th.
+        #[ink(message)]
+        pub fn tokens(&self, start: u32, limit: u32) -> Vec<AccountId> {
+            let end = start.saturating_add(limit).min(self.tokens.len());
+            (start..end)
+                .filter_map(|index| self.tokens.get(index).copied())
+                .collect()
+        }
+
+        /// Returns the account which created `token`, or `None` if the factory
+        /// did not create it.
+        #[ink(message)]
+        pub fn creator_of(&self, token: AccountId) -> Option<AccountId> {
+            self.creators.get(&token).copied()
+        }
+
+        /// Creates a token with `initial_supply` held by the caller and returns
+        /// it.
+        ///
+        /// The funds sent along endow the token. The caller is proposed as the
+        /// owner of the token. On success a `TokenCreated` event is emitted.
+        ///
+        /// # Errors
+        ///
+        /// All errors revert all changes, so the funds sent along are returned.
+        ///
+        /// Returns `InstantiationFailed` error if the token could not be
+        /// instantiated.
+        ///
+        /// Returns `TransferFailed` error if the initial supply could not be
+        /// transferred to the caller.
+        ///
+        /// Returns `HandoverFailed` error if the caller could not be proposed
+        /// as the owner of the token.
+        ///
+        /// Returns `Overflow` error if the registry is full.
+        #[ink(message, payable)]
+        pub fn create_token(
+            &mut self,
+            initial_supply: Balance,
+            name: Option<String>,
+            symbol: Option<String>,
+            decimals: u8,
+        ) -> Result<AccountId> {
+            let creator = self.env().caller();
+            let token = match self.instantiate(initial_supply, name, symbol, decimals) {
+                Ok(token) => token,
+                Err(error) => Self::revert(error),
+            };
+            if !self.call_token(
+                token,
+                ExecutionInput::new(Selector::new(TRANSFER_SELECTOR))
+                    .push_arg(creator)
+                    .push_arg(initial_supply),
+            ) {
+                Self::revert(Error::TransferFailed)
+            }
+            if !self.call_token(
+                token,
+                ExecutionInput::new(Selector::new(PROPOSE_OWNER_SELECTOR))
+                    .push_arg(creator),
+            ) {
+                Self::revert(Error::HandoverFailed)
+            }
+            if let Err(error) = self.record_token(token, creator) {
+                Self::revert(error)
+            }
+            Ok(token)
+        }
+
+        /// Records `token` as created by `creator` and returns its index.
+        fn record_token(&mut self, token: AccountId, creator: AccountId) -> Result<u32> {
+            let index = self.tokens.len();
+            if index == u32::MAX {
+                return Err(Error::Overflow)
+            }
+            self.tokens.push(token);
+            self.creators.insert(token, creator);
+            self.env().emit_event(TokenCreated {
+                token,
+                creator,
+                index,
+            });
+            Ok(index)
+        }
+
+        /// Instantiates a token with the funds sent along, salted with the
+        /// number of tokens so far so every token gets a new address.
+        fn instantiate(
+            &self,
+            initial_supply: Balance,
+            name: Option<String>,
+            symbol: Option<String>,
+            decimals: u8,
+        ) -> Result<AccountId> {
+            build_create::<DefaultEnvironment, Token>()
+                .code_hash(self.token_code_hash)
+                .gas_limit(0)
+                .endowment(self.env().transferred_balance())
+                .exec_input(
+                    ExecutionInput::new(Selector::new(NEW_SELECTOR))
+                        .push_arg(initial_supply)
+                        .push_arg(name)
+                        .push_arg(symbol)
+                        .push_arg(decimals),
+                )
+                .salt_bytes(self.tokens.len().to_le_bytes())
+                .instantiate()
+                .map(|token| token.0)
+                .map_err(|_| Error::InstantiationFailed)
+        }
+
+        /// Calls a message of `token` returning the erc20 example's `Result<()>`
+        /// and returns whether that succeeded.
+        fn call_token<Args: scale::Encode>(
+            &self,
+            token: AccountId,
+            input: ExecutionInput<Args>,
+        ) -> bool {
+            let result = build_call::<DefaultEnvironment>()
+                .callee(token)
+                .gas_limit(0)
+                .transferred_value(0)
+                .exec_input(input)
+                .returns::<ReturnType<core::result::Result<(), u8>>>()
+                .fire();
+            matches!(result, Ok(Ok(())))
+        }
+
+        /// Ends the current call with `error`, so no token is recorded, and the
+        /// endowment sent along goes back to the caller, when creating or handing
+        /// over the token fails.
+        fn revert(error: Error) -> ! {
+            ink_env::return_value::<Result<()>>(
+                ReturnFlags::default().set_reverted(true),
+                &Err(error),
+            )
+        }
+    }
+
+    /// Unit tests.
+    ///
+    /// Instantiating and handing over tokens is left to the redspot tests.
+    #[cfg(test)]
+    mod tests {
+        /// Imports all the definitions from the outer scope so we can use them here.
+        use super::*;
+
+        use ink_lang as ink;
+
+        #[ink::test]
+        fn new_works() {
+            let factory = TokenFactory::new(Hash::from([0x07; 32]));
+
+            assert_eq!(factory.token_code_hash(), Hash::from([0x07; 32]));
+            assert_eq!(factory.token_count(), 0);
+            assert_eq!(factory.token_at(0), None);
+            assert_eq!(factory.tokens(0, 10), Vec::new());
+        }
+
+        #[ink::test]
+        fn tokens_are_enumerated() {
+            let mut factory = TokenFactory::new(Hash::from([0x07; 32]));
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let tokens: Vec<AccountId> =
+                (1..=3).map(|byte| AccountId::from([byte; 32])).collect();
+
+            assert_eq!(factory.record_token(tokens[0], accounts.alice), Ok(0));
+            assert_eq!(factory.record_token(tokens[1], accounts.bob), Ok(1));
+            assert_eq!(factory.record_token(tokens[2], accounts.alice), Ok(2));
+            assert_eq!(factory.token_count(), 3);
+            assert_eq!(factory.token_at(1), Some(tokens[1]));
+            assert_eq!(factory.token_at(3), None);
+            assert_eq!(factory.tokens(0, 2), tokens[..2].to_vec());
+            assert_eq!(factory.tokens(1, 10), tokens[1..].to_vec());
+            assert_eq!(factory.tokens(3, 10), Vec::new());
+            assert_eq!(ink_env::test::recorded_events().count(), 3);
+        }
+
+        #[ink::test]
+        fn creators_are_recorded() {
+            let mut factory = TokenFactory::new(Hash::from([0x07; 32]));
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let token = AccountId::from([0x01; 32]);
+
+            assert_eq!(factory.creator_of(token), None);
+            assert_eq!(factory.record_token(token, accounts.bob), Ok(0));
+            assert_eq!(factory.creator_of(token), Some(accounts.bob));
+        }
+    }
+}
\ No newline at end of file
@@ -0,0 +1,88 @@
+import BN from 'bn.js';
+import { expect } from 'chai';
+import { patract, network } from 'redspot';
+
+const { getContractFactory, getContractAt, getRandomSigner } = patract;
+
+const { api, getSigners } = network;
+
+describe('TOKEN FACTORY', () => {
+  after(() => {
+    return api.disconnect();
+  });
+
+  async function setup() {
+    const one = new BN(10).pow(new BN(api.registry.chainDecimals[0]));
+    const signers = await getSigners();
+    const Alice = signers[0];
+    const sender = await getRandomSigner(Alice, one.muln(10000));
+    const creator = await getRandomSigner(Alice, one.muln(10000));
+    const tokenFactory = await getContractFactory('erc20', sender);
+    const contractFactory = await getContractFactory('token_factory', sender);
+
+    // The factory instantiates tokens from code already on chain, deploying
+    // the erc20 contract once on its own puts it there.
+    await tokenFactory.deploy('new', 0, null, null, 0);
+
+    const contract = await contractFactory.deploy(
+      'new',
+      (tokenFactory.abi.json as any).source.hash
+    );
+
+    return { sender, creator, contract, Alice, one };
+  }
+
+  it('Creates tokens held and owned by the creator', async () => {
+    const { contract, creator, one } = await setup();
+
+    await expect(
+      contract.tx.createToken(1000, 'Redspot', 'RDS', 10, {
+        signer: creator,
+        value: one.muln(10)
+      })
+    ).to.emit(contract, 'TokenCreated');
+    const address = (await contract.query.tokenAt(0)).output?.toString();
+    const token = await getContractAt('erc20', address as string, creator);
+
+    expect((await contract.query.creatorOf(address)).output).to.equal(
+      creator.address
+    );
+    expect((await token.query.balanceOf(creator.address)).output).to.equal(
+      1000
+    );
+    expect((await token.query.tokenSymbol()).output?.toString()).to.equal(
+      'RDS'
+    );
+    await expect(token.tx.acceptOwnership()).to.emit(
+      token,
+      'OwnershipTransferred'
+    );
+    expect((await token.query.owner()).output).to.equal(creator.address);
+  });
+
+  it('Enumerates the tokens created', async () => {
+    const { contract, sender, creator, one } = await setup();
+
+    await contract.tx.createToken(1000, 'First', 'ONE', 10, {
+      value: one.muln(10)
+    });
+    await contract.tx.createToken(2000, 'Second', 'TWO', 10, {
+      signer: creator,
+      value: one.muln(10)
+    });
+    const first = (await contract.query.tokenAt(0)).output?.toString();
+    const second = (await contract.query.tokenAt(1)).output?.toString();
+
+    expect((await contract.query.tokenCount()).output).to.equal(2);
+    expect(first).to.not.equal(second);
+    expect((await contract.query.creatorOf(first)).output).to.equal(
+      sender.address
+    );
+    expect((await contract.query.creatorOf(second)).output).to.equal(
+      creator.address
+    );
+    expect(
+      (await contract.query.tokens(1, 10)).output?.toJSON()
+    ).to.deep.equal([second]);
+  });
+});
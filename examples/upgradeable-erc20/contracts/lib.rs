@@ -0,0 +1,415 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use ink_lang as ink;
+
+#[ink::contract]
+mod upgradeable_erc20 {
+    use erc20_logic::Erc20Logic;
+    use erc20_storage::Erc20Storage;
+    use ink_env::call::FromAccountId;
+    use ink_lang::ToAccountId;
+    use ink_storage::Lazy;
+
+    /// An erc20 token whose logic can be upgraded while keeping its balances.
+    ///
+    /// The proxy is the address users talk to and never changes. The token
+    /// state lives in an `Erc20Storage` contract and the token rules in an
+    /// `Erc20Logic` contract working on that storage. Upgrading swaps the
+    /// logic contract and hands it the writer role of the storage.
+    ///
+    /// In order to deploy the proxy we first have to put the code of the
+    /// storage and logic contracts on chain and pass their code hashes to the
+    /// constructor.
+    #[ink(storage)]
+    pub struct UpgradeableErc20 {
+        /// The account allowed to upgrade the logic.
+        admin: AccountId,
+        /// The contract keeping the token state.
+        storage: Lazy<Erc20Storage>,
+        /// The contract implementing the token rules.
+        logic: Lazy<Erc20Logic>,
+    }
+
+    /// Event emitted when a token transfer occurs.
+    #[ink(event)]
+    pub struct Transfer {
+        #[ink(topic)]
+        from: Option<AccountId>,
+        #[ink(topic)]
+        to: Option<AccountId>,
+        #[ink(topic)]
+        value: Balance,
+    }
+
+    /// Event emitted when an approval occurs that `spender` is allowed to withdraw
+    /// up to the amount of `value` tokens from `owner`.
+    #[ink(event)]
+    pub struct Approval {
+        #[ink(topic)]
+        owner: AccountId,
+        #[ink(topic)]
+        spender: AccountId,
+        #[ink(topic)]
+        value: Balance,
+    }
+
+    /// Event emitted when the logic contract is replaced.
+    #[ink(event)]
+    pub struct Upgraded {
+        #[ink(topic)]
+        from: AccountId,
+        #[ink(topic)]
+        to: AccountId,
+    }
+
+    /// The upgradeable token error types.
+    #[derive(Debug, PartialEq, Eq, scale::Encode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum Error {
+        /// Returned if not enough balance to fulfill a request is available.
+        InsufficientBalance,
+        /// Returned if not enough allowance to fulfill a request is available.
+        InsufficientAllowance,
+        /// Returned if the caller is not the admin.
+        NotAdmin,
+        /// Returned if the new logic was built against another storage version.
+        VersionMismatch,
+        /// Returned if the new logic works on another storage or for another proxy.
+        ForeignLogic,
+        /// Returned if the logic is not the writer of the storage anymore.
+        NotWriter,
+    }
+
+    impl From<erc20_logic::Error> for Error {
+        fn from(error: erc20_logic::Error) -> Self {
+            match error {
+                erc20_logic::Error::InsufficientBalance => Error::InsufficientBalance,
+                erc20_logic::Error::InsufficientAllowance => Error::InsufficientAllowance,
+                erc20_logic::Error::NotWriter => Error::NotWriter,
+            }
+        }
+    }
+
+    /// The upgradeable token result type.
+    pub type Result<T> = core::result::Result<T, Error>;
+
+    impl UpgradeableErc20 {
+        /// Creates a new token with `initial_supply` tokens owned by the caller,
+        /// instantiating its storage and first logic from the given codes.
+        #[ink(constructor)]
+        pub fn new(
+            initial_supply: Balance,
+            version: u32,
+            storage_code_hash: Hash,
+            logic_code_hash: Hash,
+        ) -> Self {
+            let caller = Self::env().caller();
+            let total_balance = Self::env().balance();
+            let salt = version.to_le_bytes();
+            let mut storage = Erc20Storage::new(caller, initial_supply)
+                .endowment(total_balance / 4)
+                .code_hash(storage_code_hash)
+                .salt_bytes(salt)
+                .instantiate()
+                .expect("failed at instantiating the `Erc20Storage` contract");
+            let logic =
+                Erc20Logic::new(storage.to_account_id(), Self::env().account_id())
+                    .endowment(total_balance / 4)
+                    .code_hash(logic_code_hash)
+                    .salt_bytes(salt)
+                    .instantiate()
+                    .expect("failed at instantiating the `Erc20Logic` contract");
+            storage
+                .set_writer(logic.to_account_id())
+                .expect("the proxy is the admin of the storage it instantiated");
+            Self::env().emit_event(Transfer {
+                from: None,
+                to: Some(caller),
+                value: initial_supply,
+            });
+            Self {
+                admin: caller,
+                storage: Lazy::new(storage),
+                logic: Lazy::new(logic),
+            }
+        }
+
+        /// Returns the account allowed to upgrade the logic.
+        #[ink(message)]
+        pub fn admin(&self) -> AccountId {
+            self.admin
+        }
+
+        /// Returns the contract keeping the token state.
+        #[ink(message)]
+        pub fn storage(&self) -> AccountId {
+            self.storage.to_account_id()
+        }
+
+        /// Returns the contract implementing the token rules.
+        #[ink(message)]
+        pub fn logic(&self) -> AccountId {
+            self.logic.to_account_id()
+        }
+
+        /// Returns the total token supply.
+        #[ink(message)]
+        pub fn total_supply(&self) -> Balance {
+            self.storage.total_supply()
+        }
+
+        /// Returns the account balance for the specified `owner`.
+        ///
+        /// Returns `0` if the account is non-existent.
+        #[ink(message)]
+        pub fn balance_of(&self, owner: AccountId) -> Balance {
+            self.storage.balance_of(owner)
+        }
+
+        /// Returns the amount which `spender` is still allowed to withdraw from `owner`.
+        ///
+        /// Returns `0` if no allowance has been set.
+        #[ink(message)]
+        pub fn allowance(&self, owner: AccountId, spender: AccountId) -> Balance {
+            self.storage.allowance(owner, spender)
+        }
+
+        /// Transfers `value` amount of tokens from the caller's account to account `to`.
+        ///
+        /// On success a `Transfer` event is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `InsufficientBalance` error if there are not enough tokens on
+        /// the caller's account balance.
+        #[ink(message)]
+        pub fn transfer(&mut self, to: AccountId, value: Balance) -> Result<()> {
+            let from = self.env().caller();
+            self.logic.transfer(from, to, value)?;
+            self.env().emit_event(Transfer {
+                from: Some(from),
+                to: Some(to),
+                value,
+            });
+            Ok(())
+        }
+
+        /// Allows `spender` to withdraw from the caller's account multiple times, up to
+        /// the `value` amount.
+        ///
+        /// If this function is called again it overwrites the current allowance with `value`.
+        ///
+        /// An `Approval` event is emitted.
+        #[ink(message)]
+        pub fn approve(&mut self, spender: AccountId, value: Balance) -> Result<()> {
+            let owner = self.env().caller();
+            self.logic.approve(owner, spender, value)?;
+            self.env().emit_event(Approval {
+                owner,
+                spender,
+                value,
+            });
+            Ok(())
+        }
+
+        /// Transfers `value` tokens on the behalf of `from` to the account `to`.
+        ///
+        /// On success a `Transfer` event is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `InsufficientAllowance` error if there are not enough tokens allowed
+        /// for the caller to withdraw from `from`.
+        ///
+        /// Returns `InsufficientBalance` error if there are not enough tokens on
+        /// the account balance of `from`.
+        #[ink(message)]
+        pub fn transfer_from(
+            &mut self,
+            from: AccountId,
+            to: AccountId,
+            value: Balance,
+        ) -> Result<()> {
+            let caller = self.env().caller();
+            self.logic.transfer_from(caller, from, to, value)?;
+            self.env().emit_event(Transfer {
+                from: Some(from),
+                to: Some(to),
+                value,
+            });
+            Ok(())
+        }
+
+        /// Replaces the logic contract with `new_logic` and hands it the writer
+        /// role of the storage. Balances and allowances are kept as they are.
+        ///
+        /// On success an `Upgraded` event is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `NotAdmin` error if the caller is not the admin.
+        ///
+        /// Returns `ForeignLogic` error if `new_logic` does not work on this
+        /// token's storage on behalf of this proxy.
+        ///
+        /// Returns `VersionMismatch` error if `new_logic` was built against
+        /// another version of the storage than the one this token uses.
+        #[ink(message)]
+        pub fn upgrade(&mut self, new_logic: AccountId) -> Result<()> {
+            if self.env().caller() != self.admin {
+                return Err(Error::NotAdmin)
+            }
+            let logic: Erc20Logic = FromAccountId::from_account_id(new_logic);
+            self.check_logic(
+                logic.storage(),
+                logic.proxy(),
+                logic.storage_version(),
+                self.storage.version(),
+            )?;
+            let from = self.logic();
+            self.storage
+                .set_writer(new_logic)
+                .expect("the proxy is the admin of its storage");
+            *self.logic = logic;
+            self.env().emit_event(Upgraded {
+                from,
+                to: new_logic,
+            });
+            Ok(())
+        }
+
+        /// Checks that a logic contract working on `storage` on behalf of `proxy`
+        /// and built against version `version` of the storage may replace the
+        /// current logic, given the storage of this token has `storage_version`.
+        ///
+        /// # Errors
+        ///
+        /// Returns `ForeignLogic` error if the logic works on another storage or
+        /// for another proxy.
+        ///
+        /// Returns `VersionMismatch` error if the versions differ.
+        fn check_logic(
+            &self,
+            storage: AccountId,
+            proxy: AccountId,
+            version: u32,
+            storage_version: u32,
+        ) -> Result<()> {
+            if storage != self.storage() || proxy != self.env().account_id() {
+                return Err(Error::ForeignLogic)
+            }
+            if version != storage_version {
+                return Err(Error::VersionMismatch)
+            }
+            Ok(())
+        }
+    }
+
+    /// Unit tests.
+    ///
+    /// Instantiating the storage and the logic and forwarding calls to them are
+    /// left to the redspot tests. The proxy is set up directly on top of
+    /// accounts standing in for them, which are never called.
+    #[cfg(test)]
+    mod tests {
+        /// Imports all the definitions from the outer scope so we can use them here.
+        use super::*;
+
+        use ink_lang as ink;
+        use test_utils::set_caller;
+
+        /// Returns a proxy administered by Alice.
+        fn proxy() -> UpgradeableErc20 {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            UpgradeableErc20 {
+                admin: accounts.alice,
+                storage: Lazy::new(FromAccountId::from_account_id(AccountId::from(
+                    [0x07; 32],
+                ))),
+                logic: Lazy::new(FromAccountId::from_account_id(AccountId::from(
+                    [0x08; 32],
+                ))),
+            }
+        }
+
+        #[ink::test]
+        fn accessors_work() {
+            let proxy = proxy();
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            assert_eq!(proxy.admin(), accounts.alice);
+            assert_eq!(proxy.storage(), AccountId::from([0x07; 32]));
+            assert_eq!(proxy.logic(), AccountId::from([0x08; 32]));
+        }
+
+        #[ink::test]
+        fn only_admin_can_upgrade() {
+            let mut proxy = proxy();
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            set_caller!(accounts.bob);
+            assert_eq!(
+                proxy.upgrade(AccountId::from([0x09; 32])),
+                Err(Error::NotAdmin)
+            );
+            assert_eq!(proxy.logic(), AccountId::from([0x08; 32]));
+            assert_eq!(ink_env::test::recorded_events().count(), 0);
+        }
+
+        #[ink::test]
+        fn logic_must_work_on_this_storage_for_this_proxy() {
+            let proxy = proxy();
+            let this = ink_env::account_id::<ink_env::DefaultEnvironment>()
+                .expect("Cannot get contract id");
+            let storage = proxy.storage();
+            let other = AccountId::from([0x09; 32]);
+            let version = erc20_storage::STORAGE_VERSION;
+
+            assert_eq!(proxy.check_logic(storage, this, version, version), Ok(()));
+            assert_eq!(
+                proxy.check_logic(other, this, version, version),
+                Err(Error::ForeignLogic)
+            );
+            assert_eq!(
+                proxy.check_logic(storage, other, version, version),
+                Err(Error::ForeignLogic)
+            );
+        }
+
+        #[ink::test]
+        fn logic_must_be_built_against_the_storage_version() {
+            let proxy = proxy();
+            let this = ink_env::account_id::<ink_env::DefaultEnvironment>()
+                .expect("Cannot get contract id");
+            let storage = proxy.storage();
+
+            assert_eq!(
+                proxy.check_logic(storage, this, 2, 1),
+                Err(Error::VersionMismatch)
+            );
+            assert_eq!(
+                proxy.check_logic(storage, this, 1, 2),
+                Err(Error::VersionMismatch)
+            );
+        }
+
+        #[test]
+        fn logic_errors_are_passed_on() {
+            assert_eq!(
+                Error::from(erc20_logic::Error::InsufficientBalance),
+                Error::InsufficientBalance
+            );
+            assert_eq!(
+                Error::from(erc20_logic::Error::InsufficientAllowance),
+                Error::InsufficientAllowance
+            );
+            assert_eq!(Error::from(erc20_logic::Error::NotWriter), Error::NotWriter);
+        }
+    }
+}
\ No newline at end of file
@@ -0,0 +1,196 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+pub use self::erc20_logic::{
+    Erc20Logic,
+    Error,
+    Result,
+};
+use ink_lang as ink;
+
+#[ink::contract]
+pub mod erc20_logic {
+    use erc20_storage::Erc20Storage;
+    use ink_env::call::FromAccountId;
+    use ink_lang::ToAccountId;
+
+    /// Implements the rules of an upgradeable erc20 token.
+    ///
+    /// The logic contract keeps no token state of its own: it reads and writes
+    /// everything through its `Erc20Storage`. It is only ever called by its
+    /// proxy, which passes the account that called the proxy along as `caller`.
+    #[ink(storage)]
+    pub struct Erc20Logic {
+        /// The contract keeping the token state.
+        storage: Erc20Storage,
+        /// The only account allowed to call the token messages.
+        proxy: AccountId,
+    }
+
+    /// The upgradeable token error types.
+    #[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum Error {
+        /// Returned if not enough balance to fulfill a request is available.
+        InsufficientBalance,
+        /// Returned if not enough allowance to fulfill a request is available.
+        InsufficientAllowance,
+        /// Returned if the storage refused a change since this contract is not
+        /// its writer anymore.
+        NotWriter,
+    }
+
+    impl From<erc20_storage::Error> for Error {
+        fn from(_: erc20_storage::Error) -> Self {
+            // Only the writer messages of the storage are called, which fail
+            // with nothing else.
+            Error::NotWriter
+        }
+    }
+
+    /// The upgradeable token result type.
+    pub type Result<T> = core::result::Result<T, Error>;
+
+    impl Erc20Logic {
+        /// Creates a logic contract working on `storage` on behalf of `proxy`.
+        #[ink(constructor)]
+        pub fn new(storage: AccountId, proxy: AccountId) -> Self {
+            Self {
+                storage: FromAccountId::from_account_id(storage),
+                proxy,
+            }
+        }
+
+        /// Returns the contract keeping the token state.
+        #[ink(message)]
+        pub fn storage(&self) -> AccountId {
+            self.storage.to_account_id()
+        }
+
+        /// Returns the only account allowed to call the token messages.
+        #[ink(message)]
+        pub fn proxy(&self) -> AccountId {
+            self.proxy
+        }
+
+        /// Returns the version of the storage this contract was built against.
+        #[ink(message)]
+        pub fn storage_version(&self) -> u32 {
+            erc20_storage::STORAGE_VERSION
+        }
+
+        /// Transfers `value` amount of tokens from the account `caller` to account `to`.
+        ///
+        /// # Errors
+        ///
+        /// Returns `InsufficientBalance` error if there are not enough tokens on
+        /// the caller's account balance.
+        ///
+        /// Returns `NotWriter` error if this contract is not the writer of the
+        /// storage anymore.
+        ///
+        /// # Panics
+        ///
+        /// If not called by the proxy.
+        #[ink(message)]
+        pub fn transfer(
+            &mut self,
+            caller: AccountId,
+            to: AccountId,
+            value: Balance,
+        ) -> Result<()> {
+            self.ensure_proxy();
+            self.transfer_from_to(caller, to, value)
+        }
+
+        /// Allows `spender` to withdraw from the account `caller` multiple times, up to
+        /// the `value` amount.
+        ///
+        /// If this function is called again it overwrites the current allowance with `value`.
+        ///
+        /// # Errors
+        ///
+        /// Returns `NotWriter` error if this contract is not the writer of the
+        /// storage anymore.
+        ///
+        /// # Panics
+        ///
+        /// If not called by the proxy.
+        #[ink(message)]
+        pub fn approve(
+            &mut self,
+            caller: AccountId,
+            spender: AccountId,
+            value: Balance,
+        ) -> Result<()> {
+            self.ensure_proxy();
+            self.storage.set_allowance(caller, spender, value)?;
+            Ok(())
+        }
+
+        /// Transfers `value` tokens on the behalf of `from` to the account `to`,
+        /// spending the allowance of `caller`.
+        ///
+        /// # Errors
+        ///
+        /// Returns `InsufficientAllowance` error if there are not enough tokens allowed
+        /// for the caller to withdraw from `from`.
+        ///
+        /// Returns `InsufficientBalance` error if there are not enough tokens on
+        /// the account balance of `from`.
+        ///
+        /// Returns `NotWriter` error if this contract is not the writer of the
+        /// storage anymore.
+        ///
+        /// # Panics
+        ///
+        /// If not called by the proxy.
+        #[ink(message)]
+        pub fn transfer_from(
+            &mut self,
+            caller: AccountId,
+            from: AccountId,
+            to: AccountId,
+            value: Balance,
+        ) -> Result<()> {
+            self.ensure_proxy();
+            let allowance = self.storage.allowance(from, caller);
+            if allowance < value {
+                return Err(Error::InsufficientAllowance)
+            }
+            self.transfer_from_to(from, to, value)?;
+            self.storage
+                .set_allowance(from, caller, allowance - value)?;
+            Ok(())
+        }
+
+        /// Transfers `value` amount of tokens from the account `from` to account `to`.
+        ///
+        /// # Errors
+        ///
+        /// Returns `InsufficientBalance` error if there are not enough tokens on
+        /// the account balance of `from`.
+        ///
+        /// Returns `NotWriter` error if this contract is not the writer of the
+        /// storage anymore.
+        fn transfer_from_to(
+            &mut self,
+            from: AccountId,
+            to: AccountId,
+            value: Balance,
+        ) -> Result<()> {
+            let from_balance = self.storage.balance_of(from);
+            if from_balance < value {
+                return Err(Error::InsufficientBalance)
+            }
+            self.storage.set_balance(from, from_balance - value)?;
+            let to_balance = self.storage.balance_of(to);
+            self.storage.set_balance(to, to_balance + value)?;
+            Ok(())
+        }
+
+        /// Panics if the caller is not the proxy.
+        fn ensure_proxy(&self) {
+            assert_eq!(self.env().caller(), self.proxy, "caller is not the proxy");
+        }
+    }
+}
\ No newline at end of file
@@ -0,0 +1,256 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+pub use self::erc20_storage::{
+    Erc20Storage,
+    Error,
+    Result,
+};
+use ink_lang as ink;
+
+/// Version of the data kept by `Erc20Storage`.
+///
+/// The number is not derived from the storage layout, so it has to be bumped
+/// by hand whenever the layout or the meaning of the stored data changes. The
+/// proxy refuses to upgrade to logic contracts built against another version.
+pub const STORAGE_VERSION: u32 = 1;
+
+#[ink::contract]
+pub mod erc20_storage {
+    #[cfg(not(feature = "ink-as-dependency"))]
+    use ink_storage::{
+        collections::HashMap as StorageHashMap,
+        lazy::Lazy,
+    };
+
+    /// Keeps the state of an upgradeable erc20 token.
+    ///
+    /// The storage contract never changes. Only its `writer`, the logic
+    /// contract currently in use, may modify the state, and only its `admin`,
+    /// the proxy, may hand the writer role to a new logic contract.
+    #[ink(storage)]
+    pub struct Erc20Storage {
+        /// The account allowed to change the writer.
+        admin: AccountId,
+        /// The account allowed to modify the state.
+        writer: Lazy<AccountId>,
+        /// Total token supply.
+        total_supply: Lazy<Balance>,
+        /// Mapping from owner to number of owned token.
+        balances: StorageHashMap<AccountId, Balance>,
+        /// Mapping of the token amount which an account is allowed to withdraw
+        /// from another account.
+        allowances: StorageHashMap<(AccountId, AccountId), Balance>,
+    }
+
+    /// The storage error types.
+    #[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum Error {
+        /// Returned if the caller is not the admin.
+        NotAdmin,
+        /// Returned if the caller is not the writer.
+        NotWriter,
+    }
+
+    /// The storage result type.
+    pub type Result<T> = core::result::Result<T, Error>;
+
+    impl Erc20Storage {
+        /// Creates the storage with `initial_supply` tokens owned by `holder`.
+        ///
+        /// The caller becomes both the admin and the initial writer.
+        #[ink(constructor)]
+        pub fn new(holder: AccountId, initial_supply: Balance) -> Self {
+            let caller = Self::env().caller();
+            let mut balances = StorageHashMap::new();
+            balances.insert(holder, initial_supply);
+            Self {
+                admin: caller,
+                writer: Lazy::new(caller),
+                total_supply: Lazy::new(initial_supply),
+                balances,
+                allowances: StorageHashMap::new(),
+            }
+        }
+
+        /// Returns the account allowed to change the writer.
+        #[ink(message)]
+        pub fn admin(&self) -> AccountId {
+            self.admin
+        }
+
+        /// Returns the account allowed to modify the state.
+        #[ink(message)]
+        pub fn writer(&self) -> AccountId {
+            *self.writer
+        }
+
+        /// Returns the version of the data kept by this contract.
+        #[ink(message)]
+        pub fn version(&self) -> u32 {
+            crate::STORAGE_VERSION
+        }
+
+        /// Returns the total token supply.
+        #[ink(message)]
+        pub fn total_supply(&self) -> Balance {
+            *self.total_supply
+        }
+
+        /// Returns the account balance for the specified `owner`.
+        ///
+        /// Returns `0` if the account is non-existent.
+        #[ink(message)]
+        pub fn balance_of(&self, owner: AccountId) -> Balance {
+            self.balances.get(&owner).copied().unwrap_or(0)
+        }
+
+        /// Returns the amount which `spender` is still allowed to withdraw from `owner`.
+        ///
+        /// Returns `0` if no allowance has been set.
+        #[ink(message)]
+        pub fn allowance(&self, owner: AccountId, spender: AccountId) -> Balance {
+            self.allowances.get(&(owner, spender)).copied().unwrap_or(0)
+        }
+
+        /// Hands the writer role over to `writer`.
+        ///
+        /// # Errors
+        ///
+        /// Returns `NotAdmin` error if the caller is not the admin.
+        #[ink(message)]
+        pub fn set_writer(&mut self, writer: AccountId) -> Result<()> {
+            if self.env().caller() != self.admin {
+                return Err(Error::NotAdmin)
+            }
+            *self.writer = writer;
+            Ok(())
+        }
+
+        /// Sets the total token supply to `value`.
+        ///
+        /// # Errors
+        ///
+        /// Returns `NotWriter` error if the caller is not the writer.
+        #[ink(message)]
+        pub fn set_total_supply(&mut self, value: Balance) -> Result<()> {
+            self.ensure_writer()?;
+            *self.total_supply = value;
+            Ok(())
+        }
+
+        /// Sets the account balance of `owner` to `value`.
+        ///
+        /// # Errors
+        ///
+        /// Returns `NotWriter` error if the caller is not the writer.
+        #[ink(message)]
+        pub fn set_balance(&mut self, owner: AccountId, value: Balance) -> Result<()> {
+            self.ensure_writer()?;
+            self.balances.insert(owner, value);
+            Ok(())
+        }
+
+        /// Sets the amount `spender` is allowed to withdraw from `owner` to `value`.
+        ///
+        /// # Errors
+        ///
+        /// Returns `NotWriter` error if the caller is not the writer.
+        #[ink(message)]
+        pub fn set_allowance(
+            &mut self,
+            owner: AccountId,
+            spender: AccountId,
+            value: Balance,
+        ) -> Result<()> {
+            self.ensure_writer()?;
+            self.allowances.insert((owner, spender), value);
+            Ok(())
+        }
+
+        /// Returns `NotWriter` error if the caller is not the writer.
+        fn ensure_writer(&self) -> Result<()> {
+            if self.env().caller() != *self.writer {
+                return Err(Error::NotWriter)
+            }
+            Ok(())
+        }
+    }
+
+    /// Unit tests.
+    #[cfg(test)]
+    mod tests {
+        /// Imports all the definitions from the outer scope so we can use them here.
+        use super::*;
+
+        use ink_lang as ink;
+        use test_utils::set_caller;
+
+        #[ink::test]
+        fn new_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let storage = Erc20Storage::new(accounts.bob, 100);
+            assert_eq!(storage.admin(), accounts.alice);
+            assert_eq!(storage.writer(), accounts.alice);
+            assert_eq!(storage.total_supply(), 100);
+            assert_eq!(storage.balance_of(accounts.bob), 100);
+            assert_eq!(storage.version(), crate::STORAGE_VERSION);
+        }
+
+        #[ink::test]
+        fn writer_can_modify_state() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut storage = Erc20Storage::new(accounts.alice, 100);
+            assert_eq!(storage.set_writer(accounts.bob), Ok(()));
+
+            set_caller!(accounts.bob);
+            assert_eq!(storage.set_balance(accounts.charlie, 7), Ok(()));
+            assert_eq!(
+                storage.set_allowance(accounts.charlie, accounts.bob, 3),
+                Ok(())
+            );
+            assert_eq!(storage.set_total_supply(107), Ok(()));
+            assert_eq!(storage.balance_of(accounts.charlie), 7);
+            assert_eq!(storage.allowance(accounts.charlie, accounts.bob), 3);
+            assert_eq!(storage.total_supply(), 107);
+        }
+
+        #[ink::test]
+        fn previous_writer_is_locked_out() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut storage = Erc20Storage::new(accounts.alice, 100);
+            assert_eq!(storage.set_writer(accounts.bob), Ok(()));
+
+            assert_eq!(
+                storage.set_balance(accounts.alice, 1000),
+                Err(Error::NotWriter)
+            );
+            assert_eq!(
+                storage.set_allowance(accounts.alice, accounts.bob, 1000),
+                Err(Error::NotWriter)
+            );
+            assert_eq!(storage.set_total_supply(1000), Err(Error::NotWriter));
+            assert_eq!(storage.balance_of(accounts.alice), 100);
+            assert_eq!(storage.total_supply(), 100);
+        }
+
+        #[ink::test]
+        fn only_admin_can_set_writer() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut storage = Erc20Storage::new(accounts.alice, 100);
+            assert_eq!(storage.set_writer(accounts.bob), Ok(()));
+
+            set_caller!(accounts.bob);
+            assert_eq!(storage.set_writer(accounts.charlie), Err(Error::NotAdmin));
+            assert_eq!(storage.writer(), accounts.bob);
+        }
+    }
+}
\ No newline at end of file
@@ -0,0 +1,123 @@
+import BN from 'bn.js';
+import { expect } from 'chai';
+import { patract, network } from 'redspot';
+
+const { getContractFactory, getRandomSigner } = patract;
+
+const { api, getSigners } = network;
+
+describe('UPGRADEABLE ERC20', () => {
+  after(() => {
+    return api.disconnect();
+  });
+
+  async function setup() {
+    const one = new BN(10).pow(new BN(api.registry.chainDecimals[0]));
+    const signers = await getSigners();
+    const Alice = signers[0];
+    const sender = await getRandomSigner(Alice, one.muln(10000));
+    const storageFactory = await getContractFactory('erc20_storage', sender);
+    const logicFactory = await getContractFactory('erc20_logic', sender);
+    const proxyFactory = await getContractFactory('upgradeable_erc20', sender);
+
+    // The proxy instantiates its storage and logic from code already on
+    // chain, deploying them once on their own puts it there.
+    await storageFactory.deploy('new', sender.address, 0);
+    await logicFactory.deploy('new', sender.address, sender.address);
+
+    const contract = await proxyFactory.deploy(
+      'new',
+      1000,
+      0,
+      (storageFactory.abi.json as any).source.hash,
+      (logicFactory.abi.json as any).source.hash,
+      { value: one.muln(100) }
+    );
+    const storage = (await contract.query.storage()).output;
+    const receiver = await getRandomSigner();
+
+    return { sender, logicFactory, contract, storage, receiver, Alice, one };
+  }
+
+  it('Assigns initial balance', async () => {
+    const { contract, sender } = await setup();
+    const result = await contract.query.balanceOf(sender.address);
+    expect(result.output).to.equal(1000);
+  });
+
+  it('Transfer adds amount to destination account', async () => {
+    const { contract, receiver } = await setup();
+
+    await expect(() =>
+      contract.tx.transfer(receiver.address, 7)
+    ).to.changeTokenBalance(contract, receiver, 7);
+
+    await expect(() =>
+      contract.tx.transfer(receiver.address, 7)
+    ).to.changeTokenBalances(contract, [contract.signer, receiver], [-7, 7]);
+  });
+
+  it('Upgrade keeps balances and allowances', async () => {
+    const { contract, logicFactory, storage, sender, receiver } = await setup();
+
+    await contract.tx.transfer(receiver.address, 7);
+    await contract.tx.approve(receiver.address, 5);
+
+    const previous = (await contract.query.logic()).output;
+    const logic = await logicFactory.deploy('new', storage, contract.address);
+
+    await expect(contract.tx.upgrade(logic.address))
+      .to.emit(contract, 'Upgraded')
+      .withArgs(previous, logic.address);
+
+    expect((await contract.query.logic()).output).to.equal(logic.address);
+    expect((await contract.query.balanceOf(sender.address)).output).to.equal(
+      993
+    );
+    expect((await contract.query.balanceOf(receiver.address)).output).to.equal(
+      7
+    );
+    expect(
+      (await contract.query.allowance(sender.address, receiver.address)).output
+    ).to.equal(5);
+  });
+
+  it('Transfers keep working after an upgrade', async () => {
+    const { contract, logicFactory, storage, receiver } = await setup();
+
+    const logic = await logicFactory.deploy('new', storage, contract.address);
+    await contract.tx.upgrade(logic.address);
+
+    await expect(() =>
+      contract.tx.transfer(receiver.address, 7)
+    ).to.changeTokenBalances(contract, [contract.signer, receiver], [-7, 7]);
+  });
+
+  it('Can not upgrade without being the admin', async () => {
+    const { contract, logicFactory, storage, Alice, one } = await setup();
+
+    const otherAccount = await getRandomSigner(Alice, one.muln(10000));
+    const logic = await logicFactory.deploy('new', storage, contract.address);
+
+    await expect(
+      contract.tx.upgrade(logic.address, {
+        signer: otherAccount
+      })
+    ).to.not.emit(contract, 'Upgraded');
+  });
+
+  it('Can not upgrade to a logic bound to another storage', async () => {
+    const { contract, logicFactory, sender } = await setup();
+
+    const logic = await logicFactory.deploy(
+      'new',
+      sender.address,
+      contract.address
+    );
+
+    await expect(contract.tx.upgrade(logic.address)).to.not.emit(
+      contract,
+      'Upgraded'
+    );
+  });
+});
@@ -0,0 +1,546 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use ink_lang as ink;
+
+#[ink::contract]
+mod vesting_wallet {
+    use erc20::{
+        BALANCE_OF_SELECTOR,
+        TRANSFER_SELECTOR,
+    };
+    use ink_env::{
+        call::{
+            build_call,
+            utils::ReturnType,
+            ExecutionInput,
+            Selector,
+        },
+        DefaultEnvironment,
+        ReturnFlags,
+    };
+    #[cfg(not(feature = "ink-as-dependency"))]
+    use ink_storage::{
+        collections::HashMap as StorageHashMap,
+        lazy::Lazy,
+    };
+
+    /// Releases the native funds and erc20 tokens it holds linearly to a
+    /// beneficiary between `start` and `start + duration`.
+    ///
+    /// Nothing can be released before `start + cliff`. The wallet is funded by
+    /// the endowment, plain native transfers and erc20 transfers to it, and every
+    /// fund vests as if it was held from the start.
+    ///
+    /// The grantor, the account which created the wallet, can revoke it. Funds
+    /// vested until then stay releasable to the beneficiary, while unvested
+    /// native funds are sent back right away and unvested tokens are sent back by
+    /// `reclaim`.
+    #[ink(storage)]
+    pub struct VestingWallet {
+        /// The account which created the wallet and may revoke it.
+        grantor: AccountId,
+        /// The account funds are released to.
+        beneficiary: AccountId,
+        /// The timestamp from which the funds start to vest.
+        start: Timestamp,
+        /// The time after `start` before which nothing can be released.
+        cliff: Timestamp,
+        /// The time after `start` at which all funds are vested.
+        duration: Timestamp,
+        /// The timestamp the wallet was revoked at, if it was.
+        revoked_at: Lazy<Option<Timestamp>>,
+        /// The native funds released so far.
+        released: Lazy<Balance>,
+        /// Mapping from token to the amount of it released so far.
+        token_released: StorageHashMap<AccountId, Balance>,
+        /// The tokens whose unvested part was sent back after revocation.
+        reclaimed: StorageHashMap<AccountId, ()>,
+    }
+
+    /// Event emitted when funds are released to the beneficiary.
+    ///
+    /// `token` is `None` for native funds.
+    #[ink(event)]
+    pub struct Released {
+        #[ink(topic)]
+        token: Option<AccountId>,
+        amount: Balance,
+    }
+
+    /// Event emitted when the grantor revokes the wallet.
+    #[ink(event)]
+    pub struct Revoked {
+        at: Timestamp,
+    }
+
+    /// Event emitted when unvested funds are sent back to the grantor.
+    ///
+    /// `token` is `None` for native funds.
+    #[ink(event)]
+    pub struct Reclaimed {
+        #[ink(topic)]
+        token: Option<AccountId>,
+        amount: Balance,
+    }
+
+    /// The vesting wallet error types.
+    #[derive(Debug, PartialEq, Eq, scale::Encode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum Error {
+        /// Returned if the caller is not the grantor.
+        NotGrantor,
+        /// Returned if the wallet was revoked.
+        AlreadyRevoked,
+        /// Returned if the wallet was not revoked.
+        NotRevoked,
+        /// Returned if the unvested part of the token was sent back already.
+        AlreadyReclaimed,
+        /// Returned if the token balance of the wallet could not be read.
+        TokenCallFailed,
+        /// Returned if funds could not be transferred.
+        TransferFailed,
+    }
+
+    /// The vesting wallet result type.
+    pub type Result<T> = core::result::Result<T, Error>;
+
+    impl VestingWallet {
+        /// Creates a wallet releasing its funds to `beneficiary` linearly between
+        /// `start` and `start + duration`, with nothing releasable before
+        /// `start + cliff`.
+        ///
+        /// # Panics
+        ///
+        /// If `cliff` is greater than `duration`.
+        #[ink(constructor)]
+        pub fn new(
+            beneficiary: AccountId,
+            start: Timestamp,
+            cliff: Timestamp,
+            duration: Timestamp,
+        ) -> Self {
+            assert!(cliff <= duration, "invalid schedule");
+            Self {
+                grantor: Self::env().caller(),
+                beneficiary,
+                start,
+                cliff,
+                duration,
+                revoked_at: Lazy::new(None),
+                released: Lazy::new(0),
+                token_released: StorageHashMap::new(),
+                reclaimed: StorageHashMap::new(),
+            }
+        }
+
+        /// Returns the account which created the wallet.
+        #[ink(message)]
+        pub fn grantor(&self) -> AccountId {
+            self.grantor
+        }
+
+        /// Returns the account funds are released to.
+        #[ink(message)]
+        pub fn beneficiary(&self) -> AccountId {
+            self.beneficiary
+        }
+
+        /// Returns the `(start, cliff, duration)` of the vesting schedule.
+        #[ink(message)]
+        pub fn schedule(&self) -> (Timestamp, Timestamp, Timestamp) {
+            (self.start, self.cliff, self.duration)
+        }
+
+        /// Returns the timestamp the wallet was revoked at, if it was.
+        #[ink(message)]
+        pub fn revoked_at(&self) -> Option<Timestamp> {
+            *self.revoked_at
+        }
+
+        /// Returns the native funds released so far.
+        #[ink(message)]
+        pub fn released(&self) -> Balance {
+            *self.released
+        }
+
+        /// Returns the amount of `token` released so far.
+        #[ink(message)]
+        pub fn token_released(&self, token: AccountId) -> Balance {
+            self.token_released.get(&token).copied().unwrap_or(0)
+        }
+
+        /// Returns the native funds that vested but were not released yet.
+        ///
+        /// The wallet's minimum balance is never released.
+        #[ink(message)]
+        pub fn releasable(&self) -> Balance {
+            let released = *self.released;
+            let allocation = self.native_balance().saturating_add(released);
+            // Unvested native funds are sent back on revocation, so all that is
+            // left afterwards vested.
+            let settled = self.revoked_at.is_some();
+            self.vested_amount(allocation, settled)
+                .saturating_sub(released)
+        }
+
+        /// Returns the amount of `token` that vested but was not released yet.
+        ///
+        /// # Errors
+        ///
+        /// Returns `TokenCallFailed` error if the balance of the wallet could not
+        /// be read from `token`.
+        #[ink(message)]
+        pub fn token_releasable(&self, token: AccountId) -> Result<Balance> {
+            let released = self.token_released(token);
+            let allocation = self.token_balance(token)?.saturating_add(released);
+            let settled = self.reclaimed.contains_key(&token);
+            Ok(self
+                .vested_amount(allocation, settled)
+                .saturating_sub(released))
+        }
+
+        /// Sends the releasable native funds to the beneficiary and returns their
+        /// amount.
+        ///
+        /// Anyone may release vested funds. On success a `Released` event is
+        /// emitted if there were funds to release.
+        ///
+        /// # Errors
+        ///
+        /// Returns `TransferFailed` error if the funds could not be sent.
+        #[ink(message)]
+        pub fn release(&mut self) -> Result<Balance> {
+            let amount = self.releasable();
+            if amount == 0 {
+                return Ok(0)
+            }
+            // The beneficiary runs no code on receiving the funds, so the release is
+            // counted after it.
+            self.env()
+                .transfer(self.beneficiary, amount)
+                .map_err(|_| Error::TransferFailed)?;
+            *self.released += amount;
+            self.env().emit_event(Released {
+                token: None,
+                amount,
+            });
+            Ok(amount)
+        }
+
+        /// Sends the releasable amount of `token` to the beneficiary and returns
+        /// it.
+        ///
+        /// Anyone may release vested tokens. On success a `Released` event is
+        /// emitted if there were tokens to release.
+        ///
+        /// # Errors
+        ///
+        /// Returns `TokenCallFailed` error if the balance of the wallet could not
+        /// be read from `token`.
+        ///
+        /// Returns `TransferFailed` error, reverting all changes, if the tokens
+        /// could not be sent.
+        #[ink(message)]
+        pub fn token_release(&mut self, token: AccountId) -> Result<Balance> {
+            let amount = self.token_releasable(token)?;
+            if amount == 0 {
+                return Ok(0)
+            }
+            let released = self.token_released(token);
+            self.token_released.insert(token, released + amount);
+            if !self.token_transfer(token, self.beneficiary, amount) {
+                Self::revert(Error::TransferFailed)
+            }
+            self.env().emit_event(Released {
+                token: Some(token),
+                amount,
+            });
+            Ok(amount)
+        }
+
+        /// Stops the vesting at the current time and sends the unvested native
+        /// funds back to the grantor.
+        ///
+        /// On success a `Revoked` event and, if there were unvested native funds,
+        /// a `Reclaimed` event are emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `NotGrantor` error if the caller is not the grantor.
+        ///
+        /// Returns `AlreadyRevoked` error if the wallet was revoked.
+        ///
+        /// Returns `TransferFailed` error if the unvested funds could not be sent.
+        #[ink(message)]
+        pub fn revoke(&mut self) -> Result<()> {
+            self.ensure_grantor()?;
+            if self.revoked_at.is_some() {
+                return Err(Error::AlreadyRevoked)
+            }
+            let now = self.env().block_timestamp();
+            let allocation = self.native_balance().saturating_add(*self.released);
+            let unvested = allocation - self.vested_amount(allocation, false);
+            if unvested > 0 {
+                self.env()
+                    .transfer(self.grantor, unvested)
+                    .map_err(|_| Error::TransferFailed)?;
+                self.env().emit_event(Reclaimed {
+                    token: None,
+                    amount: unvested,
+                });
+            }
+            *self.revoked_at = Some(now);
+            self.env().emit_event(Revoked { at: now });
+            Ok(())
+        }
+
+        /// Sends the amount of `token` that did not vest until the revocation back
+        /// to the grantor and returns it.
+        ///
+        /// On success a `Reclaimed` event is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `NotGrantor` error if the caller is not the grantor.
+        ///
+        /// Returns `NotRevoked` error if the wallet was not revoked.
+        ///
+        /// Returns `AlreadyReclaimed` error if `token` was reclaimed already.
+        ///
+        /// Returns `TokenCallFailed` error if the balance of the wallet could not
+        /// be read from `token`.
+        ///
+        /// Returns `TransferFailed` error, reverting all changes, if the tokens
+        /// could not be sent.
+        #[ink(message)]
+        pub fn reclaim(&mut self, token: AccountId) -> Result<Balance> {
+            self.ensure_grantor()?;
+            if self.revoked_at.is_none() {
+                return Err(Error::NotRevoked)
+            }
+            if self.reclaimed.contains_key(&token) {
+                return Err(Error::AlreadyReclaimed)
+            }
+            let allocation = self
+                .token_balance(token)?
+                .saturating_add(self.token_released(token));
+            let unvested = allocation - self.vested_amount(allocation, false);
+            self.reclaimed.insert(token, ());
+            if !self.token_transfer(token, self.grantor, unvested) {
+                Self::revert(Error::TransferFailed)
+            }
+            self.env().emit_event(Reclaimed {
+                token: Some(token),
+                amount: unvested,
+            });
+            Ok(unvested)
+        }
+
+        /// Returns the part of `allocation` vested now, or at the revocation if
+        /// the wallet was revoked.
+        ///
+        /// All of `allocation` counts as vested if it is `settled`, that is if its
+        /// unvested part was sent back after revocation.
+        fn vested_amount(&self, allocation: Balance, settled: bool) -> Balance {
+            if settled {
+                return allocation
+            }
+            let now = self
+                .revoked_at
+                .unwrap_or_else(|| self.env().block_timestamp());
+            let elapsed = now.saturating_sub(self.start);
+            if elapsed < self.cliff {
+                return 0
+            }
+            if elapsed >= self.duration {
+                return allocation
+            }
+            // Split `allocation * elapsed / duration` so the multiplication can
+            // not overflow.
+            let (elapsed, duration) = (elapsed as Balance, self.duration as Balance);
+            allocation / duration * elapsed + allocation % duration * elapsed / duration
+        }
+
+        /// Returns the native funds of the wallet above its minimum balance.
+        fn native_balance(&self) -> Balance {
+            self.env()
+                .balance()
+                .saturating_sub(self.env().minimum_balance())
+        }
+
+        /// Returns the balance of the wallet in `token`.
+        fn token_balance(&self, token: AccountId) -> Result<Balance> {
+            build_call::<DefaultEnvironment>()
+                .callee(token)
+                .gas_limit(0)
+                .transferred_value(0)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(BALANCE_OF_SELECTOR))
+                        .push_arg(self.env().account_id()),
+                )
+                .returns::<ReturnType<Balance>>()
+                .fire()
+                .map_err(|_| Error::TokenCallFailed)
+        }
+
+        /// Transfers `value` of `token` from the wallet to `to` and returns
+        /// whether that succeeded.
+        fn token_transfer(
+            &self,
+            token: AccountId,
+            to: AccountId,
+            value: Balance,
+        ) -> bool {
+            let result = build_call::<DefaultEnvironment>()
+                .callee(token)
+                .gas_limit(0)
+                .transferred_value(0)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(TRANSFER_SELECTOR))
+                        .push_arg(to)
+                        .push_arg(value),
+                )
+                .returns::<ReturnType<core::result::Result<(), u8>>>()
+                .fire();
+            matches!(result, Ok(Ok(())))
+        }
+
+        /// Returns `NotGrantor` error if the caller is not the grantor.
+        fn ensure_grantor(&self) -> Result<()> {
+            if self.env().caller() != self.grantor {
+                return Err(Error::NotGrantor)
+            }
+            Ok(())
+        }
+
+        /// Ends the current call with `error`, so tokens are not counted as
+        /// released or reclaimed when paying them out fails.
+        fn revert(error: Error) -> ! {
+            ink_env::return_value::<Result<()>>(
+                ReturnFlags::default().set_reverted(true),
+                &Err(error),
+            )
+        }
+    }
+
+    /// Unit tests.
+    ///
+    /// Releasing and reclaiming tokens is left to the redspot tests. Tokens follow
+    /// the schedule the native funds are checked against here.
+    #[cfg(test)]
+    mod tests {
+        /// Imports all the definitions from the outer scope so we can use them here.
+        use super::*;
+
+        use ink_lang as ink;
+        use test_utils::set_caller;
+
+        fn native_balance_of(account: AccountId) -> Balance {
+            ink_env::test::get_account_balance::<ink_env::DefaultEnvironment>(account)
+                .expect("Cannot get account balance")
+        }
+
+        /// Advances one block and returns a wallet for Bob, vesting from `0`, that
+        /// is half way through a linear schedule and holds `100` native funds
+        /// above its minimum balance.
+        fn half_vested_wallet(cliff: Timestamp) -> VestingWallet {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            ink_env::test::advance_block::<ink_env::DefaultEnvironment>()
+                .expect("Cannot advance block");
+            let now = ink_env::block_timestamp::<ink_env::DefaultEnvironment>()
+                .expect("Cannot get block timestamp");
+            let wallet = VestingWallet::new(accounts.bob, 0, cliff, 2 * now);
+            let contract = ink_env::account_id::<ink_env::DefaultEnvironment>()
+                .expect("Cannot get contract id");
+            let minimum_balance =
+                ink_env::minimum_balance::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get minimum balance");
+            ink_env::test::set_account_balance::<ink_env::DefaultEnvironment>(
+                contract,
+                minimum_balance + 100,
+            )
+            .expect("Cannot set contract balance");
+            wallet
+        }
+
+        #[ink::test]
+        fn new_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let wallet = VestingWallet::new(accounts.bob, 1, 2, 3);
+
+            assert_eq!(wallet.grantor(), accounts.alice);
+            assert_eq!(wallet.beneficiary(), accounts.bob);
+            assert_eq!(wallet.schedule(), (1, 2, 3));
+            assert_eq!(wallet.revoked_at(), None);
+            assert_eq!(wallet.released(), 0);
+        }
+
+        #[ink::test]
+        #[should_panic(expected = "invalid schedule")]
+        fn new_rejects_cliff_after_end() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            VestingWallet::new(accounts.bob, 0, 11, 10);
+        }
+
+        #[ink::test]
+        fn native_funds_are_released_linearly() {
+            let mut wallet = half_vested_wallet(0);
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let bob_native = native_balance_of(accounts.bob);
+
+            assert_eq!(wallet.releasable(), 50);
+            assert_eq!(wallet.release(), Ok(50));
+            assert_eq!(wallet.release(), Ok(0));
+            assert_eq!(wallet.released(), 50);
+            assert_eq!(native_balance_of(accounts.bob), bob_native + 50);
+            assert_eq!(ink_env::test::recorded_events().count(), 1);
+        }
+
+        #[ink::test]
+        fn nothing_is_releasable_before_cliff() {
+            let wallet = half_vested_wallet(Timestamp::MAX);
+
+            assert_eq!(wallet.releasable(), 0);
+        }
+
+        #[ink::test]
+        fn revoke_returns_unvested_funds() {
+            let mut wallet = half_vested_wallet(0);
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let alice_native = native_balance_of(accounts.alice);
+
+            assert_eq!(wallet.reclaim(accounts.eve), Err(Error::NotRevoked));
+            assert_eq!(wallet.revoke(), Ok(()));
+            assert_eq!(wallet.revoke(), Err(Error::AlreadyRevoked));
+            assert_eq!(native_balance_of(accounts.alice), alice_native + 50);
+            assert!(wallet.revoked_at().is_some());
+
+            // Vesting stopped, so what vested until the revocation stays releasable.
+            ink_env::test::advance_block::<ink_env::DefaultEnvironment>()
+                .expect("Cannot advance block");
+            assert_eq!(wallet.releasable(), 50);
+        }
+
+        #[ink::test]
+        fn only_grantor_revokes() {
+            let mut wallet = half_vested_wallet(0);
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            set_caller!(accounts.bob);
+            assert_eq!(wallet.revoke(), Err(Error::NotGrantor));
+            assert_eq!(wallet.reclaim(accounts.eve), Err(Error::NotGrantor));
+        }
+    }
+}
\ No newline at end of file
@@ -0,0 +1,103 @@
+import BN from 'bn.js';
+import { expect } from 'chai';
+import { patract, network } from 'redspot';
+
+const { getContractFactory, getRandomSigner } = patract;
+
+const { api, getSigners } = network;
+
+// A cliff no test runs long enough to reach.
+const NEVER = '18446744073709551615';
+
+describe('VESTING WALLET', () => {
+  after(() => {
+    return api.disconnect();
+  });
+
+  async function setup(cliff: number | string, duration: number | string) {
+    const one = new BN(10).pow(new BN(api.registry.chainDecimals[0]));
+    const signers = await getSigners();
+    const Alice = signers[0];
+    const sender = await getRandomSigner(Alice, one.muln(10000));
+    const beneficiary = await getRandomSigner(Alice, one.muln(10000));
+    const tokenFactory = await getContractFactory('erc20', sender);
+    const token = await tokenFactory.deploy(
+      'new',
+      '1000',
+      'Redspot',
+      'RDS',
+      10
+    );
+    const contractFactory = await getContractFactory('vesting_wallet', sender);
+    const contract = await contractFactory.deploy(
+      'new',
+      beneficiary.address,
+      0,
+      cliff,
+      duration,
+      { value: one.muln(10) }
+    );
+    await token.tx.transfer(contract.address, 100);
+
+    return {
+      sender,
+      beneficiary,
+      token,
+      contractFactory,
+      contract,
+      Alice,
+      one
+    };
+  }
+
+  it('Releases vested tokens to the beneficiary', async () => {
+    const { contract, token, beneficiary } = await setup(0, 1);
+
+    await expect(() =>
+      contract.tx.tokenRelease(token.address)
+    ).to.changeTokenBalance(token, beneficiary, 100);
+    expect((await contract.query.tokenReleased(token.address)).output).to.equal(
+      100
+    );
+  });
+
+  it('Releases vested native funds to the beneficiary', async () => {
+    const { contract } = await setup(0, 1);
+
+    await expect(contract.tx.release()).to.emit(contract, 'Released');
+    expect((await contract.query.releasable()).output).to.equal(0);
+  });
+
+  it('Releases nothing before the cliff', async () => {
+    const { contract, token } = await setup(NEVER, NEVER);
+
+    await expect(contract.tx.tokenRelease(token.address)).to.not.emit(
+      contract,
+      'Released'
+    );
+    await expect(contract.tx.release()).to.not.emit(contract, 'Released');
+  });
+
+  it('Sends unvested funds back on revocation', async () => {
+    const { contract, token, sender } = await setup(NEVER, NEVER);
+
+    await expect(contract.tx.revoke()).to.emit(contract, 'Revoked');
+    await expect(() =>
+      contract.tx.reclaim(token.address)
+    ).to.changeTokenBalance(token, sender, 100);
+    await expect(contract.tx.reclaim(token.address)).to.not.emit(
+      contract,
+      'Reclaimed'
+    );
+  });
+
+  it('Can not revoke without being the grantor', async () => {
+    const { contract, beneficiary } = await setup(NEVER, NEVER);
+
+    await expect(
+      contract.tx.revoke({
+        signer: beneficiary
+      })
+    ).to.not.emit(contract, 'Revoked');
+  });
+});
@@ -0,0 +1,341 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use ink_lang as ink;
+
+#[ink::contract]
+mod wnative {
+    #[cfg(not(feature = "ink-as-dependency"))]
+    use ink_storage::{
+        collections::HashMap as StorageHashMap,
+        lazy::Lazy,
+    };
+
+    /// An ERC-20 token backed 1:1 by the chain's native currency.
+    ///
+    /// Native funds sent with `deposit` are wrapped into tokens which can be
+    /// moved around like any other ERC-20 token and unwrapped again with
+    /// `withdraw`.
+    #[ink(storage)]
+    pub struct Wnative {
+        /// Total token supply, equal to the native funds held for depositors.
+        total_supply: Lazy<Balance>,
+        /// Mapping from owner to number of owned token.
+        balances: StorageHashMap<AccountId, Balance>,
+        /// Mapping of the token amount which an account is allowed to withdraw
+        /// from another account.
+        allowances: StorageHashMap<(AccountId, AccountId), Balance>,
+    }
+
+    /// Event emitted when a token transfer occurs.
+    #[ink(event)]
+    pub struct Transfer {
+        #[ink(topic)]
+        from: Option<AccountId>,
+        #[ink(topic)]
+        to: Option<AccountId>,
+        #[ink(topic)]
+        value: Balance,
+    }
+
+    /// Event emitted when an approval occurs that `spender` is allowed to withdraw
+    /// up to the amount of `value` tokens from `owner`.
+    #[ink(event)]
+    pub struct Approval {
+        #[ink(topic)]
+        owner: AccountId,
+        #[ink(topic)]
+        spender: AccountId,
+        #[ink(topic)]
+        value: Balance,
+    }
+
+    /// Event emitted when `value` native funds are wrapped for `to`.
+    #[ink(event)]
+    pub struct Deposit {
+        #[ink(topic)]
+        to: AccountId,
+        value: Balance,
+    }
+
+    /// Event emitted when `value` tokens of `from` are unwrapped into native funds.
+    #[ink(event)]
+    pub struct Withdrawal {
+        #[ink(topic)]
+        from: AccountId,
+        value: Balance,
+    }
+
+    /// The wrapped token error types.
+    #[derive(Debug, PartialEq, Eq, scale::Encode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum Error {
+        /// Returned if not enough balance to fulfill a request is available.
+        InsufficientBalance,
+        /// Returned if not enough allowance to fulfill a request is available.
+        InsufficientAllowance,
+        /// Returned if the native funds could not be sent back.
+        TransferFailed,
+    }
+
+    /// The wrapped token result type.
+    pub type Result<T> = core::result::Result<T, Error>;
+
+    impl Wnative {
+        /// Creates a new wrapped token without any supply.
+        #[ink(constructor)]
+        pub fn new() -> Self {
+            Self {
+                total_supply: Lazy::new(0),
+                balances: StorageHashMap::new(),
+                allowances: StorageHashMap::new(),
+            }
+        }
+
+        /// Returns the total token supply.
+        #[ink(message)]
+        pub fn total_supply(&self) -> Balance {
+            *self.total_supply
+        }
+
+        /// Returns the account balance for the specified `owner`.
+        ///
+        /// Returns `0` if the account is non-existent.
+        #[ink(message)]
+        pub fn balance_of(&self, owner: AccountId) -> Balance {
+            self.balances.get(&owner).copied().unwrap_or(0)
+        }
+
+        /// Returns the amount which `spender` is still allowed to withdraw from `owner`.
+        ///
+        /// Returns `0` if no allowance has been set.
+        #[ink(message)]
+        pub fn allowance(&self, owner: AccountId, spender: AccountId) -> Balance {
+            self.allowances.get(&(owner, spender)).copied().unwrap_or(0)
+        }
+
+        /// Wraps the native funds sent with this call into tokens for the caller.
+        ///
+        /// A `Transfer` event from `None` and a `Deposit` event are emitted.
+        #[ink(message, payable)]
+        pub fn deposit(&mut self) {
+            let to = self.env().caller();
+            let value = self.env().transferred_balance();
+            let to_balance = self.balance_of(to);
+            self.balances.insert(to, to_balance + value);
+            *self.total_supply += value;
+            self.env().emit_event(Transfer {
+                from: None,
+                to: Some(to),
+                value,
+            });
+            self.env().emit_event(Deposit { to, value });
+        }
+
+        /// Burns `value` tokens of the caller and sends the same amount of native
+        /// funds back to it.
+        ///
+        /// On success a `Transfer` event to `None` and a `Withdrawal` event are
+        /// emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `InsufficientBalance` error if there are not enough tokens on
+        /// the caller's account balance.
+        ///
+        /// Returns `TransferFailed` error if the native funds could not be sent.
+        #[ink(message)]
+        pub fn withdraw(&mut self, value: Balance) -> Result<()> {
+            let from = self.env().caller();
+            let from_balance = self.balance_of(from);
+            if from_balance < value {
+                return Err(Error::InsufficientBalance)
+            }
+            // The tokens are burnt only once the funds went out, which runs no
+            // code of `from`.
+            self.env()
+                .transfer(from, value)
+                .map_err(|_| Error::TransferFailed)?;
+            self.balances.insert(from, from_balance - value);
+            *self.total_supply -= value;
+            self.env().emit_event(Transfer {
+                from: Some(from),
+                to: None,
+                value,
+            });
+            self.env().emit_event(Withdrawal { from, value });
+            Ok(())
+        }
+
+        /// Transfers `value` amount of tokens from the caller's account to account `to`.
+        ///
+        /// On success a `Transfer` event is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `InsufficientBalance` error if there are not enough tokens on
+        /// the caller's account balance.
+        #[ink(message)]
+        pub fn transfer(&mut self, to: AccountId, value: Balance) -> Result<()> {
+            let from = self.env().caller();
+            self.transfer_from_to(from, to, value)
+        }
+
+        /// Allows `spender` to withdraw from the caller's account multiple times, up to
+        /// the `value` amount.
+        ///
+        /// If this function is called again it overwrites the current allowance with `value`.
+        ///
+        /// An `Approval` event is emitted.
+        #[ink(message)]
+        pub fn approve(&mut self, spender: AccountId, value: Balance) -> Result<()> {
+            let owner = self.env().caller();
+            self.allowances.insert((owner, spender), value);
+            self.env().emit_event(Approval {
+                owner,
+                spender,
+                value,
+            });
+            Ok(())
+        }
+
+        /// Transfers `value` tokens on the behalf of `from` to the account `to`.
+        ///
+        /// On success a `Transfer` event is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `InsufficientAllowance` error if there are not enough tokens allowed
+        /// for the caller to withdraw from `from`.
+        ///
+        /// Returns `InsufficientBalance` error if there are not enough tokens on
+        /// the account balance of `from`.
+        #[ink(message)]
+        pub fn transfer_from(
+            &mut self,
+            from: AccountId,
+            to: AccountId,
+            value: Balance,
+        ) -> Result<()> {
+            let caller = self.env().caller();
+            let allowance = self.allowance(from, caller);
+            if allowance < value {
+                return Err(Error::InsufficientAllowance)
+            }
+            self.transfer_from_to(from, to, value)?;
+            self.allowances.insert((from, caller), allowance - value);
+            Ok(())
+        }
+
+        /// Transfers `value` amount of tokens from the account `from` to account `to`.
+        ///
+        /// On success a `Transfer` event is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `InsufficientBalance` error if there are not enough tokens on
+        /// the account balance of `from`.
+        fn transfer_from_to(
+            &mut self,
+            from: AccountId,
+            to: AccountId,
+            value: Balance,
+        ) -> Result<()> {
+            let from_balance = self.balance_of(from);
+            if from_balance < value {
+                return Err(Error::InsufficientBalance)
+            }
+            self.balances.insert(from, from_balance - value);
+            let to_balance = self.balance_of(to);
+            self.balances.insert(to, to_balance + value);
+            self.env().emit_event(Transfer {
+                from: Some(from),
+                to: Some(to),
+                value,
+            });
+            Ok(())
+        }
+    }
+
+    /// Unit tests.
+    #[cfg(test)]
+    mod tests {
+        /// Imports all the definitions from the outer scope so we can use them here.
+        use super::*;
+
+        use ink_lang as ink;
+        use test_utils::set_caller;
+
+        #[ink::test]
+        fn deposit_wraps_transferred_funds() {
+            let mut wnative = Wnative::new();
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            set_caller!(accounts.bob, 100);
+            wnative.deposit();
+            assert_eq!(wnative.balance_of(accounts.bob), 100);
+            assert_eq!(wnative.total_supply(), 100);
+            assert_eq!(ink_env::test::recorded_events().count(), 2);
+        }
+
+        #[ink::test]
+        fn withdraw_unwraps_tokens() {
+            let mut wnative = Wnative::new();
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let contract = ink_env::account_id::<ink_env::DefaultEnvironment>()
+                .expect("Cannot get contract id");
+
+            set_caller!(accounts.bob, 100);
+            wnative.deposit();
+            ink_env::test::set_account_balance::<ink_env::DefaultEnvironment>(
+                contract, 100,
+            )
+            .expect("Cannot set contract balance");
+            let bob_native = ink_env::test::get_account_balance::<
+                ink_env::DefaultEnvironment,
+            >(accounts.bob)
+            .expect("Cannot get account balance");
+
+            assert_eq!(wnative.withdraw(101), Err(Error::InsufficientBalance));
+            assert_eq!(wnative.withdraw(40), Ok(()));
+            assert_eq!(wnative.balance_of(accounts.bob), 60);
+            assert_eq!(wnative.total_supply(), 60);
+            assert_eq!(
+                ink_env::test::get_account_balance::<ink_env::DefaultEnvironment>(
+                    accounts.bob
+                ),
+                Ok(bob_native + 40)
+            );
+        }
+
+        #[ink::test]
+        fn wrapped_tokens_are_transferable() {
+            let mut wnative = Wnative::new();
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            set_caller!(accounts.alice, 50);
+            wnative.deposit();
+            assert_eq!(wnative.transfer(accounts.bob, 20), Ok(()));
+            assert_eq!(wnative.approve(accounts.bob, 10), Ok(()));
+            ink_env::test::pop_execution_context();
+
+            set_caller!(accounts.bob, 0);
+            assert_eq!(
+                wnative.transfer_from(accounts.alice, accounts.charlie, 11),
+                Err(Error::InsufficientAllowance)
+            );
+            assert_eq!(
+                wnative.transfer_from(accounts.alice, accounts.charlie, 10),
+                Ok(())
+            );
+            assert_eq!(wnative.balance_of(accounts.alice), 20);
+            assert_eq!(wnative.balance_of(accounts.bob), 20);
+            assert_eq!(wnative.balance_of(accounts.charlie), 10);
+        }
+    }
+}
\ No newline at end of file
@@ -0,0 +1,70 @@
+import BN from 'bn.js';
+import { expect } from 'chai';
+import { patract, network } from 'redspot';
+
+const { getContractFactory, getRandomSigner } = patract;
+
+const { api, getSigners } = network;
+
+describe('WNATIVE', () => {
+  after(() => {
+    return api.disconnect();
+  });
+
+  async function setup() {
+    const one = new BN(10).pow(new BN(api.registry.chainDecimals[0]));
+    const signers = await getSigners();
+    const Alice = signers[0];
+    const sender = await getRandomSigner(Alice, one.muln(10000));
+    const contractFactory = await getContractFactory('wnative', sender);
+    const contract = await contractFactory.deploy('new');
+    const receiver = await getRandomSigner();
+
+    return { sender, contractFactory, contract, receiver, Alice, one };
+  }
+
+  it('Deposit wraps the transferred value', async () => {
+    const { contract, sender, one } = await setup();
+
+    await expect(() =>
+      contract.tx.deposit({ value: one })
+    ).to.changeTokenBalance(contract, sender, one);
+
+    const result = await contract.query.totalSupply();
+    expect(result.output).to.equal(one);
+  });
+
+  it('Withdraw burns tokens and returns native funds', async () => {
+    const { contract, sender, one } = await setup();
+
+    await contract.tx.deposit({ value: one.muln(2) });
+
+    await expect(contract.tx.withdraw(one))
+      .to.emit(contract, 'Withdrawal')
+      .withArgs(sender.address, one);
+
+    const result = await contract.query.balanceOf(sender.address);
+    expect(result.output).to.equal(one);
+  });
+
+  it('Can not withdraw above the wrapped amount', async () => {
+    const { contract, one } = await setup();
+
+    await contract.tx.deposit({ value: one });
+
+    await expect(contract.tx.withdraw(one.muln(2))).to.not.emit(
+      contract,
+      'Withdrawal'
+    );
+  });
+
+  it('Wrapped tokens can be transferred', async () => {
+    const { contract, receiver, one } = await setup();
+
+    await contract.tx.deposit({ value: one });
+
+    await expect(() =>
+      contract.tx.transfer(receiver.address, 7)
+    ).to.changeTokenBalances(contract, [contract.signer, receiver], [-7, 7]);
+  });
+});